@@ -0,0 +1,45 @@
+use clarinet_files::chainhook_types::StacksNetwork;
+use clarinet_files::{NetworkManifest, ProjectManifest};
+use serde_json::Value as JsonValue;
+
+// Sends a request to the control API of the running devnet (GET when there is no body)
+pub fn send_control_request(
+    manifest: &ProjectManifest,
+    path: &str,
+    body: Option<JsonValue>,
+) -> Result<JsonValue, String> {
+    let network_manifest = NetworkManifest::from_project_manifest_location(
+        &manifest.location,
+        &StacksNetwork::Devnet.get_networks(),
+        Some(&manifest.project.cache_location),
+        None,
+    )?;
+    let control_port = match network_manifest.devnet {
+        Some(devnet) => devnet.orchestrator_control_port,
+        None => return Err("unable to read settings/Devnet.toml".into()),
+    };
+
+    let url = format!("http://localhost:{}{}", control_port, path);
+    let client = reqwest::blocking::Client::new();
+    let request = match body {
+        Some(body) => client.post(url).json(&body),
+        None => client.get(url),
+    };
+    let response = request.send().map_err(|e| {
+        format!(
+            "unable to reach the devnet control API on port {} (is devnet running?): {}",
+            control_port, e
+        )
+    })?;
+    let status = response.status();
+    let result: JsonValue = response
+        .json()
+        .map_err(|e| format!("unable to parse response: {}", e))?;
+    match status.is_success() {
+        true => Ok(result),
+        false => Err(result["error"]
+            .as_str()
+            .unwrap_or("unknown error")
+            .to_string()),
+    }
+}
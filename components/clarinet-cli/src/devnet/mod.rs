@@ -1,2 +1,3 @@
+pub mod fault;
 pub mod package;
 pub mod start;
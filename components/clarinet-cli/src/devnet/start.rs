@@ -21,6 +21,7 @@ pub fn start(
     deployment: DeploymentSpecification,
     log_tx: Option<Sender<LogData>>,
     display_dashboard: bool,
+    json_output: bool,
 ) -> Result<
     (
         Option<mpsc::Receiver<DevnetEvent>>,
@@ -35,7 +36,12 @@ pub fn start(
     ) {
         Ok(hooks) => hooks,
         Err(e) => {
-            println!("{}", e);
+            // stdout is reserved to the events stream
+            if json_output {
+                eprintln!("{}", e);
+            } else {
+                println!("{}", e);
+            }
             std::process::exit(1);
         }
     };
@@ -76,15 +82,19 @@ pub fn start(
         &mut Some(hooks),
         log_tx,
         display_dashboard,
+        json_output,
         ctx,
         orchestrator_terminated_tx,
         Some(orchestrator_terminated_rx),
     ));
-    println!(
-        "{} logs and chainstate available at location {}",
-        yellow!("\nterminating devnet network:"),
-        working_dir
-    );
+    // stdout is reserved to the events stream
+    if !json_output {
+        println!(
+            "{} logs and chainstate available at location {}",
+            yellow!("\nterminating devnet network:"),
+            working_dir
+        );
+    }
 
     res
 }
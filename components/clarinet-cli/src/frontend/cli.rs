@@ -3,13 +3,19 @@ use crate::deployments::{
     self, check_deployments, generate_default_deployment, get_absolute_deployment_path,
     write_deployment,
 };
+use crate::devnet::fault::send_control_request;
 use crate::devnet::package::{self as Package, ConfigurationPackage};
 use crate::devnet::start::start;
 use crate::generate::{
     self,
     changes::{Changes, TOMLEdition},
+    docs::{get_docs_files, ContractDocs},
 };
 use crate::lsp::run_lsp;
+use crate::test_runner::report::{
+    get_costs_report, get_junit_report, get_tap_report, get_traces_report,
+};
+use crate::test_runner::{discover_test_contracts, run_tests};
 
 use clap::{CommandFactory, Parser, Subcommand};
 use clap_complete::{Generator, Shell};
@@ -24,10 +30,12 @@ use clarinet_deployments::{
 };
 use clarinet_files::chainhook_types::StacksNetwork;
 use clarinet_files::{
-    get_manifest_location, FileLocation, NetworkManifest, ProjectManifest, ProjectManifestFile,
-    RequirementConfig,
+    get_manifest_location, DevnetConfigFile, FileLocation, NetworkManifest, ProjectManifest,
+    ProjectManifestFile, RequirementConfig,
 };
 use clarity_repl::analysis::call_checker::ContractAnalysis;
+use clarity_repl::analysis::coverage::CoverageReporter;
+use clarity_repl::analysis::doc_comments::get_definitions_docs;
 use clarity_repl::clarity::vm::analysis::AnalysisDatabase;
 use clarity_repl::clarity::vm::costs::LimitedCostTracker;
 use clarity_repl::clarity::vm::types::QualifiedContractIdentifier;
@@ -85,6 +93,12 @@ enum Command {
     /// Check contracts syntax
     #[clap(name = "check", bin_name = "check")]
     Check(Check),
+    /// Generate the documentation of the contracts from their doc comments
+    #[clap(name = "docs", bin_name = "docs")]
+    Docs(Docs),
+    /// Run the test functions of the Clarity test contracts (tests/**/*_test.clar)
+    #[clap(name = "test", bin_name = "test")]
+    Test(Test),
     /// Start a local Devnet network for interacting with your contracts from your browser
     #[clap(name = "integrate", bin_name = "integrate")]
     Integrate(DevnetStart),
@@ -108,6 +122,46 @@ enum Devnet {
     /// Start a local Devnet network for interacting with your contracts from your browser
     #[clap(name = "start", bin_name = "start")]
     DevnetStart(DevnetStart),
+
+    /// Inject faults (latency, dropped events, paused services, bitcoin disconnects) in a running Devnet
+    #[clap(name = "fault", bin_name = "fault")]
+    DevnetFault(DevnetFault),
+}
+
+#[derive(Subcommand, PartialEq, Clone, Debug)]
+enum DevnetFaultCommand {
+    /// Display the faults currently injected
+    #[clap(name = "status", bin_name = "status")]
+    Status,
+    /// Delay the deliveries of the stacks-node events (0 to disable)
+    #[clap(name = "latency", bin_name = "latency")]
+    Latency { ms: u64 },
+    /// Drop the deliveries of the stacks-node events
+    #[clap(name = "drop-events", bin_name = "drop-events")]
+    DropEvents {
+        /// Deliver the events again
+        #[clap(long = "off")]
+        off: bool,
+    },
+    /// Refuse the bitcoin rpc calls of the stacks nodes
+    #[clap(name = "disconnect-bitcoin", bin_name = "disconnect-bitcoin")]
+    DisconnectBitcoin {
+        /// Reconnect the bitcoin node
+        #[clap(long = "off")]
+        off: bool,
+    },
+    /// Pause a service (stacks-node, stacks-api, postgres, bitcoin-node, ...)
+    #[clap(name = "pause", bin_name = "pause")]
+    Pause { service: String },
+    /// Resume a paused service
+    #[clap(name = "resume", bin_name = "resume")]
+    Resume { service: String },
+    /// Restart a service
+    #[clap(name = "restart", bin_name = "restart")]
+    Restart { service: String },
+    /// Clear all the faults and resume the paused services
+    #[clap(name = "reset", bin_name = "reset")]
+    Reset,
 }
 
 #[derive(Subcommand, PartialEq, Clone, Debug)]
@@ -388,6 +442,21 @@ struct DevnetStart {
         conflicts_with = "manifest_path"
     )]
     pub package: Option<String>,
+    /// Boot from (or save after the deployment) the named chain state snapshot
+    #[clap(long = "snapshot")]
+    pub snapshot: Option<String>,
+    /// Stream devnet events as newline-delimited JSON on stdout (implies --no-dashboard)
+    #[clap(long = "json")]
+    pub json: bool,
+}
+
+#[derive(Parser, PartialEq, Clone, Debug)]
+struct DevnetFault {
+    /// Path to Clarinet.toml
+    #[clap(long = "manifest-path", short = 'm')]
+    pub manifest_path: Option<String>,
+    #[clap(subcommand)]
+    pub command: DevnetFaultCommand,
 }
 
 #[derive(Parser, PartialEq, Clone, Debug)]
@@ -419,6 +488,41 @@ struct Check {
     pub enable_clarity_wasm: bool,
 }
 
+#[derive(Parser, PartialEq, Clone, Debug)]
+struct Docs {
+    /// Path to Clarinet.toml
+    #[clap(long = "manifest-path", short = 'm')]
+    pub manifest_path: Option<String>,
+    /// Generate HTML pages instead of Markdown files
+    #[clap(long = "html")]
+    pub html: bool,
+    /// Directory where the documentation is written (default: ./docs)
+    #[clap(long = "output-dir", short = 'o')]
+    pub output_dir: Option<String>,
+}
+
+#[derive(Parser, PartialEq, Clone, Debug)]
+struct Test {
+    /// Path to Clarinet.toml
+    #[clap(long = "manifest-path", short = 'm')]
+    pub manifest_path: Option<String>,
+    /// Only run the tests whose name (<contract>::<function>) contains this filter
+    #[clap(long = "filter", short = 'f')]
+    pub filter: Option<String>,
+    /// Write a JUnit XML report of the results in this file
+    #[clap(long = "junit")]
+    pub junit: Option<String>,
+    /// Write an lcov coverage report (default: coverage.lcov)
+    #[clap(long = "coverage", num_args = 0..=1, default_missing_value = "coverage.lcov")]
+    pub coverage: Option<String>,
+    /// Write a JSON report of the costs of the tests (default: costs-reports.json)
+    #[clap(long = "costs", num_args = 0..=1, default_missing_value = "costs-reports.json")]
+    pub costs: Option<String>,
+    /// Write a JSON report of the execution traces of the failed tests (default: traces.json)
+    #[clap(long = "trace", num_args = 0..=1, default_missing_value = "traces.json")]
+    pub trace: Option<String>,
+}
+
 #[derive(Parser, PartialEq, Clone, Debug)]
 struct Completions {
     /// Specify which shell to generation completions script for
@@ -1134,6 +1238,61 @@ pub fn main() {
             }
             std::process::exit(exit_code);
         }
+        Command::Docs(cmd) => {
+            let manifest = load_manifest_or_exit(cmd.manifest_path);
+            let (deployment, _, artifacts) =
+                load_deployment_and_artifacts_or_exit(&manifest, &None, false, false);
+
+            let mut contracts = vec![];
+            for (contract_id, (source, location)) in deployment.contracts.iter() {
+                // requirements are not part of the project documentation
+                if !manifest.contracts_settings.contains_key(location) {
+                    continue;
+                }
+                let ast = match artifacts.asts.get(contract_id) {
+                    Some(ast) => ast,
+                    None => continue,
+                };
+                contracts.push(ContractDocs {
+                    name: contract_id.name.to_string(),
+                    docs: get_definitions_docs(source, &ast.expressions),
+                });
+            }
+
+            let output_dir = match cmd.output_dir {
+                Some(output_dir) => FileLocation::from_path_string(&output_dir),
+                None => manifest
+                    .location
+                    .get_project_root_location()
+                    .and_then(|mut location| location.append_path("docs").map(|_| location)),
+            };
+            let output_dir = match output_dir {
+                Ok(output_dir) => output_dir,
+                Err(message) => {
+                    println!("{} {}", red!("error:"), message);
+                    std::process::exit(1);
+                }
+            };
+
+            let files = get_docs_files(&manifest.project.name, &contracts, cmd.html);
+            for (file_name, content) in files.iter() {
+                let mut location = output_dir.clone();
+                let result = location
+                    .append_path(file_name)
+                    .and_then(|_| location.write_content(content.as_bytes()));
+                if let Err(message) = result {
+                    println!("{} {}", red!("error:"), message);
+                    std::process::exit(1);
+                }
+            }
+            println!(
+                "{} Documentation of {} generated in {}",
+                green!("✔"),
+                pluralize!(contracts.len(), "contract"),
+                output_dir
+            );
+        }
+        Command::Test(cmd) => run_test_command(cmd),
         Command::Integrate(cmd) => {
             println!(
                 "{}",
@@ -1158,6 +1317,7 @@ pub fn main() {
                 }
             }
             Devnet::DevnetStart(cmd) => devnet_start(cmd, global_settings),
+            Devnet::DevnetFault(cmd) => devnet_fault(cmd),
         },
     };
 }
@@ -1731,18 +1891,145 @@ fn display_deploy_hint() {
     display_hint_footer();
 }
 
+fn run_test_command(cmd: Test) {
+    let manifest = load_manifest_or_exit(cmd.manifest_path);
+    let (deployment, _, artifacts) =
+        load_deployment_and_artifacts_or_exit(&manifest, &None, false, false);
+    if !artifacts.success {
+        let diags_digest = DiagnosticsDigest::new(&artifacts.diags, &deployment);
+        println!("{}", diags_digest.message);
+        println!(
+            "{} {} detected",
+            red!("x"),
+            pluralize!(diags_digest.errors, "error")
+        );
+        process::exit(1);
+    }
+
+    let project_root = match manifest.location.get_project_root_location() {
+        Ok(location) => location.to_string(),
+        Err(message) => {
+            println!("{} {}", red!("error:"), message);
+            process::exit(1);
+        }
+    };
+    let contracts = match discover_test_contracts(std::path::Path::new(&project_root)) {
+        Ok(contracts) => contracts,
+        Err(message) => {
+            println!("{} {}", red!("error:"), message);
+            process::exit(1);
+        }
+    };
+    if contracts.is_empty() {
+        println!(
+            "{} no test contracts found (tests/**/*_test.clar)",
+            yellow!("note:")
+        );
+        return;
+    }
+
+    let mut run = run_tests(
+        &artifacts.session,
+        &contracts,
+        cmd.filter.as_deref(),
+        cmd.trace.is_some(),
+    );
+    print!("{}", get_tap_report(&run.results));
+
+    let mut reports: Vec<(String, String)> = vec![];
+    if let Some(path) = cmd.junit {
+        reports.push((path, get_junit_report(&run.results)));
+    }
+    if let Some(path) = cmd.coverage {
+        let mut coverage_reporter = CoverageReporter::new();
+        coverage_reporter.asts = artifacts.session.asts.clone();
+        for (contract_id, (_, location)) in deployment.contracts.iter() {
+            // only the project's contracts are covered, not its requirements
+            if manifest.contracts_settings.contains_key(location) {
+                coverage_reporter
+                    .contract_paths
+                    .insert(contract_id.name.to_string(), location.to_string());
+            }
+        }
+        coverage_reporter.reports.append(&mut run.coverage_reports);
+        reports.push((path, coverage_reporter.build_lcov_content()));
+    }
+    if let Some(path) = cmd.costs {
+        match get_costs_report(&run.costs_reports) {
+            Ok(content) => reports.push((path, content)),
+            Err(message) => println!("{} {}", red!("error:"), message),
+        }
+    }
+    if let Some(path) = cmd.trace {
+        match get_traces_report(&run.traces) {
+            Ok(content) => reports.push((path, content)),
+            Err(message) => println!("{} {}", red!("error:"), message),
+        }
+    }
+    for (path, content) in reports.iter() {
+        if let Err(e) = fs::write(path, content) {
+            println!("{} unable to write {}: {}", red!("error:"), path, e);
+            process::exit(1);
+        }
+    }
+
+    if run.failures() > 0 {
+        process::exit(1);
+    }
+}
+
+fn devnet_fault(cmd: DevnetFault) {
+    let manifest = load_manifest_or_exit(cmd.manifest_path);
+    let toggle = |off: bool| Some(json!({ "enabled": !off }));
+    let (path, body) = match cmd.command {
+        DevnetFaultCommand::Status => ("/v1/faults".to_string(), None),
+        DevnetFaultCommand::Latency { ms } => {
+            ("/v1/faults/latency".to_string(), Some(json!({ "ms": ms })))
+        }
+        DevnetFaultCommand::DropEvents { off } => {
+            ("/v1/faults/drop-events".to_string(), toggle(off))
+        }
+        DevnetFaultCommand::DisconnectBitcoin { off } => {
+            ("/v1/faults/disconnect-bitcoin".to_string(), toggle(off))
+        }
+        DevnetFaultCommand::Pause { service } => {
+            (format!("/v1/services/{}/pause", service), Some(json!({})))
+        }
+        DevnetFaultCommand::Resume { service } => {
+            (format!("/v1/services/{}/resume", service), Some(json!({})))
+        }
+        DevnetFaultCommand::Restart { service } => {
+            (format!("/v1/services/{}/restart", service), Some(json!({})))
+        }
+        DevnetFaultCommand::Reset => ("/v1/faults/reset".to_string(), Some(json!({}))),
+    };
+    match send_control_request(&manifest, &path, body) {
+        Ok(faults) => println!(
+            "{}",
+            serde_json::to_string_pretty(&faults).unwrap_or(faults.to_string())
+        ),
+        Err(e) => {
+            println!("{} {}", red!("error:"), e);
+            process::exit(1);
+        }
+    }
+}
+
 fn devnet_start(cmd: DevnetStart, global_settings: GlobalSettings) {
     let manifest = load_manifest_or_exit(cmd.manifest_path);
-    println!("Computing deployment plan");
+    // with --json, stdout is reserved to the events stream
+    if !cmd.json {
+        println!("Computing deployment plan");
+    }
     let result = match cmd.deployment_plan_path {
         None => {
             let res = if let Some(package) = cmd.package {
                 let package_file = match File::open(package) {
                     Ok(file) => file,
-                    Err(_) => {
-                        println!("{} package file not found", red!("error:"));
-                        std::process::exit(1);
-                    }
+                    Err(_) => exit_devnet_start(
+                        format!("{} package file not found", red!("error:")),
+                        cmd.json,
+                    ),
                 };
                 let deployment: ConfigurationPackage = serde_json::from_reader(package_file)
                     .expect("error while reading deployment specification");
@@ -1757,10 +2044,12 @@ fn devnet_start(cmd: DevnetStart, global_settings: GlobalSettings) {
             };
             match res {
                 Some(Ok(deployment)) => {
-                    println!(
-                        "{} using existing deployments/default.devnet-plan.yaml",
-                        yellow!("note:")
-                    );
+                    if !cmd.json {
+                        println!(
+                            "{} using existing deployments/default.devnet-plan.yaml",
+                            yellow!("note:")
+                        );
+                    }
                     // TODO(lgalabru): Think more about the desired DX.
                     // Compute the latest version, display differences and propose overwrite?
                     Ok(deployment)
@@ -1773,20 +2062,19 @@ fn devnet_start(cmd: DevnetStart, global_settings: GlobalSettings) {
                         match generate_default_deployment(&manifest, &StacksNetwork::Devnet, false)
                         {
                             Ok(deployment) => deployment,
-                            Err(message) => {
-                                println!("{}", red!(message));
-                                std::process::exit(1);
-                            }
+                            Err(message) => exit_devnet_start(red!(message), cmd.json),
                         };
                     let res = write_deployment(&deployment, &default_deployment_path, true);
                     if let Err(message) = res {
                         Err(message)
                     } else {
-                        println!(
-                            "{} {}",
-                            green!("Generated file"),
-                            default_deployment_path.get_relative_location().unwrap()
-                        );
+                        if !cmd.json {
+                            println!(
+                                "{} {}",
+                                green!("Generated file"),
+                                default_deployment_path.get_relative_location().unwrap()
+                            );
+                        }
                         Ok(deployment)
                     }
                 }
@@ -1801,18 +2089,16 @@ fn devnet_start(cmd: DevnetStart, global_settings: GlobalSettings) {
 
     let deployment = match result {
         Ok(deployment) => deployment,
-        Err(e) => {
-            println!("{}", format_err!(e));
-            std::process::exit(1);
-        }
+        Err(e) => exit_devnet_start(format_err!(e), cmd.json),
     };
 
-    let orchestrator = match DevnetOrchestrator::new(manifest, None, None, true) {
+    let devnet_override = cmd.snapshot.map(|snapshot| DevnetConfigFile {
+        snapshot: Some(snapshot),
+        ..Default::default()
+    });
+    let orchestrator = match DevnetOrchestrator::new(manifest, None, devnet_override, true) {
         Ok(orchestrator) => orchestrator,
-        Err(e) => {
-            println!("{}", format_err!(e));
-            process::exit(1);
-        }
+        Err(e) => exit_devnet_start(format_err!(e), cmd.json),
     };
 
     if orchestrator.manifest.project.telemetry {
@@ -1824,13 +2110,11 @@ fn devnet_start(cmd: DevnetStart, global_settings: GlobalSettings) {
             ),
         ));
     }
-    match start(orchestrator, deployment, None, !cmd.no_dashboard) {
-        Err(e) => {
-            println!("{}", format_err!(e));
-            process::exit(1);
-        }
+    let display_dashboard = !cmd.no_dashboard && !cmd.json;
+    match start(orchestrator, deployment, None, display_dashboard, cmd.json) {
+        Err(e) => exit_devnet_start(format_err!(e), cmd.json),
         Ok(_) => {
-            if global_settings.enable_hints.unwrap_or(true) {
+            if global_settings.enable_hints.unwrap_or(true) && !cmd.json {
                 display_deploy_hint();
             }
             process::exit(0);
@@ -1838,6 +2122,16 @@ fn devnet_start(cmd: DevnetStart, global_settings: GlobalSettings) {
     }
 }
 
+// with --json, stdout is reserved to the events stream and the errors go to stderr
+fn exit_devnet_start(message: String, json_output: bool) -> ! {
+    if json_output {
+        eprintln!("{}", message);
+    } else {
+        println!("{}", message);
+    }
+    process::exit(1);
+}
+
 #[cfg(test)]
 mod tests {
     use clap_complete::generate;
@@ -0,0 +1,168 @@
+use clarity_repl::analysis::doc_comments::DefinitionDoc;
+
+const SECTIONS: [(&str, &str); 9] = [
+    ("define-public", "Public functions"),
+    ("define-read-only", "Read-only functions"),
+    ("define-private", "Private functions"),
+    ("define-constant", "Constants"),
+    ("define-data-var", "Data variables"),
+    ("define-map", "Maps"),
+    ("define-fungible-token", "Fungible tokens"),
+    ("define-non-fungible-token", "Non-fungible tokens"),
+    ("define-trait", "Traits"),
+];
+
+pub struct ContractDocs {
+    pub name: String,
+    pub docs: Vec<DefinitionDoc>,
+}
+
+fn get_sections(docs: &[DefinitionDoc]) -> Vec<(&'static str, Vec<&DefinitionDoc>)> {
+    SECTIONS
+        .iter()
+        .filter_map(|(kind, title)| {
+            let definitions = docs
+                .iter()
+                .filter(|doc| doc.kind == *kind)
+                .collect::<Vec<_>>();
+            match definitions.is_empty() {
+                true => None,
+                false => Some((*title, definitions)),
+            }
+        })
+        .collect()
+}
+
+fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+pub fn get_contract_markdown(contract: &ContractDocs) -> String {
+    let mut markdown = vec![format!("# {}", contract.name)];
+    for (title, definitions) in get_sections(&contract.docs) {
+        markdown.push(format!("## {}", title));
+        for doc in definitions {
+            markdown.push(format!("### {}", doc.name));
+            markdown.push(doc.to_markdown());
+        }
+    }
+    markdown.join("\n\n") + "\n"
+}
+
+pub fn get_index_markdown(project_name: &str, contracts: &[ContractDocs]) -> String {
+    let mut markdown = vec![format!("# {}", project_name)];
+    let links = contracts
+        .iter()
+        .map(|contract| format!("- [{}]({}.md)", contract.name, contract.name))
+        .collect::<Vec<_>>();
+    markdown.push(links.join("\n"));
+    markdown.join("\n\n") + "\n"
+}
+
+fn get_definition_html(doc: &DefinitionDoc) -> String {
+    let mut html = vec![
+        format!("<h3 id=\"{}\">{}</h3>", doc.name, escape_html(&doc.name)),
+        format!(
+            "<pre><code>{}</code></pre>",
+            escape_html(&doc.get_signature())
+        ),
+    ];
+    for paragraph in doc.comment.description.split("\n\n") {
+        if !paragraph.is_empty() {
+            html.push(format!("<p>{}</p>", escape_html(paragraph)));
+        }
+    }
+    if !doc.parameters.is_empty() {
+        html.push("<h4>Parameters</h4>".to_string());
+        html.push("<ul>".to_string());
+        for (name, type_signature) in doc.parameters.iter() {
+            let description = match doc.comment.get_param(name) {
+                Some(text) if !text.is_empty() => format!(": {}", escape_html(text)),
+                _ => String::new(),
+            };
+            html.push(format!(
+                "<li><code>{}</code> <code>{}</code>{}</li>",
+                escape_html(name),
+                escape_html(type_signature),
+                description
+            ));
+        }
+        html.push("</ul>".to_string());
+    }
+    if let Some(returns) = &doc.comment.returns {
+        html.push(format!(
+            "<p><strong>Returns</strong> {}</p>",
+            escape_html(returns)
+        ));
+    }
+    html.join("\n")
+}
+
+fn get_html_page(title: &str, body: &str) -> String {
+    format!(
+        "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>{}</title>\n</head>\n<body>\n{}\n</body>\n</html>\n",
+        escape_html(title),
+        body
+    )
+}
+
+pub fn get_contract_html(contract: &ContractDocs) -> String {
+    let mut body = vec![format!("<h1>{}</h1>", escape_html(&contract.name))];
+    for (title, definitions) in get_sections(&contract.docs) {
+        body.push(format!("<h2>{}</h2>", title));
+        for doc in definitions {
+            body.push(get_definition_html(doc));
+        }
+    }
+    get_html_page(&contract.name, &body.join("\n"))
+}
+
+pub fn get_index_html(project_name: &str, contracts: &[ContractDocs]) -> String {
+    let mut body = vec![format!("<h1>{}</h1>", escape_html(project_name))];
+    body.push("<ul>".to_string());
+    for contract in contracts {
+        body.push(format!(
+            "<li><a href=\"{}.html\">{}</a></li>",
+            contract.name,
+            escape_html(&contract.name)
+        ));
+    }
+    body.push("</ul>".to_string());
+    get_html_page(project_name, &body.join("\n"))
+}
+
+// the files of the documentation, as (file name, content)
+pub fn get_docs_files(
+    project_name: &str,
+    contracts: &[ContractDocs],
+    html: bool,
+) -> Vec<(String, String)> {
+    let mut files = vec![];
+    if html {
+        files.push((
+            "index.html".to_string(),
+            get_index_html(project_name, contracts),
+        ));
+        for contract in contracts {
+            files.push((
+                format!("{}.html", contract.name),
+                get_contract_html(contract),
+            ));
+        }
+    } else {
+        files.push((
+            "README.md".to_string(),
+            get_index_markdown(project_name, contracts),
+        ));
+        for contract in contracts {
+            files.push((
+                format!("{}.md", contract.name),
+                get_contract_markdown(contract),
+            ));
+        }
+    }
+    files
+}
@@ -1,5 +1,6 @@
 pub mod changes;
 mod contract;
+pub mod docs;
 mod project;
 
 pub use changes::Changes;
@@ -363,12 +363,19 @@ disable_stacks_api = false
 # disable_subnet_api = false
 # disable_bitcoin_explorer = true
 # working_dir = "tmp/devnet"
+# container_runtime = "docker" # "docker", "podman" or "native" (locally installed bitcoind and stacks-node)
+# snapshot = "after-deployment" # reuse the chain state saved after the deployment (requires bind_containers_volumes = true)
+# scenarios = ["scenarios/smoke.yaml"] # contract-calls, mining and assertions executed after the deployment
+# enable_lite_explorer = true # serve a built-in explorer (blocks, transactions, contracts, balances), enabled when disable_stacks_explorer is set
+# enable_fault_injection = true # route the stacks-node events and bitcoin rpc calls through a proxy injecting faults on command
 # stacks_node_events_observers = ["host.docker.internal:8002"]
 # miner_mnemonic = "{default_stacks_miner_mnemonic}"
 # miner_derivation_path = "{default_derivation_path}"
 # faucet_mnemonic = "{default_stacks_faucet_mnemonic}"
 # faucet_derivation_path = "{default_derivation_path}"
 # orchestrator_port = 20445
+# orchestrator_control_port = 20446
+# fault_proxy_port = 20447
 # bitcoin_node_p2p_port = 18444
 # bitcoin_node_rpc_port = 18443
 # bitcoin_node_username = "devnet"
@@ -380,6 +387,7 @@ disable_stacks_api = false
 # stacks_api_events_port = 3700
 # bitcoin_explorer_port = 8001
 # stacks_explorer_port = 8000
+# lite_explorer_port = 8003
 # postgres_port = 5432
 # postgres_username = "postgres"
 # postgres_password = "postgres"
@@ -412,6 +420,13 @@ disable_stacks_api = false
 # epoch_2_5 = {DEFAULT_EPOCH_2_5}
 # epoch_3_0 = {DEFAULT_EPOCH_3_0}
 
+# Run additional stacks nodes (miners or followers), peering with the main stacks-node
+# [[devnet.stacks_nodes]]
+# name = "stacks-node-1"
+# miner = true
+# rpc_port = 20453
+# p2p_port = 20454
+# derivation_path = "m/44'/5757'/0'/0/1"
 
 # Send some stacking orders
 [[devnet.pox_stacking_orders]]
@@ -434,6 +449,20 @@ duration = 12
 wallet = "wallet_3"
 slots = 1
 btc_address = "mvZtbibDAAA3WLpY7zXXFqRa3T4XSknBX7"
+
+# Stacking orders can also be increased once locked
+# increase_at_cycle = 4
+# increase_slots = 1
+
+# Delegate some STX to a pool operator, aggregating and committing them each cycle
+# [[devnet.pox_delegation_orders]]
+# start_at_cycle = 3
+# duration = 2
+# pool_operator = "wallet_4"
+# delegators = ["wallet_5", "wallet_6"]
+# slots = 1
+# btc_address = "mg1C76bNTutiCDV3t9nWhZs3Dc8LzUufj8"
+# auto_restack = true
 "#,
             default_derivation_path = DEFAULT_DERIVATION_PATH,
             default_bitcoin_node_image = DEFAULT_BITCOIN_NODE_IMAGE,
@@ -575,6 +604,7 @@ import { vitestSetupFilePath, getClarinetVitestsArgv } from "@hirosystems/clarin
   The `getClarinetVitestsArgv()` will parse options passed to the command `vitest run --`
     - vitest run -- --manifest ./Clarinet.toml  # pass a custom path
     - vitest run -- --coverage --costs          # collect coverage and cost reports
+    - vitest run -- --snapshot                  # start the sessions from a deployment snapshot
 */
 
 export default defineConfig({
@@ -15,6 +15,7 @@ pub extern crate clarity_repl;
 
 pub mod deployments;
 pub mod generate;
+pub mod test_runner;
 
 pub mod devnet;
 #[cfg(feature = "cli")]
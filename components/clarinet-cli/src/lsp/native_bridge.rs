@@ -2,11 +2,15 @@ use super::utils;
 use crate::lsp::clarity_diagnostics_to_tower_lsp_type;
 use clarity_lsp::backend::{
     process_mutating_request, process_notification, process_request, EditorStateInput,
-    LspNotification, LspNotificationResponse, LspRequest, LspRequestResponse,
+    FunctionCallArguments, LspNotification, LspNotificationResponse, LspRequest,
+    LspRequestResponse, DEBUG_FUNCTION_COMMAND, RUN_FUNCTION_COMMAND,
 };
 use clarity_lsp::lsp_types::{
-    DocumentSymbolParams, DocumentSymbolResponse, GotoDefinitionParams, GotoDefinitionResponse,
-    SignatureHelp, SignatureHelpParams,
+    CallHierarchyIncomingCall, CallHierarchyIncomingCallsParams, CallHierarchyItem,
+    CallHierarchyOutgoingCall, CallHierarchyOutgoingCallsParams, CallHierarchyPrepareParams,
+    CodeLens, CodeLensParams, DocumentSymbolParams, DocumentSymbolResponse, GotoDefinitionParams,
+    GotoDefinitionResponse, InlayHint, InlayHintParams, SignatureHelp, SignatureHelpParams,
+    SymbolInformation, WorkspaceSymbolParams,
 };
 use clarity_lsp::state::EditorState;
 use crossbeam_channel::{Receiver as MultiplexableReceiver, Select, Sender as MultiplexableSender};
@@ -97,6 +101,30 @@ impl LspNativeBridge {
     }
 }
 
+impl LspNativeBridge {
+    // wait for the response of the last notification and publish its diagnostics
+    async fn publish_notification_diagnostics(&self) {
+        let mut aggregated_diagnostics = vec![];
+        if let Ok(response_rx) = self.response_rx.lock() {
+            if let Ok(LspResponse::Notification(ref mut notification_response)) = response_rx.recv()
+            {
+                aggregated_diagnostics.append(&mut notification_response.aggregated_diagnostics);
+            }
+        }
+        for (location, mut diags) in aggregated_diagnostics.drain(..) {
+            if let Ok(url) = location.to_url_string() {
+                self.client
+                    .publish_diagnostics(
+                        Url::parse(&url).unwrap(),
+                        clarity_diagnostics_to_tower_lsp_type(&mut diags),
+                        None,
+                    )
+                    .await;
+            }
+        }
+    }
+}
+
 #[async_trait]
 impl LanguageServer for LspNativeBridge {
     async fn initialize(&self, params: InitializeParams) -> Result<InitializeResult> {
@@ -119,8 +147,41 @@ impl LanguageServer for LspNativeBridge {
         Ok(())
     }
 
-    async fn execute_command(&self, _: ExecuteCommandParams) -> Result<Option<Value>> {
-        Ok(None)
+    async fn execute_command(&self, params: ExecuteCommandParams) -> Result<Option<Value>> {
+        let function_call = match params.arguments.first().and_then(|argument| {
+            serde_json::from_value::<FunctionCallArguments>(argument.clone()).ok()
+        }) {
+            Some(function_call) => function_call,
+            None => return Ok(None),
+        };
+        // the arguments are prompted by the client before it sends the command
+        if let Err(message) = function_call.check_args() {
+            return Err(Error::invalid_params(message));
+        }
+
+        match params.command.as_str() {
+            DEBUG_FUNCTION_COMMAND => Ok(Some(function_call.get_debug_configuration())),
+            RUN_FUNCTION_COMMAND => {
+                let _ = match self.notification_tx.lock() {
+                    Ok(tx) => tx.send(LspNotification::RunFunction(function_call)),
+                    Err(_) => return Ok(None),
+                };
+
+                let mut notification = None;
+                if let Ok(response_rx) = self.response_rx.lock() {
+                    if let Ok(LspResponse::Notification(ref mut notification_response)) =
+                        response_rx.recv()
+                    {
+                        notification = notification_response.notification.take();
+                    }
+                }
+                if let Some((level, message)) = notification {
+                    self.client.show_message(level, message).await;
+                }
+                Ok(None)
+            }
+            _ => Ok(None),
+        }
     }
 
     async fn completion(&self, params: CompletionParams) -> Result<Option<CompletionResponse>> {
@@ -174,6 +235,108 @@ impl LanguageServer for LspNativeBridge {
         Ok(None)
     }
 
+    async fn symbol(
+        &self,
+        params: WorkspaceSymbolParams,
+    ) -> Result<Option<Vec<SymbolInformation>>> {
+        let _ = match self.request_tx.lock() {
+            Ok(tx) => tx.send(LspRequest::WorkspaceSymbol(params)),
+            Err(_) => return Ok(None),
+        };
+
+        let response_rx = self.response_rx.lock().expect("failed to lock response_rx");
+        let response = &response_rx.recv().expect("failed to get value from recv");
+        if let LspResponse::Request(LspRequestResponse::WorkspaceSymbol(symbols)) = response {
+            return Ok(Some(symbols.to_vec()));
+        }
+
+        Ok(None)
+    }
+
+    async fn prepare_call_hierarchy(
+        &self,
+        params: CallHierarchyPrepareParams,
+    ) -> Result<Option<Vec<CallHierarchyItem>>> {
+        let _ = match self.request_tx.lock() {
+            Ok(tx) => tx.send(LspRequest::PrepareCallHierarchy(params)),
+            Err(_) => return Ok(None),
+        };
+
+        let response_rx = self.response_rx.lock().expect("failed to lock response_rx");
+        let response = &response_rx.recv().expect("failed to get value from recv");
+        if let LspResponse::Request(LspRequestResponse::PrepareCallHierarchy(items)) = response {
+            return Ok(items.to_owned());
+        }
+
+        Ok(None)
+    }
+
+    async fn incoming_calls(
+        &self,
+        params: CallHierarchyIncomingCallsParams,
+    ) -> Result<Option<Vec<CallHierarchyIncomingCall>>> {
+        let _ = match self.request_tx.lock() {
+            Ok(tx) => tx.send(LspRequest::IncomingCalls(params)),
+            Err(_) => return Ok(None),
+        };
+
+        let response_rx = self.response_rx.lock().expect("failed to lock response_rx");
+        let response = &response_rx.recv().expect("failed to get value from recv");
+        if let LspResponse::Request(LspRequestResponse::IncomingCalls(calls)) = response {
+            return Ok(Some(calls.to_vec()));
+        }
+
+        Ok(None)
+    }
+
+    async fn outgoing_calls(
+        &self,
+        params: CallHierarchyOutgoingCallsParams,
+    ) -> Result<Option<Vec<CallHierarchyOutgoingCall>>> {
+        let _ = match self.request_tx.lock() {
+            Ok(tx) => tx.send(LspRequest::OutgoingCalls(params)),
+            Err(_) => return Ok(None),
+        };
+
+        let response_rx = self.response_rx.lock().expect("failed to lock response_rx");
+        let response = &response_rx.recv().expect("failed to get value from recv");
+        if let LspResponse::Request(LspRequestResponse::OutgoingCalls(calls)) = response {
+            return Ok(Some(calls.to_vec()));
+        }
+
+        Ok(None)
+    }
+
+    async fn code_lens(&self, params: CodeLensParams) -> Result<Option<Vec<CodeLens>>> {
+        let _ = match self.request_tx.lock() {
+            Ok(tx) => tx.send(LspRequest::CodeLens(params)),
+            Err(_) => return Ok(None),
+        };
+
+        let response_rx = self.response_rx.lock().expect("failed to lock response_rx");
+        let response = &response_rx.recv().expect("failed to get value from recv");
+        if let LspResponse::Request(LspRequestResponse::CodeLens(code_lenses)) = response {
+            return Ok(Some(code_lenses.to_vec()));
+        }
+
+        Ok(None)
+    }
+
+    async fn inlay_hint(&self, params: InlayHintParams) -> Result<Option<Vec<InlayHint>>> {
+        let _ = match self.request_tx.lock() {
+            Ok(tx) => tx.send(LspRequest::InlayHint(params)),
+            Err(_) => return Ok(None),
+        };
+
+        let response_rx = self.response_rx.lock().expect("failed to lock response_rx");
+        let response = &response_rx.recv().expect("failed to get value from recv");
+        if let LspResponse::Request(LspRequestResponse::InlayHint(inlay_hints)) = response {
+            return Ok(Some(inlay_hints.to_vec()));
+        }
+
+        Ok(None)
+    }
+
     async fn hover(&self, params: HoverParams) -> Result<Option<Hover>> {
         let _ = match self.request_tx.lock() {
             Ok(tx) => tx.send(LspRequest::Hover(params)),
@@ -217,6 +380,13 @@ impl LanguageServer for LspNativeBridge {
                 Ok(tx) => tx.send(LspNotification::ManifestOpened(manifest_location)),
                 Err(_) => return,
             };
+        } else if let Some(deployment_location) =
+            utils::get_deployment_location(&params.text_document.uri)
+        {
+            let _ = match self.notification_tx.lock() {
+                Ok(tx) => tx.send(LspNotification::DeploymentOpened(deployment_location)),
+                Err(_) => return,
+            };
         } else {
             self.client
                 .log_message(MessageType::WARNING, "Unsupported file opened")
@@ -306,6 +476,17 @@ impl LanguageServer for LspNativeBridge {
                     params.content_changes[0].text.to_string(),
                 ));
             };
+        } else if let Some(deployment_location) =
+            utils::get_deployment_location(&params.text_document.uri)
+        {
+            let _ = match self.notification_tx.lock() {
+                Ok(tx) => tx.send(LspNotification::DeploymentChanged(
+                    deployment_location,
+                    params.content_changes[0].text.to_string(),
+                )),
+                Err(_) => return,
+            };
+            self.publish_notification_diagnostics().await;
         }
     }
 
@@ -314,6 +495,14 @@ impl LanguageServer for LspNativeBridge {
             if let Ok(tx) = self.notification_tx.lock() {
                 let _ = tx.send(LspNotification::ContractClosed(contract_location));
             };
+        } else if let Some(deployment_location) =
+            utils::get_deployment_location(&params.text_document.uri)
+        {
+            let _ = match self.notification_tx.lock() {
+                Ok(tx) => tx.send(LspNotification::DeploymentClosed(deployment_location)),
+                Err(_) => return,
+            };
+            self.publish_notification_diagnostics().await;
         }
     }
 }
@@ -0,0 +1,397 @@
+pub mod report;
+
+use clarity_repl::analysis::ast_dependency_detector::ASTDependencyDetector;
+use clarity_repl::analysis::coverage::TestCoverageReport;
+use clarity_repl::clarity::vm::diagnostic::Diagnostic;
+use clarity_repl::clarity::vm::types::FunctionType;
+use clarity_repl::clarity::vm::EvaluationResult;
+use clarity_repl::clarity::{ClarityVersion, Value};
+use clarity_repl::repl::diagnostic::output_diagnostic;
+use clarity_repl::repl::session::CostsReport;
+use clarity_repl::repl::tracer::CallFrame;
+use clarity_repl::repl::{ClarityCodeSource, ClarityContract, ContractDeployer, Session};
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+const TESTS_DIR: &str = "tests";
+const TEST_CONTRACT_SUFFIX: &str = "_test.clar";
+const TEST_FUNCTION_PREFIX: &str = "test-";
+
+pub struct TestContract {
+    pub name: String,
+    pub path: PathBuf,
+    pub source: String,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum TestOutcome {
+    Passed,
+    Failed(String),
+    Skipped(String),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct TestResult {
+    pub contract: String,
+    pub name: String,
+    pub outcome: TestOutcome,
+}
+
+impl TestResult {
+    pub fn full_name(&self) -> String {
+        format!("{}::{}", self.contract, self.name)
+    }
+}
+
+#[derive(Default)]
+pub struct TestRun {
+    pub results: Vec<TestResult>,
+    pub coverage_reports: Vec<TestCoverageReport>,
+    pub costs_reports: Vec<CostsReport>,
+    // call traces of the failed tests, by test name
+    pub traces: Vec<(String, CallFrame)>,
+}
+
+impl TestRun {
+    pub fn failures(&self) -> usize {
+        self.results
+            .iter()
+            .filter(|r| matches!(r.outcome, TestOutcome::Failed(_)))
+            .count()
+    }
+}
+
+// Test contracts are the `tests/**/*_test.clar` files of the project. They are deployed with
+// their file name, which must be unique
+pub fn discover_test_contracts(project_root: &Path) -> Result<Vec<TestContract>, String> {
+    let mut paths = vec![];
+    collect_test_contracts_paths(&project_root.join(TESTS_DIR), &mut paths)?;
+    paths.sort();
+
+    let mut contracts: Vec<TestContract> = vec![];
+    for path in paths.into_iter() {
+        let source = fs::read_to_string(&path)
+            .map_err(|e| format!("unable to read {}: {}", path.display(), e))?;
+        let name = path
+            .file_name()
+            .and_then(|name| name.to_str())
+            .map(|name| name.trim_end_matches(".clar").to_string())
+            .ok_or_else(|| format!("invalid test contract path {}", path.display()))?;
+        if let Some(contract) = contracts.iter().find(|contract| contract.name == name) {
+            return Err(format!(
+                "test contracts {} and {} have the same name {}, test contract names must be unique",
+                contract.path.display(),
+                path.display(),
+                name
+            ));
+        }
+        contracts.push(TestContract { name, path, source });
+    }
+    Ok(contracts)
+}
+
+fn collect_test_contracts_paths(dir: &Path, paths: &mut Vec<PathBuf>) -> Result<(), String> {
+    if !dir.is_dir() {
+        return Ok(());
+    }
+    let entries =
+        fs::read_dir(dir).map_err(|e| format!("unable to read {}: {}", dir.display(), e))?;
+    for entry in entries {
+        let path = entry.map_err(|e| e.to_string())?.path();
+        if path.is_dir() {
+            collect_test_contracts_paths(&path, paths)?;
+        } else if path.to_string_lossy().ends_with(TEST_CONTRACT_SUFFIX) {
+            paths.push(path);
+        }
+    }
+    Ok(())
+}
+
+// Deploys the test contracts on top of the project's session, then runs each test function
+// in its own copy of this session, so that tests can't affect each other
+pub fn run_tests(
+    session: &Session,
+    contracts: &[TestContract],
+    filter: Option<&str>,
+    trace_failures: bool,
+) -> TestRun {
+    let mut run = TestRun::default();
+    let mut base_session = session.clone();
+    base_session.coverage_reports.clear();
+    base_session.costs_reports.clear();
+    base_session.set_call_tracing(trace_failures);
+    let deployer = base_session.get_tx_sender();
+
+    let ordered_contracts = match order_test_contracts(&base_session, contracts) {
+        Ok(ordered_contracts) => ordered_contracts,
+        Err(message) => {
+            run.results.push(TestResult {
+                contract: TESTS_DIR.into(),
+                name: "deployment".into(),
+                outcome: TestOutcome::Failed(message),
+            });
+            return run;
+        }
+    };
+
+    let mut test_functions = vec![];
+    for (contract, clarity_contract) in ordered_contracts.into_iter() {
+        let analysis =
+            match base_session.deploy_contract(&clarity_contract, None, false, None, &mut None) {
+                Ok(execution) => match execution.result {
+                    EvaluationResult::Contract(result) => result.contract.analysis,
+                    EvaluationResult::Snippet(_) => continue,
+                },
+                Err(diagnostics) => {
+                    let path = contract.path.display().to_string();
+                    run.results.push(TestResult {
+                        contract: contract.name.clone(),
+                        name: "deployment".into(),
+                        outcome: TestOutcome::Failed(format_diagnostics(
+                            &diagnostics,
+                            &path,
+                            &contract.source,
+                        )),
+                    });
+                    continue;
+                }
+            };
+
+        for (function_name, signature) in analysis.public_function_types.iter() {
+            if !function_name.starts_with(TEST_FUNCTION_PREFIX) {
+                continue;
+            }
+            let full_name = format!("{}::{}", contract.name, function_name);
+            if let Some(filter) = filter {
+                if !full_name.contains(filter) {
+                    continue;
+                }
+            }
+            let takes_arguments = match signature {
+                FunctionType::Fixed(function) => !function.args.is_empty(),
+                _ => true,
+            };
+            test_functions.push((
+                contract.name.clone(),
+                function_name.to_string(),
+                takes_arguments,
+            ));
+        }
+    }
+
+    for (contract_name, function_name, takes_arguments) in test_functions.into_iter() {
+        if takes_arguments {
+            run.results.push(TestResult {
+                contract: contract_name,
+                name: function_name,
+                outcome: TestOutcome::Skipped("test functions can't take arguments".into()),
+            });
+            continue;
+        }
+
+        let mut session = base_session.clone();
+        let contract_id = format!("{}.{}", deployer, contract_name);
+        let test_name = format!("{}::{}", contract_name, function_name);
+        let outcome = match session.invoke_contract_call(
+            &contract_id,
+            &function_name,
+            &[],
+            &deployer,
+            test_name.clone(),
+        ) {
+            Ok((execution, _)) => match execution.result {
+                EvaluationResult::Snippet(result) => match result.result {
+                    Value::Response(response) if response.committed => TestOutcome::Passed,
+                    value => TestOutcome::Failed(format!("returned {}", value)),
+                },
+                EvaluationResult::Contract(_) => {
+                    TestOutcome::Failed("unexpected contract evaluation".into())
+                }
+            },
+            Err(diagnostics) => {
+                let call = format!("(contract-call? '{} {})", contract_id, function_name);
+                TestOutcome::Failed(format_diagnostics(&diagnostics, &test_name, &call))
+            }
+        };
+        if let (TestOutcome::Failed(_), Some(trace)) = (&outcome, session.take_call_trace()) {
+            run.traces
+                .push((format!("{}::{}", contract_name, function_name), trace));
+        }
+        run.coverage_reports.append(&mut session.coverage_reports);
+        run.costs_reports.append(&mut session.costs_reports);
+        run.results.push(TestResult {
+            contract: contract_name,
+            name: function_name,
+            outcome,
+        });
+    }
+    run
+}
+
+// Test contracts can call each other, they are deployed after the contracts they depend on.
+// Their calls to the project's contracts are unresolved here, these are already deployed
+fn order_test_contracts<'a>(
+    session: &Session,
+    contracts: &'a [TestContract],
+) -> Result<Vec<(&'a TestContract, ClarityContract)>, String> {
+    let deployer = session.get_tx_sender();
+    let mut contracts_by_id = HashMap::new();
+    let mut contract_data = BTreeMap::new();
+    let mut contract_epochs = HashMap::new();
+    for contract in contracts.iter() {
+        let clarity_contract = ClarityContract {
+            code_source: ClarityCodeSource::ContractInMemory(contract.source.clone()),
+            deployer: ContractDeployer::Address(deployer.clone()),
+            name: contract.name.clone(),
+            clarity_version: ClarityVersion::default_for_epoch(session.current_epoch),
+            epoch: session.current_epoch,
+        };
+        let contract_id = clarity_contract.expect_resolved_contract_identifier(None);
+        let (ast, _, _) = session.interpreter.build_ast(&clarity_contract);
+        contract_data.insert(contract_id.clone(), (clarity_contract.clarity_version, ast));
+        contract_epochs.insert(contract_id.clone(), clarity_contract.epoch);
+        contracts_by_id.insert(contract_id, (contract, clarity_contract));
+    }
+
+    let dependencies =
+        match ASTDependencyDetector::detect_dependencies(&contract_data, &BTreeMap::new()) {
+            Ok(dependencies) => dependencies,
+            Err((dependencies, _)) => dependencies,
+        };
+    let ordered_contracts_ids =
+        ASTDependencyDetector::order_contracts(&dependencies, &contract_epochs)
+            .map_err(|e| e.err.to_string())?;
+
+    Ok(ordered_contracts_ids
+        .into_iter()
+        .filter_map(|contract_id| contracts_by_id.remove(contract_id))
+        .collect())
+}
+
+// diagnostics are formatted like the ones of `clarinet check`, `source` is the code they refer to
+fn format_diagnostics(diagnostics: &[Diagnostic], name: &str, source: &str) -> String {
+    let lines: Vec<String> = source.lines().map(|line| line.to_string()).collect();
+    diagnostics
+        .iter()
+        .flat_map(|diagnostic| output_diagnostic(diagnostic, name, &lines))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use clarity_repl::repl::SessionSettings;
+
+    fn build_test_contract(name: &str, source: &str) -> TestContract {
+        TestContract {
+            name: name.into(),
+            path: PathBuf::from(format!("tests/{}.clar", name)),
+            source: source.into(),
+        }
+    }
+
+    #[test]
+    fn run_test_functions() {
+        let session = Session::new(SessionSettings::default());
+        let contract = build_test_contract(
+            "counter_test",
+            r#"(define-data-var count uint u0)
+(define-public (test-increment)
+  (begin
+    (var-set count (+ (var-get count) u1))
+    (asserts! (is-eq (var-get count) u1) (err u1))
+    (ok true)))
+;; runs on a fresh session, the increment above is not visible
+(define-public (test-isolation)
+  (begin
+    (var-set count (+ (var-get count) u1))
+    (asserts! (is-eq (var-get count) u1) (err u1))
+    (ok true)))
+(define-public (test-failure) (err u42))
+(define-public (test-with-args (n uint)) (ok n))
+(define-public (helper) (ok true))"#,
+        );
+
+        let run = run_tests(&session, &[contract], None, true);
+        let outcomes = run
+            .results
+            .iter()
+            .map(|r| (r.full_name(), r.outcome.clone()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            outcomes,
+            vec![
+                (
+                    "counter_test::test-failure".to_string(),
+                    TestOutcome::Failed("returned (err u42)".into())
+                ),
+                (
+                    "counter_test::test-increment".to_string(),
+                    TestOutcome::Passed
+                ),
+                (
+                    "counter_test::test-isolation".to_string(),
+                    TestOutcome::Passed
+                ),
+                (
+                    "counter_test::test-with-args".to_string(),
+                    TestOutcome::Skipped("test functions can't take arguments".into())
+                ),
+            ]
+        );
+        assert_eq!(run.failures(), 1);
+        assert_eq!(run.coverage_reports.len(), 3);
+        assert_eq!(run.traces.len(), 1);
+        assert_eq!(run.traces[0].0, "counter_test::test-failure");
+        assert_eq!(
+            run.traces[0].1.result,
+            Some(Value::error(Value::UInt(42)).unwrap())
+        );
+
+        let contract = build_test_contract("counter_test", "(define-public (test-ok) (ok true))");
+        let run = run_tests(&session, &[contract], Some("test-none"), false);
+        assert!(run.results.is_empty());
+    }
+
+    #[test]
+    fn report_deployment_errors() {
+        let session = Session::new(SessionSettings::default());
+        let contract = build_test_contract("broken_test", "(define-public (test-ok) (ok true)");
+        let run = run_tests(&session, &[contract], None, false);
+        assert_eq!(run.results.len(), 1);
+        assert_eq!(run.results[0].name, "deployment");
+        assert_eq!(run.failures(), 1);
+    }
+
+    #[test]
+    fn deploy_test_contracts_after_their_dependencies() {
+        let session = Session::new(SessionSettings::default());
+        let consumer = build_test_contract(
+            "a_test",
+            "(define-public (test-helper) (contract-call? .z_helper_test get-answer))",
+        );
+        let helper = build_test_contract("z_helper_test", "(define-public (get-answer) (ok u42))");
+        let run = run_tests(&session, &[consumer, helper], None, false);
+        assert_eq!(run.results.len(), 1);
+        assert_eq!(run.results[0].full_name(), "a_test::test-helper");
+        assert_eq!(run.results[0].outcome, TestOutcome::Passed);
+    }
+
+    #[test]
+    fn reject_test_contracts_with_the_same_name() {
+        let project = secure_tempfile::tempdir().unwrap();
+        for dir in ["a", "b"] {
+            let dir = project.path().join(TESTS_DIR).join(dir);
+            fs::create_dir_all(&dir).unwrap();
+            fs::write(
+                dir.join("foo_test.clar"),
+                "(define-public (test-ok) (ok true))",
+            )
+            .unwrap();
+        }
+        let error = discover_test_contracts(project.path()).err().unwrap();
+        assert!(error.contains("have the same name foo_test"));
+    }
+}
@@ -0,0 +1,214 @@
+use super::{TestOutcome, TestResult};
+use clarity_repl::clarity::costs::ExecutionCost;
+use clarity_repl::repl::session::CostsReport;
+use clarity_repl::repl::tracer::CallFrame;
+
+// TAP version 13, failures messages are reported in YAML diagnostic blocks
+pub fn get_tap_report(results: &[TestResult]) -> String {
+    let mut lines = vec![
+        "TAP version 13".to_string(),
+        format!("1..{}", results.len()),
+    ];
+    for (i, result) in results.iter().enumerate() {
+        match &result.outcome {
+            TestOutcome::Passed => lines.push(format!("ok {} - {}", i + 1, result.full_name())),
+            TestOutcome::Skipped(reason) => lines.push(format!(
+                "ok {} - {} # SKIP {}",
+                i + 1,
+                result.full_name(),
+                reason
+            )),
+            TestOutcome::Failed(message) => {
+                lines.push(format!("not ok {} - {}", i + 1, result.full_name()));
+                lines.push("  ---".into());
+                lines.push("  message: |".into());
+                for line in message.lines() {
+                    lines.push(format!("    {}", line));
+                }
+                lines.push("  ...".into());
+            }
+        }
+    }
+    lines.join("\n") + "\n"
+}
+
+fn escape_xml(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+// One test suite per test contract
+pub fn get_junit_report(results: &[TestResult]) -> String {
+    let mut suites: Vec<(&str, Vec<&TestResult>)> = vec![];
+    for result in results.iter() {
+        match suites.iter_mut().find(|(name, _)| *name == result.contract) {
+            Some((_, suite)) => suite.push(result),
+            None => suites.push((&result.contract, vec![result])),
+        }
+    }
+
+    let count_failures = |results: &[&TestResult]| {
+        results
+            .iter()
+            .filter(|r| matches!(r.outcome, TestOutcome::Failed(_)))
+            .count()
+    };
+    let all_results = results.iter().collect::<Vec<_>>();
+    let mut xml = vec![
+        r#"<?xml version="1.0" encoding="UTF-8"?>"#.to_string(),
+        format!(
+            r#"<testsuites name="clarinet" tests="{}" failures="{}">"#,
+            results.len(),
+            count_failures(&all_results)
+        ),
+    ];
+    for (name, suite) in suites.iter() {
+        xml.push(format!(
+            r#"  <testsuite name="{}" tests="{}" failures="{}">"#,
+            escape_xml(name),
+            suite.len(),
+            count_failures(suite)
+        ));
+        for result in suite.iter() {
+            let testcase = format!(
+                r#"<testcase classname="{}" name="{}""#,
+                escape_xml(&result.contract),
+                escape_xml(&result.name)
+            );
+            match &result.outcome {
+                TestOutcome::Passed => xml.push(format!("    {} />", testcase)),
+                TestOutcome::Skipped(reason) => {
+                    xml.push(format!("    {}>", testcase));
+                    xml.push(format!(
+                        r#"      <skipped message="{}" />"#,
+                        escape_xml(reason)
+                    ));
+                    xml.push("    </testcase>".into());
+                }
+                TestOutcome::Failed(message) => {
+                    xml.push(format!("    {}>", testcase));
+                    xml.push(format!(
+                        r#"      <failure message="{}" />"#,
+                        escape_xml(message)
+                    ));
+                    xml.push("    </testcase>".into());
+                }
+            }
+        }
+        xml.push("  </testsuite>".into());
+    }
+    xml.push("</testsuites>".into());
+    xml.join("\n") + "\n"
+}
+
+// CostSynthesis is not serializable, the reports are written with the same layout as the sdk's
+#[derive(Serialize)]
+struct SerializableCostSynthesis<'a> {
+    total: &'a ExecutionCost,
+    limit: &'a ExecutionCost,
+    memory: u64,
+    memory_limit: u64,
+}
+
+#[derive(Serialize)]
+struct SerializableCostsReport<'a> {
+    test_name: &'a str,
+    contract_id: &'a str,
+    method: &'a str,
+    args: &'a [String],
+    cost_result: SerializableCostSynthesis<'a>,
+}
+
+pub fn get_costs_report(costs_reports: &[CostsReport]) -> Result<String, String> {
+    let reports = costs_reports
+        .iter()
+        .map(|report| SerializableCostsReport {
+            test_name: &report.test_name,
+            contract_id: &report.contract_id,
+            method: &report.method,
+            args: &report.args,
+            cost_result: SerializableCostSynthesis {
+                total: &report.cost_result.total,
+                limit: &report.cost_result.limit,
+                memory: report.cost_result.memory,
+                memory_limit: report.cost_result.memory_limit,
+            },
+        })
+        .collect::<Vec<_>>();
+    serde_json::to_string_pretty(&reports).map_err(|e| e.to_string())
+}
+
+// JSON object of the call traces, keyed by test name
+pub fn get_traces_report(traces: &[(String, CallFrame)]) -> Result<String, String> {
+    let traces = traces
+        .iter()
+        .map(|(test_name, trace)| (test_name.clone(), trace.to_json()))
+        .collect::<serde_json::Map<_, _>>();
+    serde_json::to_string_pretty(&traces).map_err(|e| e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn build_results() -> Vec<TestResult> {
+        vec![
+            TestResult {
+                contract: "counter_test".into(),
+                name: "test-increment".into(),
+                outcome: TestOutcome::Passed,
+            },
+            TestResult {
+                contract: "counter_test".into(),
+                name: "test-decrement".into(),
+                outcome: TestOutcome::Failed("returned (err u1)".into()),
+            },
+            TestResult {
+                contract: "token_test".into(),
+                name: "test-transfer".into(),
+                outcome: TestOutcome::Skipped("test functions can't take arguments".into()),
+            },
+        ]
+    }
+
+    #[test]
+    fn format_tap_report() {
+        assert_eq!(
+            get_tap_report(&build_results()),
+            r#"TAP version 13
+1..3
+ok 1 - counter_test::test-increment
+not ok 2 - counter_test::test-decrement
+  ---
+  message: |
+    returned (err u1)
+  ...
+ok 3 - token_test::test-transfer # SKIP test functions can't take arguments
+"#
+        );
+    }
+
+    #[test]
+    fn format_junit_report() {
+        assert_eq!(
+            get_junit_report(&build_results()),
+            r#"<?xml version="1.0" encoding="UTF-8"?>
+<testsuites name="clarinet" tests="3" failures="1">
+  <testsuite name="counter_test" tests="2" failures="1">
+    <testcase classname="counter_test" name="test-increment" />
+    <testcase classname="counter_test" name="test-decrement">
+      <failure message="returned (err u1)" />
+    </testcase>
+  </testsuite>
+  <testsuite name="token_test" tests="1" failures="0">
+    <testcase classname="token_test" name="test-transfer">
+      <skipped message="test functions can't take arguments" />
+    </testcase>
+  </testsuite>
+</testsuites>
+"#
+        );
+    }
+}
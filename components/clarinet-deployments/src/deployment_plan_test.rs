@@ -5,8 +5,11 @@ use clarity_repl::clarity::{
     vm::types::{QualifiedContractIdentifier, StandardPrincipalData},
     ClarityName, ClarityVersion, ContractName,
 };
+use clarity_repl::repl::post_conditions::TransactionPostConditionMode;
+use clarity_repl::repl::{Session, SessionSettings, DEFAULT_EPOCH};
 
 use crate::types::*;
+use crate::update_session_with_contracts_executions;
 
 fn get_test_txs() -> (TransactionSpecification, TransactionSpecification) {
     let contract_id =
@@ -32,6 +35,8 @@ fn get_test_txs() -> (TransactionSpecification, TransactionSpecification) {
             emulated_sender: tx_sender.clone(),
             method: ClarityName::try_from("test".to_string()).unwrap(),
             parameters: vec![],
+            post_condition_mode: TransactionPostConditionMode::Allow,
+            post_conditions: vec![],
         });
 
     (contract_publish_tx, contract_call_txs)
@@ -115,3 +120,125 @@ fn test_merge_batches() {
 
     assert_eq!(plan, new_plan);
 }
+
+#[test]
+fn test_post_conditions_specifications() {
+    let specs = EmulatedContractCallSpecificationFile {
+        contract_id: "ST1PQHQKV0RJXZFY1DGX8MNSNYVE3VGZJSRTPGZGM.test".to_string(),
+        emulated_sender: "ST1PQHQKV0RJXZFY1DGX8MNSNYVE3VGZJSRTPGZGM".to_string(),
+        method: "test".to_string(),
+        parameters: vec![],
+        post_condition_mode: Some("deny".to_string()),
+        post_conditions: Some(vec![
+            PostConditionSpecificationFile {
+                principal: "origin".to_string(),
+                asset: None,
+                condition: "sent-le".to_string(),
+                amount: Some(100),
+                value: None,
+            },
+            PostConditionSpecificationFile {
+                principal: "ST1PQHQKV0RJXZFY1DGX8MNSNYVE3VGZJSRTPGZGM.test".to_string(),
+                asset: Some("ST1PQHQKV0RJXZFY1DGX8MNSNYVE3VGZJSRTPGZGM.test::nft".to_string()),
+                condition: "sent".to_string(),
+                amount: None,
+                value: Some("u1".to_string()),
+            },
+        ]),
+    };
+
+    let tx = EmulatedContractCallSpecification::from_specifications(&specs).unwrap();
+    assert_eq!(tx.post_condition_mode, TransactionPostConditionMode::Deny);
+    assert_eq!(tx.post_conditions.len(), 2);
+
+    let (post_condition_mode, post_conditions) = PostConditionSpecification::to_specification_files(
+        &tx.post_condition_mode,
+        &tx.post_conditions,
+    );
+    assert_eq!(post_condition_mode, specs.post_condition_mode);
+    assert_eq!(post_conditions, specs.post_conditions);
+
+    // a fungible condition code can't be used for a non-fungible asset
+    let specs = EmulatedContractCallSpecificationFile {
+        post_conditions: Some(vec![PostConditionSpecificationFile {
+            principal: "origin".to_string(),
+            asset: Some("ST1PQHQKV0RJXZFY1DGX8MNSNYVE3VGZJSRTPGZGM.test::nft".to_string()),
+            condition: "sent-eq".to_string(),
+            amount: None,
+            value: Some("u1".to_string()),
+        }]),
+        ..specs
+    };
+    assert!(EmulatedContractCallSpecification::from_specifications(&specs).is_err());
+}
+
+#[test]
+fn test_emulated_call_with_invalid_post_condition_is_reported() {
+    let contract_id =
+        QualifiedContractIdentifier::parse("ST1PQHQKV0RJXZFY1DGX8MNSNYVE3VGZJSRTPGZGM.test")
+            .unwrap();
+    let tx_sender = StandardPrincipalData::from(contract_id.issuer.clone());
+    let contract_publish_tx =
+        TransactionSpecification::EmulatedContractPublish(EmulatedContractPublishSpecification {
+            contract_name: ContractName::try_from("test".to_string()).unwrap(),
+            emulated_sender: tx_sender.clone(),
+            location: FileLocation::from_path_string("/contracts/test.clar").unwrap(),
+            source: "(define-public (test) (ok true))".to_string(),
+            clarity_version: ClarityVersion::Clarity2,
+        });
+
+    let specs = EmulatedContractCallSpecificationFile {
+        contract_id: contract_id.to_string(),
+        emulated_sender: tx_sender.to_address(),
+        method: "test".to_string(),
+        parameters: vec![],
+        post_condition_mode: Some("deny".to_string()),
+        post_conditions: Some(vec![PostConditionSpecificationFile {
+            principal: "origin".to_string(),
+            asset: Some("ST1PQHQKV0RJXZFY1DGX8MNSNYVE3VGZJSRTPGZGM.test::nft".to_string()),
+            condition: "sent".to_string(),
+            amount: None,
+            value: Some("(unknown-function)".to_string()),
+        }]),
+    };
+    let contract_call_tx = TransactionSpecification::EmulatedContractCall(
+        EmulatedContractCallSpecification::from_specifications(&specs).unwrap(),
+    );
+
+    let deployment = build_test_deployement_plan(vec![TransactionsBatchSpecification {
+        id: 0,
+        transactions: vec![contract_publish_tx, contract_call_tx],
+        epoch: Some(EpochSpec::Epoch2_4),
+    }]);
+    let mut session = Session::new(SessionSettings::default());
+    let results =
+        update_session_with_contracts_executions(&mut session, &deployment, None, false, None);
+
+    let diagnostics = results.get(&contract_id).unwrap().as_ref().unwrap_err();
+    assert!(diagnostics
+        .last()
+        .unwrap()
+        .message
+        .starts_with("unable to emulate the call to"));
+}
+
+#[test]
+fn test_post_condition_value_is_not_evaluated() {
+    let to_post_condition = |value: &str| {
+        let specs = vec![PostConditionSpecificationFile {
+            principal: "origin".to_string(),
+            asset: Some("ST1PQHQKV0RJXZFY1DGX8MNSNYVE3VGZJSRTPGZGM.test::nft".to_string()),
+            condition: "sent".to_string(),
+            amount: None,
+            value: Some(value.to_string()),
+        }];
+        let (_, post_conditions) =
+            PostConditionSpecification::from_specifications(&None, &Some(specs)).unwrap();
+        post_conditions[0].to_post_condition(DEFAULT_EPOCH)
+    };
+
+    assert!(
+        to_post_condition("{id: u1, owner: 'ST1PQHQKV0RJXZFY1DGX8MNSNYVE3VGZJSRTPGZGM}").is_ok()
+    );
+    assert!(to_post_condition("(begin (stx-burn? u1 tx-sender) u1)").is_err());
+}
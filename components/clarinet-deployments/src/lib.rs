@@ -17,15 +17,16 @@ pub mod types;
 mod deployment_plan_test;
 
 use self::types::{
-    DeploymentSpecification, EmulatedContractPublishSpecification, GenesisSpecification,
-    TransactionPlanSpecification, TransactionsBatchSpecification, WalletSpecification,
+    DeploymentSpecification, DeploymentSpecificationFile, EmulatedContractPublishSpecification,
+    GenesisSpecification, TransactionPlanSpecification, TransactionSpecificationFile,
+    TransactionsBatchSpecification, WalletSpecification,
 };
 use clarinet_files::chainhook_types::StacksNetwork;
 use clarinet_files::{FileAccessor, FileLocation};
 use clarinet_files::{NetworkManifest, ProjectManifest};
 use clarity_repl::analysis::ast_dependency_detector::{ASTDependencyDetector, DependencySet};
 use clarity_repl::clarity::vm::ast::ContractAST;
-use clarity_repl::clarity::vm::diagnostic::Diagnostic;
+use clarity_repl::clarity::vm::diagnostic::{Diagnostic, Level};
 use clarity_repl::clarity::vm::types::PrincipalData;
 use clarity_repl::clarity::vm::types::QualifiedContractIdentifier;
 use clarity_repl::clarity::vm::ContractName;
@@ -188,13 +189,51 @@ pub fn update_session_with_contracts_executions(
                     session.set_tx_sender(default_tx_sender);
                 }
                 TransactionSpecification::EmulatedContractCall(tx) => {
-                    let _ = session.invoke_contract_call(
-                        &tx.contract_id.to_string(),
-                        &tx.method.to_string(),
-                        &tx.parameters,
-                        &tx.emulated_sender.to_string(),
-                        "deployment".to_string(),
-                    );
+                    let post_conditions = tx
+                        .post_conditions
+                        .iter()
+                        .map(|post_condition| {
+                            post_condition.to_post_condition(session.current_epoch)
+                        })
+                        .collect::<Result<Vec<_>, _>>();
+                    match post_conditions {
+                        Ok(post_conditions) => {
+                            let _ = session.invoke_contract_call_with_post_conditions(
+                                &tx.contract_id.to_string(),
+                                &tx.method.to_string(),
+                                &tx.parameters,
+                                &tx.emulated_sender.to_string(),
+                                "deployment".to_string(),
+                                tx.post_condition_mode,
+                                &post_conditions,
+                            );
+                        }
+                        // the call can't be emulated, the error is reported on the called contract
+                        Err(message) => {
+                            let diagnostic = Diagnostic {
+                                level: Level::Error,
+                                message: format!(
+                                    "unable to emulate the call to {}::{}: {}",
+                                    tx.contract_id, tx.method, message
+                                ),
+                                spans: vec![],
+                                suggestion: None,
+                            };
+                            let diagnostics = match results.remove(&tx.contract_id) {
+                                Some(Ok(execution)) => {
+                                    let mut diagnostics = execution.diagnostics;
+                                    diagnostics.push(diagnostic);
+                                    diagnostics
+                                }
+                                Some(Err(mut diagnostics)) => {
+                                    diagnostics.push(diagnostic);
+                                    diagnostics
+                                }
+                                None => vec![diagnostic],
+                            };
+                            results.insert(tx.contract_id.clone(), Err(diagnostics));
+                        }
+                    }
                 }
             }
         }
@@ -848,3 +887,95 @@ pub fn load_deployment(
     };
     Ok(spec)
 }
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SimnetDeploymentPlanOrigin {
+    Existing,
+    Updated,
+    Generated,
+}
+
+// Loads the simnet deployment plan used by the SDKs: the plan on disk is used as long as its
+// contract publish transactions match the ones generated from the manifest. Otherwise, the
+// generated plan (keeping the custom transactions of the existing one) is written to disk.
+pub async fn load_simnet_deployment_plan(
+    manifest: &ProjectManifest,
+    deployment_plan_location: &FileLocation,
+    file_accessor: Option<&dyn FileAccessor>,
+) -> Result<
+    (
+        DeploymentSpecification,
+        DeploymentGenerationArtifacts,
+        SimnetDeploymentPlanOrigin,
+    ),
+    String,
+> {
+    let (mut default_deployment, default_artifacts) = generate_default_deployment(
+        manifest,
+        &StacksNetwork::Simnet,
+        false,
+        file_accessor,
+        Some(StacksEpochId::Epoch21),
+    )
+    .await?;
+
+    let existing_deployment = match file_accessor {
+        None => match deployment_plan_location.exists() {
+            true => Some(load_deployment(manifest, deployment_plan_location)?),
+            false => None,
+        },
+        Some(file_accessor) => {
+            match file_accessor
+                .file_exists(deployment_plan_location.to_string())
+                .await?
+            {
+                true => {
+                    let spec_file = DeploymentSpecificationFile::from_file_accessor(
+                        deployment_plan_location,
+                        file_accessor,
+                    )
+                    .await?;
+                    let contracts_paths = spec_file.get_emulated_contracts_paths();
+                    let contracts_sources = file_accessor.read_files(contracts_paths).await?;
+                    let project_root_location = manifest.location.get_project_root_location()?;
+                    Some(DeploymentSpecification::from_specifications(
+                        &spec_file,
+                        &StacksNetwork::Simnet,
+                        &project_root_location,
+                        Some(&contracts_sources),
+                    )?)
+                }
+                false => None,
+            }
+        }
+    };
+
+    let origin = match existing_deployment {
+        Some(existing_deployment) => {
+            let (deployment_with_only_contract_publish_txs, custom_batches) =
+                existing_deployment.extract_no_contract_publish_txs();
+            if deployment_with_only_contract_publish_txs == default_deployment {
+                let artifacts = setup_session_with_deployment(manifest, &existing_deployment, None);
+                return Ok((
+                    existing_deployment,
+                    artifacts,
+                    SimnetDeploymentPlanOrigin::Existing,
+                ));
+            }
+            default_deployment.merge_batches(custom_batches);
+            SimnetDeploymentPlanOrigin::Updated
+        }
+        None => SimnetDeploymentPlanOrigin::Generated,
+    };
+
+    let deployment_file = default_deployment.to_file_content()?;
+    match file_accessor {
+        None => deployment_plan_location.write_content(&deployment_file)?,
+        Some(file_accessor) => {
+            file_accessor
+                .write_file(deployment_plan_location.to_string(), &deployment_file)
+                .await?
+        }
+    };
+    Ok((default_deployment, default_artifacts, origin))
+}
@@ -15,8 +15,8 @@ use clarity_repl::clarity::{ClarityVersion, ContractName, EvaluationResult};
 use clarity_repl::codec::{
     SinglesigHashMode, SinglesigSpendingCondition, StacksString, StacksTransactionSigner,
     TokenTransferMemo, TransactionAuth, TransactionContractCall, TransactionPayload,
-    TransactionPostConditionMode, TransactionPublicKeyEncoding, TransactionSmartContract,
-    TransactionSpendingCondition, TransactionVersion,
+    TransactionPostCondition, TransactionPostConditionMode, TransactionPublicKeyEncoding,
+    TransactionSmartContract, TransactionSpendingCondition, TransactionVersion,
 };
 use clarity_repl::codec::{StacksTransaction, TransactionAnchorMode};
 use clarity_repl::repl::session::{
@@ -92,6 +92,8 @@ fn sign_transaction_payload(
     nonce: u64,
     tx_fee: u64,
     anchor_mode: TransactionAnchorMode,
+    post_condition_mode: TransactionPostConditionMode,
+    post_conditions: Vec<TransactionPostCondition>,
     network: &StacksNetwork,
 ) -> Result<StacksTransaction, String> {
     let (_, secret_key, public_key) = get_keypair(account);
@@ -118,8 +120,8 @@ fn sign_transaction_payload(
         },
         auth,
         anchor_mode,
-        post_condition_mode: TransactionPostConditionMode::Allow,
-        post_conditions: vec![],
+        post_condition_mode,
+        post_conditions,
         payload,
     };
 
@@ -142,6 +144,8 @@ pub fn encode_contract_call(
     nonce: u64,
     tx_fee: u64,
     anchor_mode: TransactionAnchorMode,
+    post_condition_mode: TransactionPostConditionMode,
+    post_conditions: Vec<TransactionPostCondition>,
     network: &StacksNetwork,
 ) -> Result<StacksTransaction, String> {
     let payload = TransactionContractCall {
@@ -156,6 +160,8 @@ pub fn encode_contract_call(
         nonce,
         tx_fee,
         anchor_mode,
+        post_condition_mode,
+        post_conditions,
         network,
     )
 }
@@ -171,7 +177,16 @@ pub fn encode_stx_transfer(
     network: &StacksNetwork,
 ) -> Result<StacksTransaction, String> {
     let payload = TransactionPayload::TokenTransfer(recipient, amount, TokenTransferMemo(memo));
-    sign_transaction_payload(account, payload, nonce, tx_fee, anchor_mode, network)
+    sign_transaction_payload(
+        account,
+        payload,
+        nonce,
+        tx_fee,
+        anchor_mode,
+        TransactionPostConditionMode::Allow,
+        vec![],
+        network,
+    )
 }
 
 pub fn encode_contract_publish(
@@ -194,6 +209,8 @@ pub fn encode_contract_publish(
         nonce,
         tx_fee,
         anchor_mode,
+        TransactionPostConditionMode::Allow,
+        vec![],
         network,
     )
 }
@@ -517,6 +534,22 @@ pub fn apply_on_chain_deployment(
                         };
                     }
 
+                    let mut post_conditions = vec![];
+                    for post_condition in tx.post_conditions.iter() {
+                        match post_condition.to_post_condition(session.current_epoch) {
+                            Ok(post_condition) => post_conditions.push(post_condition),
+                            Err(e) => {
+                                let _ = deployment_event_tx.send(DeploymentEvent::Interrupted(
+                                    format!(
+                                        "unable to process contract-call {}::{}: {}",
+                                        tx.contract_id, tx.method, e
+                                    ),
+                                ));
+                                return;
+                            }
+                        }
+                    }
+
                     let anchor_mode = match tx.anchor_block_only {
                         true => TransactionAnchorMode::OnChainOnly,
                         false => TransactionAnchorMode::Any,
@@ -530,6 +563,8 @@ pub fn apply_on_chain_deployment(
                         nonce,
                         tx.cost,
                         anchor_mode,
+                        tx.post_condition_mode,
+                        post_conditions,
                         &network,
                     ) {
                         Ok(res) => res,
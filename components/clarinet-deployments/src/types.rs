@@ -9,8 +9,14 @@ use clarity_repl::clarity::vm::types::{
 };
 
 use clarity_repl::analysis::ast_dependency_detector::DependencySet;
+use clarity_repl::clarity::types::chainstate::StacksAddress;
 use clarity_repl::clarity::{ClarityName, ClarityVersion, ContractName, StacksEpochId, Value};
+use clarity_repl::repl::post_conditions::{
+    AssetInfo, FungibleConditionCode, NonfungibleConditionCode, PostConditionPrincipal,
+    TransactionPostCondition, TransactionPostConditionMode,
+};
 use clarity_repl::repl::{Session, DEFAULT_CLARITY_VERSION};
+use clarity_repl::utils::parse_clarity_value;
 use serde::{Deserialize, Serialize};
 use serde_yaml;
 use std::collections::BTreeMap;
@@ -142,6 +148,23 @@ pub struct ContractCallSpecificationFile {
     pub cost: u64,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub anchor_block_only: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub post_condition_mode: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub post_conditions: Option<Vec<PostConditionSpecificationFile>>,
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct PostConditionSpecificationFile {
+    pub principal: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub asset: Option<String>,
+    pub condition: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub amount: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub value: Option<String>,
 }
 
 #[derive(Debug, PartialEq, Serialize, Deserialize)]
@@ -186,6 +209,10 @@ pub struct EmulatedContractCallSpecificationFile {
     pub emulated_sender: String,
     pub method: String,
     pub parameters: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub post_condition_mode: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub post_conditions: Option<Vec<PostConditionSpecificationFile>>,
 }
 
 #[derive(Debug, PartialEq, Serialize, Deserialize)]
@@ -384,6 +411,8 @@ pub struct ContractCallSpecification {
     pub parameters: Vec<String>,
     pub cost: u64,
     pub anchor_block_only: bool,
+    pub post_condition_mode: TransactionPostConditionMode,
+    pub post_conditions: Vec<PostConditionSpecification>,
 }
 
 impl ContractCallSpecification {
@@ -421,6 +450,12 @@ impl ContractCallSpecification {
             }
         };
 
+        let (post_condition_mode, post_conditions) =
+            PostConditionSpecification::from_specifications(
+                &specs.post_condition_mode,
+                &specs.post_conditions,
+            )?;
+
         Ok(ContractCallSpecification {
             contract_id,
             expected_sender,
@@ -428,10 +463,276 @@ impl ContractCallSpecification {
             parameters: specs.parameters.clone(),
             cost: specs.cost,
             anchor_block_only: specs.anchor_block_only.unwrap_or(true),
+            post_condition_mode,
+            post_conditions,
         })
     }
 }
 
+// Post-conditions attached to a contract call. The value of a non-fungible asset is clarity
+// code, evaluated like the parameters of the call.
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
+pub enum PostConditionSpecification {
+    STX(PostConditionPrincipal, FungibleConditionCode, u64),
+    Fungible(
+        PostConditionPrincipal,
+        AssetInfo,
+        FungibleConditionCode,
+        u64,
+    ),
+    Nonfungible(
+        PostConditionPrincipal,
+        AssetInfo,
+        String,
+        NonfungibleConditionCode,
+    ),
+}
+
+impl PostConditionSpecification {
+    pub fn from_specifications(
+        mode: &Option<String>,
+        specs: &Option<Vec<PostConditionSpecificationFile>>,
+    ) -> Result<
+        (
+            TransactionPostConditionMode,
+            Vec<PostConditionSpecification>,
+        ),
+        String,
+    > {
+        let mode = match mode.as_deref() {
+            None | Some("allow") => TransactionPostConditionMode::Allow,
+            Some("deny") => TransactionPostConditionMode::Deny,
+            Some(mode) => {
+                return Err(format!(
+                    "unable to parse '{}' as a post-condition mode (allow or deny)",
+                    mode
+                ))
+            }
+        };
+        let mut post_conditions = vec![];
+        for spec in specs.iter().flatten() {
+            post_conditions.push(PostConditionSpecification::from_specification(spec)?);
+        }
+        Ok((mode, post_conditions))
+    }
+
+    fn from_specification(
+        spec: &PostConditionSpecificationFile,
+    ) -> Result<PostConditionSpecification, String> {
+        let principal = if spec.principal == "origin" {
+            PostConditionPrincipal::Origin
+        } else {
+            match PrincipalData::parse(&spec.principal) {
+                Ok(PrincipalData::Standard(principal)) => {
+                    PostConditionPrincipal::Standard(StacksAddress::from(principal))
+                }
+                Ok(PrincipalData::Contract(contract_id)) => PostConditionPrincipal::Contract(
+                    StacksAddress::from(contract_id.issuer),
+                    contract_id.name,
+                ),
+                Err(_) => {
+                    return Err(format!(
+                        "unable to parse '{}' as a valid post-condition principal",
+                        spec.principal
+                    ))
+                }
+            }
+        };
+
+        let asset = match &spec.asset {
+            None => None,
+            Some(asset) => {
+                let asset_info = match asset.split_once("::") {
+                    Some((contract_id, asset_name)) => {
+                        match (
+                            QualifiedContractIdentifier::parse(contract_id),
+                            ClarityName::try_from(asset_name.to_string()),
+                        ) {
+                            (Ok(contract_id), Ok(asset_name)) => Some(AssetInfo {
+                                contract_address: StacksAddress::from(contract_id.issuer),
+                                contract_name: contract_id.name,
+                                asset_name,
+                            }),
+                            _ => None,
+                        }
+                    }
+                    None => None,
+                };
+                match asset_info {
+                    Some(asset_info) => Some(asset_info),
+                    None => {
+                        return Err(format!(
+                            "unable to parse '{}' as a valid asset identifier",
+                            asset
+                        ))
+                    }
+                }
+            }
+        };
+
+        let fungible_condition_code = match spec.condition.as_str() {
+            "sent-eq" => Some(FungibleConditionCode::SentEq),
+            "sent-gt" => Some(FungibleConditionCode::SentGt),
+            "sent-ge" => Some(FungibleConditionCode::SentGe),
+            "sent-lt" => Some(FungibleConditionCode::SentLt),
+            "sent-le" => Some(FungibleConditionCode::SentLe),
+            _ => None,
+        };
+        let nonfungible_condition_code = match spec.condition.as_str() {
+            "sent" => Some(NonfungibleConditionCode::Sent),
+            "not-sent" => Some(NonfungibleConditionCode::NotSent),
+            _ => None,
+        };
+
+        match (
+            asset,
+            &spec.value,
+            fungible_condition_code,
+            nonfungible_condition_code,
+            spec.amount,
+        ) {
+            (None, None, Some(condition_code), None, Some(amount)) => Ok(
+                PostConditionSpecification::STX(principal, condition_code, amount),
+            ),
+            (Some(asset_info), None, Some(condition_code), None, Some(amount)) => {
+                Ok(PostConditionSpecification::Fungible(
+                    principal,
+                    asset_info,
+                    condition_code,
+                    amount,
+                ))
+            }
+            (Some(asset_info), Some(value), None, Some(condition_code), None) => {
+                Ok(PostConditionSpecification::Nonfungible(
+                    principal,
+                    asset_info,
+                    value.clone(),
+                    condition_code,
+                ))
+            }
+            _ => Err(format!(
+                "invalid post-condition on '{}': stx and fungible assets expect an amount and a sent-eq, sent-gt, sent-ge, sent-lt or sent-le condition, non-fungible assets expect a value and a sent or not-sent condition",
+                spec.principal
+            )),
+        }
+    }
+
+    pub fn to_specification_files(
+        mode: &TransactionPostConditionMode,
+        post_conditions: &[PostConditionSpecification],
+    ) -> (Option<String>, Option<Vec<PostConditionSpecificationFile>>) {
+        let mode = match mode {
+            TransactionPostConditionMode::Allow => None,
+            TransactionPostConditionMode::Deny => Some("deny".to_string()),
+        };
+        if post_conditions.is_empty() {
+            return (mode, None);
+        }
+        let specs = post_conditions
+            .iter()
+            .map(|post_condition| post_condition.to_specification_file())
+            .collect();
+        (mode, Some(specs))
+    }
+
+    fn to_specification_file(&self) -> PostConditionSpecificationFile {
+        let principal_to_string = |principal: &PostConditionPrincipal| match principal {
+            PostConditionPrincipal::Origin => "origin".to_string(),
+            PostConditionPrincipal::Standard(address) => address.to_string(),
+            PostConditionPrincipal::Contract(address, contract_name) => {
+                format!("{}.{}", address, contract_name)
+            }
+        };
+        let asset_to_string = |asset_info: &AssetInfo| {
+            format!(
+                "{}.{}::{}",
+                asset_info.contract_address, asset_info.contract_name, asset_info.asset_name
+            )
+        };
+        let fungible_condition_to_string = |condition_code: &FungibleConditionCode| {
+            match condition_code {
+                FungibleConditionCode::SentEq => "sent-eq",
+                FungibleConditionCode::SentGt => "sent-gt",
+                FungibleConditionCode::SentGe => "sent-ge",
+                FungibleConditionCode::SentLt => "sent-lt",
+                FungibleConditionCode::SentLe => "sent-le",
+            }
+            .to_string()
+        };
+
+        match self {
+            PostConditionSpecification::STX(principal, condition_code, amount) => {
+                PostConditionSpecificationFile {
+                    principal: principal_to_string(principal),
+                    asset: None,
+                    condition: fungible_condition_to_string(condition_code),
+                    amount: Some(*amount),
+                    value: None,
+                }
+            }
+            PostConditionSpecification::Fungible(principal, asset_info, condition_code, amount) => {
+                PostConditionSpecificationFile {
+                    principal: principal_to_string(principal),
+                    asset: Some(asset_to_string(asset_info)),
+                    condition: fungible_condition_to_string(condition_code),
+                    amount: Some(*amount),
+                    value: None,
+                }
+            }
+            PostConditionSpecification::Nonfungible(
+                principal,
+                asset_info,
+                value,
+                condition_code,
+            ) => PostConditionSpecificationFile {
+                principal: principal_to_string(principal),
+                asset: Some(asset_to_string(asset_info)),
+                condition: match condition_code {
+                    NonfungibleConditionCode::Sent => "sent".to_string(),
+                    NonfungibleConditionCode::NotSent => "not-sent".to_string(),
+                },
+                amount: None,
+                value: Some(value.clone()),
+            },
+        }
+    }
+
+    // the value of a non-fungible post-condition is parsed as a literal, it is not evaluated
+    pub fn to_post_condition(
+        &self,
+        epoch: StacksEpochId,
+    ) -> Result<TransactionPostCondition, String> {
+        match self {
+            PostConditionSpecification::STX(principal, condition_code, amount) => Ok(
+                TransactionPostCondition::STX(principal.clone(), *condition_code, *amount),
+            ),
+            PostConditionSpecification::Fungible(principal, asset_info, condition_code, amount) => {
+                Ok(TransactionPostCondition::Fungible(
+                    principal.clone(),
+                    asset_info.clone(),
+                    *condition_code,
+                    *amount,
+                ))
+            }
+            PostConditionSpecification::Nonfungible(
+                principal,
+                asset_info,
+                value,
+                condition_code,
+            ) => {
+                let value = parse_clarity_value(value, epoch)
+                    .map_err(|e| format!("invalid post-condition value: {}", e))?;
+                Ok(TransactionPostCondition::Nonfungible(
+                    principal.clone(),
+                    asset_info.clone(),
+                    value,
+                    *condition_code,
+                ))
+            }
+        }
+    }
+}
+
 #[derive(Debug, PartialEq, Serialize, Deserialize, Clone)]
 pub struct ContractPublishSpecification {
     pub contract_name: ContractName,
@@ -756,6 +1057,8 @@ pub struct EmulatedContractCallSpecification {
     pub emulated_sender: StandardPrincipalData,
     pub method: ClarityName,
     pub parameters: Vec<String>,
+    pub post_condition_mode: TransactionPostConditionMode,
+    pub post_conditions: Vec<PostConditionSpecification>,
 }
 
 impl EmulatedContractCallSpecification {
@@ -793,11 +1096,19 @@ impl EmulatedContractCallSpecification {
             }
         };
 
+        let (post_condition_mode, post_conditions) =
+            PostConditionSpecification::from_specifications(
+                &specs.post_condition_mode,
+                &specs.post_conditions,
+            )?;
+
         Ok(EmulatedContractCallSpecification {
             contract_id,
             emulated_sender,
             method,
             parameters: specs.parameters.clone(),
+            post_condition_mode,
+            post_conditions,
         })
     }
 }
@@ -1234,6 +1545,24 @@ impl DeploymentSpecificationFile {
         serde_yaml::from_str(&spec_file_content)
             .map_err(|msg| format!("unable to read file {}", msg))
     }
+
+    pub fn get_emulated_contracts_paths(&self) -> Vec<String> {
+        match self.plan {
+            Some(ref plan) => plan
+                .batches
+                .iter()
+                .flat_map(|b| {
+                    b.transactions.iter().filter_map(|t| match t {
+                        TransactionSpecificationFile::EmulatedContractPublish(ref deploy) => {
+                            deploy.path.clone()
+                        }
+                        _ => None,
+                    })
+                })
+                .collect(),
+            None => vec![],
+        }
+    }
 }
 
 #[derive(Debug, PartialEq, Serialize, Deserialize)]
@@ -1337,6 +1666,11 @@ impl TransactionPlanSpecification {
             for tx in batch.transactions.iter() {
                 let tx = match tx {
                     TransactionSpecification::ContractCall(tx) => {
+                        let (post_condition_mode, post_conditions) =
+                            PostConditionSpecification::to_specification_files(
+                                &tx.post_condition_mode,
+                                &tx.post_conditions,
+                            );
                         TransactionSpecificationFile::ContractCall(ContractCallSpecificationFile {
                             contract_id: tx.contract_id.to_string(),
                             expected_sender: tx.expected_sender.to_address(),
@@ -1344,6 +1678,8 @@ impl TransactionPlanSpecification {
                             parameters: tx.parameters.clone(),
                             cost: tx.cost,
                             anchor_block_only: Some(tx.anchor_block_only),
+                            post_condition_mode,
+                            post_conditions,
                         })
                     }
                     TransactionSpecification::ContractPublish(tx) => {
@@ -1364,12 +1700,19 @@ impl TransactionPlanSpecification {
                         )
                     }
                     TransactionSpecification::EmulatedContractCall(tx) => {
+                        let (post_condition_mode, post_conditions) =
+                            PostConditionSpecification::to_specification_files(
+                                &tx.post_condition_mode,
+                                &tx.post_conditions,
+                            );
                         TransactionSpecificationFile::EmulatedContractCall(
                             EmulatedContractCallSpecificationFile {
                                 contract_id: tx.contract_id.to_string(),
                                 emulated_sender: tx.emulated_sender.to_address(),
                                 method: tx.method.to_string(),
                                 parameters: tx.parameters.clone(),
+                                post_condition_mode,
+                                post_conditions,
                             },
                         )
                     }
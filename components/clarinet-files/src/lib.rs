@@ -19,17 +19,19 @@ pub use chainhook_types;
 use chainhook_types::StacksNetwork;
 pub use network_manifest::{
     compute_addresses, AccountConfig, DevnetConfig, DevnetConfigFile, NetworkManifest,
-    NetworkManifestFile, PoxStackingOrder, DEFAULT_BITCOIN_EXPLORER_IMAGE,
-    DEFAULT_BITCOIN_NODE_IMAGE, DEFAULT_DERIVATION_PATH, DEFAULT_DOCKER_PLATFORM,
-    DEFAULT_EPOCH_2_0, DEFAULT_EPOCH_2_05, DEFAULT_EPOCH_2_1, DEFAULT_EPOCH_2_2, DEFAULT_EPOCH_2_3,
-    DEFAULT_EPOCH_2_4, DEFAULT_EPOCH_2_5, DEFAULT_EPOCH_3_0, DEFAULT_FAUCET_MNEMONIC,
-    DEFAULT_FIRST_BURN_HEADER_HEIGHT, DEFAULT_POSTGRES_IMAGE, DEFAULT_STACKS_API_IMAGE,
-    DEFAULT_STACKS_API_IMAGE_NAKA, DEFAULT_STACKS_EXPLORER_IMAGE, DEFAULT_STACKS_MINER_MNEMONIC,
-    DEFAULT_STACKS_NODE_IMAGE, DEFAULT_STACKS_NODE_IMAGE_NAKA, DEFAULT_SUBNET_API_IMAGE,
-    DEFAULT_SUBNET_CONTRACT_ID, DEFAULT_SUBNET_MNEMONIC, DEFAULT_SUBNET_NODE_IMAGE,
+    NetworkManifestFile, PoxDelegationOrder, PoxStackingOrder, StacksNodeConfig,
+    StacksNodeConfigFile, DEFAULT_BITCOIN_EXPLORER_IMAGE, DEFAULT_BITCOIN_NODE_IMAGE,
+    DEFAULT_DERIVATION_PATH, DEFAULT_DOCKER_PLATFORM, DEFAULT_EPOCH_2_0, DEFAULT_EPOCH_2_05,
+    DEFAULT_EPOCH_2_1, DEFAULT_EPOCH_2_2, DEFAULT_EPOCH_2_3, DEFAULT_EPOCH_2_4, DEFAULT_EPOCH_2_5,
+    DEFAULT_EPOCH_3_0, DEFAULT_FAUCET_MNEMONIC, DEFAULT_FIRST_BURN_HEADER_HEIGHT,
+    DEFAULT_POSTGRES_IMAGE, DEFAULT_STACKS_API_IMAGE, DEFAULT_STACKS_API_IMAGE_NAKA,
+    DEFAULT_STACKS_EXPLORER_IMAGE, DEFAULT_STACKS_MINER_MNEMONIC, DEFAULT_STACKS_NODE_IMAGE,
+    DEFAULT_STACKS_NODE_IMAGE_NAKA, DEFAULT_SUBNET_API_IMAGE, DEFAULT_SUBNET_CONTRACT_ID,
+    DEFAULT_SUBNET_MNEMONIC, DEFAULT_SUBNET_NODE_IMAGE,
 };
 pub use project_manifest::{
-    ProjectManifest, ProjectManifestFile, RequirementConfig, INVALID_CLARITY_VERSION,
+    get_epoch_and_clarity_version, ProjectManifest, ProjectManifestFile, RequirementConfig,
+    INVALID_CLARITY_VERSION,
 };
 use serde::ser::{Serialize, SerializeMap, Serializer};
 use std::collections::HashMap;
@@ -38,6 +38,7 @@ pub const DEFAULT_DOCKER_SOCKET: &str = "unix:///var/run/docker.sock";
 pub const DEFAULT_DOCKER_SOCKET: &str = "npipe:////./pipe/docker_engine";
 #[cfg(target_family = "wasm")]
 pub const DEFAULT_DOCKER_SOCKET: &str = "/var/run/docker.sock";
+pub const DEFAULT_PODMAN_SOCKET: &str = "unix:///run/podman/podman.sock";
 pub const DEFAULT_DOCKER_PLATFORM: &str = "linux/amd64";
 
 pub const DEFAULT_EPOCH_2_0: u64 = 100;
@@ -79,6 +80,7 @@ pub struct DevnetConfigFile {
     pub network_id: Option<u16>,
     pub orchestrator_port: Option<u16>,
     pub orchestrator_control_port: Option<u16>,
+    pub fault_proxy_port: Option<u16>,
     pub bitcoin_node_p2p_port: Option<u16>,
     pub bitcoin_node_rpc_port: Option<u16>,
     pub stacks_node_p2p_port: Option<u16>,
@@ -88,12 +90,14 @@ pub struct DevnetConfigFile {
     pub stacks_node_first_attempt_time_ms: Option<u32>,
     pub stacks_node_subsequent_attempt_time_ms: Option<u32>,
     pub stacks_node_env_vars: Option<Vec<String>>,
+    pub stacks_nodes: Option<Vec<StacksNodeConfigFile>>,
     pub stacks_api_env_vars: Option<Vec<String>>,
     pub stacks_explorer_env_vars: Option<Vec<String>>,
     pub subnet_node_env_vars: Option<Vec<String>>,
     pub stacks_api_port: Option<u16>,
     pub stacks_api_events_port: Option<u16>,
     pub bitcoin_explorer_port: Option<u16>,
+    pub lite_explorer_port: Option<u16>,
     pub stacks_explorer_port: Option<u16>,
     pub bitcoin_node_username: Option<String>,
     pub bitcoin_node_password: Option<String>,
@@ -112,6 +116,7 @@ pub struct DevnetConfigFile {
     pub stacks_api_postgres_database: Option<String>,
     pub subnet_api_postgres_database: Option<String>,
     pub pox_stacking_orders: Option<Vec<PoxStackingOrder>>,
+    pub pox_delegation_orders: Option<Vec<PoxDelegationOrder>>,
     pub execute_script: Option<Vec<ExecuteScript>>,
     pub bitcoin_node_image_url: Option<String>,
     pub bitcoin_explorer_image_url: Option<String>,
@@ -122,6 +127,7 @@ pub struct DevnetConfigFile {
     pub disable_bitcoin_explorer: Option<bool>,
     pub disable_stacks_explorer: Option<bool>,
     pub disable_stacks_api: Option<bool>,
+    pub enable_lite_explorer: Option<bool>,
     pub bind_containers_volumes: Option<bool>,
     pub enable_subnet_node: Option<bool>,
     pub subnet_node_image_url: Option<String>,
@@ -138,6 +144,10 @@ pub struct DevnetConfigFile {
     pub subnet_api_env_vars: Option<Vec<String>>,
     pub disable_subnet_api: Option<bool>,
     pub docker_host: Option<String>,
+    pub container_runtime: Option<String>,
+    pub snapshot: Option<String>,
+    pub scenarios: Option<Vec<String>>,
+    pub enable_fault_injection: Option<bool>,
     pub components_host: Option<String>,
     pub epoch_2_0: Option<u64>,
     pub epoch_2_05: Option<u64>,
@@ -161,6 +171,17 @@ pub struct PoxStackingOrderFile {
     pub bitcoin_address: String,
 }
 
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct StacksNodeConfigFile {
+    pub name: Option<String>,
+    pub miner: Option<bool>,
+    pub p2p_port: Option<u16>,
+    pub rpc_port: Option<u16>,
+    pub mnemonic: Option<String>,
+    pub derivation_path: Option<String>,
+    pub env_vars: Option<Vec<String>>,
+}
+
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct ExecuteScript {
     pub script: String,
@@ -234,6 +255,7 @@ pub struct DevnetConfig {
     pub network_id: Option<u16>,
     pub orchestrator_ingestion_port: u16,
     pub orchestrator_control_port: u16,
+    pub fault_proxy_port: u16,
     pub bitcoin_node_p2p_port: u16,
     pub bitcoin_node_rpc_port: u16,
     pub bitcoin_node_username: String,
@@ -245,12 +267,14 @@ pub struct DevnetConfig {
     pub stacks_node_subsequent_attempt_time_ms: u32,
     pub stacks_node_events_observers: Vec<String>,
     pub stacks_node_env_vars: Vec<String>,
+    pub stacks_nodes: Vec<StacksNodeConfig>,
     pub stacks_api_port: u16,
     pub stacks_api_events_port: u16,
     pub stacks_api_env_vars: Vec<String>,
     pub stacks_explorer_port: u16,
     pub stacks_explorer_env_vars: Vec<String>,
     pub bitcoin_explorer_port: u16,
+    pub lite_explorer_port: u16,
     pub bitcoin_controller_block_time: u32,
     pub bitcoin_controller_automining_disabled: bool,
     pub miner_stx_address: String,
@@ -272,6 +296,7 @@ pub struct DevnetConfig {
     pub stacks_api_postgres_database: String,
     pub subnet_api_postgres_database: String,
     pub pox_stacking_orders: Vec<PoxStackingOrder>,
+    pub pox_delegation_orders: Vec<PoxDelegationOrder>,
     pub execute_script: Vec<ExecuteScript>,
     pub bitcoin_node_image_url: String,
     pub stacks_node_image_url: String,
@@ -282,6 +307,7 @@ pub struct DevnetConfig {
     pub disable_bitcoin_explorer: bool,
     pub disable_stacks_explorer: bool,
     pub disable_stacks_api: bool,
+    pub enable_lite_explorer: bool,
     pub bind_containers_volumes: bool,
     pub enable_subnet_node: bool,
     pub subnet_node_image_url: String,
@@ -303,6 +329,10 @@ pub struct DevnetConfig {
     pub subnet_api_env_vars: Vec<String>,
     pub disable_subnet_api: bool,
     pub docker_host: String,
+    pub container_runtime: String,
+    pub snapshot: Option<String>,
+    pub scenarios: Vec<String>,
+    pub enable_fault_injection: bool,
     pub components_host: String,
     pub epoch_2_0: u64,
     pub epoch_2_05: u64,
@@ -317,6 +347,19 @@ pub struct DevnetConfig {
     pub use_nakamoto: bool,
 }
 
+// An additional stacks node, peering with the main stacks-node
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct StacksNodeConfig {
+    pub name: String,
+    pub miner: bool,
+    pub p2p_port: u16,
+    pub rpc_port: u16,
+    pub secret_key_hex: String,
+    pub stx_address: String,
+    pub btc_address: String,
+    pub env_vars: Vec<String>,
+}
+
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct PoxStackingOrder {
     pub start_at_cycle: u32,
@@ -325,6 +368,19 @@ pub struct PoxStackingOrder {
     pub slots: u64,
     pub btc_address: String,
     pub auto_extend: Option<bool>,
+    pub increase_at_cycle: Option<u32>,
+    pub increase_slots: Option<u64>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct PoxDelegationOrder {
+    pub start_at_cycle: u32,
+    pub duration: u32,
+    pub pool_operator: String,
+    pub delegators: Vec<String>,
+    pub slots: u64,
+    pub btc_address: String,
+    pub auto_restack: Option<bool>,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
@@ -495,6 +551,10 @@ impl NetworkManifest {
                     devnet_config.orchestrator_control_port = Some(val);
                 }
 
+                if let Some(val) = devnet_override.fault_proxy_port {
+                    devnet_config.fault_proxy_port = Some(val);
+                }
+
                 if let Some(val) = devnet_override.bitcoin_node_p2p_port {
                     devnet_config.bitcoin_node_p2p_port = Some(val);
                 }
@@ -527,6 +587,10 @@ impl NetworkManifest {
                     devnet_config.bitcoin_explorer_port = Some(val);
                 }
 
+                if let Some(val) = devnet_override.lite_explorer_port {
+                    devnet_config.lite_explorer_port = Some(val);
+                }
+
                 if let Some(val) = devnet_override.stacks_explorer_port {
                     devnet_config.stacks_explorer_port = Some(val);
                 }
@@ -579,6 +643,10 @@ impl NetworkManifest {
                     devnet_config.pox_stacking_orders = Some(val.clone());
                 }
 
+                if let Some(ref val) = devnet_override.pox_delegation_orders {
+                    devnet_config.pox_delegation_orders = Some(val.clone());
+                }
+
                 if let Some(ref val) = devnet_override.execute_script {
                     devnet_config.execute_script = Some(val.clone());
                 }
@@ -619,6 +687,10 @@ impl NetworkManifest {
                     devnet_config.disable_stacks_api = Some(val);
                 }
 
+                if let Some(val) = devnet_override.enable_lite_explorer {
+                    devnet_config.enable_lite_explorer = Some(val);
+                }
+
                 if let Some(val) = devnet_override.bitcoin_controller_automining_disabled {
                     devnet_config.bitcoin_controller_automining_disabled = Some(val);
                 }
@@ -698,6 +770,26 @@ impl NetworkManifest {
                 if let Some(val) = devnet_override.use_docker_gateway_routing {
                     devnet_config.use_docker_gateway_routing = Some(val);
                 }
+
+                if let Some(ref val) = devnet_override.container_runtime {
+                    devnet_config.container_runtime = Some(val.clone());
+                }
+
+                if let Some(ref val) = devnet_override.snapshot {
+                    devnet_config.snapshot = Some(val.clone());
+                }
+
+                if let Some(ref val) = devnet_override.scenarios {
+                    devnet_config.scenarios = Some(val.clone());
+                }
+
+                if let Some(ref val) = devnet_override.stacks_nodes {
+                    devnet_config.stacks_nodes = Some(val.clone());
+                }
+
+                if let Some(val) = devnet_override.enable_fault_injection {
+                    devnet_config.enable_fault_injection = Some(val);
+                }
             };
 
             let now = clarity_repl::clarity::util::get_epoch_time_secs();
@@ -759,6 +851,37 @@ impl NetworkManifest {
                 networks,
             );
 
+            // additional nodes default to the next derivation paths of the miner mnemonic,
+            // and to the ports following the ones of the main stacks-node
+            let stacks_node_p2p_port = devnet_config.stacks_node_p2p_port.unwrap_or(20444);
+            let stacks_node_rpc_port = devnet_config.stacks_node_rpc_port.unwrap_or(20443);
+            let stacks_nodes = devnet_config
+                .stacks_nodes
+                .take()
+                .unwrap_or_default()
+                .into_iter()
+                .enumerate()
+                .map(|(i, node)| {
+                    let index = i as u16 + 1;
+                    let mnemonic = node.mnemonic.unwrap_or(miner_mnemonic.clone());
+                    let derivation_path = node
+                        .derivation_path
+                        .unwrap_or(format!("m/44'/5757'/0'/0/{}", index));
+                    let (stx_address, btc_address, secret_key_hex) =
+                        compute_addresses(&mnemonic, &derivation_path, networks);
+                    StacksNodeConfig {
+                        name: node.name.unwrap_or(format!("stacks-node-{}", index)),
+                        miner: node.miner.unwrap_or(false),
+                        p2p_port: node.p2p_port.unwrap_or(stacks_node_p2p_port + 10 * index),
+                        rpc_port: node.rpc_port.unwrap_or(stacks_node_rpc_port + 10 * index),
+                        secret_key_hex,
+                        stx_address,
+                        btc_address,
+                        env_vars: node.env_vars.unwrap_or_default(),
+                    }
+                })
+                .collect::<Vec<_>>();
+
             let enable_subnet_node = devnet_config.enable_subnet_node.unwrap_or(false);
             let subnet_events_ingestion_port =
                 devnet_config.subnet_events_ingestion_port.unwrap_or(30445);
@@ -803,15 +926,55 @@ impl NetworkManifest {
                     if !wallet_is_in_accounts {
                         return Err(format!("Account data was not provided for the wallet ({}) listed in stacking order {}.", wallet_name, i + 1));
                     };
+                    match (
+                        stacking_order.increase_at_cycle,
+                        stacking_order.increase_slots,
+                    ) {
+                        (None, None) => {}
+                        (Some(increase_at_cycle), Some(_))
+                            if increase_at_cycle >= stacking_order.start_at_cycle => {}
+                        _ => {
+                            return Err(format!("Stacking order {} must specify both increase_at_cycle and increase_slots, with increase_at_cycle not lower than start_at_cycle.", i + 1));
+                        }
+                    }
                 }
 
                 devnet_config.pox_stacking_orders = Some(val.clone());
             }
+
+            // for delegation orders, the pool operator and the delegators must be known accounts
+            if let Some(ref val) = devnet_config.pox_delegation_orders {
+                for (i, delegation_order) in val.iter().enumerate() {
+                    let wallets = std::iter::once(&delegation_order.pool_operator)
+                        .chain(delegation_order.delegators.iter());
+                    for wallet_name in wallets {
+                        let wallet_is_in_accounts = accounts
+                            .iter()
+                            .any(|(account_name, _)| wallet_name == account_name);
+                        if !wallet_is_in_accounts {
+                            return Err(format!("Account data was not provided for the wallet ({}) listed in delegation order {}.", wallet_name, i + 1));
+                        };
+                    }
+                    if delegation_order.delegators.is_empty() || delegation_order.duration == 0 {
+                        return Err(format!("Delegation order {} must have at least one delegator and a non-zero duration.", i + 1));
+                    }
+                }
+            }
+
+            let container_runtime = devnet_config
+                .container_runtime
+                .take()
+                .unwrap_or("docker".into());
+            let default_docker_host = match container_runtime.as_str() {
+                "podman" => DEFAULT_PODMAN_SOCKET,
+                _ => DEFAULT_DOCKER_SOCKET,
+            };
             let config = DevnetConfig {
                 name: devnet_config.name.take().unwrap_or("devnet".into()),
                 network_id: devnet_config.network_id,
                 orchestrator_ingestion_port: devnet_config.orchestrator_port.unwrap_or(20445),
                 orchestrator_control_port: devnet_config.orchestrator_control_port.unwrap_or(20446),
+                fault_proxy_port: devnet_config.fault_proxy_port.unwrap_or(20447),
                 bitcoin_node_p2p_port: devnet_config.bitcoin_node_p2p_port.unwrap_or(18444),
                 bitcoin_node_rpc_port: devnet_config.bitcoin_node_rpc_port.unwrap_or(18443),
                 bitcoin_node_username: devnet_config
@@ -828,8 +991,8 @@ impl NetworkManifest {
                 bitcoin_controller_automining_disabled: devnet_config
                     .bitcoin_controller_automining_disabled
                     .unwrap_or(false),
-                stacks_node_p2p_port: devnet_config.stacks_node_p2p_port.unwrap_or(20444),
-                stacks_node_rpc_port: devnet_config.stacks_node_rpc_port.unwrap_or(20443),
+                stacks_node_p2p_port,
+                stacks_node_rpc_port,
                 stacks_node_events_observers,
                 stacks_node_wait_time_for_microblocks: devnet_config
                     .stacks_node_wait_time_for_microblocks
@@ -844,6 +1007,7 @@ impl NetworkManifest {
                 stacks_api_events_port: devnet_config.stacks_api_events_port.unwrap_or(3700),
                 stacks_explorer_port: devnet_config.stacks_explorer_port.unwrap_or(8000),
                 bitcoin_explorer_port: devnet_config.bitcoin_explorer_port.unwrap_or(8001),
+                lite_explorer_port: devnet_config.lite_explorer_port.unwrap_or(8003),
                 miner_btc_address,
                 miner_stx_address: miner_stx_address.clone(),
                 miner_mnemonic,
@@ -915,9 +1079,14 @@ impl NetworkManifest {
                     .take()
                     .unwrap_or(DEFAULT_BITCOIN_EXPLORER_IMAGE.to_string()),
                 pox_stacking_orders: devnet_config.pox_stacking_orders.take().unwrap_or(vec![]),
+                pox_delegation_orders: devnet_config.pox_delegation_orders.take().unwrap_or(vec![]),
                 disable_bitcoin_explorer: devnet_config.disable_bitcoin_explorer.unwrap_or(false),
                 disable_stacks_api: devnet_config.disable_stacks_api.unwrap_or(false),
                 disable_stacks_explorer: devnet_config.disable_stacks_explorer.unwrap_or(false),
+                // the built-in explorer replaces the stacks explorer when it's disabled
+                enable_lite_explorer: devnet_config
+                    .enable_lite_explorer
+                    .unwrap_or(devnet_config.disable_stacks_explorer.unwrap_or(false)),
                 bind_containers_volumes: devnet_config.bind_containers_volumes.unwrap_or(false),
                 enable_subnet_node,
                 subnet_node_image_url: devnet_config
@@ -949,7 +1118,11 @@ impl NetworkManifest {
                     .unwrap_or(!enable_subnet_node),
                 docker_host: devnet_config
                     .docker_host
-                    .unwrap_or(DEFAULT_DOCKER_SOCKET.into()),
+                    .unwrap_or(default_docker_host.into()),
+                container_runtime,
+                snapshot: devnet_config.snapshot.take(),
+                scenarios: devnet_config.scenarios.take().unwrap_or_default(),
+                enable_fault_injection: devnet_config.enable_fault_injection.unwrap_or(false),
                 components_host: devnet_config.components_host.unwrap_or("127.0.0.1".into()),
                 epoch_2_0: devnet_config.epoch_2_0.unwrap_or(DEFAULT_EPOCH_2_0),
                 epoch_2_05: devnet_config.epoch_2_05.unwrap_or(DEFAULT_EPOCH_2_05),
@@ -960,6 +1133,7 @@ impl NetworkManifest {
                 epoch_2_5: devnet_config.epoch_2_5.unwrap_or(DEFAULT_EPOCH_2_5),
                 epoch_3_0: devnet_config.epoch_3_0.unwrap_or(DEFAULT_EPOCH_3_0),
                 stacks_node_env_vars: devnet_config.stacks_node_env_vars.take().unwrap_or(vec![]),
+                stacks_nodes,
                 stacks_api_env_vars: devnet_config.stacks_api_env_vars.take().unwrap_or(vec![]),
                 stacks_explorer_env_vars: devnet_config
                     .stacks_explorer_env_vars
@@ -397,7 +397,7 @@ impl ProjectManifest {
     }
 }
 
-fn get_epoch_and_clarity_version(
+pub fn get_epoch_and_clarity_version(
     settings_epoch: Option<&Value>,
     settings_clarity_version: Option<&Value>,
 ) -> Result<(StacksEpochId, ClarityVersion), String> {
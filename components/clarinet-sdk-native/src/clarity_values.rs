@@ -0,0 +1,23 @@
+use clarity_repl::clarity::{codec::StacksMessageCodec, util::hash, Value};
+
+// The datastore returns consensus serialized values, as `0x` prefixed hex strings
+pub fn raw_value_to_value(raw_value: &str) -> Result<Value, String> {
+    let bytes = hash::hex_bytes(raw_value.trim_start_matches("0x"))
+        .map_err(|e| format!("failed to decode clarity value {}: {}", raw_value, e))?;
+    Value::consensus_deserialize(&mut &bytes[..])
+        .map_err(|e| format!("failed to parse clarity value {}: {}", raw_value, e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_decodes_raw_values() {
+        let mut bytes = vec![];
+        Value::Int(-3).consensus_serialize(&mut bytes).unwrap();
+        let raw_value = format!("0x{}", hash::to_hex(&bytes));
+        assert_eq!(raw_value_to_value(&raw_value), Ok(Value::Int(-3)));
+        assert!(raw_value_to_value("0xzz").is_err());
+    }
+}
@@ -0,0 +1,69 @@
+use clarity_repl::clarity::vm::events::{
+    FTBurnEventData, FTEventType, FTMintEventData, FTTransferEventData, NFTBurnEventData,
+    NFTEventType, NFTMintEventData, NFTTransferEventData, STXBurnEventData, STXEventType,
+    STXLockEventData, STXMintEventData, STXTransferEventData, StacksTransactionEvent,
+};
+use clarity_repl::clarity::vm::types::QualifiedContractIdentifier;
+use clarity_repl::clarity::Value;
+
+// Typed equivalent of the events serialized by the wasm sdk (`print_event`, `stx_transfer_event`...)
+#[derive(Debug, Clone, PartialEq)]
+pub enum StacksEvent {
+    Print {
+        contract_identifier: QualifiedContractIdentifier,
+        topic: String,
+        value: Value,
+    },
+    StxTransfer(STXTransferEventData),
+    StxMint(STXMintEventData),
+    StxBurn(STXBurnEventData),
+    StxLock(STXLockEventData),
+    NftTransfer(NFTTransferEventData),
+    NftMint(NFTMintEventData),
+    NftBurn(NFTBurnEventData),
+    FtTransfer(FTTransferEventData),
+    FtMint(FTMintEventData),
+    FtBurn(FTBurnEventData),
+}
+
+impl From<&StacksTransactionEvent> for StacksEvent {
+    fn from(event: &StacksTransactionEvent) -> Self {
+        match event {
+            StacksTransactionEvent::SmartContractEvent(data) => StacksEvent::Print {
+                contract_identifier: data.key.0.clone(),
+                topic: data.key.1.clone(),
+                value: data.value.clone(),
+            },
+            StacksTransactionEvent::STXEvent(STXEventType::STXTransferEvent(data)) => {
+                StacksEvent::StxTransfer(data.clone())
+            }
+            StacksTransactionEvent::STXEvent(STXEventType::STXMintEvent(data)) => {
+                StacksEvent::StxMint(data.clone())
+            }
+            StacksTransactionEvent::STXEvent(STXEventType::STXBurnEvent(data)) => {
+                StacksEvent::StxBurn(data.clone())
+            }
+            StacksTransactionEvent::STXEvent(STXEventType::STXLockEvent(data)) => {
+                StacksEvent::StxLock(data.clone())
+            }
+            StacksTransactionEvent::NFTEvent(NFTEventType::NFTTransferEvent(data)) => {
+                StacksEvent::NftTransfer(data.clone())
+            }
+            StacksTransactionEvent::NFTEvent(NFTEventType::NFTMintEvent(data)) => {
+                StacksEvent::NftMint(data.clone())
+            }
+            StacksTransactionEvent::NFTEvent(NFTEventType::NFTBurnEvent(data)) => {
+                StacksEvent::NftBurn(data.clone())
+            }
+            StacksTransactionEvent::FTEvent(FTEventType::FTTransferEvent(data)) => {
+                StacksEvent::FtTransfer(data.clone())
+            }
+            StacksTransactionEvent::FTEvent(FTEventType::FTMintEvent(data)) => {
+                StacksEvent::FtMint(data.clone())
+            }
+            StacksTransactionEvent::FTEvent(FTEventType::FTBurnEvent(data)) => {
+                StacksEvent::FtBurn(data.clone())
+            }
+        }
+    }
+}
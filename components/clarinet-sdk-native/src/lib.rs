@@ -0,0 +1,13 @@
+mod clarity_values;
+pub mod events;
+pub mod simnet;
+
+pub use clarity_repl::repl::datastore::BlockContext;
+pub use clarity_repl::repl::mempool::{MempoolAdmission, MempoolOrdering, MinedTransaction};
+pub use clarity_repl::repl::post_conditions::{
+    TransactionPostCondition, TransactionPostConditionMode,
+};
+pub use clarity_repl::repl::stubs::StubCall;
+pub use clarity_repl::repl::tracer::CallFrame;
+pub use events::StacksEvent;
+pub use simnet::{PostConditions, SessionReport, Simnet, TransactionResult, Tx};
@@ -0,0 +1,967 @@
+use clarinet_deployments::diagnostic_digest::DiagnosticsDigest;
+use clarinet_deployments::{
+    initiate_session_from_deployment, load_simnet_deployment_plan,
+    update_session_with_contracts_executions, update_session_with_genesis_accounts,
+};
+use clarinet_files::{FileLocation, ProjectManifest};
+use clarity_repl::analysis::coverage::CoverageReporter;
+use clarity_repl::clarity::analysis::contract_interface_builder::{
+    ContractInterface, ContractInterfaceFunction, ContractInterfaceFunctionAccess,
+};
+use clarity_repl::clarity::vm::diagnostic::Diagnostic;
+use clarity_repl::clarity::vm::types::QualifiedContractIdentifier;
+use clarity_repl::clarity::{
+    ClarityVersion, EvaluationResult, ExecutionResult, ParsedContract, StacksEpochId, Value,
+};
+use clarity_repl::repl::datastore::BlockContext;
+use clarity_repl::repl::mempool::{
+    MempoolAdmission, MempoolOrdering, MempoolTransaction, MempoolTxPayload, MinedTransaction,
+};
+use clarity_repl::repl::post_conditions::{TransactionPostCondition, TransactionPostConditionMode};
+use clarity_repl::repl::session::CostsReport;
+use clarity_repl::repl::stubs::StubCall;
+use clarity_repl::repl::tracer::CallFrame;
+use clarity_repl::repl::{
+    ClarityCodeSource, ClarityContract, ContractDeployer, Session, DEFAULT_EPOCH,
+};
+use clarity_repl::utils::value_to_clarity_code;
+use std::collections::{BTreeMap, HashMap};
+use std::path::Path;
+
+use crate::clarity_values::raw_value_to_value;
+use crate::events::StacksEvent;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct TransactionResult {
+    pub result: Value,
+    pub events: Vec<StacksEvent>,
+}
+
+impl TransactionResult {
+    fn from_execution_result(execution: &ExecutionResult) -> Self {
+        let result = match &execution.result {
+            EvaluationResult::Snippet(result) => result.result.clone(),
+            EvaluationResult::Contract(contract) => match contract.result {
+                Some(ref result) => result.clone(),
+                None => Value::none(),
+            },
+        };
+        TransactionResult {
+            result,
+            events: execution.events.iter().map(StacksEvent::from).collect(),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub enum Tx {
+    CallPublicFn {
+        contract: String,
+        method: String,
+        args: Vec<Value>,
+        sender: String,
+        post_conditions: Option<PostConditions>,
+        sponsor: Option<String>,
+    },
+    DeployContract {
+        name: String,
+        content: String,
+        clarity_version: Option<ClarityVersion>,
+        sender: String,
+    },
+    TransferStx {
+        amount: u64,
+        recipient: String,
+        sender: String,
+        post_conditions: Option<PostConditions>,
+    },
+}
+
+// Post-conditions checked against the assets sent by a transaction, which is aborted if they
+// aren't met
+#[derive(Debug, Clone, PartialEq)]
+pub struct PostConditions {
+    pub mode: TransactionPostConditionMode,
+    pub post_conditions: Vec<TransactionPostCondition>,
+}
+
+pub struct SessionReport {
+    pub coverage: String,
+    pub costs: Vec<CostsReport>,
+}
+
+pub struct Simnet {
+    pub deployer: String,
+    session: Session,
+    accounts: BTreeMap<String, String>,
+    contracts_locations: HashMap<QualifiedContractIdentifier, FileLocation>,
+    contracts_interfaces: HashMap<QualifiedContractIdentifier, ContractInterface>,
+    parsed_contracts: HashMap<QualifiedContractIdentifier, ParsedContract>,
+    current_test_name: String,
+}
+
+impl Simnet {
+    // Loads the project the same way the js sdk does: the default simnet deployment plan is
+    // generated (or updated) and its transactions are executed on a new session
+    pub fn init_session(manifest_path: &Path) -> Result<Self, String> {
+        let manifest_location = FileLocation::from_path(manifest_path.to_path_buf());
+        let manifest = ProjectManifest::from_location(&manifest_location)?;
+        let mut deployment_plan_location = manifest.location.get_project_root_location()?;
+        deployment_plan_location.append_path("deployments/default.simnet-plan.yaml")?;
+
+        let (deployment, artifacts, _) = hiro_system_kit::nestable_block_on(
+            load_simnet_deployment_plan(&manifest, &deployment_plan_location, None),
+        )?;
+
+        if !artifacts.success {
+            let diags_digest = DiagnosticsDigest::new(&artifacts.diags, &deployment);
+            if diags_digest.errors > 0 {
+                return Err(diags_digest.message);
+            }
+        }
+
+        let mut session = initiate_session_from_deployment(&manifest);
+        update_session_with_genesis_accounts(&mut session, &deployment);
+        let results = update_session_with_contracts_executions(
+            &mut session,
+            &deployment,
+            Some(&artifacts.asts),
+            false,
+            Some(DEFAULT_EPOCH),
+        );
+
+        let mut simnet = Simnet {
+            deployer: String::new(),
+            session,
+            accounts: BTreeMap::new(),
+            contracts_locations: HashMap::new(),
+            contracts_interfaces: HashMap::new(),
+            parsed_contracts: HashMap::new(),
+            current_test_name: String::new(),
+        };
+
+        if let Some(ref spec) = deployment.genesis {
+            for wallet in spec.wallets.iter() {
+                if wallet.name == "deployer" {
+                    simnet.deployer = wallet.address.to_string();
+                }
+                simnet
+                    .accounts
+                    .insert(wallet.name.clone(), wallet.address.to_string());
+            }
+        }
+
+        for (contract_id, (_, location)) in deployment.contracts {
+            simnet.contracts_locations.insert(contract_id, location);
+        }
+
+        for (contract_id, result) in results.into_iter() {
+            match result {
+                Ok(execution_result) => simnet.add_contract(&execution_result),
+                Err(diagnostics) => {
+                    return Err(format!(
+                        "unable to deploy {}: {}",
+                        contract_id,
+                        format_diagnostics(&diagnostics)
+                    ))
+                }
+            }
+        }
+
+        Ok(simnet)
+    }
+
+    fn add_contract(&mut self, execution_result: &ExecutionResult) {
+        if let EvaluationResult::Contract(ref result) = &execution_result.result {
+            let contract_id = result.contract.analysis.contract_identifier.clone();
+            if let Some(contract_interface) = &result.contract.analysis.contract_interface {
+                self.contracts_interfaces
+                    .insert(contract_id.clone(), contract_interface.clone());
+            }
+            self.parsed_contracts
+                .insert(contract_id, result.contract.clone());
+        };
+    }
+
+    fn desugar_contract_id(&self, contract: &str) -> Result<QualifiedContractIdentifier, String> {
+        let contract_id = if contract.starts_with('S') {
+            contract.to_string()
+        } else {
+            format!("{}.{}", self.deployer, contract)
+        };
+        QualifiedContractIdentifier::parse(&contract_id).map_err(|e| e.to_string())
+    }
+
+    pub fn session(&self) -> &Session {
+        &self.session
+    }
+
+    pub fn session_mut(&mut self) -> &mut Session {
+        &mut self.session
+    }
+
+    pub fn block_height(&mut self) -> u32 {
+        self.session.interpreter.get_block_height()
+    }
+
+    pub fn block_time(&mut self) -> u64 {
+        self.session.interpreter.get_block_time()
+    }
+
+    pub fn current_epoch(&self) -> StacksEpochId {
+        self.session.current_epoch
+    }
+
+    pub fn set_epoch(&mut self, epoch: StacksEpochId) {
+        self.session.update_epoch(epoch)
+    }
+
+    // Fee charged to the sender (or the sponsor) of every transaction, none by default
+    pub fn set_tx_fee(&mut self, fee: Option<u64>) {
+        self.session.set_tx_fee(fee)
+    }
+
+    pub fn get_tx_fee(&self) -> Option<u64> {
+        self.session.get_tx_fee()
+    }
+
+    // When enabled, the calls made by each contract call are traced, the trace of the last one
+    // can be taken with `take_call_trace`, even if it failed
+    pub fn set_call_tracing(&mut self, enabled: bool) {
+        self.session.set_call_tracing(enabled)
+    }
+
+    pub fn take_call_trace(&mut self) -> Option<CallFrame> {
+        self.session.take_call_trace()
+    }
+
+    pub fn set_current_test_name(&mut self, test_name: &str) {
+        self.current_test_name = test_name.to_string();
+    }
+
+    pub fn get_accounts(&self) -> &BTreeMap<String, String> {
+        &self.accounts
+    }
+
+    pub fn get_assets_maps(&self) -> BTreeMap<String, BTreeMap<String, u128>> {
+        self.session.get_assets_maps()
+    }
+
+    pub fn get_contracts_interfaces(
+        &self,
+    ) -> &HashMap<QualifiedContractIdentifier, ContractInterface> {
+        &self.contracts_interfaces
+    }
+
+    pub fn get_contract_source(&self, contract: &str) -> Option<String> {
+        let contract_id = self.desugar_contract_id(contract).ok()?;
+        let contract = self.parsed_contracts.get(&contract_id)?;
+        Some(contract.code.clone())
+    }
+
+    pub fn get_data_var(&mut self, contract: &str, var_name: &str) -> Result<Value, String> {
+        let contract_id = self.desugar_contract_id(contract)?;
+        let raw_value = self
+            .session
+            .interpreter
+            .get_data_var(&contract_id, var_name)
+            .ok_or("value not found")?;
+        raw_value_to_value(&raw_value)
+    }
+
+    // Returns `none` if the map has no entry for this key, like `map-get?` does
+    pub fn get_map_entry(
+        &mut self,
+        contract: &str,
+        map_name: &str,
+        map_key: &Value,
+    ) -> Result<Value, String> {
+        let contract_id = self.desugar_contract_id(contract)?;
+        match self
+            .session
+            .interpreter
+            .get_map_entry(&contract_id, map_name, map_key)
+        {
+            Some(raw_value) => raw_value_to_value(&raw_value),
+            None => Ok(Value::none()),
+        }
+    }
+
+    fn get_function_interface(
+        &self,
+        contract: &str,
+        method: &str,
+    ) -> Result<&ContractInterfaceFunction, String> {
+        let contract_id = self.desugar_contract_id(contract)?;
+        let contract_interface = self
+            .contracts_interfaces
+            .get(&contract_id)
+            .ok_or(format!("unable to get contract interface for {contract}"))?;
+        contract_interface
+            .functions
+            .iter()
+            .find(|func| func.name == method)
+            .ok_or(format!("contract {contract} has no function {method}"))
+    }
+
+    fn invoke_contract_call(
+        &mut self,
+        contract: &str,
+        method: &str,
+        args: &[Value],
+        sender: &str,
+        post_conditions: Option<&PostConditions>,
+    ) -> Result<TransactionResult, String> {
+        let clarity_args: Vec<String> = args.iter().map(value_to_clarity_code).collect();
+        let test_name = self.current_test_name.clone();
+        let result = match post_conditions {
+            Some(post_conditions) => self.session.invoke_contract_call_with_post_conditions(
+                contract,
+                method,
+                &clarity_args,
+                sender,
+                test_name,
+                post_conditions.mode,
+                &post_conditions.post_conditions,
+            ),
+            None => self.session.invoke_contract_call(
+                contract,
+                method,
+                &clarity_args,
+                sender,
+                test_name,
+            ),
+        };
+        let (execution, _) = result.map_err(|diagnostics| {
+            format!(
+                "Contract call error: {}::{}({}) -> {}",
+                contract,
+                method,
+                clarity_args.join(", "),
+                format_diagnostics(&diagnostics)
+            )
+        })?;
+        Ok(TransactionResult::from_execution_result(&execution))
+    }
+
+    pub fn call_read_only_fn(
+        &mut self,
+        contract: &str,
+        method: &str,
+        args: &[Value],
+        sender: &str,
+    ) -> Result<TransactionResult, String> {
+        let interface = self.get_function_interface(contract, method)?;
+        if interface.access != ContractInterfaceFunctionAccess::read_only {
+            return Err(format!("{} is not a read-only function", method));
+        }
+        self.invoke_contract_call(contract, method, args, sender, None)
+    }
+
+    fn call_public_fn_private(
+        &mut self,
+        contract: &str,
+        method: &str,
+        args: &[Value],
+        sender: &str,
+        post_conditions: Option<&PostConditions>,
+        sponsor: Option<&str>,
+        advance_chain_tip: bool,
+    ) -> Result<TransactionResult, String> {
+        let interface = self.get_function_interface(contract, method)?;
+        if interface.access != ContractInterfaceFunctionAccess::public {
+            return Err(format!("{} is not a public function", method));
+        }
+        if advance_chain_tip {
+            self.session.advance_chain_tip(1);
+        }
+        // the fee is paid even if the call fails, like on chain
+        let initial_tx_sponsor = self.session.interpreter.get_tx_sponsor();
+        self.session.set_tx_sponsor(sponsor.map(String::from))?;
+        self.session.charge_tx_fee_on_next_transaction();
+        let result = self.invoke_contract_call(contract, method, args, sender, post_conditions);
+        self.session.interpreter.set_tx_sponsor(initial_tx_sponsor);
+        result
+    }
+
+    fn transfer_stx_private(
+        &mut self,
+        amount: u64,
+        recipient: &str,
+        sender: &str,
+        post_conditions: Option<&PostConditions>,
+        advance_chain_tip: bool,
+    ) -> Result<TransactionResult, String> {
+        let initial_tx_sender = self.session.get_tx_sender();
+        self.session.set_tx_sender(sender.to_string());
+        self.session.charge_tx_fee_on_next_transaction();
+        let execution = match post_conditions {
+            Some(post_conditions) => self.session.stx_transfer_with_post_conditions(
+                amount,
+                recipient,
+                post_conditions.mode,
+                &post_conditions.post_conditions,
+            ),
+            None => self.session.stx_transfer(amount, recipient),
+        };
+        self.session.set_tx_sender(initial_tx_sender);
+        let execution = execution.map_err(|diagnostics| {
+            format!(
+                "STX transfer error: {} -> {}",
+                sender,
+                format_diagnostics(&diagnostics)
+            )
+        })?;
+        if advance_chain_tip {
+            self.session.advance_chain_tip(1);
+        }
+        Ok(TransactionResult::from_execution_result(&execution))
+    }
+
+    fn deploy_contract_private(
+        &mut self,
+        name: &str,
+        content: &str,
+        clarity_version: Option<ClarityVersion>,
+        sender: &str,
+        advance_chain_tip: bool,
+    ) -> Result<TransactionResult, String> {
+        if advance_chain_tip {
+            self.session.advance_chain_tip(1);
+        }
+        self.session.charge_tx_fee_on_next_transaction();
+        let epoch = self.session.current_epoch;
+        let contract = ClarityContract {
+            code_source: ClarityCodeSource::ContractInMemory(content.to_string()),
+            name: name.to_string(),
+            deployer: ContractDeployer::Address(sender.to_string()),
+            clarity_version: clarity_version
+                .unwrap_or_else(|| ClarityVersion::default_for_epoch(epoch)),
+            epoch,
+        };
+        let execution = self
+            .session
+            .deploy_contract(&contract, None, false, Some(name.to_string()), &mut None)
+            .map_err(|diagnostics| {
+                format!(
+                    "Contract deployment runtime error: {}.{} -> {}",
+                    sender,
+                    name,
+                    format_diagnostics(&diagnostics)
+                )
+            })?;
+        self.add_contract(&execution);
+        Ok(TransactionResult::from_execution_result(&execution))
+    }
+
+    pub fn call_public_fn(
+        &mut self,
+        contract: &str,
+        method: &str,
+        args: &[Value],
+        sender: &str,
+    ) -> Result<TransactionResult, String> {
+        self.call_public_fn_private(contract, method, args, sender, None, None, true)
+    }
+
+    pub fn call_public_fn_with_post_conditions(
+        &mut self,
+        contract: &str,
+        method: &str,
+        args: &[Value],
+        sender: &str,
+        post_conditions: &PostConditions,
+    ) -> Result<TransactionResult, String> {
+        self.call_public_fn_private(
+            contract,
+            method,
+            args,
+            sender,
+            Some(post_conditions),
+            None,
+            true,
+        )
+    }
+
+    // The transaction fee (see `set_tx_fee`) is paid by the sponsor, which is also exposed to
+    // the contract as `tx-sponsor?`
+    pub fn call_sponsored_public_fn(
+        &mut self,
+        contract: &str,
+        method: &str,
+        args: &[Value],
+        sender: &str,
+        sponsor: &str,
+    ) -> Result<TransactionResult, String> {
+        self.call_public_fn_private(contract, method, args, sender, None, Some(sponsor), true)
+    }
+
+    pub fn transfer_stx(
+        &mut self,
+        amount: u64,
+        recipient: &str,
+        sender: &str,
+    ) -> Result<TransactionResult, String> {
+        self.transfer_stx_private(amount, recipient, sender, None, true)
+    }
+
+    pub fn transfer_stx_with_post_conditions(
+        &mut self,
+        amount: u64,
+        recipient: &str,
+        sender: &str,
+        post_conditions: &PostConditions,
+    ) -> Result<TransactionResult, String> {
+        self.transfer_stx_private(amount, recipient, sender, Some(post_conditions), true)
+    }
+
+    pub fn deploy_contract(
+        &mut self,
+        name: &str,
+        content: &str,
+        clarity_version: Option<ClarityVersion>,
+        sender: &str,
+    ) -> Result<TransactionResult, String> {
+        self.deploy_contract_private(name, content, clarity_version, sender, true)
+    }
+
+    // Executes the transactions in a single block
+    pub fn mine_block(&mut self, txs: Vec<Tx>) -> Result<Vec<TransactionResult>, String> {
+        self.mine_block_with_context(txs, &BlockContext::default())
+    }
+
+    pub fn mine_block_with_context(
+        &mut self,
+        txs: Vec<Tx>,
+        context: &BlockContext,
+    ) -> Result<Vec<TransactionResult>, String> {
+        let mut results = vec![];
+        for tx in txs.into_iter() {
+            let result = match tx {
+                Tx::CallPublicFn {
+                    contract,
+                    method,
+                    args,
+                    sender,
+                    post_conditions,
+                    sponsor,
+                } => self.call_public_fn_private(
+                    &contract,
+                    &method,
+                    &args,
+                    &sender,
+                    post_conditions.as_ref(),
+                    sponsor.as_deref(),
+                    false,
+                ),
+                Tx::DeployContract {
+                    name,
+                    content,
+                    clarity_version,
+                    sender,
+                } => self.deploy_contract_private(&name, &content, clarity_version, &sender, false),
+                Tx::TransferStx {
+                    amount,
+                    recipient,
+                    sender,
+                    post_conditions,
+                } => self.transfer_stx_private(
+                    amount,
+                    &recipient,
+                    &sender,
+                    post_conditions.as_ref(),
+                    false,
+                ),
+            }?;
+            results.push(result);
+        }
+        self.session.advance_chain_tip_with_context(1, context);
+        Ok(results)
+    }
+
+    pub fn set_mempool_ordering(&mut self, ordering: MempoolOrdering) {
+        self.session.mempool.set_ordering(ordering)
+    }
+
+    // nonce expected by the mempool for the next transaction of the account
+    pub fn get_nonce(&self, sender: &str) -> u64 {
+        self.session.mempool.get_nonce(sender)
+    }
+
+    // Adds a contract call or a STX transfer to the mempool, see `mine_mempool_block`
+    pub fn submit_tx(&mut self, tx: Tx, nonce: u64, fee: u64) -> Result<MempoolAdmission, String> {
+        let (sender, sponsor, post_conditions, payload) = match tx {
+            Tx::CallPublicFn {
+                contract,
+                method,
+                args,
+                sender,
+                post_conditions,
+                sponsor,
+            } => {
+                let payload = MempoolTxPayload::ContractCall {
+                    contract,
+                    method,
+                    args: args.iter().map(value_to_clarity_code).collect(),
+                };
+                (sender, sponsor, post_conditions, payload)
+            }
+            Tx::TransferStx {
+                amount,
+                recipient,
+                sender,
+                post_conditions,
+            } => (
+                sender,
+                None,
+                post_conditions,
+                MempoolTxPayload::StxTransfer { amount, recipient },
+            ),
+            Tx::DeployContract { .. } => {
+                return Err("contract deployments can not be submitted to the mempool".into())
+            }
+        };
+        self.session.submit_transaction(MempoolTransaction {
+            sender,
+            nonce,
+            fee,
+            sponsor,
+            post_conditions: post_conditions.map(|p| (p.mode, p.post_conditions)),
+            payload,
+        })
+    }
+
+    // Mines the ready transactions of the mempool in a new block. The result of a transaction
+    // aborted by a runtime error is an `Err`, its fee is paid anyway
+    pub fn mine_mempool_block(
+        &mut self,
+    ) -> Vec<MinedTransaction<Result<TransactionResult, String>>> {
+        self.mine_mempool_block_with_context(&BlockContext::default())
+    }
+
+    pub fn mine_mempool_block_with_context(
+        &mut self,
+        context: &BlockContext,
+    ) -> Vec<MinedTransaction<Result<TransactionResult, String>>> {
+        let test_name = self.current_test_name.clone();
+        let block = self.session.mine_mempool_block(&test_name, context);
+        block
+            .transactions
+            .into_iter()
+            .map(|mined| MinedTransaction {
+                txid: mined.txid,
+                tx: mined.tx,
+                result: mined
+                    .result
+                    .map(|execution| TransactionResult::from_execution_result(&execution))
+                    .map_err(|diagnostics| format_diagnostics(&diagnostics)),
+            })
+            .collect()
+    }
+
+    pub fn mine_empty_block(&mut self) -> u32 {
+        self.session.advance_chain_tip(1)
+    }
+
+    pub fn mine_empty_blocks(&mut self, count: u32) -> u32 {
+        self.session.advance_chain_tip(count)
+    }
+
+    pub fn mine_empty_blocks_with_context(&mut self, count: u32, context: &BlockContext) -> u32 {
+        self.session.advance_chain_tip_with_context(count, context)
+    }
+
+    // Replaces the body of a function of a deployed contract with a clarity expression, the
+    // function keeps its signature and the calls made to it are recorded
+    pub fn stub_function(
+        &mut self,
+        contract: &str,
+        method: &str,
+        body: &str,
+    ) -> Result<(), String> {
+        self.session.stub_function(contract, method, body)
+    }
+
+    pub fn stub_function_with_value(
+        &mut self,
+        contract: &str,
+        method: &str,
+        value: &Value,
+    ) -> Result<(), String> {
+        self.stub_function(contract, method, &value_to_clarity_code(value))
+    }
+
+    pub fn remove_stub(&mut self, contract: &str, method: &str) -> Result<(), String> {
+        self.session.remove_stub(contract, method)
+    }
+
+    pub fn get_stub_calls(&self) -> Vec<StubCall> {
+        self.session.get_stub_calls()
+    }
+
+    pub fn run_snippet(&mut self, snippet: &str) -> Result<Value, String> {
+        let execution = self
+            .session
+            .eval(snippet.to_string(), None, false)
+            .map_err(|diagnostics| format_diagnostics(&diagnostics))?;
+        match execution.result {
+            EvaluationResult::Snippet(result) => Ok(result.result),
+            EvaluationResult::Contract(_) => {
+                Err("unexpected contract evaluation result".to_string())
+            }
+        }
+    }
+
+    // Empties the session coverage and costs reports, and returns them
+    pub fn collect_report(&mut self) -> SessionReport {
+        let mut coverage_reporter = CoverageReporter::new();
+        coverage_reporter.asts.append(&mut self.session.asts);
+        for (contract_id, contract_location) in self.contracts_locations.iter() {
+            coverage_reporter
+                .contract_paths
+                .insert(contract_id.name.to_string(), contract_location.to_string());
+        }
+        coverage_reporter
+            .reports
+            .append(&mut self.session.coverage_reports);
+        let coverage = coverage_reporter.build_lcov_content();
+
+        let mut costs = vec![];
+        costs.append(&mut self.session.costs_reports);
+        SessionReport { coverage, costs }
+    }
+}
+
+fn format_diagnostics(diagnostics: &[Diagnostic]) -> String {
+    diagnostics
+        .iter()
+        .map(|d| d.message.clone())
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use clarity_repl::clarity::vm::types::{PrincipalData, TupleData};
+    use clarity_repl::repl::post_conditions::{FungibleConditionCode, PostConditionPrincipal};
+    use std::path::PathBuf;
+
+    // the simnet of the sdk fixture project, along with the address of its wallet_1
+    fn init_fixture_session() -> (Simnet, String) {
+        let manifest_path = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+            .join("../clarinet-sdk/tests/fixtures/Clarinet.toml");
+        let simnet = Simnet::init_session(&manifest_path).unwrap();
+        let sender = simnet.get_accounts().get("wallet_1").unwrap().clone();
+        (simnet, sender)
+    }
+
+    #[test]
+    fn it_calls_contracts_with_typed_values() {
+        let (mut simnet, sender) = init_fixture_session();
+        let deployer = simnet.deployer.clone();
+        simnet.set_current_test_name("counter");
+
+        let block_height = simnet.block_height();
+        let results = simnet
+            .mine_block(vec![
+                Tx::CallPublicFn {
+                    contract: "counter".into(),
+                    method: "increment".into(),
+                    args: vec![],
+                    sender: sender.clone(),
+                    post_conditions: None,
+                    sponsor: None,
+                },
+                Tx::CallPublicFn {
+                    contract: "counter".into(),
+                    method: "add".into(),
+                    args: vec![Value::UInt(10)],
+                    sender: sender.clone(),
+                    post_conditions: None,
+                    sponsor: None,
+                },
+            ])
+            .unwrap();
+        assert_eq!(simnet.block_height(), block_height + 1);
+        assert_eq!(results[1].result, Value::okay(Value::Bool(true)).unwrap());
+        assert!(matches!(
+            results[1].events[0],
+            StacksEvent::Print { ref topic, .. } if topic == "print"
+        ));
+        assert!(matches!(results[1].events[1], StacksEvent::StxTransfer(_)));
+
+        let count = simnet
+            .call_read_only_fn("counter", "get-count", &[], &sender)
+            .unwrap();
+        let expected = TupleData::from_data(vec![("count".into(), Value::UInt(11))]).unwrap();
+        assert_eq!(count.result, Value::okay(Value::Tuple(expected)).unwrap());
+        assert_eq!(simnet.get_data_var("counter", "count"), Ok(Value::UInt(11)));
+
+        let participant = Value::Principal(PrincipalData::parse(&sender).unwrap());
+        assert_eq!(
+            simnet.get_map_entry("counter", "participants", &participant),
+            Ok(Value::some(Value::Bool(true)).unwrap())
+        );
+        let deployer_principal = Value::Principal(PrincipalData::parse(&deployer).unwrap());
+        assert_eq!(
+            simnet.get_map_entry("counter", "participants", &deployer_principal),
+            Ok(Value::none())
+        );
+
+        assert!(simnet
+            .call_public_fn("counter", "get-count", &[], &sender)
+            .is_err());
+
+        let report = simnet.collect_report();
+        assert!(report.coverage.contains("counter.clar"));
+        assert_eq!(report.costs.len(), 3);
+        assert!(simnet.collect_report().costs.is_empty());
+    }
+
+    #[test]
+    fn it_enforces_post_conditions() {
+        let (mut simnet, sender) = init_fixture_session();
+
+        let post_conditions = |condition_code| PostConditions {
+            mode: TransactionPostConditionMode::Deny,
+            post_conditions: vec![TransactionPostCondition::STX(
+                PostConditionPrincipal::Origin,
+                condition_code,
+                1000000,
+            )],
+        };
+
+        let result = simnet.call_public_fn_with_post_conditions(
+            "counter",
+            "increment",
+            &[],
+            &sender,
+            &post_conditions(FungibleConditionCode::SentEq),
+        );
+        assert!(result.is_ok());
+
+        let result = simnet.call_public_fn_with_post_conditions(
+            "counter",
+            "increment",
+            &[],
+            &sender,
+            &post_conditions(FungibleConditionCode::SentLt),
+        );
+        assert!(result.unwrap_err().contains("post-condition check failure"));
+        assert_eq!(simnet.get_data_var("counter", "count"), Ok(Value::UInt(1)));
+    }
+
+    #[test]
+    fn it_charges_tx_fees() {
+        let (mut simnet, sender) = init_fixture_session();
+        let sponsor = simnet.get_accounts().get("wallet_2").unwrap().clone();
+        simnet.set_tx_fee(Some(1000));
+        let balance = |simnet: &Simnet, account: &str| simnet.get_assets_maps()["STX"][account];
+        let sender_balance = balance(&simnet, &sender);
+        let sponsor_balance = balance(&simnet, &sponsor);
+
+        simnet
+            .call_public_fn("counter", "increment", &[], &sender)
+            .unwrap();
+        assert_eq!(balance(&simnet, &sender), sender_balance - 1001000);
+
+        simnet
+            .mine_block(vec![Tx::CallPublicFn {
+                contract: "counter".into(),
+                method: "increment".into(),
+                args: vec![],
+                sender: sender.clone(),
+                post_conditions: None,
+                sponsor: Some(sponsor.clone()),
+            }])
+            .unwrap();
+        assert_eq!(balance(&simnet, &sender), sender_balance - 2001000);
+        assert_eq!(balance(&simnet, &sponsor), sponsor_balance - 1000);
+    }
+
+    #[test]
+    fn it_mines_mempool_transactions() {
+        let (mut simnet, sender) = init_fixture_session();
+        simnet.set_mempool_ordering(MempoolOrdering::FeePriority);
+
+        let add = |n| Tx::CallPublicFn {
+            contract: "counter".into(),
+            method: "add".into(),
+            args: vec![Value::UInt(n)],
+            sender: sender.clone(),
+            post_conditions: None,
+            sponsor: None,
+        };
+        simnet.submit_tx(add(2), 1, 500).unwrap();
+        simnet.submit_tx(add(3), 0, 100).unwrap();
+        assert!(simnet.submit_tx(add(4), 0, 100).is_err());
+
+        let block = simnet.mine_mempool_block();
+        // the nonce order prevails over the fees of the transactions of a same sender
+        assert_eq!(block.iter().map(|t| t.txid).collect::<Vec<_>>(), vec![1, 0]);
+        assert!(block.iter().all(|t| t.result.is_ok()));
+        assert_eq!(simnet.get_nonce(&sender), 2);
+        assert_eq!(simnet.get_data_var("counter", "count"), Ok(Value::UInt(5)));
+    }
+
+    #[test]
+    fn it_stubs_contract_functions() {
+        let (mut simnet, sender) = init_fixture_session();
+
+        let stub = Value::okay(Value::Bool(true)).unwrap();
+        simnet
+            .stub_function_with_value("counter", "add", &stub)
+            .unwrap();
+        let result = simnet
+            .call_public_fn("counter", "add", &[Value::UInt(5)], &sender)
+            .unwrap();
+        assert_eq!(result.result, stub);
+        assert_eq!(simnet.get_data_var("counter", "count"), Ok(Value::UInt(0)));
+
+        let calls = simnet.get_stub_calls();
+        assert_eq!(calls.len(), 1);
+        assert_eq!(calls[0].function, "add");
+        assert_eq!(calls[0].args, vec![Value::UInt(5)]);
+        assert_eq!(
+            calls[0].caller.as_ref().map(|c| c.to_string()),
+            Some(sender)
+        );
+
+        assert!(simnet
+            .stub_function("counter", "unknown", "(ok true)")
+            .is_err());
+        assert!(simnet.stub_function("counter", "add", "(ok u1)").is_err());
+
+        simnet.remove_stub("counter", "add").unwrap();
+        simnet
+            .call_public_fn("counter", "add", &[Value::UInt(5)], &sender)
+            .unwrap();
+        assert_eq!(simnet.get_data_var("counter", "count"), Ok(Value::UInt(5)));
+    }
+
+    #[test]
+    fn it_traces_contract_calls() {
+        let (mut simnet, sender) = init_fixture_session();
+        let multiplier = Value::Principal(
+            PrincipalData::parse(&format!("{}.multiplier-contract", simnet.deployer)).unwrap(),
+        );
+
+        simnet.set_call_tracing(true);
+        simnet
+            .call_public_fn("counter", "call-multiply", &[multiplier], &sender)
+            .unwrap();
+        let trace = simnet.take_call_trace().unwrap();
+        assert_eq!(trace.function, "call-multiply");
+        assert_eq!(trace.calls.len(), 1);
+        assert_eq!(trace.calls[0].function, "multiply");
+        assert_eq!(trace.calls[0].args, vec![Value::UInt(2), Value::UInt(2)]);
+        assert_eq!(
+            trace.calls[0].result,
+            Some(Value::okay(Value::UInt(4)).unwrap())
+        );
+
+        let json = trace.to_json();
+        assert_eq!(json["calls"][0]["result"], "(ok u4)");
+    }
+}
@@ -4,19 +4,28 @@ use clarinet_deployments::types::{
     TransactionSpecificationFile,
 };
 use clarinet_deployments::{
-    generate_default_deployment, initiate_session_from_deployment, setup_session_with_deployment,
+    initiate_session_from_deployment, load_simnet_deployment_plan,
     update_session_with_contracts_executions, update_session_with_genesis_accounts,
+    SimnetDeploymentPlanOrigin,
 };
-use clarinet_files::chainhook_types::StacksNetwork;
 use clarinet_files::{FileAccessor, FileLocation, ProjectManifest, WASMFileSystemAccessor};
 use clarity_repl::analysis::coverage::CoverageReporter;
 use clarity_repl::clarity::analysis::contract_interface_builder::{
     ContractInterface, ContractInterfaceFunction, ContractInterfaceFunctionAccess,
 };
-use clarity_repl::clarity::vm::types::QualifiedContractIdentifier;
+use clarity_repl::clarity::codec::StacksMessageCodec;
+use clarity_repl::clarity::costs::ExecutionCost;
+use clarity_repl::clarity::types::chainstate::{StacksAddress, VRFSeed};
+use clarity_repl::clarity::util::hash;
+use clarity_repl::clarity::vm::types::{PrincipalData, QualifiedContractIdentifier};
 use clarity_repl::clarity::{
     ClarityVersion, EvaluationResult, ExecutionResult, ParsedContract, StacksEpochId,
 };
+use clarity_repl::repl::datastore::BlockContext;
+use clarity_repl::repl::mempool::{MempoolOrdering, MempoolTransaction, MempoolTxPayload};
+use clarity_repl::repl::post_conditions::{TransactionPostCondition, TransactionPostConditionMode};
+use clarity_repl::repl::session::SessionSnapshot;
+use clarity_repl::repl::tracer::CallFrame;
 use clarity_repl::repl::{
     ClarityCodeSource, ClarityContract, ContractDeployer, Session, DEFAULT_CLARITY_VERSION,
     DEFAULT_EPOCH,
@@ -25,16 +34,15 @@ use colored::*;
 use gloo_utils::format::JsValueSerdeExt;
 use js_sys::Function as JsFunction;
 use serde::{Deserialize, Serialize};
-use serde_json::json;
 use serde_wasm_bindgen::to_value as encode_to_js;
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::{panic, path::PathBuf};
 use wasm_bindgen::prelude::*;
 use wasm_bindgen::JsValue;
 
 use crate::utils::clarity_values::{self, uint8_to_string, uint8_to_value};
 use crate::utils::costs::SerializableCostsReport;
-use crate::utils::events::serialize_event;
+use crate::utils::events::{serialize_event, StacksEvent};
 
 #[wasm_bindgen(typescript_custom_section)]
 const SET_EPOCH: &'static str = r#"
@@ -55,20 +63,123 @@ extern "C" {
     pub type Accounts;
     #[wasm_bindgen(typescript_type = "EpochString")]
     pub type EpochString;
+    #[wasm_bindgen(typescript_type = "TransactionResult")]
+    pub type TransactionResult;
+    #[wasm_bindgen(typescript_type = "TransactionResult[]")]
+    pub type TransactionResults;
+    #[wasm_bindgen(typescript_type = "BlockOptions")]
+    pub type BlockOptions;
+    #[wasm_bindgen(typescript_type = "MempoolAdmission")]
+    pub type MempoolAdmissionResult;
+    #[wasm_bindgen(typescript_type = "MinedMempoolTransaction[]")]
+    pub type MinedMempoolTransactions;
+    #[wasm_bindgen(typescript_type = "StubCall[]")]
+    pub type StubCalls;
 }
 
+#[wasm_bindgen(typescript_custom_section)]
+const BLOCK_OPTIONS: &'static str = r#"
+export type BlockOptions = {
+  time?: number;
+  burnBlockHeight?: number;
+  miner?: string;
+  vrfSeed?: string;
+};
+"#;
+
+#[wasm_bindgen(typescript_custom_section)]
+const TRANSACTION_RESULT: &'static str = r#"
+export type ExecutionCost = {
+  write_length: number;
+  write_count: number;
+  read_length: number;
+  read_count: number;
+  runtime: number;
+};
+export type CallTrace = {
+  contract: string;
+  method: string;
+  args: string[];
+  result?: string;
+  error?: string;
+  events: StacksEvent[];
+  cost: ExecutionCost;
+  calls: CallTrace[];
+};
+export type TransactionResult = { result: string; events: StacksEvent[]; trace?: CallTrace };
+"#;
+
+#[wasm_bindgen(typescript_custom_section)]
+const MEMPOOL: &'static str = r#"
+export type MempoolOrdering = "fee-priority" | "fifo" | "random";
+export type MempoolAdmission = { txid: number; replaced?: number };
+export type MinedMempoolTransaction = { txid: number; result?: TransactionResult; error?: string };
+"#;
+
+#[wasm_bindgen(typescript_custom_section)]
+const STUB_CALL: &'static str = r#"
+export type StubCall = { contract: string; method: string; caller?: string; args: string[] };
+"#;
+
 macro_rules! log {
     ( $( $t:tt )* ) => {
         web_sys::console::log_1(&format!( $( $t )* ).into());
     }
 }
 
+// post-conditions are consensus serialized by the js sdk (`serializePostCondition`)
+// and the mode is the value of the stacks.js `PostConditionMode` enum
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct PostConditionsArgs {
+    #[serde(default)]
+    post_conditions: Vec<Vec<u8>>,
+    #[serde(default)]
+    post_condition_mode: Option<u8>,
+}
+
+impl PostConditionsArgs {
+    fn new(post_conditions: Vec<js_sys::Uint8Array>, post_condition_mode: Option<u8>) -> Self {
+        Self {
+            post_conditions: post_conditions.iter().map(|p| p.to_vec()).collect(),
+            post_condition_mode,
+        }
+    }
+
+    // like in stacks.js, the mode defaults to deny when post-conditions are set.
+    // without any of them, the transaction isn't checked at all
+    fn decode(
+        &self,
+    ) -> Result<Option<(TransactionPostConditionMode, Vec<TransactionPostCondition>)>, String> {
+        let mode = match self.post_condition_mode {
+            None if self.post_conditions.is_empty() => return Ok(None),
+            None => TransactionPostConditionMode::Deny,
+            Some(1) => TransactionPostConditionMode::Allow,
+            Some(2) => TransactionPostConditionMode::Deny,
+            Some(mode) => return Err(format!("Invalid post-condition mode {}", mode)),
+        };
+        let mut post_conditions = vec![];
+        for bytes in self.post_conditions.iter() {
+            let post_condition = TransactionPostCondition::consensus_deserialize(&mut &bytes[..])
+                .map_err(|e| format!("Failed to parse post-condition: {}", e))?;
+            post_conditions.push(post_condition);
+        }
+        Ok(Some((mode, post_conditions)))
+    }
+}
+
 #[derive(Debug, Deserialize)]
 struct CallContractArgsJSON {
     contract: String,
     method: String,
     args_maps: Vec<HashMap<usize, u8>>,
     sender: String,
+    #[serde(flatten)]
+    post_conditions: PostConditionsArgs,
+    #[serde(default)]
+    sponsor: Option<String>,
+    // the trace of the call is returned along with its result
+    #[serde(default)]
+    trace: bool,
 }
 
 #[derive(Debug, Deserialize)]
@@ -78,6 +189,14 @@ pub struct CallContractArgs {
     method: String,
     args: Vec<Vec<u8>>,
     sender: String,
+    #[serde(flatten)]
+    post_conditions: PostConditionsArgs,
+    // the sponsor pays the transaction fee and is exposed as `tx-sponsor?`
+    #[serde(default)]
+    sponsor: Option<String>,
+    // the trace of the call is returned along with its result
+    #[serde(default)]
+    trace: bool,
 }
 
 #[wasm_bindgen]
@@ -88,12 +207,19 @@ impl CallContractArgs {
         method: String,
         args: Vec<js_sys::Uint8Array>,
         sender: String,
+        post_conditions: Vec<js_sys::Uint8Array>,
+        post_condition_mode: Option<u8>,
+        sponsor: Option<String>,
+        trace: Option<bool>,
     ) -> Self {
         Self {
             contract,
             method,
             args: args.iter().map(|a| a.to_vec()).collect::<Vec<Vec<u8>>>(),
             sender,
+            post_conditions: PostConditionsArgs::new(post_conditions, post_condition_mode),
+            sponsor,
+            trace: trace.unwrap_or(false),
         }
     }
 
@@ -108,6 +234,9 @@ impl CallContractArgs {
             method,
             args_maps,
             sender,
+            post_conditions,
+            sponsor,
+            trace,
         }: CallContractArgsJSON,
     ) -> Self {
         let mut args: Vec<Vec<u8>> = vec![];
@@ -123,6 +252,9 @@ impl CallContractArgs {
             method,
             args,
             sender,
+            post_conditions,
+            sponsor,
+            trace,
         }
     }
 }
@@ -175,22 +307,76 @@ impl DeployContractArgs {
     }
 }
 
+// overrides of the info of the mined block, see `BlockContext`
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct BlockOptionsJSON {
+    time: Option<u64>,
+    burn_block_height: Option<u32>,
+    miner: Option<String>,
+    vrf_seed: Option<String>,
+}
+
+impl BlockOptionsJSON {
+    fn from_js(options: Option<BlockOptions>) -> Result<BlockContext, String> {
+        let options: BlockOptionsJSON = match options {
+            Some(options) => JsValue::from(options)
+                .into_serde()
+                .map_err(|e| format!("Failed to parse block options: {:}", e))?,
+            None => return Ok(BlockContext::default()),
+        };
+        let miner = match options.miner {
+            Some(ref miner) => Some(StacksAddress::from(
+                PrincipalData::parse_standard_principal(miner)
+                    .map_err(|_| format!("Invalid miner address {}", miner))?,
+            )),
+            None => None,
+        };
+        let vrf_seed = match options.vrf_seed {
+            Some(ref seed) => {
+                let bytes = hash::hex_bytes(seed.trim_start_matches("0x"))
+                    .map_err(|e| format!("Invalid vrf seed {}: {}", seed, e))?;
+                let bytes: [u8; 32] = bytes
+                    .try_into()
+                    .map_err(|_| "The vrf seed must be 32 bytes long".to_string())?;
+                Some(VRFSeed(bytes))
+            }
+            None => None,
+        };
+        Ok(BlockContext {
+            time: options.time,
+            burn_block_height: options.burn_block_height,
+            miner,
+            vrf_seed,
+        })
+    }
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 #[wasm_bindgen]
 pub struct TransferSTXArgs {
     amount: u64,
     recipient: String,
     sender: String,
+    #[serde(flatten)]
+    post_conditions: PostConditionsArgs,
 }
 
 #[wasm_bindgen]
 impl TransferSTXArgs {
     #[wasm_bindgen(constructor)]
-    pub fn new(amount: u64, recipient: String, sender: String) -> Self {
+    pub fn new(
+        amount: u64,
+        recipient: String,
+        sender: String,
+        post_conditions: Vec<js_sys::Uint8Array>,
+        post_condition_mode: Option<u8>,
+    ) -> Self {
         Self {
             amount,
             recipient,
             sender,
+            post_conditions: PostConditionsArgs::new(post_conditions, post_condition_mode),
         }
     }
 }
@@ -205,17 +391,95 @@ pub struct TxArgs {
     transfer_stx: Option<TransferSTXArgs>,
 }
 
-#[wasm_bindgen(getter_with_clone)]
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct MempoolTxArgs {
+    call_public_fn: Option<CallContractArgsJSON>,
+    #[serde(rename = "transferSTX")]
+    transfer_stx: Option<TransferSTXArgs>,
+    nonce: u64,
+    fee: u64,
+}
+
+#[derive(Debug, Serialize)]
+struct MempoolAdmissionRes {
+    txid: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    replaced: Option<u64>,
+}
+
+// a transaction aborted by a runtime error is mined with an `error` instead of a `result`
+#[derive(Debug, Serialize)]
+struct MinedMempoolTransactionRes {
+    txid: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    result: Option<TransactionRes>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+}
+
+// the arguments are hex encoded clarity values
+#[derive(Debug, Serialize)]
+struct StubCallRes {
+    contract: String,
+    method: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    caller: Option<String>,
+    args: Vec<String>,
+}
+
+// the arguments and the result are hex encoded clarity values
+#[derive(Debug, Serialize)]
+pub struct CallTraceRes {
+    contract: String,
+    method: String,
+    args: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    result: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+    events: Vec<StacksEvent>,
+    cost: ExecutionCost,
+    calls: Vec<CallTraceRes>,
+}
+
+impl CallTraceRes {
+    fn from_call_frame(frame: &CallFrame) -> Self {
+        CallTraceRes {
+            contract: frame.contract_id.to_string(),
+            method: frame.function.clone(),
+            args: frame
+                .args
+                .iter()
+                .map(clarity_values::to_raw_value)
+                .collect(),
+            result: frame.result.as_ref().map(clarity_values::to_raw_value),
+            error: frame.error.clone(),
+            events: frame.events.iter().map(serialize_event).collect(),
+            cost: frame.cost.clone(),
+            calls: frame
+                .calls
+                .iter()
+                .map(CallTraceRes::from_call_frame)
+                .collect(),
+        }
+    }
+}
+
+// `result` is the hex encoded clarity value, decoded by the js sdk with `Cl.deserialize`
+#[derive(Debug, Serialize)]
 pub struct TransactionRes {
     pub result: String,
-    pub events: String,
+    pub events: Vec<StacksEvent>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub trace: Option<CallTraceRes>,
 }
 
-#[derive(Serialize, Deserialize)]
-pub struct TransactionResRaw {
-    pub result: String,
-    pub events: Vec<String>,
+impl TransactionRes {
+    fn into_js(self) -> Result<TransactionResult, String> {
+        let result = encode_to_js(&self).map_err(|e| format!("error: {}", e))?;
+        Ok(result.unchecked_into::<TransactionResult>())
+    }
 }
 
 #[wasm_bindgen(getter_with_clone)]
@@ -232,18 +496,34 @@ pub fn execution_result_to_transaction_res(execution: &ExecutionResult) -> Trans
             _ => "0x03".into(),
         },
     };
-    let events_as_strings = execution
-        .events
-        .iter()
-        .map(|e| json!(serialize_event(e)).to_string())
-        .collect::<Vec<String>>();
-
     TransactionRes {
         result,
-        events: json!(events_as_strings).to_string(),
+        events: execution.events.iter().map(serialize_event).collect(),
+        trace: None,
     }
 }
 
+// the post-deployment state of the simnet is shared by the test files of a project through
+// this file, it's rebuilt as soon as the manifest, the deployment plan or a contract changes
+static SIMNET_SNAPSHOT_PATH: &str = ".cache/simnet-snapshot.json";
+
+#[derive(Clone, Serialize, Deserialize)]
+struct SnapshotContract {
+    contract_id: String,
+    location: String,
+    interface: Option<ContractInterface>,
+    contract: ParsedContract,
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+struct SimnetSnapshot {
+    hash: String,
+    deployer: String,
+    accounts: HashMap<String, String>,
+    contracts: Vec<SnapshotContract>,
+    session: SessionSnapshot,
+}
+
 #[wasm_bindgen(getter_with_clone)]
 pub struct SDK {
     pub deployer: String,
@@ -254,6 +534,7 @@ pub struct SDK {
     contracts_interfaces: HashMap<QualifiedContractIdentifier, ContractInterface>,
     parsed_contracts: HashMap<QualifiedContractIdentifier, ParsedContract>,
     cache: HashMap<FileLocation, (DeploymentSpecification, DeploymentGenerationArtifacts)>,
+    snapshots: HashMap<FileLocation, SimnetSnapshot>,
     current_test_name: String,
 }
 
@@ -274,6 +555,7 @@ impl SDK {
             contracts_interfaces: HashMap::new(),
             parsed_contracts: HashMap::new(),
             cache: HashMap::new(),
+            snapshots: HashMap::new(),
             current_test_name: String::new(),
         }
     }
@@ -289,7 +571,12 @@ impl SDK {
     }
 
     #[wasm_bindgen(js_name=initSession)]
-    pub async fn init_session(&mut self, cwd: String, manifest_path: String) -> Result<(), String> {
+    pub async fn init_session(
+        &mut self,
+        cwd: String,
+        manifest_path: String,
+        snapshot: Option<bool>,
+    ) -> Result<(), String> {
         let cwd_path = PathBuf::from(cwd);
         let cwd_root = FileLocation::FileSystem { path: cwd_path };
         let manifest_location = FileLocation::try_parse(&manifest_path, Some(&cwd_root))
@@ -299,98 +586,45 @@ impl SDK {
             FileLocation::try_parse("deployments/default.simnet-plan.yaml", Some(&project_root))
                 .ok_or("Failed to parse default deployment location")?;
 
+        let snapshot_location = FileLocation::try_parse(SIMNET_SNAPSHOT_PATH, Some(&project_root))
+            .ok_or("Failed to parse simnet snapshot location")?;
+        let use_snapshot = snapshot.unwrap_or(false);
+
         let manifest =
             ProjectManifest::from_file_accessor(&manifest_location, &*self.file_accessor).await?;
 
-        let (mut default_deployment, default_artifacts) = generate_default_deployment(
-            &manifest,
-            &StacksNetwork::Simnet,
-            false,
-            Some(&*self.file_accessor),
-            Some(StacksEpochId::Epoch21),
-        )
-        .await?;
+        if use_snapshot {
+            let snapshot = self
+                .load_simnet_snapshot(&manifest, &deployment_plan_location, &snapshot_location)
+                .await?;
+            if let Some(snapshot) = snapshot {
+                return self.restore_simnet_snapshot(&manifest, snapshot);
+            }
+        }
 
         let (deployment, artifacts) = match self.cache.get(&manifest_location) {
             Some(cache) => cache.clone(),
             None => {
-                if self
-                    .file_accessor
-                    .file_exists(deployment_plan_location.to_string())
-                    .await?
-                {
-                    let spec_file = DeploymentSpecificationFile::from_file_accessor(
-                        &deployment_plan_location,
-                        &*self.file_accessor,
-                    )
-                    .await?;
-
-                    let contracts_paths = match spec_file.plan {
-                        Some(ref plan) => plan
-                            .batches
-                            .iter()
-                            .flat_map(|b| {
-                                b.transactions
-                                    .iter()
-                                    .filter_map(|t| match t {
-                                        TransactionSpecificationFile::EmulatedContractPublish(
-                                            ref deploy,
-                                        ) => deploy.path.clone(),
-                                        _ => None,
-                                    })
-                                    .collect::<Vec<String>>()
-                            })
-                            .collect::<Vec<String>>(),
-                        None => {
-                            vec![]
-                        }
-                    };
-
-                    let contracts_sources = self.file_accessor.read_files(contracts_paths).await?;
-
-                    let existing_deployment = DeploymentSpecification::from_specifications(
-                        &spec_file,
-                        &StacksNetwork::Simnet,
-                        &project_root,
-                        Some(&contracts_sources),
-                    )
-                    .map_err(|e| e.to_string())?;
-
-                    let (deployment_with_only_contract_publish_txs, custom_batches) =
-                        existing_deployment.extract_no_contract_publish_txs();
-
-                    let (deployment, artifacts) = if deployment_with_only_contract_publish_txs
-                        == default_deployment
-                    {
-                        log!("{}", "using existing deployment plan".yellow().bold());
-                        let artifacts =
-                            setup_session_with_deployment(&manifest, &existing_deployment, None);
-                        (existing_deployment, artifacts)
-                    } else {
-                        log!("{}", "using updated deployment plan".yellow().bold());
-                        default_deployment.merge_batches(custom_batches);
-                        let deployment_file = default_deployment.to_file_content()?;
-                        self.file_accessor
-                            .write_file(deployment_plan_location.to_string(), &deployment_file)
-                            .await?;
-                        (default_deployment, default_artifacts)
-                    };
-
-                    let cache = (deployment, artifacts);
-                    self.cache.insert(manifest_location, cache.clone());
-                    cache
-                } else {
-                    log!("{}", "generated a new deployment plan".green().bold());
-                    let cache = (default_deployment.clone(), default_artifacts.clone());
-                    self.cache.insert(manifest_location, cache.clone());
-
-                    let deployment_file = default_deployment.to_file_content()?;
-                    self.file_accessor
-                        .write_file(deployment_plan_location.to_string(), &deployment_file)
-                        .await?;
-
-                    cache
-                }
+                let (deployment, artifacts, origin) = load_simnet_deployment_plan(
+                    &manifest,
+                    &deployment_plan_location,
+                    Some(&*self.file_accessor),
+                )
+                .await?;
+                match origin {
+                    SimnetDeploymentPlanOrigin::Existing => {
+                        log!("{}", "using existing deployment plan".yellow().bold())
+                    }
+                    SimnetDeploymentPlanOrigin::Updated => {
+                        log!("{}", "using updated deployment plan".yellow().bold())
+                    }
+                    SimnetDeploymentPlanOrigin::Generated => {
+                        log!("{}", "generated a new deployment plan".green().bold())
+                    }
+                };
+                let cache = (deployment, artifacts);
+                self.cache.insert(manifest_location.clone(), cache.clone());
+                cache
             }
         };
 
@@ -421,9 +655,9 @@ impl SDK {
             }
         }
 
-        for (contract_id, (_, location)) in deployment.contracts {
+        for (contract_id, (_, location)) in deployment.contracts.iter() {
             self.contracts_locations
-                .insert(contract_id, location.clone());
+                .insert(contract_id.clone(), location.clone());
         }
 
         for (_, result) in results.into_iter() {
@@ -438,6 +672,224 @@ impl SDK {
             }
         }
 
+        self.session = Some(session);
+
+        if use_snapshot {
+            // the deployment plan may have been generated or updated above
+            self.save_simnet_snapshot(
+                &manifest,
+                &deployment_plan_location,
+                &snapshot_location,
+                &deployment,
+            )
+            .await?;
+        }
+        Ok(())
+    }
+
+    // hash of everything the post-deployment state depends on, none if there is no plan yet
+    async fn get_simnet_snapshot_hash(
+        &self,
+        manifest: &ProjectManifest,
+        deployment_plan_location: &FileLocation,
+    ) -> Result<Option<String>, String> {
+        let file_accessor = &*self.file_accessor;
+        if !file_accessor
+            .file_exists(deployment_plan_location.to_string())
+            .await?
+        {
+            return Ok(None);
+        }
+
+        let manifest_content = file_accessor
+            .read_file(manifest.location.to_string())
+            .await?;
+        let plan_content = file_accessor
+            .read_file(deployment_plan_location.to_string())
+            .await?;
+        let spec_file = DeploymentSpecificationFile::from_file_accessor(
+            deployment_plan_location,
+            file_accessor,
+        )
+        .await?;
+        let contracts_sources: BTreeMap<String, String> = file_accessor
+            .read_files(spec_file.get_emulated_contracts_paths())
+            .await?
+            .into_iter()
+            .collect();
+
+        // the requirements are read from the cache, along with their epoch and clarity version
+        let mut requirements: Vec<String> = manifest
+            .project
+            .requirements
+            .iter()
+            .flatten()
+            .map(|requirement| requirement.contract_id.clone())
+            .collect();
+        if let Some(ref plan) = spec_file.plan {
+            for batch in plan.batches.iter() {
+                for transaction in batch.transactions.iter() {
+                    if let TransactionSpecificationFile::EmulatedContractPublish(spec) = transaction
+                    {
+                        requirements
+                            .push(format!("{}.{}", spec.emulated_sender, spec.contract_name));
+                    }
+                }
+            }
+        }
+        let mut cached_paths = vec![];
+        for contract_id in requirements {
+            for extension in ["clar", "json"] {
+                let mut location = manifest.project.cache_location.clone();
+                location.append_path("requirements")?;
+                location.append_path(&format!("{}.{}", contract_id, extension))?;
+                let path = location.to_string();
+                if !cached_paths.contains(&path) && file_accessor.file_exists(path.clone()).await? {
+                    cached_paths.push(path);
+                }
+            }
+        }
+        let cached_requirements: BTreeMap<String, String> = file_accessor
+            .read_files(cached_paths)
+            .await?
+            .into_iter()
+            .collect();
+
+        let mut data = vec![];
+        data.extend_from_slice(env!("CARGO_PKG_VERSION").as_bytes());
+        data.extend_from_slice(manifest_content.as_bytes());
+        data.extend_from_slice(plan_content.as_bytes());
+        for (path, content) in contracts_sources.iter().chain(cached_requirements.iter()) {
+            data.extend_from_slice(path.as_bytes());
+            data.extend_from_slice(content.as_bytes());
+        }
+        Ok(Some(hash::Sha512Trunc256Sum::from_data(&data).to_hex()))
+    }
+
+    async fn load_simnet_snapshot(
+        &mut self,
+        manifest: &ProjectManifest,
+        deployment_plan_location: &FileLocation,
+        snapshot_location: &FileLocation,
+    ) -> Result<Option<SimnetSnapshot>, String> {
+        let hash = match self
+            .get_simnet_snapshot_hash(manifest, deployment_plan_location)
+            .await?
+        {
+            Some(hash) => hash,
+            None => return Ok(None),
+        };
+        // the snapshot is only read once per manifest, but the files it was built from may
+        // have changed since then (e.g. a cached requirement)
+        if let Some(snapshot) = self.snapshots.get(&manifest.location) {
+            if snapshot.hash == hash {
+                return Ok(Some(snapshot.clone()));
+            }
+            self.snapshots.remove(&manifest.location);
+        }
+        if !self
+            .file_accessor
+            .file_exists(snapshot_location.to_string())
+            .await?
+        {
+            return Ok(None);
+        }
+        let content = self
+            .file_accessor
+            .read_file(snapshot_location.to_string())
+            .await?;
+        let snapshot: SimnetSnapshot = match serde_json::from_str(&content) {
+            Ok(snapshot) => snapshot,
+            // a snapshot written by another version is simply rebuilt
+            Err(_) => return Ok(None),
+        };
+        if snapshot.hash != hash {
+            return Ok(None);
+        }
+
+        log!("{}", "using simnet snapshot".yellow().bold());
+        self.snapshots
+            .insert(manifest.location.clone(), snapshot.clone());
+        Ok(Some(snapshot))
+    }
+
+    async fn save_simnet_snapshot(
+        &mut self,
+        manifest: &ProjectManifest,
+        deployment_plan_location: &FileLocation,
+        snapshot_location: &FileLocation,
+        deployment: &DeploymentSpecification,
+    ) -> Result<(), String> {
+        let hash = match self
+            .get_simnet_snapshot_hash(manifest, deployment_plan_location)
+            .await?
+        {
+            Some(hash) => hash,
+            None => return Ok(()),
+        };
+
+        let contracts = deployment
+            .contracts
+            .keys()
+            .filter_map(|contract_id| {
+                Some(SnapshotContract {
+                    contract_id: contract_id.to_string(),
+                    location: self.contracts_locations.get(contract_id)?.to_string(),
+                    interface: self.contracts_interfaces.get(contract_id).cloned(),
+                    contract: self.parsed_contracts.get(contract_id)?.clone(),
+                })
+            })
+            .collect();
+        let snapshot = SimnetSnapshot {
+            hash,
+            deployer: self.deployer.clone(),
+            accounts: self.accounts.clone(),
+            contracts,
+            session: self.get_session().snapshot(),
+        };
+
+        let content = serde_json::to_vec(&snapshot)
+            .map_err(|e| format!("failed to serialize simnet snapshot: {}", e))?;
+        self.file_accessor
+            .write_file(snapshot_location.to_string(), &content)
+            .await?;
+        self.snapshots.insert(manifest.location.clone(), snapshot);
+        Ok(())
+    }
+
+    fn restore_simnet_snapshot(
+        &mut self,
+        manifest: &ProjectManifest,
+        snapshot: SimnetSnapshot,
+    ) -> Result<(), String> {
+        let mut session = initiate_session_from_deployment(manifest);
+        session.restore_snapshot(snapshot.session)?;
+
+        // the contracts of a previous session are replaced by the ones of the snapshot
+        self.contracts_locations.clear();
+        self.contracts_interfaces.clear();
+        self.parsed_contracts.clear();
+        self.deployer = snapshot.deployer;
+        self.accounts = snapshot.accounts;
+        for contract in snapshot.contracts {
+            let contract_id = QualifiedContractIdentifier::parse(&contract.contract_id)
+                .map_err(|e| e.to_string())?;
+            let location = FileLocation::try_parse(&contract.location, None).ok_or(format!(
+                "invalid contract location in snapshot: {}",
+                contract.location
+            ))?;
+            session
+                .asts
+                .insert(contract_id.clone(), contract.contract.ast.clone());
+            self.contracts_locations
+                .insert(contract_id.clone(), location);
+            if let Some(interface) = contract.interface {
+                self.contracts_interfaces
+                    .insert(contract_id.clone(), interface);
+            }
+            self.parsed_contracts.insert(contract_id, contract.contract);
+        }
+
         self.session = Some(session);
         Ok(())
     }
@@ -500,6 +952,18 @@ impl SDK {
         session.update_epoch(epoch)
     }
 
+    #[wasm_bindgen(js_name=setTxFee)]
+    pub fn set_tx_fee(&mut self, fee: Option<u64>) {
+        let session = self.get_session_mut();
+        session.set_tx_fee(fee)
+    }
+
+    #[wasm_bindgen(js_name=getTxFee)]
+    pub fn get_tx_fee(&mut self) -> Option<u64> {
+        let session = self.get_session_mut();
+        session.get_tx_fee()
+    }
+
     #[wasm_bindgen(js_name=getContractsInterfaces)]
     pub fn get_contracts_interfaces(&self) -> Result<JsValue, JsError> {
         let stringified_contracts_interfaces: HashMap<String, ContractInterface> = self
@@ -592,18 +1056,37 @@ impl SDK {
             method,
             args,
             sender,
+            post_conditions,
+            trace,
+            ..
         } = call_contract_args;
 
         let clarity_args: Vec<String> = args.iter().map(|a| uint8_to_string(a)).collect();
+        let post_conditions = post_conditions.decode()?;
 
         let session = self.get_session_mut();
-        let (execution, _) = match session.invoke_contract_call(
-            contract,
-            method,
-            &clarity_args,
-            sender,
-            test_name.into(),
-        ) {
+        session.set_call_tracing(*trace);
+        let result = match post_conditions {
+            Some((mode, post_conditions)) => session.invoke_contract_call_with_post_conditions(
+                contract,
+                method,
+                &clarity_args,
+                sender,
+                test_name.into(),
+                mode,
+                &post_conditions,
+            ),
+            None => session.invoke_contract_call(
+                contract,
+                method,
+                &clarity_args,
+                sender,
+                test_name.into(),
+            ),
+        };
+        let call_trace = session.take_call_trace();
+        session.set_call_tracing(false);
+        let (execution, _) = match result {
             Ok(res) => res,
             Err(diagnostics) => {
                 let mut message = format!(
@@ -620,17 +1103,23 @@ impl SDK {
             }
         };
 
-        Ok(execution_result_to_transaction_res(&execution))
+        let mut transaction_res = execution_result_to_transaction_res(&execution);
+        transaction_res.trace = call_trace.as_ref().map(CallTraceRes::from_call_frame);
+        Ok(transaction_res)
     }
 
     #[wasm_bindgen(js_name=callReadOnlyFn)]
-    pub fn call_read_only_fn(&mut self, args: &CallContractArgs) -> Result<TransactionRes, String> {
+    pub fn call_read_only_fn(
+        &mut self,
+        args: &CallContractArgs,
+    ) -> Result<TransactionResult, String> {
         let interface = self.get_function_interface(&args.contract, &args.method)?;
         if interface.access != ContractInterfaceFunctionAccess::read_only {
             return Err(format!("{} is not a read-only function", &args.method));
         }
 
-        self.invoke_contract_call(args, &self.current_test_name.clone())
+        self.invoke_contract_call(args, &self.current_test_name.clone())?
+            .into_js()
     }
 
     fn call_public_fn_private(
@@ -648,7 +1137,16 @@ impl SDK {
             session.advance_chain_tip(1);
         }
 
-        self.invoke_contract_call(args, &self.current_test_name.clone())
+        // the fee is paid even if the call fails, like on chain
+        let initial_tx_sponsor = session.interpreter.get_tx_sponsor();
+        session.set_tx_sponsor(args.sponsor.clone())?;
+        session.charge_tx_fee_on_next_transaction();
+        let result = self.invoke_contract_call(args, &self.current_test_name.clone());
+        let session = self.get_session_mut();
+        // the call is not run if its post-conditions can't be decoded
+        session.interpreter.clear_next_transaction();
+        session.interpreter.set_tx_sponsor(initial_tx_sponsor);
+        result
     }
 
     fn transfer_stx_private(
@@ -656,13 +1154,25 @@ impl SDK {
         args: &TransferSTXArgs,
         advance_chain_tip: bool,
     ) -> Result<TransactionRes, String> {
+        let post_conditions = args.post_conditions.decode()?;
         let session = self.get_session_mut();
         let initial_tx_sender = session.get_tx_sender();
         session.set_tx_sender(args.sender.to_string());
-
-        let execution = match session.stx_transfer(args.amount, &args.recipient) {
+        session.charge_tx_fee_on_next_transaction();
+
+        let result = match post_conditions {
+            Some((mode, post_conditions)) => session.stx_transfer_with_post_conditions(
+                args.amount,
+                &args.recipient,
+                mode,
+                &post_conditions,
+            ),
+            None => session.stx_transfer(args.amount, &args.recipient),
+        };
+        let execution = match result {
             Ok(res) => res,
             Err(diagnostics) => {
+                session.set_tx_sender(initial_tx_sender);
                 let mut message = format!("{}: {}", "STX transfer error", args.sender);
                 if let Some(diag) = diagnostics.last() {
                     message = format!("{} -> {}", message, diag.message);
@@ -688,6 +1198,7 @@ impl SDK {
             if advance_chain_tip {
                 session.advance_chain_tip(1);
             }
+            session.charge_tx_fee_on_next_transaction();
 
             let contract = ClarityContract {
                 code_source: ClarityCodeSource::ContractInMemory(args.content.clone()),
@@ -722,22 +1233,30 @@ impl SDK {
     }
 
     #[wasm_bindgen(js_name=deployContract)]
-    pub fn deploy_contract(&mut self, args: &DeployContractArgs) -> Result<TransactionRes, String> {
-        self.deploy_contract_private(args, true)
+    pub fn deploy_contract(
+        &mut self,
+        args: &DeployContractArgs,
+    ) -> Result<TransactionResult, String> {
+        self.deploy_contract_private(args, true)?.into_js()
     }
 
     #[wasm_bindgen(js_name = "transferSTX")]
-    pub fn transfer_stx(&mut self, args: &TransferSTXArgs) -> Result<TransactionRes, String> {
-        self.transfer_stx_private(args, true)
+    pub fn transfer_stx(&mut self, args: &TransferSTXArgs) -> Result<TransactionResult, String> {
+        self.transfer_stx_private(args, true)?.into_js()
     }
 
     #[wasm_bindgen(js_name = "callPublicFn")]
-    pub fn call_public_fn(&mut self, args: &CallContractArgs) -> Result<TransactionRes, String> {
-        self.call_public_fn_private(args, true)
+    pub fn call_public_fn(&mut self, args: &CallContractArgs) -> Result<TransactionResult, String> {
+        self.call_public_fn_private(args, true)?.into_js()
     }
 
     #[wasm_bindgen(js_name=mineBlock)]
-    pub fn mine_block_js(&mut self, js_txs: js_sys::Array) -> Result<JsValue, String> {
+    pub fn mine_block_js(
+        &mut self,
+        js_txs: js_sys::Array,
+        options: Option<BlockOptions>,
+    ) -> Result<TransactionResults, String> {
+        let context = BlockOptionsJSON::from_js(options)?;
         let mut results: Vec<TransactionRes> = vec![];
 
         let txs: Vec<TxArgs> = js_txs
@@ -758,21 +1277,169 @@ impl SDK {
         }
 
         let session = self.get_session_mut();
-        session.advance_chain_tip(1);
+        session.advance_chain_tip_with_context(1, &context);
+
+        let results = encode_to_js(&results).map_err(|e| format!("error: {}", e))?;
+        Ok(results.unchecked_into::<TransactionResults>())
+    }
+
+    #[wasm_bindgen(js_name=setMempoolOrdering)]
+    pub fn set_mempool_ordering(
+        &mut self,
+        ordering: &str,
+        seed: Option<u64>,
+    ) -> Result<(), String> {
+        let ordering = match ordering {
+            "fee-priority" => MempoolOrdering::FeePriority,
+            "fifo" => MempoolOrdering::Fifo,
+            "random" => MempoolOrdering::Random(seed.unwrap_or(0)),
+            _ => return Err(format!("Invalid mempool ordering {}", ordering)),
+        };
+        let session = self.get_session_mut();
+        session.mempool.set_ordering(ordering);
+        Ok(())
+    }
+
+    // the stub is either a clarity expression or a serialized clarity value
+    #[wasm_bindgen(js_name=stubFunction)]
+    pub fn stub_function(
+        &mut self,
+        contract: &str,
+        method: &str,
+        expression: Option<String>,
+        value: Option<js_sys::Uint8Array>,
+    ) -> Result<(), String> {
+        let body = match (expression, value) {
+            (Some(expression), None) => expression,
+            (None, Some(value)) => uint8_to_string(&value.to_vec()),
+            _ => return Err("a stub is either an expression or a value".into()),
+        };
+        let session = self.get_session_mut();
+        session.stub_function(contract, method, &body)
+    }
+
+    #[wasm_bindgen(js_name=removeStub)]
+    pub fn remove_stub(&mut self, contract: &str, method: &str) -> Result<(), String> {
+        let session = self.get_session_mut();
+        session.remove_stub(contract, method)
+    }
+
+    #[wasm_bindgen(js_name=getStubCalls)]
+    pub fn get_stub_calls(&mut self) -> Result<StubCalls, String> {
+        let session = self.get_session_mut();
+        let calls: Vec<StubCallRes> = session
+            .get_stub_calls()
+            .iter()
+            .map(|call| StubCallRes {
+                contract: call.contract_id.to_string(),
+                method: call.function.clone(),
+                caller: call.caller.as_ref().map(|c| c.to_string()),
+                args: call.args.iter().map(clarity_values::to_raw_value).collect(),
+            })
+            .collect();
+        let calls = encode_to_js(&calls).map_err(|e| format!("error: {}", e))?;
+        Ok(calls.unchecked_into::<StubCalls>())
+    }
 
-        encode_to_js(&results).map_err(|e| format!("error: {}", e))
+    #[wasm_bindgen(js_name=getNonce)]
+    pub fn get_nonce(&mut self, sender: &str) -> u64 {
+        let session = self.get_session_mut();
+        session.mempool.get_nonce(sender)
+    }
+
+    #[wasm_bindgen(js_name=submitTx)]
+    pub fn submit_tx(&mut self, js_tx: JsValue) -> Result<MempoolAdmissionResult, String> {
+        let args: MempoolTxArgs = js_tx
+            .into_serde()
+            .map_err(|e| format!("Failed to parse js tx: {:}", e))?;
+
+        let (sender, sponsor, post_conditions, payload) = if let Some(args) = args.call_public_fn {
+            let args = CallContractArgs::from_json_args(args);
+            let payload = MempoolTxPayload::ContractCall {
+                contract: args.contract,
+                method: args.method,
+                args: args.args.iter().map(|a| uint8_to_string(a)).collect(),
+            };
+            let post_conditions = args.post_conditions.decode()?;
+            (args.sender, args.sponsor, post_conditions, payload)
+        } else if let Some(args) = args.transfer_stx {
+            let payload = MempoolTxPayload::StxTransfer {
+                amount: args.amount,
+                recipient: args.recipient,
+            };
+            let post_conditions = args.post_conditions.decode()?;
+            (args.sender, None, post_conditions, payload)
+        } else {
+            return Err("Invalid tx arguments".into());
+        };
+
+        let session = self.get_session_mut();
+        let admission = session.submit_transaction(MempoolTransaction {
+            sender,
+            nonce: args.nonce,
+            fee: args.fee,
+            sponsor,
+            post_conditions,
+            payload,
+        })?;
+
+        let result = encode_to_js(&MempoolAdmissionRes {
+            txid: admission.txid,
+            replaced: admission.replaced,
+        })
+        .map_err(|e| format!("error: {}", e))?;
+        Ok(result.unchecked_into::<MempoolAdmissionResult>())
+    }
+
+    #[wasm_bindgen(js_name=mineMempoolBlock)]
+    pub fn mine_mempool_block(
+        &mut self,
+        options: Option<BlockOptions>,
+    ) -> Result<MinedMempoolTransactions, String> {
+        let context = BlockOptionsJSON::from_js(options)?;
+        let test_name = self.current_test_name.clone();
+        let session = self.get_session_mut();
+        let block = session.mine_mempool_block(&test_name, &context);
+
+        let mut results = vec![];
+        for mined in block.transactions {
+            let (result, error) = match mined.result {
+                Ok(execution) => (Some(execution_result_to_transaction_res(&execution)), None),
+                Err(diagnostics) => {
+                    let message = diagnostics
+                        .last()
+                        .map(|diag| diag.message.clone())
+                        .unwrap_or_else(|| "Runtime error".into());
+                    (None, Some(message))
+                }
+            };
+            results.push(MinedMempoolTransactionRes {
+                txid: mined.txid,
+                result,
+                error,
+            });
+        }
+
+        let results = encode_to_js(&results).map_err(|e| format!("error: {}", e))?;
+        Ok(results.unchecked_into::<MinedMempoolTransactions>())
     }
 
     #[wasm_bindgen(js_name=mineEmptyBlock)]
-    pub fn mine_empty_block(&mut self) -> u32 {
+    pub fn mine_empty_block(&mut self, options: Option<BlockOptions>) -> Result<u32, String> {
+        let context = BlockOptionsJSON::from_js(options)?;
         let session = self.get_session_mut();
-        session.advance_chain_tip(1)
+        Ok(session.advance_chain_tip_with_context(1, &context))
     }
 
     #[wasm_bindgen(js_name=mineEmptyBlocks)]
-    pub fn mine_empty_blocks(&mut self, count: Option<u32>) -> u32 {
+    pub fn mine_empty_blocks(
+        &mut self,
+        count: Option<u32>,
+        options: Option<BlockOptions>,
+    ) -> Result<u32, String> {
+        let context = BlockOptionsJSON::from_js(options)?;
         let session = self.get_session_mut();
-        session.advance_chain_tip(count.unwrap_or(1))
+        Ok(session.advance_chain_tip_with_context(count.unwrap_or(1), &context))
     }
 
     #[wasm_bindgen(js_name=runSnippet)]
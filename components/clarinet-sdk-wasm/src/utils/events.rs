@@ -1,75 +1,242 @@
 use clarity_repl::clarity::{
     events::{FTEventType, NFTEventType, STXEventType},
+    util::hash,
     vm::events::StacksTransactionEvent,
 };
-use serde::{Deserialize, Serialize};
-
-#[derive(Deserialize, Serialize)]
-#[serde(rename_all = "camelCase")]
-struct SmartContractEvent {
-    contract_identifier: String,
-    topic: String,
-    value: String,
+use clarity_repl::utils::value_to_string;
+use serde::Serialize;
+use tsify::Tsify;
+
+use super::clarity_values::to_raw_value;
+
+// the typescript definitions of the events are generated by tsify
+// `value` is the printed clarity value formatted like in the console, `raw_value` is its hex
+// encoding, decoded by the js sdk
+#[derive(Debug, PartialEq, Serialize, Tsify)]
+pub struct PrintEventData {
+    pub contract_identifier: String,
+    pub topic: String,
+    pub value: String,
+    pub raw_value: String,
 }
 
-#[derive(Deserialize, Serialize)]
-pub struct StacksEvent {
-    pub event: String,
-    pub data: serde_json::Value,
+// amounts are serialized as strings since they don't fit in a js number
+#[derive(Debug, PartialEq, Serialize, Tsify)]
+pub struct StxTransferEventData {
+    pub sender: String,
+    pub recipient: String,
+    pub amount: String,
+    pub memo: String,
 }
 
-#[allow(unused_macros)]
-macro_rules! log {
-    ( $( $t:tt )* ) => {
-        web_sys::console::log_1(&format!( $( $t )* ).into());
-    }
+#[derive(Debug, PartialEq, Serialize, Tsify)]
+pub struct StxMintEventData {
+    pub recipient: String,
+    pub amount: String,
+}
+
+#[derive(Debug, PartialEq, Serialize, Tsify)]
+pub struct StxBurnEventData {
+    pub sender: String,
+    pub amount: String,
+}
+
+#[derive(Debug, PartialEq, Serialize, Tsify)]
+pub struct StxLockEventData {
+    pub locked_amount: String,
+    pub unlock_height: String,
+    pub locked_address: String,
+    pub contract_identifier: String,
+}
+
+#[derive(Debug, PartialEq, Serialize, Tsify)]
+pub struct NftTransferEventData {
+    pub asset_identifier: String,
+    pub sender: String,
+    pub recipient: String,
+    pub raw_value: String,
+}
+
+#[derive(Debug, PartialEq, Serialize, Tsify)]
+pub struct NftMintEventData {
+    pub asset_identifier: String,
+    pub recipient: String,
+    pub raw_value: String,
+}
+
+#[derive(Debug, PartialEq, Serialize, Tsify)]
+pub struct NftBurnEventData {
+    pub asset_identifier: String,
+    pub sender: String,
+    pub raw_value: String,
+}
+
+#[derive(Debug, PartialEq, Serialize, Tsify)]
+pub struct FtTransferEventData {
+    pub asset_identifier: String,
+    pub sender: String,
+    pub recipient: String,
+    pub amount: String,
+}
+
+#[derive(Debug, PartialEq, Serialize, Tsify)]
+pub struct FtMintEventData {
+    pub asset_identifier: String,
+    pub recipient: String,
+    pub amount: String,
+}
+
+#[derive(Debug, PartialEq, Serialize, Tsify)]
+pub struct FtBurnEventData {
+    pub asset_identifier: String,
+    pub sender: String,
+    pub amount: String,
+}
+
+#[derive(Debug, PartialEq, Serialize, Tsify)]
+#[serde(tag = "event", content = "data", rename_all = "snake_case")]
+pub enum StacksEvent {
+    PrintEvent(PrintEventData),
+    StxTransferEvent(StxTransferEventData),
+    StxMintEvent(StxMintEventData),
+    StxBurnEvent(StxBurnEventData),
+    StxLockEvent(StxLockEventData),
+    NftTransferEvent(NftTransferEventData),
+    NftMintEvent(NftMintEventData),
+    NftBurnEvent(NftBurnEventData),
+    FtTransferEvent(FtTransferEventData),
+    FtMintEvent(FtMintEventData),
+    FtBurnEvent(FtBurnEventData),
 }
 
 pub fn serialize_event(event: &StacksTransactionEvent) -> StacksEvent {
     match event {
-        StacksTransactionEvent::SmartContractEvent(data) => StacksEvent {
-            event: "print_event".into(),
-            data: data.json_serialize(),
-        },
-        StacksTransactionEvent::STXEvent(STXEventType::STXTransferEvent(data)) => StacksEvent {
-            event: "stx_transfer_event".into(),
-            data: data.json_serialize(),
-        },
-        StacksTransactionEvent::STXEvent(STXEventType::STXMintEvent(data)) => StacksEvent {
-            event: "stx_mint_event".into(),
-            data: data.json_serialize(),
-        },
-        StacksTransactionEvent::STXEvent(STXEventType::STXBurnEvent(data)) => StacksEvent {
-            event: "stx_burn_event".into(),
-            data: data.json_serialize(),
-        },
-        StacksTransactionEvent::STXEvent(STXEventType::STXLockEvent(data)) => StacksEvent {
-            event: "stx_lock_event".into(),
-            data: data.json_serialize(),
-        },
-        StacksTransactionEvent::NFTEvent(NFTEventType::NFTTransferEvent(data)) => StacksEvent {
-            event: "nft_transfer_event".into(),
-            data: data.json_serialize(),
-        },
-        StacksTransactionEvent::NFTEvent(NFTEventType::NFTMintEvent(data)) => StacksEvent {
-            event: "nft_mint_event".into(),
-            data: data.json_serialize(),
-        },
-        StacksTransactionEvent::NFTEvent(NFTEventType::NFTBurnEvent(data)) => StacksEvent {
-            event: "nft_burn_event".into(),
-            data: data.json_serialize(),
-        },
-        StacksTransactionEvent::FTEvent(FTEventType::FTTransferEvent(data)) => StacksEvent {
-            event: "ft_transfer_event".into(),
-            data: data.json_serialize(),
-        },
-        StacksTransactionEvent::FTEvent(FTEventType::FTMintEvent(data)) => StacksEvent {
-            event: "ft_mint_event".into(),
-            data: data.json_serialize(),
-        },
-        StacksTransactionEvent::FTEvent(FTEventType::FTBurnEvent(data)) => StacksEvent {
-            event: "ft_burn_event".into(),
-            data: data.json_serialize(),
-        },
+        StacksTransactionEvent::SmartContractEvent(data) => {
+            StacksEvent::PrintEvent(PrintEventData {
+                contract_identifier: data.key.0.to_string(),
+                topic: data.key.1.clone(),
+                value: value_to_string(&data.value),
+                raw_value: to_raw_value(&data.value),
+            })
+        }
+        StacksTransactionEvent::STXEvent(STXEventType::STXTransferEvent(data)) => {
+            StacksEvent::StxTransferEvent(StxTransferEventData {
+                sender: data.sender.to_string(),
+                recipient: data.recipient.to_string(),
+                amount: data.amount.to_string(),
+                memo: format!("0x{}", hash::to_hex(&data.memo.data)),
+            })
+        }
+        StacksTransactionEvent::STXEvent(STXEventType::STXMintEvent(data)) => {
+            StacksEvent::StxMintEvent(StxMintEventData {
+                recipient: data.recipient.to_string(),
+                amount: data.amount.to_string(),
+            })
+        }
+        StacksTransactionEvent::STXEvent(STXEventType::STXBurnEvent(data)) => {
+            StacksEvent::StxBurnEvent(StxBurnEventData {
+                sender: data.sender.to_string(),
+                amount: data.amount.to_string(),
+            })
+        }
+        StacksTransactionEvent::STXEvent(STXEventType::STXLockEvent(data)) => {
+            StacksEvent::StxLockEvent(StxLockEventData {
+                locked_amount: data.locked_amount.to_string(),
+                unlock_height: data.unlock_height.to_string(),
+                locked_address: data.locked_address.to_string(),
+                contract_identifier: data.contract_identifier.to_string(),
+            })
+        }
+        StacksTransactionEvent::NFTEvent(NFTEventType::NFTTransferEvent(data)) => {
+            StacksEvent::NftTransferEvent(NftTransferEventData {
+                asset_identifier: data.asset_identifier.to_string(),
+                sender: data.sender.to_string(),
+                recipient: data.recipient.to_string(),
+                raw_value: to_raw_value(&data.value),
+            })
+        }
+        StacksTransactionEvent::NFTEvent(NFTEventType::NFTMintEvent(data)) => {
+            StacksEvent::NftMintEvent(NftMintEventData {
+                asset_identifier: data.asset_identifier.to_string(),
+                recipient: data.recipient.to_string(),
+                raw_value: to_raw_value(&data.value),
+            })
+        }
+        StacksTransactionEvent::NFTEvent(NFTEventType::NFTBurnEvent(data)) => {
+            StacksEvent::NftBurnEvent(NftBurnEventData {
+                asset_identifier: data.asset_identifier.to_string(),
+                sender: data.sender.to_string(),
+                raw_value: to_raw_value(&data.value),
+            })
+        }
+        StacksTransactionEvent::FTEvent(FTEventType::FTTransferEvent(data)) => {
+            StacksEvent::FtTransferEvent(FtTransferEventData {
+                asset_identifier: data.asset_identifier.to_string(),
+                sender: data.sender.to_string(),
+                recipient: data.recipient.to_string(),
+                amount: data.amount.to_string(),
+            })
+        }
+        StacksTransactionEvent::FTEvent(FTEventType::FTMintEvent(data)) => {
+            StacksEvent::FtMintEvent(FtMintEventData {
+                asset_identifier: data.asset_identifier.to_string(),
+                recipient: data.recipient.to_string(),
+                amount: data.amount.to_string(),
+            })
+        }
+        StacksTransactionEvent::FTEvent(FTEventType::FTBurnEvent(data)) => {
+            StacksEvent::FtBurnEvent(FtBurnEventData {
+                asset_identifier: data.asset_identifier.to_string(),
+                sender: data.sender.to_string(),
+                amount: data.amount.to_string(),
+            })
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use clarity_repl::clarity::vm::events::{SmartContractEventData, StacksTransactionEvent};
+    use clarity_repl::clarity::vm::types::QualifiedContractIdentifier;
+    use clarity_repl::clarity::vm::Value;
+
+    use super::{serialize_event, StacksEvent, StxMintEventData};
+
+    #[test]
+    fn it_serializes_events_as_tagged_objects() {
+        let event = StacksEvent::StxMintEvent(StxMintEventData {
+            recipient: "ST1PQHQKV0RJXZFY1DGX8MNSNYVE3VGZJSRTPGZGM".into(),
+            amount: "1000".into(),
+        });
+        assert_eq!(
+            serde_json::to_value(&event).unwrap(),
+            serde_json::json!({
+                "event": "stx_mint_event",
+                "data": {
+                    "recipient": "ST1PQHQKV0RJXZFY1DGX8MNSNYVE3VGZJSRTPGZGM",
+                    "amount": "1000",
+                },
+            })
+        );
+    }
+
+    #[test]
+    fn it_serializes_the_printed_value() {
+        let event = StacksTransactionEvent::SmartContractEvent(SmartContractEventData {
+            key: (
+                QualifiedContractIdentifier::parse(
+                    "ST1PQHQKV0RJXZFY1DGX8MNSNYVE3VGZJSRTPGZGM.counter",
+                )
+                .unwrap(),
+                "print".into(),
+            ),
+            value: Value::some(Value::UInt(1)).unwrap(),
+        });
+        let data = match serialize_event(&event) {
+            StacksEvent::PrintEvent(data) => data,
+            _ => unreachable!(),
+        };
+        assert_eq!(data.value, "(some u1)");
+        assert_eq!(data.raw_value, "0x0a0100000000000000000000000000000001");
     }
 }
@@ -1,7 +1,19 @@
-import { Cl, ClarityValue } from "@stacks/transactions";
+import {
+  Cl,
+  ClarityValue,
+  PostCondition,
+  PostConditionMode,
+  serializePostCondition,
+} from "@stacks/transactions";
 import {
   SDK,
-  TransactionRes,
+  TransactionResult,
+  StacksEvent,
+  CallTrace,
+  ExecutionCost,
+  BlockOptions,
+  MempoolAdmission,
+  MempoolOrdering,
   CallContractArgs,
   DeployContractArgs,
   TransferSTXArgs,
@@ -20,14 +32,45 @@ BigInt.prototype.toJSON = function () {
   return this.toString();
 };
 
-export type ClarityEvent = {
-  event: string;
-  data: { raw_value?: string; value?: ClarityValue; [key: string]: any };
+// events carrying a clarity value (print and nft events) also expose it decoded as `value`,
+// in place of the string representation of the print events
+type DecodedEvent<E extends StacksEvent> = E extends { data: { raw_value: string } }
+  ? { event: E["event"]; data: Omit<E["data"], "value"> & { value: ClarityValue } }
+  : E;
+
+export type ClarityEvent = DecodedEvent<StacksEvent>;
+
+// the calls made by a contract call, with their arguments, results, events and costs
+export type ParsedCallTrace = {
+  contract: string;
+  method: string;
+  args: ClarityValue[];
+  result?: ClarityValue;
+  error?: string;
+  events: ClarityEvent[];
+  cost: ExecutionCost;
+  calls: ParsedCallTrace[];
 };
 
 export type ParsedTransactionResult = {
   result: ClarityValue;
   events: ClarityEvent[];
+  trace?: ParsedCallTrace;
+};
+
+// post-conditions are checked against the assets sent by the transaction, which is aborted if they
+// aren't met. like in stacks.js, the mode defaults to `Deny` when post-conditions are provided
+export type PostConditionsOptions = {
+  postConditions?: PostCondition[];
+  postConditionMode?: PostConditionMode;
+};
+
+// when a transaction fee is set with `setTxFee`, it's paid by the sponsor instead of the sender.
+// the sponsor is also exposed to the contract as `tx-sponsor?`
+// `trace` returns the trace of the call along with its result
+export type CallFnOptions = PostConditionsOptions & {
+  sponsor?: string;
+  trace?: boolean;
 };
 
 export type CallFn = (
@@ -35,6 +78,7 @@ export type CallFn = (
   method: string,
   args: ClarityValue[],
   sender: string,
+  options?: CallFnOptions,
 ) => ParsedTransactionResult;
 
 export type DeployContractOptions = {
@@ -51,6 +95,7 @@ export type TransferSTX = (
   amount: number | bigint,
   recipient: string,
   sender: string,
+  options?: PostConditionsOptions,
 ) => ParsedTransactionResult;
 
 export type Tx =
@@ -60,7 +105,7 @@ export type Tx =
         method: string;
         args: ClarityValue[];
         sender: string;
-      };
+      } & CallFnOptions;
       deployContract?: never;
       transferSTX?: never;
     }
@@ -77,12 +122,18 @@ export type Tx =
   | {
       callPublicFn?: never;
       deployContradct?: never;
-      transferSTX: { amount: number; recipient: string; sender: string };
+      transferSTX: { amount: number; recipient: string; sender: string } & PostConditionsOptions;
     };
 
 export const tx = {
-  callPublicFn: (contract: string, method: string, args: ClarityValue[], sender: string): Tx => ({
-    callPublicFn: { contract, method, args, sender },
+  callPublicFn: (
+    contract: string,
+    method: string,
+    args: ClarityValue[],
+    sender: string,
+    options?: CallFnOptions,
+  ): Tx => ({
+    callPublicFn: { contract, method, args, sender, ...options },
   }),
   deployContract: (
     name: string,
@@ -92,17 +143,47 @@ export const tx = {
   ): Tx => ({
     deployContract: { name, content, options, sender },
   }),
-  transferSTX: (amount: number, recipient: string, sender: string): Tx => ({
-    transferSTX: { amount, recipient, sender },
+  transferSTX: (
+    amount: number,
+    recipient: string,
+    sender: string,
+    options?: PostConditionsOptions,
+  ): Tx => ({
+    transferSTX: { amount, recipient, sender, ...options },
   }),
 };
 
-export type MineBlock = (txs: Array<Tx>) => ParsedTransactionResult[];
+export type { BlockOptions, ExecutionCost, MempoolAdmission, MempoolOrdering };
+
+export type MineBlock = (txs: Array<Tx>, options?: BlockOptions) => ParsedTransactionResult[];
+
+// transactions submitted to the mempool are mined by `mineMempoolBlock`, in the order defined by
+// `setMempoolOrdering` and once the previous nonces of their sender have been mined
+export type MempoolTxOptions = { nonce: number; fee: number };
+export type SubmitTx = (tx: Tx, options: MempoolTxOptions) => MempoolAdmission;
+// a transaction aborted by a runtime error is mined with an `error` instead of a `result`
+export type MinedMempoolTx = {
+  txid: number;
+  result?: ClarityValue;
+  events?: ClarityEvent[];
+  error?: string;
+};
+export type MineMempoolBlock = (options?: BlockOptions) => MinedMempoolTx[];
 export type GetDataVar = (contract: string, dataVar: string) => ClarityValue;
 export type GetMapEntry = (contract: string, mapName: string, mapKey: ClarityValue) => ClarityValue;
 export type GetContractAST = (contractId: string) => ContractAST;
 export type GetContractsInterfaces = () => Map<string, ContractInterface>;
 export type RunSnippet = (snippet: string) => ClarityValue | string;
+// the stub replaces the body of the function, it's either a value or a clarity expression
+// evaluated in the context of the contract
+export type StubFunction = (contract: string, method: string, stub: ClarityValue | string) => void;
+export type ParsedStubCall = {
+  contract: string;
+  method: string;
+  caller?: string;
+  args: ClarityValue[];
+};
+export type GetStubCalls = () => ParsedStubCall[];
 
 // because the session is wrapped in a proxy the types need to be hardcoded
 export type Simnet = {
@@ -116,43 +197,86 @@ export type Simnet = {
           ? TransferSTX
           : K extends "mineBlock"
             ? MineBlock
-            : K extends "getDataVar"
-              ? GetDataVar
-              : K extends "getMapEntry"
-                ? GetMapEntry
-                : K extends "getContractAST"
-                  ? GetContractAST
-                  : K extends "getContractsInterfaces"
-                    ? GetContractsInterfaces
-                    : SDK[K];
+            : K extends "submitTx"
+              ? SubmitTx
+              : K extends "mineMempoolBlock"
+                ? MineMempoolBlock
+                : K extends "getDataVar"
+                  ? GetDataVar
+                  : K extends "getMapEntry"
+                    ? GetMapEntry
+                    : K extends "getContractAST"
+                      ? GetContractAST
+                      : K extends "getContractsInterfaces"
+                        ? GetContractsInterfaces
+                        : K extends "stubFunction"
+                          ? StubFunction
+                          : K extends "getStubCalls"
+                            ? GetStubCalls
+                            : SDK[K];
 };
 
-function parseEvents(events: string): ClarityEvent[] {
-  try {
-    // @todo: improve type safety
-    return JSON.parse(events).map((e: string) => {
-      const { event, data } = JSON.parse(e);
-      if ("raw_value" in data) {
-        data.value = Cl.deserialize(data.raw_value);
-      }
-      return {
-        event: event,
-        data: data,
-      };
-    });
-  } catch (e) {
-    console.error(`Fail to parse events: ${e}`);
-    return [];
+function parseEvent(event: StacksEvent): ClarityEvent {
+  if ("raw_value" in event.data) {
+    return {
+      ...event,
+      data: { ...event.data, value: Cl.deserialize(event.data.raw_value) },
+    } as ClarityEvent;
   }
+  return event as ClarityEvent;
+}
+
+function parseCallTrace(trace: CallTrace): ParsedCallTrace {
+  return {
+    ...trace,
+    args: trace.args.map((a) => Cl.deserialize(a)),
+    result: trace.result ? Cl.deserialize(trace.result) : undefined,
+    events: trace.events.map(parseEvent),
+    calls: trace.calls.map(parseCallTrace),
+  };
 }
 
-function parseTxResponse(response: TransactionRes): ParsedTransactionResult {
+function parseTxResponse(response: TransactionResult): ParsedTransactionResult {
   return {
     result: Cl.deserialize(response.result),
-    events: parseEvents(response.events),
+    events: response.events.map(parseEvent),
+    ...(response.trace && { trace: parseCallTrace(response.trace) }),
+  };
+}
+
+function serializePostConditions(options?: PostConditionsOptions) {
+  return (options?.postConditions ?? []).map((p) => serializePostCondition(p));
+}
+
+// the txs of mineBlock are passed as json, so the bytes are passed as arrays of numbers
+function serializePostConditionsJSON(options: PostConditionsOptions) {
+  return {
+    post_conditions: serializePostConditions(options).map((p) => Array.from(p)),
+    post_condition_mode: options.postConditionMode,
   };
 }
 
+// the txs of mineBlock and submitTx are passed as json
+function serializeTxJSON(tx: Tx) {
+  if (tx.callPublicFn) {
+    const { postConditions, postConditionMode, ...callPublicFn } = tx.callPublicFn;
+    return {
+      callPublicFn: {
+        ...callPublicFn,
+        args_maps: tx.callPublicFn.args.map((a) => Cl.serialize(a)),
+        ...serializePostConditionsJSON(tx.callPublicFn),
+      },
+    };
+  }
+  if (tx.transferSTX) {
+    const { postConditions, postConditionMode, ...transferSTX } = tx.transferSTX;
+    return {
+      transferSTX: { ...transferSTX, ...serializePostConditionsJSON(tx.transferSTX) },
+    };
+  }
+  return tx;
+}
+
 const getSessionProxy = () => ({
   get(session: SDK, prop: keyof SDK, receiver: any) {
     // some of the WASM methods are proxied here to:
@@ -160,13 +284,17 @@ const getSessionProxy = () => ({
     // - deserialize output into clarity values
 
     if (prop === "callReadOnlyFn" || prop === "callPublicFn") {
-      const callFn: CallFn = (contract, method, args, sender) => {
+      const callFn: CallFn = (contract, method, args, sender, options) => {
         const response = session[prop](
           new CallContractArgs(
             contract,
             method,
             args.map((a) => Cl.serialize(a)),
             sender,
+            serializePostConditions(options),
+            options?.postConditionMode,
+            options?.sponsor,
+            options?.trace,
           ),
         );
         return parseTxResponse(response);
@@ -201,33 +329,67 @@ const getSessionProxy = () => ({
     }
 
     if (prop === "transferSTX") {
-      const callTransferSTX: TransferSTX = (amount, ...args) => {
-        const response = session.transferSTX(new TransferSTXArgs(BigInt(amount), ...args));
+      const callTransferSTX: TransferSTX = (amount, recipient, sender, options) => {
+        const response = session.transferSTX(
+          new TransferSTXArgs(
+            BigInt(amount),
+            recipient,
+            sender,
+            serializePostConditions(options),
+            options?.postConditionMode,
+          ),
+        );
         return parseTxResponse(response);
       };
       return callTransferSTX;
     }
 
     if (prop === "mineBlock") {
-      const callMineBlock: MineBlock = (txs) => {
-        const serializedTxs = txs.map((tx) => {
-          if (tx.callPublicFn) {
-            return {
-              callPublicFn: {
-                ...tx.callPublicFn,
-                args_maps: tx.callPublicFn.args.map((a) => Cl.serialize(a)),
-              },
-            };
-          }
-          return tx;
-        });
-
-        const responses: TransactionRes[] = session.mineBlock(serializedTxs);
+      const callMineBlock: MineBlock = (txs, options) => {
+        const responses = session.mineBlock(txs.map(serializeTxJSON), options);
         return responses.map(parseTxResponse);
       };
       return callMineBlock;
     }
 
+    if (prop === "submitTx") {
+      const callSubmitTx: SubmitTx = (tx, options) => {
+        return session.submitTx({ ...serializeTxJSON(tx), ...options });
+      };
+      return callSubmitTx;
+    }
+
+    if (prop === "mineMempoolBlock") {
+      const callMineMempoolBlock: MineMempoolBlock = (options) => {
+        const responses = session.mineMempoolBlock(options);
+        return responses.map(({ txid, result, error }) =>
+          result ? { txid, ...parseTxResponse(result) } : { txid, error },
+        );
+      };
+      return callMineMempoolBlock;
+    }
+
+    if (prop === "stubFunction") {
+      const stubFunction: StubFunction = (contract, method, stub) => {
+        if (typeof stub === "string") {
+          session.stubFunction(contract, method, stub);
+        } else {
+          session.stubFunction(contract, method, undefined, Cl.serialize(stub));
+        }
+      };
+      return stubFunction;
+    }
+
+    if (prop === "getStubCalls") {
+      const getStubCalls: GetStubCalls = () => {
+        return session.getStubCalls().map((call) => ({
+          ...call,
+          args: call.args.map((a) => Cl.deserialize(a)),
+        }));
+      };
+      return getStubCalls;
+    }
+
     if (prop === "getDataVar") {
       const getDataVar: GetDataVar = (...args) => {
         const response = session.getDataVar(...args);
@@ -250,18 +412,28 @@ const getSessionProxy = () => ({
   },
 });
 
+export type InitSimnetOptions = {
+  // start from the post-deployment snapshot stored in .cache/, it's built on first use and
+  // rebuilt whenever the manifest, the deployment plan or a contract changes
+  snapshot?: boolean;
+};
+
 // load wasm only once and memoize it
 function memoizedInit() {
   let simnet: Simnet | null = null;
 
-  return async (manifestPath = "./Clarinet.toml", noCache = false) => {
+  return async (
+    manifestPath = "./Clarinet.toml",
+    noCache = false,
+    options: InitSimnetOptions = {},
+  ) => {
     if (noCache || !simnet) {
       const module = await wasmModule;
       simnet = new Proxy(new module.SDK(vfs), getSessionProxy()) as unknown as Simnet;
     }
 
     // start a new simnet session
-    await simnet.initSession(process.cwd(), manifestPath);
+    await simnet.initSession(process.cwd(), manifestPath, options.snapshot);
     return simnet;
   };
 }
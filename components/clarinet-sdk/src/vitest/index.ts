@@ -36,6 +36,11 @@ export function getClarinetVitestsArgv() {
       alias: "costs-file",
       type: "string",
       default: "costs-reports.json",
+    })
+    .option("snapshot", {
+      description: "Share a snapshot of the deployed simnet across the test files",
+      type: "boolean",
+      default: false,
     }).argv;
 }
 
@@ -16,6 +16,7 @@ import { vitestSetupFilePath, getClarinetVitestsArgv } from "@hirosystems/clarin
   The `getClarinetVitestsArgv()` will parse options passed to the command `vitest run --`
     - vitest run -- --manifest ./Clarinet.toml  # pass a custom path
     - vitest run -- --coverage --costs          # collect coverage and cost reports
+    - vitest run -- --snapshot                  # start the sessions from a deployment snapshot
 */
 
 export default defineConfig({
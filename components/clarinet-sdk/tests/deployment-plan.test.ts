@@ -17,14 +17,23 @@ const customDeploymentPlanPath = path.join(
   "tests/fixtures/deployments/custom.simnet-plan.yaml",
 );
 
+const snapshotPath = path.join(process.cwd(), "tests/fixtures/.cache/simnet-snapshot.json");
+
 function deleteExistingDeploymentPlan() {
   if (fs.existsSync(deploymentPlanPath)) {
     fs.unlinkSync(deploymentPlanPath);
   }
 }
 
+function deleteExistingSnapshot() {
+  if (fs.existsSync(snapshotPath)) {
+    fs.unlinkSync(snapshotPath);
+  }
+}
+
 afterEach(() => {
   deleteExistingDeploymentPlan();
+  deleteExistingSnapshot();
 });
 
 describe("deployment plans test", async () => {
@@ -70,3 +79,40 @@ describe("deployment plans test", async () => {
     expect(count).toStrictEqual(Cl.uint(2));
   });
 });
+
+describe("simnet snapshot test", async () => {
+  const address1 = "ST1SJ3DTE5DN7X54YDH5D64R3BCB6A2AG2ZQ8YPD5";
+
+  it("starts new sessions from the post-deployment snapshot", async () => {
+    deleteExistingSnapshot();
+
+    const simnet = await initSimnet("tests/fixtures/Clarinet.toml", true, { snapshot: true });
+    expect(fs.existsSync(snapshotPath)).toBe(true);
+    const blockHeight = simnet.blockHeight;
+    const interfaces = simnet.getContractsInterfaces();
+
+    simnet.callPublicFn("counter", "increment", [], address1);
+    expect(simnet.getDataVar("counter", "count")).toStrictEqual(Cl.uint(1));
+
+    // a new sdk instance, as in another test file, starts from the snapshot
+    const restored = await initSimnet("tests/fixtures/Clarinet.toml", true, { snapshot: true });
+    expect(restored.blockHeight).toBe(blockHeight);
+    expect(restored.getContractsInterfaces().size).toBe(interfaces.size);
+    expect(restored.getDataVar("counter", "count")).toStrictEqual(Cl.uint(0));
+
+    const { result } = restored.callPublicFn("counter", "increment", [], address1);
+    expect(result).toStrictEqual(Cl.ok(Cl.bool(true)));
+    expect(restored.getDataVar("counter", "count")).toStrictEqual(Cl.uint(1));
+  });
+
+  it("rebuilds an outdated snapshot", async () => {
+    await initSimnet("tests/fixtures/Clarinet.toml", true, { snapshot: true });
+    const snapshot = JSON.parse(fs.readFileSync(snapshotPath, "utf-8"));
+    const hash = snapshot.hash;
+    fs.writeFileSync(snapshotPath, JSON.stringify({ ...snapshot, hash: "outdated" }));
+
+    const simnet = await initSimnet("tests/fixtures/Clarinet.toml", true, { snapshot: true });
+    expect(simnet.getDataVar("counter", "count")).toStrictEqual(Cl.uint(0));
+    expect(JSON.parse(fs.readFileSync(snapshotPath, "utf-8")).hash).toBe(hash);
+  });
+});
@@ -1,6 +1,11 @@
 import fs from "node:fs";
 import path from "node:path";
-import { Cl } from "@stacks/transactions";
+import {
+  Cl,
+  FungibleConditionCode,
+  PostConditionMode,
+  makeStandardSTXPostCondition,
+} from "@stacks/transactions";
 import { describe, expect, it, beforeEach, afterEach, assert } from "vitest";
 
 // test the built package and not the source code
@@ -114,8 +119,19 @@ describe("simnet can call contracts function", () => {
 
     expect(res.events).toHaveLength(2);
     const printEvent = res.events[0];
-    expect(printEvent.event).toBe("print_event");
+    assert(printEvent.event === "print_event");
+    expect(printEvent.data.topic).toBe("print");
     expect(printEvent.data.value).toStrictEqual(Cl.stringAscii("call increment"));
+
+    expect(res.events[1]).toStrictEqual({
+      event: "stx_transfer_event",
+      data: {
+        sender: address1,
+        recipient: `${deployerAddr}.counter`,
+        amount: "1000000",
+        memo: "0x",
+      },
+    });
   });
 
   it("can call public functions with arguments", () => {
@@ -154,6 +170,21 @@ describe("simnet can call contracts function", () => {
     expect(simnet.blockHeight).toStrictEqual(initalBH + 1);
   });
 
+  it("can set the info of the mined blocks", () => {
+    simnet.mineEmptyBlock({ time: 1700000000, miner: address2 });
+    simnet.mineEmptyBlock();
+    const time = simnet.runSnippet("(get-block-info? time (- block-height u1))");
+    expect(time).toStrictEqual(Cl.some(Cl.uint(1700000000)));
+    const miner = simnet.runSnippet("(get-block-info? miner-address (- block-height u1))");
+    expect(miner).toStrictEqual(Cl.some(Cl.standardPrincipal(address2)));
+
+    // the following blocks are derived from the overridden one
+    simnet.mineBlock([], { time: 1800000000 });
+    simnet.mineEmptyBlocks(2);
+    const nextTime = simnet.runSnippet("(get-block-info? time (- block-height u1))");
+    expect(nextTime).toStrictEqual(Cl.some(Cl.uint(1800001800)));
+  });
+
   it("can get updated assets map", () => {
     simnet.callPublicFn("counter", "increment", [], address1);
     simnet.callPublicFn("counter", "increment", [], address1);
@@ -288,6 +319,228 @@ describe("simnet can transfer stx", () => {
   });
 });
 
+describe("simnet enforces post-conditions", () => {
+  it("can call public functions with post-conditions", () => {
+    const postConditions = [
+      makeStandardSTXPostCondition(address1, FungibleConditionCode.LessEqual, 1000000),
+    ];
+    const res = simnet.callPublicFn("counter", "increment", [], address1, { postConditions });
+    expect(res.result).toStrictEqual(Cl.ok(Cl.bool(true)));
+  });
+
+  it("aborts transactions that don't meet their post-conditions", () => {
+    const postConditions = [
+      makeStandardSTXPostCondition(address1, FungibleConditionCode.Less, 1000000),
+    ];
+    expect(() =>
+      simnet.callPublicFn("counter", "increment", [], address1, { postConditions }),
+    ).toThrow("post-condition check failure");
+
+    // the transaction is rolled back
+    const count = simnet.callReadOnlyFn("counter", "get-count", [], address1);
+    expect(count.result).toStrictEqual(Cl.ok(Cl.tuple({ count: Cl.uint(0) })));
+  });
+
+  it("denies the transfer of assets without post-conditions in deny mode", () => {
+    expect(() =>
+      simnet.transferSTX(1000, address2, address1, { postConditionMode: PostConditionMode.Deny }),
+    ).toThrow("post-condition check failure");
+
+    const res = simnet.transferSTX(1000, address2, address1, {
+      postConditionMode: PostConditionMode.Allow,
+    });
+    expect(res.result).toStrictEqual(Cl.ok(Cl.bool(true)));
+  });
+
+  it("checks the post-conditions of the transactions of a block", () => {
+    const res = simnet.mineBlock([
+      tx.callPublicFn("counter", "increment", [], address1, {
+        postConditions: [
+          makeStandardSTXPostCondition(address1, FungibleConditionCode.Equal, 1000000),
+        ],
+      }),
+      tx.transferSTX(1000, address2, address1, {
+        postConditions: [makeStandardSTXPostCondition(address1, FungibleConditionCode.Equal, 1000)],
+      }),
+    ]);
+    expect(res).toHaveLength(2);
+    expect(res[1].result).toStrictEqual(Cl.ok(Cl.bool(true)));
+  });
+});
+
+describe("simnet charges transaction fees", () => {
+  it("charges the fee to the sender", () => {
+    simnet.setTxFee(1000n);
+    expect(simnet.getTxFee()).toBe(1000n);
+
+    simnet.callPublicFn("counter", "increment", [], address1);
+    simnet.transferSTX(1000, address2, address1);
+
+    const STX = simnet.getAssetsMap().get("STX")!;
+    expect(STX.get(address1)).toBe(99999998997000n);
+    expect(STX.get(address2)).toBe(100000000001000n);
+  });
+
+  it("charges the fee to the sponsor of a contract call", () => {
+    simnet.setTxFee(1000n);
+
+    const res = simnet.callPublicFn("counter", "increment", [], address1, { sponsor: address2 });
+    expect(res.result).toStrictEqual(Cl.ok(Cl.bool(true)));
+
+    const block = simnet.mineBlock([
+      tx.callPublicFn("counter", "increment", [], address1, { sponsor: address2 }),
+    ]);
+    expect(block[0].result).toStrictEqual(Cl.ok(Cl.bool(true)));
+
+    const STX = simnet.getAssetsMap().get("STX")!;
+    expect(STX.get(address1)).toBe(99999998000000n);
+    expect(STX.get(address2)).toBe(99999999998000n);
+  });
+
+  it("reports an invalid sponsor", () => {
+    simnet.setTxFee(1000n);
+
+    expect(() =>
+      simnet.callPublicFn("counter", "increment", [], address1, { sponsor: "invalid" }),
+    ).toThrow("invalid sponsor invalid");
+
+    const STX = simnet.getAssetsMap().get("STX")!;
+    expect(STX.get(address1)).toBe(100000000000000n);
+  });
+});
+
+describe("simnet mempool", () => {
+  it("mines the pending transactions by fee priority", () => {
+    simnet.submitTx(tx.callPublicFn("counter", "add", [Cl.uint(2)], address1), {
+      nonce: 0,
+      fee: 100,
+    });
+    simnet.submitTx(tx.callPublicFn("counter", "add", [Cl.uint(3)], address2), {
+      nonce: 0,
+      fee: 200,
+    });
+
+    const block = simnet.mineMempoolBlock();
+    expect(block.map(({ txid }) => txid)).toStrictEqual([1, 0]);
+    expect(block[0].events?.[0].event).toBe("print_event");
+    expect(simnet.getNonce(address1)).toBe(1n);
+
+    const count = simnet.getDataVar("counter", "count");
+    expect(count).toStrictEqual(Cl.uint(5));
+  });
+
+  it("waits for the missing nonces and replaces transactions", () => {
+    simnet.setMempoolOrdering("fifo");
+    simnet.submitTx(tx.transferSTX(100, address2, address1), { nonce: 1, fee: 100 });
+    expect(simnet.mineMempoolBlock()).toHaveLength(0);
+
+    simnet.submitTx(tx.transferSTX(100, address2, address1), { nonce: 0, fee: 100 });
+    expect(() =>
+      simnet.submitTx(tx.transferSTX(200, address2, address1), { nonce: 0, fee: 100 }),
+    ).toThrow("conflicting nonce");
+    const admission = simnet.submitTx(tx.transferSTX(200, address2, address1), {
+      nonce: 0,
+      fee: 150,
+    });
+    expect(admission).toStrictEqual({ txid: 2, replaced: 1 });
+
+    const block = simnet.mineMempoolBlock();
+    expect(block.map(({ txid }) => txid)).toStrictEqual([2, 0]);
+    const STX = simnet.getAssetsMap().get("STX")!;
+    expect(STX.get(address1)).toBe(99999999999450n);
+  });
+
+  it("checks the post-conditions of the pending transactions", () => {
+    simnet.submitTx(
+      tx.transferSTX(1000, address2, address1, {
+        postConditions: [makeStandardSTXPostCondition(address1, FungibleConditionCode.Less, 1000)],
+      }),
+      { nonce: 0, fee: 100 },
+    );
+
+    const block = simnet.mineMempoolBlock();
+    expect(block).toHaveLength(1);
+    expect(block[0].error).toContain("post-condition check failure");
+    // the transfer is rolled back, the fee is paid
+    const STX = simnet.getAssetsMap().get("STX")!;
+    expect(STX.get(address1)).toBe(99999999999900n);
+  });
+});
+
+describe("simnet can stub contract functions", () => {
+  it("replaces the body of a function and records its calls", () => {
+    simnet.stubFunction("counter", "add", Cl.ok(Cl.bool(true)));
+
+    const res = simnet.callPublicFn("counter", "add", [Cl.uint(5)], address1);
+    expect(res.result).toStrictEqual(Cl.ok(Cl.bool(true)));
+    expect(simnet.getDataVar("counter", "count")).toStrictEqual(Cl.uint(0));
+
+    const calls = simnet.getStubCalls();
+    expect(calls).toHaveLength(1);
+    expect(calls[0]).toStrictEqual({
+      contract: `${deployerAddr}.counter`,
+      method: "add",
+      caller: address1,
+      args: [Cl.uint(5)],
+    });
+  });
+
+  it("can stub a function with a clarity expression", () => {
+    simnet.stubFunction("counter", "get-count", "(ok { count: (+ (var-get count) u42) })");
+
+    const res = simnet.callReadOnlyFn("counter", "get-count", [], address1);
+    expect(res.result).toStrictEqual(Cl.ok(Cl.tuple({ count: Cl.uint(42) })));
+  });
+
+  it("rejects a stub not returning the type of the function", () => {
+    expect(() => simnet.stubFunction("counter", "get-count", Cl.uint(1))).toThrow(
+      "invalid stub for get-count",
+    );
+  });
+
+  it("can restore the original function", () => {
+    simnet.stubFunction("counter", "get-count", "(ok { count: u42 })");
+    simnet.removeStub("counter", "get-count");
+
+    const res = simnet.callReadOnlyFn("counter", "get-count", [], address1);
+    expect(res.result).toStrictEqual(Cl.ok(Cl.tuple({ count: Cl.uint(0) })));
+  });
+});
+
+describe("simnet can trace contract calls", () => {
+  it("returns the trace of the call when requested", () => {
+    const res = simnet.callPublicFn("counter", "add", [Cl.uint(2)], address1);
+    expect(res.trace).toBeUndefined();
+
+    const { trace } = simnet.callPublicFn("counter", "add", [Cl.uint(2)], address1, {
+      trace: true,
+    });
+    expect(trace?.contract).toBe(`${deployerAddr}.counter`);
+    expect(trace?.method).toBe("add");
+    expect(trace?.args).toStrictEqual([Cl.uint(2)]);
+    expect(trace?.result).toStrictEqual(Cl.ok(Cl.bool(true)));
+    expect(trace?.events.map(({ event }) => event)).toStrictEqual([
+      "print_event",
+      "stx_transfer_event",
+    ]);
+    expect(trace?.cost.runtime).toBeGreaterThan(0);
+  });
+
+  it("traces the nested calls", () => {
+    const trait = Cl.contractPrincipal(simnet.deployer, "multiplier-contract");
+    const { trace } = simnet.callPublicFn("counter", "call-multiply", [trait], address1, {
+      trace: true,
+    });
+    expect(trace?.calls).toHaveLength(1);
+    const [multiply] = trace!.calls;
+    expect(multiply.contract).toBe(`${deployerAddr}.multiplier-contract`);
+    expect(multiply.method).toBe("multiply");
+    expect(multiply.args).toStrictEqual([Cl.uint(2), Cl.uint(2)]);
+    expect(multiply.result).toStrictEqual(Cl.ok(Cl.uint(4)));
+    expect(multiply.calls).toHaveLength(0);
+  });
+});
+
 describe("simnet can get session reports", () => {
   it("can get line coverage", () => {
     simnet.callPublicFn("counter", "increment", [], address1);
@@ -13,6 +13,7 @@ declare global {
       coverageFilename: string;
       costs: boolean;
       costsFilename: string;
+      snapshot: boolean;
     };
   };
 }
@@ -15,13 +15,14 @@ function getFullTestName(task: Task, names: string[]) {
   If the session is initialised before each test, the reports are collected after each test.
   If the session is not initialised before each test, it'll be initialized in the `beforeAll`, which
   will run for all test file. In that case reports are collected in the after all.
+  With the `snapshot` option, the deployed state is loaded from a snapshot shared by all test files.
 */
 
 beforeEach(async (ctx) => {
-  const { coverage, initBeforeEach, manifestPath } = global.options.clarinet;
+  const { coverage, initBeforeEach, manifestPath, snapshot } = global.options.clarinet;
 
   if (initBeforeEach) {
-    await simnet.initSession(process.cwd(), manifestPath);
+    await simnet.initSession(process.cwd(), manifestPath, snapshot);
   }
 
   if (coverage) {
@@ -42,10 +43,10 @@ afterEach(async () => {
 });
 
 beforeAll(async () => {
-  const { initBeforeEach, manifestPath } = global.options.clarinet;
+  const { initBeforeEach, manifestPath, snapshot } = global.options.clarinet;
 
   if (!initBeforeEach) {
-    await simnet.initSession(process.cwd(), manifestPath);
+    await simnet.initSession(process.cwd(), manifestPath, snapshot);
   }
 });
 
@@ -1,18 +1,25 @@
 use crate::lsp_types::MessageType;
-use crate::state::{build_state, EditorState, ProtocolState};
-use crate::utils::get_contract_location;
+use crate::state::{build_state, run_function, EditorState, ProtocolState};
+use crate::utils::{get_contract_location, get_deployment_location};
 use clarinet_files::{FileAccessor, FileLocation, ProjectManifest};
 use clarity_repl::clarity::diagnostic::Diagnostic;
 use clarity_repl::repl::ContractDeployer;
 use lsp_types::{
-    CompletionItem, CompletionParams, DocumentSymbol, DocumentSymbolParams, GotoDefinitionParams,
-    Hover, HoverParams, InitializeParams, InitializeResult, Location, SignatureHelp,
-    SignatureHelpParams,
+    CallHierarchyIncomingCall, CallHierarchyIncomingCallsParams, CallHierarchyItem,
+    CallHierarchyOutgoingCall, CallHierarchyOutgoingCallsParams, CallHierarchyPrepareParams,
+    CodeLens, CodeLensParams, CompletionItem, CompletionParams, DocumentSymbol,
+    DocumentSymbolParams, GotoDefinitionParams, Hover, HoverParams, InitializeParams,
+    InitializeResult, InlayHint, InlayHintParams, Location, SignatureHelp, SignatureHelpParams,
+    SymbolInformation, WorkspaceSymbolParams,
 };
 use serde::{Deserialize, Serialize};
 use std::sync::{Arc, RwLock};
 
 use super::requests::capabilities::{get_capabilities, InitializationOptions};
+pub use super::requests::code_lens::{
+    FunctionCallArguments, DEBUG_FUNCTION_COMMAND, RUN_FUNCTION_COMMAND,
+};
+use super::requests::manifest::get_manifest_diagnostics;
 
 #[derive(Debug, Clone)]
 pub enum EditorStateInput {
@@ -56,6 +63,10 @@ pub enum LspNotification {
     ContractSaved(FileLocation),
     ContractChanged(FileLocation, String),
     ContractClosed(FileLocation),
+    DeploymentOpened(FileLocation),
+    DeploymentChanged(FileLocation, String),
+    DeploymentClosed(FileLocation),
+    RunFunction(FunctionCallArguments),
 }
 
 #[derive(Debug, Default, PartialEq, Deserialize, Serialize)]
@@ -73,6 +84,16 @@ impl LspNotificationResponse {
     }
 }
 
+async fn read_file(
+    location: &FileLocation,
+    file_accessor: Option<&dyn FileAccessor>,
+) -> Result<String, String> {
+    match file_accessor {
+        None => location.read_content_as_utf8(),
+        Some(file_accessor) => file_accessor.read_file(location.to_string()).await,
+    }
+}
+
 pub async fn process_notification(
     command: LspNotification,
     editor_state: &mut EditorStateInput,
@@ -80,43 +101,72 @@ pub async fn process_notification(
 ) -> Result<LspNotificationResponse, String> {
     match command {
         LspNotification::ManifestOpened(manifest_location) => {
+            let manifest_diagnostics = read_file(&manifest_location, file_accessor)
+                .await
+                .map(|source| get_manifest_diagnostics(&source))
+                .unwrap_or_default();
+
             // Only build the initial protocal state if it does not exist
             if editor_state.try_read(|es| es.protocols.contains_key(&manifest_location))? {
-                return Ok(LspNotificationResponse::default());
+                return Ok(LspNotificationResponse {
+                    aggregated_diagnostics: vec![(manifest_location, manifest_diagnostics)],
+                    notification: None,
+                });
             }
 
             // With this manifest_location, let's initialize our state.
             let mut protocol_state = ProtocolState::new();
             match build_state(&manifest_location, &mut protocol_state, file_accessor).await {
                 Ok(_) => {
-                    editor_state
-                        .try_write(|es| es.index_protocol(manifest_location, protocol_state))?;
-                    let (aggregated_diagnostics, notification) =
+                    editor_state.try_write(|es| {
+                        es.index_protocol(manifest_location.clone(), protocol_state)
+                    })?;
+                    let (mut aggregated_diagnostics, notification) =
                         editor_state.try_read(|es| es.get_aggregated_diagnostics())?;
+                    aggregated_diagnostics.push((manifest_location, manifest_diagnostics));
                     Ok(LspNotificationResponse {
                         aggregated_diagnostics,
                         notification,
                     })
                 }
-                Err(e) => Ok(LspNotificationResponse::error(&e)),
+                Err(e) => {
+                    let mut response = LspNotificationResponse::error(&e);
+                    response
+                        .aggregated_diagnostics
+                        .push((manifest_location, manifest_diagnostics));
+                    Ok(response)
+                }
             }
         }
 
         LspNotification::ManifestSaved(manifest_location) => {
+            let manifest_diagnostics = read_file(&manifest_location, file_accessor)
+                .await
+                .map(|source| get_manifest_diagnostics(&source))
+                .unwrap_or_default();
+
             // We will rebuild the entire state, without to try any optimizations for now
             let mut protocol_state = ProtocolState::new();
             match build_state(&manifest_location, &mut protocol_state, file_accessor).await {
                 Ok(_) => {
-                    editor_state
-                        .try_write(|es| es.index_protocol(manifest_location, protocol_state))?;
-                    let (aggregated_diagnostics, notification) =
+                    editor_state.try_write(|es| {
+                        es.index_protocol(manifest_location.clone(), protocol_state)
+                    })?;
+                    let (mut aggregated_diagnostics, notification) =
                         editor_state.try_read(|es| es.get_aggregated_diagnostics())?;
+                    aggregated_diagnostics.push((manifest_location, manifest_diagnostics));
                     Ok(LspNotificationResponse {
                         aggregated_diagnostics,
                         notification,
                     })
                 }
-                Err(e) => Ok(LspNotificationResponse::error(&e)),
+                Err(e) => {
+                    let mut response = LspNotificationResponse::error(&e);
+                    response
+                        .aggregated_diagnostics
+                        .push((manifest_location, manifest_diagnostics));
+                    Ok(response)
+                }
             }
         }
 
@@ -248,6 +298,72 @@ pub async fn process_notification(
             editor_state.try_write(|es| es.active_contracts.remove_entry(&contract_location))?;
             Ok(LspNotificationResponse::default())
         }
+
+        LspNotification::DeploymentOpened(deployment_location) => {
+            let manifest_location = deployment_location
+                .get_project_manifest_location(file_accessor)
+                .await?;
+            let deployment_source = read_file(&deployment_location, file_accessor).await?;
+
+            // the plan can be partially checked even if the protocol fails to build
+            if !editor_state.try_read(|es| es.protocols.contains_key(&manifest_location))? {
+                let mut protocol_state = ProtocolState::new();
+                if build_state(&manifest_location, &mut protocol_state, file_accessor)
+                    .await
+                    .is_ok()
+                {
+                    editor_state.try_write(|es| {
+                        es.index_protocol(manifest_location.clone(), protocol_state)
+                    })?;
+                }
+            }
+
+            let diagnostics = editor_state.try_write(|es| {
+                es.insert_active_deployment(
+                    deployment_location.clone(),
+                    manifest_location,
+                    &deployment_source,
+                );
+                es.get_deployment_diagnostics(&deployment_location)
+            })?;
+            Ok(LspNotificationResponse {
+                aggregated_diagnostics: vec![(deployment_location, diagnostics)],
+                notification: None,
+            })
+        }
+
+        LspNotification::DeploymentChanged(deployment_location, deployment_source) => {
+            let diagnostics = editor_state.try_write(|es| {
+                match es.active_deployments.get_mut(&deployment_location) {
+                    Some(deployment) => deployment.source = deployment_source,
+                    None => return vec![],
+                };
+                es.get_deployment_diagnostics(&deployment_location)
+            })?;
+            Ok(LspNotificationResponse {
+                aggregated_diagnostics: vec![(deployment_location, diagnostics)],
+                notification: None,
+            })
+        }
+
+        LspNotification::DeploymentClosed(deployment_location) => {
+            editor_state.try_write(|es| es.active_deployments.remove(&deployment_location))?;
+            Ok(LspNotificationResponse {
+                aggregated_diagnostics: vec![(deployment_location, vec![])],
+                notification: None,
+            })
+        }
+
+        LspNotification::RunFunction(function_call) => {
+            let notification = match run_function(&function_call, file_accessor).await {
+                Ok(output) => (MessageType::INFO, output),
+                Err(err) => (MessageType::ERROR, err),
+            };
+            Ok(LspNotificationResponse {
+                aggregated_diagnostics: vec![],
+                notification: Some(notification),
+            })
+        }
     }
 }
 
@@ -258,15 +374,27 @@ pub enum LspRequest {
     Definition(GotoDefinitionParams),
     Hover(HoverParams),
     DocumentSymbol(DocumentSymbolParams),
+    WorkspaceSymbol(WorkspaceSymbolParams),
+    PrepareCallHierarchy(CallHierarchyPrepareParams),
+    IncomingCalls(CallHierarchyIncomingCallsParams),
+    OutgoingCalls(CallHierarchyOutgoingCallsParams),
+    CodeLens(CodeLensParams),
+    InlayHint(InlayHintParams),
     Initialize(InitializeParams),
 }
 
-#[derive(Debug, PartialEq, Deserialize, Serialize)]
+#[derive(Debug, Deserialize, Serialize)]
 pub enum LspRequestResponse {
     CompletionItems(Vec<CompletionItem>),
     SignatureHelp(Option<SignatureHelp>),
     Definition(Option<Location>),
     DocumentSymbol(Vec<DocumentSymbol>),
+    WorkspaceSymbol(Vec<SymbolInformation>),
+    PrepareCallHierarchy(Option<Vec<CallHierarchyItem>>),
+    IncomingCalls(Vec<CallHierarchyIncomingCall>),
+    OutgoingCalls(Vec<CallHierarchyOutgoingCall>),
+    CodeLens(Vec<CodeLens>),
+    InlayHint(Vec<InlayHint>),
     Hover(Option<Hover>),
     Initialize(InitializeResult),
 }
@@ -280,6 +408,15 @@ pub fn process_request(
             let file_url = params.text_document_position.text_document.uri;
             let position = params.text_document_position.position;
 
+            if let Some(deployment_location) = get_deployment_location(&file_url) {
+                let completion_items = editor_state
+                    .try_read(|es| {
+                        es.get_deployment_completion_items(&deployment_location, &position)
+                    })
+                    .unwrap_or_default();
+                return Ok(LspRequestResponse::CompletionItems(completion_items));
+            }
+
             let contract_location = match get_contract_location(&file_url) {
                 Some(contract_location) => contract_location,
                 None => return Ok(LspRequestResponse::CompletionItems(vec![])),
@@ -343,13 +480,79 @@ pub fn process_request(
             Ok(LspRequestResponse::DocumentSymbol(document_symbols))
         }
 
+        LspRequest::WorkspaceSymbol(params) => {
+            let workspace_symbols = editor_state
+                .try_read(|es| es.get_workspace_symbols(&params.query))
+                .unwrap_or_default();
+            Ok(LspRequestResponse::WorkspaceSymbol(workspace_symbols))
+        }
+
+        LspRequest::PrepareCallHierarchy(params) => {
+            let file_url = params.text_document_position_params.text_document.uri;
+            let contract_location = match get_contract_location(&file_url) {
+                Some(contract_location) => contract_location,
+                None => return Ok(LspRequestResponse::PrepareCallHierarchy(None)),
+            };
+            let position = params.text_document_position_params.position;
+            let items = editor_state
+                .try_read(|es| es.get_call_hierarchy_items(&contract_location, &position))
+                .unwrap_or_default();
+            Ok(LspRequestResponse::PrepareCallHierarchy(items))
+        }
+
+        LspRequest::IncomingCalls(params) => {
+            let incoming_calls = editor_state
+                .try_read(|es| es.get_incoming_calls(&params.item))
+                .unwrap_or_default();
+            Ok(LspRequestResponse::IncomingCalls(incoming_calls))
+        }
+
+        LspRequest::OutgoingCalls(params) => {
+            let outgoing_calls = editor_state
+                .try_read(|es| es.get_outgoing_calls(&params.item))
+                .unwrap_or_default();
+            Ok(LspRequestResponse::OutgoingCalls(outgoing_calls))
+        }
+
+        LspRequest::CodeLens(params) => {
+            let file_url = params.text_document.uri;
+            let contract_location = match get_contract_location(&file_url) {
+                Some(contract_location) => contract_location,
+                None => return Ok(LspRequestResponse::CodeLens(vec![])),
+            };
+            let code_lenses = editor_state
+                .try_read(|es| es.get_code_lenses(&contract_location))
+                .unwrap_or_default();
+            Ok(LspRequestResponse::CodeLens(code_lenses))
+        }
+
+        LspRequest::InlayHint(params) => {
+            let file_url = params.text_document.uri;
+            let contract_location = match get_contract_location(&file_url) {
+                Some(contract_location) => contract_location,
+                None => return Ok(LspRequestResponse::InlayHint(vec![])),
+            };
+            let inlay_hints = editor_state
+                .try_read(|es| es.get_cost_inlay_hints(&contract_location))
+                .unwrap_or_default();
+            Ok(LspRequestResponse::InlayHint(inlay_hints))
+        }
+
         LspRequest::Hover(params) => {
             let file_url = params.text_document_position_params.text_document.uri;
+            let position = params.text_document_position_params.position;
+
+            if let Some(deployment_location) = get_deployment_location(&file_url) {
+                let hover_data = editor_state
+                    .try_read(|es| es.get_deployment_hover_data(&deployment_location, &position))
+                    .unwrap_or_default();
+                return Ok(LspRequestResponse::Hover(hover_data));
+            }
+
             let contract_location = match get_contract_location(&file_url) {
                 Some(contract_location) => contract_location,
                 None => return Ok(LspRequestResponse::Hover(None)),
             };
-            let position = params.text_document_position_params.position;
             let hover_data = editor_state
                 .try_read(|es| es.get_hover_data(&contract_location, &position))
                 .unwrap_or_default();
@@ -0,0 +1,341 @@
+use std::collections::HashMap;
+
+use clarity_repl::analysis::ast_visitor::{traverse, ASTVisitor, TypedVar};
+use clarity_repl::clarity::vm::types::QualifiedContractIdentifier;
+use clarity_repl::clarity::{ClarityName, SymbolicExpression};
+use lsp_types::{Position, Range};
+
+use super::helpers::span_to_range;
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum CallTarget {
+    Internal(ClarityName),
+    External(QualifiedContractIdentifier, ClarityName),
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct FunctionDefinition {
+    pub name: ClarityName,
+    pub kind: String,
+    pub range: Range,
+    pub selection_range: Range,
+    // the target of each call made in the body of the function,
+    // along with the range of the called function name
+    pub calls: Vec<(CallTarget, Range)>,
+}
+
+impl FunctionDefinition {
+    pub fn get_call_at_position(&self, position: &Position) -> Option<&CallTarget> {
+        self.calls
+            .iter()
+            .find(|(_, range)| is_position_within_range(position, range))
+            .map(|(target, _)| target)
+    }
+}
+
+pub fn is_position_within_range(position: &Position, range: &Range) -> bool {
+    &range.start <= position && position <= &range.end
+}
+
+// `current_calls` is only set while traversing the body of a function,
+// calls made at the top-level of a contract are not part of the hierarchy
+#[derive(Clone, Debug, Default)]
+pub struct CallGraph {
+    pub functions: HashMap<ClarityName, FunctionDefinition>,
+    current_calls: Option<Vec<(CallTarget, Range)>>,
+}
+
+impl<'a> CallGraph {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn run(&mut self, expressions: &'a [SymbolicExpression]) {
+        traverse(self, expressions);
+    }
+
+    fn add_call(&mut self, target: CallTarget, name_expr: Option<&SymbolicExpression>) {
+        if let (Some(calls), Some(name_expr)) = (self.current_calls.as_mut(), name_expr) {
+            calls.push((target, span_to_range(&name_expr.span)));
+        }
+    }
+
+    fn add_internal_call_at_index(&mut self, expr: &SymbolicExpression, name: &ClarityName) {
+        let name_expr = expr.match_list().and_then(|l| l.get(1));
+        self.add_call(CallTarget::Internal(name.to_owned()), name_expr);
+    }
+
+    fn insert_function(&mut self, expr: &SymbolicExpression, name: &ClarityName, kind: &str) {
+        let selection_range = expr
+            .match_list()
+            .and_then(|l| l.get(1))
+            .and_then(|signature| signature.match_list())
+            .and_then(|signature| signature.first())
+            .map(|name_expr| span_to_range(&name_expr.span))
+            .unwrap_or_else(|| span_to_range(&expr.span));
+
+        self.functions.insert(
+            name.to_owned(),
+            FunctionDefinition {
+                name: name.to_owned(),
+                kind: kind.to_string(),
+                range: span_to_range(&expr.span),
+                selection_range,
+                calls: self.current_calls.take().unwrap_or_default(),
+            },
+        );
+    }
+}
+
+impl<'a> ASTVisitor<'a> for CallGraph {
+    fn traverse_define_private(
+        &mut self,
+        expr: &'a SymbolicExpression,
+        name: &'a ClarityName,
+        parameters: Option<Vec<TypedVar<'a>>>,
+        body: &'a SymbolicExpression,
+    ) -> bool {
+        self.current_calls = Some(vec![]);
+        self.traverse_expr(body) && self.visit_define_private(expr, name, parameters, body)
+    }
+
+    fn visit_define_private(
+        &mut self,
+        expr: &'a SymbolicExpression,
+        name: &'a ClarityName,
+        _parameters: Option<Vec<TypedVar<'a>>>,
+        _body: &'a SymbolicExpression,
+    ) -> bool {
+        self.insert_function(expr, name, "private");
+        true
+    }
+
+    fn traverse_define_read_only(
+        &mut self,
+        expr: &'a SymbolicExpression,
+        name: &'a ClarityName,
+        parameters: Option<Vec<TypedVar<'a>>>,
+        body: &'a SymbolicExpression,
+    ) -> bool {
+        self.current_calls = Some(vec![]);
+        self.traverse_expr(body) && self.visit_define_read_only(expr, name, parameters, body)
+    }
+
+    fn visit_define_read_only(
+        &mut self,
+        expr: &'a SymbolicExpression,
+        name: &'a ClarityName,
+        _parameters: Option<Vec<TypedVar<'a>>>,
+        _body: &'a SymbolicExpression,
+    ) -> bool {
+        self.insert_function(expr, name, "read-only");
+        true
+    }
+
+    fn traverse_define_public(
+        &mut self,
+        expr: &'a SymbolicExpression,
+        name: &'a ClarityName,
+        parameters: Option<Vec<TypedVar<'a>>>,
+        body: &'a SymbolicExpression,
+    ) -> bool {
+        self.current_calls = Some(vec![]);
+        self.traverse_expr(body) && self.visit_define_public(expr, name, parameters, body)
+    }
+
+    fn visit_define_public(
+        &mut self,
+        expr: &'a SymbolicExpression,
+        name: &'a ClarityName,
+        _parameters: Option<Vec<TypedVar<'a>>>,
+        _body: &'a SymbolicExpression,
+    ) -> bool {
+        self.insert_function(expr, name, "public");
+        true
+    }
+
+    fn visit_call_user_defined(
+        &mut self,
+        expr: &'a SymbolicExpression,
+        name: &'a ClarityName,
+        _args: &'a [SymbolicExpression],
+    ) -> bool {
+        let name_expr = expr.match_list().and_then(|l| l.first());
+        self.add_call(CallTarget::Internal(name.to_owned()), name_expr);
+        true
+    }
+
+    fn visit_map(
+        &mut self,
+        expr: &'a SymbolicExpression,
+        func: &'a ClarityName,
+        _sequences: &'a [SymbolicExpression],
+    ) -> bool {
+        self.add_internal_call_at_index(expr, func);
+        true
+    }
+
+    fn visit_filter(
+        &mut self,
+        expr: &'a SymbolicExpression,
+        func: &'a ClarityName,
+        _sequence: &'a SymbolicExpression,
+    ) -> bool {
+        self.add_internal_call_at_index(expr, func);
+        true
+    }
+
+    fn visit_fold(
+        &mut self,
+        expr: &'a SymbolicExpression,
+        func: &'a ClarityName,
+        _sequence: &'a SymbolicExpression,
+        _initial: &'a SymbolicExpression,
+    ) -> bool {
+        self.add_internal_call_at_index(expr, func);
+        true
+    }
+
+    fn visit_static_contract_call(
+        &mut self,
+        expr: &'a SymbolicExpression,
+        contract_identifier: &'a QualifiedContractIdentifier,
+        function_name: &'a ClarityName,
+        _args: &'a [SymbolicExpression],
+    ) -> bool {
+        let name_expr = expr.match_list().and_then(|l| l.get(2));
+        self.add_call(
+            CallTarget::External(contract_identifier.to_owned(), function_name.to_owned()),
+            name_expr,
+        );
+        true
+    }
+}
+
+pub fn get_call_graph(
+    expressions: &[SymbolicExpression],
+) -> HashMap<ClarityName, FunctionDefinition> {
+    let mut call_graph = CallGraph::new();
+    call_graph.run(expressions);
+    call_graph.functions
+}
+
+#[cfg(test)]
+mod call_graph_tests {
+    use clarity_repl::clarity::ast::build_ast_with_rules;
+    use clarity_repl::clarity::vm::types::QualifiedContractIdentifier;
+    use clarity_repl::clarity::StacksEpochId;
+    use clarity_repl::clarity::{ClarityName, ClarityVersion, SymbolicExpression};
+    use lsp_types::{Position, Range};
+
+    use super::{get_call_graph, CallTarget};
+
+    fn get_ast(source: &str) -> Vec<SymbolicExpression> {
+        let contract_ast = build_ast_with_rules(
+            &QualifiedContractIdentifier::parse("S1G2081040G2081040G2081040G208105NK8PE5.contract")
+                .unwrap(),
+            source,
+            &mut (),
+            ClarityVersion::Clarity2,
+            StacksEpochId::Epoch21,
+            clarity_repl::clarity::ast::ASTRules::Typical,
+        )
+        .unwrap();
+        contract_ast.expressions
+    }
+
+    fn name(name: &str) -> ClarityName {
+        ClarityName::try_from(name.to_string()).unwrap()
+    }
+
+    fn new_range(start_line: u32, start_column: u32, end_line: u32, end_column: u32) -> Range {
+        Range::new(
+            Position::new(start_line, start_column),
+            Position::new(end_line, end_column),
+        )
+    }
+
+    #[test]
+    fn find_internal_calls() {
+        let functions = get_call_graph(&get_ast(
+            [
+                "(define-private (double (n int)) (* n 2))",
+                "(define-read-only (quadruple (n int)) (double (double n)))",
+            ]
+            .join("\n")
+            .as_str(),
+        ));
+
+        assert_eq!(functions.len(), 2);
+        let double = functions.get(&name("double")).unwrap();
+        assert_eq!(double.kind, "private");
+        assert_eq!(double.selection_range, new_range(0, 17, 0, 23));
+        assert!(double.calls.is_empty());
+
+        let quadruple = functions.get(&name("quadruple")).unwrap();
+        assert_eq!(quadruple.kind, "read-only");
+        let double_name = name("double");
+        assert_eq!(
+            quadruple.calls,
+            vec![
+                (
+                    CallTarget::Internal(double_name.clone()),
+                    new_range(1, 47, 1, 53)
+                ),
+                (CallTarget::Internal(double_name), new_range(1, 39, 1, 45)),
+            ]
+        );
+    }
+
+    #[test]
+    fn find_calls_in_higher_order_functions() {
+        let functions = get_call_graph(&get_ast(
+            [
+                "(define-private (is-even (n int)) (is-eq (mod n 2) 0))",
+                "(define-read-only (evens) (filter is-even (list 1 2 3)))",
+            ]
+            .join("\n")
+            .as_str(),
+        ));
+
+        let evens = functions.get(&name("evens")).unwrap();
+        assert_eq!(
+            evens.calls,
+            vec![(
+                CallTarget::Internal(name("is-even")),
+                new_range(1, 34, 1, 41)
+            )]
+        );
+    }
+
+    #[test]
+    fn find_contract_calls() {
+        let functions = get_call_graph(&get_ast(
+            "(define-public (proxy) (contract-call? .counter increment))",
+        ));
+
+        let proxy = functions.get(&name("proxy")).unwrap();
+        assert_eq!(proxy.kind, "public");
+        assert_eq!(
+            proxy.calls,
+            vec![(
+                CallTarget::External(
+                    QualifiedContractIdentifier::parse(
+                        "S1G2081040G2081040G2081040G208105NK8PE5.counter"
+                    )
+                    .unwrap(),
+                    name("increment")
+                ),
+                new_range(0, 48, 0, 57)
+            )]
+        );
+    }
+
+    #[test]
+    fn ignore_top_level_calls() {
+        let functions = get_call_graph(&get_ast(
+            "(define-private (double (n int)) (* n 2)) (double 1)",
+        ));
+        assert!(functions.get(&name("double")).unwrap().calls.is_empty());
+    }
+}
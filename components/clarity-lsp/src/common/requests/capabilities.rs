@@ -1,10 +1,12 @@
 use lsp_types::{
-    CompletionOptions, HoverProviderCapability, ServerCapabilities, SignatureHelpOptions,
-    TextDocumentSyncCapability, TextDocumentSyncKind, TextDocumentSyncOptions,
-    TextDocumentSyncSaveOptions,
+    CallHierarchyServerCapability, CodeLensOptions, CompletionOptions, ExecuteCommandOptions,
+    HoverProviderCapability, ServerCapabilities, SignatureHelpOptions, TextDocumentSyncCapability,
+    TextDocumentSyncKind, TextDocumentSyncOptions, TextDocumentSyncSaveOptions,
 };
 use serde::{Deserialize, Serialize};
 
+use super::code_lens::{DEBUG_FUNCTION_COMMAND, RUN_FUNCTION_COMMAND};
+
 #[derive(Debug, Clone, Deserialize, Serialize, Default)]
 #[serde(rename_all = "camelCase")]
 pub struct InitializationOptions {
@@ -12,6 +14,11 @@ pub struct InitializationOptions {
     pub completion_smart_parenthesis_wrap: bool,
     pub completion_include_native_placeholders: bool,
     document_symbols: bool,
+    workspace_symbols: bool,
+    call_hierarchy: bool,
+    code_lens: bool,
+    pub cost_hints: bool,
+    pub cost_warning_threshold: f64,
     go_to_definition: bool,
     hover: bool,
     signature_help: bool,
@@ -24,6 +31,11 @@ impl InitializationOptions {
             completion_smart_parenthesis_wrap: true,
             completion_include_native_placeholders: true,
             document_symbols: false,
+            workspace_symbols: true,
+            call_hierarchy: true,
+            code_lens: true,
+            cost_hints: true,
+            cost_warning_threshold: 0.5,
             go_to_definition: true,
             hover: true,
             signature_help: true,
@@ -54,6 +66,35 @@ pub fn get_capabilities(initialization_options: &InitializationOptions) -> Serve
             true => Some(lsp_types::OneOf::Left(true)),
             false => None,
         },
+        workspace_symbol_provider: match initialization_options.workspace_symbols {
+            true => Some(lsp_types::OneOf::Left(true)),
+            false => None,
+        },
+        call_hierarchy_provider: match initialization_options.call_hierarchy {
+            true => Some(CallHierarchyServerCapability::Simple(true)),
+            false => None,
+        },
+        code_lens_provider: match initialization_options.code_lens {
+            true => Some(CodeLensOptions {
+                resolve_provider: Some(false),
+            }),
+            false => None,
+        },
+        // the commands of the code lenses
+        execute_command_provider: match initialization_options.code_lens {
+            true => Some(ExecuteCommandOptions {
+                commands: vec![
+                    RUN_FUNCTION_COMMAND.to_string(),
+                    DEBUG_FUNCTION_COMMAND.to_string(),
+                ],
+                work_done_progress_options: Default::default(),
+            }),
+            false => None,
+        },
+        inlay_hint_provider: match initialization_options.cost_hints {
+            true => Some(lsp_types::OneOf::Left(true)),
+            false => None,
+        },
         definition_provider: match initialization_options.go_to_definition {
             true => Some(lsp_types::OneOf::Left(true)),
             false => None,
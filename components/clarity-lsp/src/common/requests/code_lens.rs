@@ -0,0 +1,201 @@
+use std::collections::HashMap;
+
+use clarinet_files::FileLocation;
+use clarity_repl::clarity::analysis::ContractAnalysis;
+use clarity_repl::clarity::vm::types::{FunctionType, QualifiedContractIdentifier};
+use clarity_repl::clarity::ClarityName;
+use lsp_types::{CodeLens, Command};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+
+use super::call_hierarchy::FunctionDefinition;
+
+pub const RUN_FUNCTION_COMMAND: &str = "clarity.runFunction";
+pub const DEBUG_FUNCTION_COMMAND: &str = "clarity.debugFunction";
+
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct FunctionParameter {
+    pub name: String,
+    #[serde(rename = "type")]
+    pub signature: String,
+}
+
+// sent as the argument of the code lens commands,
+// `args` are expected to be filled by the client before running the function
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FunctionCallArguments {
+    pub manifest: String,
+    pub contract_id: String,
+    pub function_name: String,
+    pub parameters: Vec<FunctionParameter>,
+    #[serde(default)]
+    pub args: Vec<String>,
+    #[serde(default)]
+    pub sender: Option<String>,
+}
+
+impl FunctionCallArguments {
+    // the commands can't run before the client has provided a value for each parameter
+    pub fn check_args(&self) -> Result<(), String> {
+        if self.args.len() == self.parameters.len() {
+            return Ok(());
+        }
+        let parameters: Vec<String> = self
+            .parameters
+            .iter()
+            .map(|p| format!("({} {})", p.name, p.signature))
+            .collect();
+        Err(format!(
+            "{} expects {} argument(s) {}, {} provided",
+            self.function_name,
+            self.parameters.len(),
+            parameters.join(" "),
+            self.args.len()
+        ))
+    }
+
+    pub fn get_expression(&self) -> String {
+        let mut expression = format!(
+            "(contract-call? '{} {}",
+            self.contract_id, self.function_name
+        );
+        for arg in self.args.iter() {
+            expression.push(' ');
+            expression.push_str(arg);
+        }
+        expression.push(')');
+        expression
+    }
+
+    // launch configuration of the clarinet debugger (see `clarinet dap`)
+    pub fn get_debug_configuration(&self) -> serde_json::Value {
+        json!({
+            "type": "clarinet",
+            "request": "launch",
+            "name": format!("Debug {}", self.function_name),
+            "manifest": self.manifest,
+            "expression": self.get_expression(),
+        })
+    }
+}
+
+fn get_function_parameters(
+    analysis: &ContractAnalysis,
+    function_name: &ClarityName,
+) -> Vec<FunctionParameter> {
+    let signature = analysis
+        .public_function_types
+        .get(function_name)
+        .or_else(|| analysis.read_only_function_types.get(function_name));
+
+    match signature {
+        Some(FunctionType::Fixed(function)) => function
+            .args
+            .iter()
+            .map(|arg| FunctionParameter {
+                name: arg.name.to_string(),
+                signature: arg.signature.to_string(),
+            })
+            .collect(),
+        _ => vec![],
+    }
+}
+
+pub fn get_code_lenses(
+    manifest_location: &FileLocation,
+    contract_id: &QualifiedContractIdentifier,
+    functions: &HashMap<ClarityName, FunctionDefinition>,
+    analysis: &ContractAnalysis,
+) -> Vec<CodeLens> {
+    let mut callable_functions = functions
+        .values()
+        .filter(|function| function.kind == "public" || function.kind == "read-only")
+        .collect::<Vec<_>>();
+    callable_functions.sort_by_key(|function| function.range.start);
+
+    let mut code_lenses = vec![];
+    for function in callable_functions {
+        let arguments = FunctionCallArguments {
+            manifest: manifest_location.to_string(),
+            contract_id: contract_id.to_string(),
+            function_name: function.name.to_string(),
+            parameters: get_function_parameters(analysis, &function.name),
+            args: vec![],
+            sender: None,
+        };
+        let arguments = match serde_json::to_value(arguments) {
+            Ok(arguments) => arguments,
+            Err(_) => continue,
+        };
+
+        code_lenses.push(CodeLens {
+            range: function.selection_range,
+            command: Some(Command {
+                title: "▶ Run in simnet".to_string(),
+                command: RUN_FUNCTION_COMMAND.to_string(),
+                arguments: Some(vec![arguments.clone()]),
+            }),
+            data: None,
+        });
+        code_lenses.push(CodeLens {
+            range: function.selection_range,
+            command: Some(Command {
+                title: "Debug".to_string(),
+                command: DEBUG_FUNCTION_COMMAND.to_string(),
+                arguments: Some(vec![arguments]),
+            }),
+            data: None,
+        });
+    }
+    code_lenses
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{FunctionCallArguments, FunctionParameter};
+
+    #[test]
+    fn build_contract_call_expression() {
+        let arguments = FunctionCallArguments {
+            manifest: "/project/Clarinet.toml".to_string(),
+            contract_id: "ST1PQHQKV0RJXZFY1DGX8MNSNYVE3VGZJSRTPGZGM.counter".to_string(),
+            function_name: "add".to_string(),
+            parameters: vec![FunctionParameter {
+                name: "n".to_string(),
+                signature: "uint".to_string(),
+            }],
+            args: vec!["u2".to_string()],
+            sender: None,
+        };
+        assert_eq!(
+            arguments.get_expression(),
+            "(contract-call? 'ST1PQHQKV0RJXZFY1DGX8MNSNYVE3VGZJSRTPGZGM.counter add u2)"
+        );
+        assert_eq!(
+            arguments.get_debug_configuration()["expression"],
+            arguments.get_expression()
+        );
+    }
+
+    #[test]
+    fn check_provided_arguments() {
+        let mut arguments = FunctionCallArguments {
+            manifest: "/project/Clarinet.toml".to_string(),
+            contract_id: "ST1PQHQKV0RJXZFY1DGX8MNSNYVE3VGZJSRTPGZGM.counter".to_string(),
+            function_name: "add".to_string(),
+            parameters: vec![FunctionParameter {
+                name: "n".to_string(),
+                signature: "uint".to_string(),
+            }],
+            args: vec![],
+            sender: None,
+        };
+        assert_eq!(
+            arguments.check_args(),
+            Err("add expects 1 argument(s) (n uint), 0 provided".to_string())
+        );
+        arguments.args.push("u2".to_string());
+        assert_eq!(arguments.check_args(), Ok(()));
+    }
+}
@@ -1,7 +1,10 @@
 use std::{collections::HashMap, vec};
 
 use clarity_repl::{
-    analysis::ast_visitor::{traverse, ASTVisitor, TypedVar},
+    analysis::{
+        ast_visitor::{traverse, ASTVisitor, TypedVar},
+        doc_comments::DefinitionDoc,
+    },
     clarity::{
         analysis::ContractAnalysis,
         docs::{make_api_reference, make_define_reference, make_keyword_reference},
@@ -169,6 +172,17 @@ impl<'a> ContractDefinedData {
         None
     }
 
+    pub fn set_functions_documentation(&mut self, docs: &[DefinitionDoc]) {
+        for item in self.functions_completion_items.iter_mut() {
+            if let Some(doc) = docs.iter().find(|doc| doc.name == item.label) {
+                item.documentation = Some(Documentation::MarkupContent(MarkupContent {
+                    kind: MarkupKind::Markdown,
+                    value: doc.to_markdown(),
+                }));
+            }
+        }
+    }
+
     pub fn get_contract_completion_items(&self) -> Vec<CompletionItem> {
         [&self.consts[..], &self.locals[..]]
             .concat()
@@ -293,7 +307,10 @@ fn build_contract_calls_args(signature: &FunctionType) -> (Vec<String>, Vec<Stri
     (snippet_args, doc_args)
 }
 
-pub fn get_contract_calls(analysis: &ContractAnalysis) -> Vec<CompletionItem> {
+pub fn get_contract_calls(
+    analysis: &ContractAnalysis,
+    docs: &[DefinitionDoc],
+) -> Vec<CompletionItem> {
     let mut inter_contract = vec![];
     for (name, signature) in analysis
         .public_function_types
@@ -307,9 +324,12 @@ pub fn get_contract_calls(analysis: &ContractAnalysis) -> Vec<CompletionItem> {
         );
         let documentation = MarkupContent {
             kind: MarkupKind::Markdown,
-            value: [vec![format!("**{}**", name.to_string())], doc_args]
-                .concat()
-                .join("\n\n"),
+            value: match docs.iter().find(|doc| doc.name == name.as_str()) {
+                Some(doc) => doc.to_markdown(),
+                None => [vec![format!("**{}**", name.to_string())], doc_args]
+                    .concat()
+                    .join("\n\n"),
+            },
         };
         let insert_text = format!(
             "contract-call? .{} {} {}",
@@ -0,0 +1,895 @@
+use std::collections::HashMap;
+
+use clarity_repl::clarity::analysis::ContractAnalysis;
+use clarity_repl::clarity::ast::{build_ast_with_rules, ASTRules};
+use clarity_repl::clarity::costs::ExecutionCost;
+use clarity_repl::clarity::diagnostic::{Diagnostic, Level};
+use clarity_repl::clarity::functions::define::DefineFunctions;
+use clarity_repl::clarity::representations::Span;
+use clarity_repl::clarity::vm::types::{
+    FunctionType, QualifiedContractIdentifier, SequenceSubtype, TypeSignature, Value,
+};
+use clarity_repl::clarity::{
+    ClarityName, ClarityVersion, StacksEpochId, SymbolicExpression, SymbolicExpressionType,
+};
+use clarity_repl::repl::boot::BOOT_CODE_COSTS_3;
+use lazy_static::lazy_static;
+use lsp_types::{InlayHint, InlayHintLabel, InlayHintTooltip, MarkupContent, MarkupKind, Position};
+
+use super::call_hierarchy::is_position_within_range;
+use super::helpers::span_to_range;
+
+// block limit of the stacks mainnet since epoch 2.1
+pub const BLOCK_LIMIT: ExecutionCost = ExecutionCost {
+    write_length: 15_000_000,
+    write_count: 15_000,
+    read_length: 100_000_000,
+    read_count: 15_000,
+    runtime: 5_000_000_000,
+};
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum CostFormula {
+    Constant(u64),
+    Linear(u64, u64),
+    LogN(u64, u64),
+    NLogN(u64, u64),
+}
+
+impl CostFormula {
+    pub fn eval(&self, n: u64) -> u64 {
+        let log2 = |n: u64| u64::from(n.max(1).ilog2());
+        match *self {
+            CostFormula::Constant(b) => b,
+            CostFormula::Linear(a, b) => a.saturating_mul(n).saturating_add(b),
+            CostFormula::LogN(a, b) => a.saturating_mul(log2(n)).saturating_add(b),
+            CostFormula::NLogN(a, b) => a
+                .saturating_mul(n.saturating_mul(log2(n)))
+                .saturating_add(b),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct CostSpecification {
+    pub runtime: CostFormula,
+    pub read_count: CostFormula,
+    pub read_length: CostFormula,
+    pub write_count: CostFormula,
+    pub write_length: CostFormula,
+}
+
+impl CostSpecification {
+    pub fn eval(&self, n: u64) -> ExecutionCost {
+        ExecutionCost {
+            runtime: self.runtime.eval(n),
+            read_count: self.read_count.eval(n),
+            read_length: self.read_length.eval(n),
+            write_count: self.write_count.eval(n),
+            write_length: self.write_length.eval(n),
+        }
+    }
+}
+
+fn parse_cost_formula(expr: &SymbolicExpression) -> Option<CostFormula> {
+    let as_u64 = |expr: &SymbolicExpression| match expr.expr {
+        SymbolicExpressionType::LiteralValue(Value::UInt(value)) => u64::try_from(value).ok(),
+        _ => None,
+    };
+
+    if let Some(value) = as_u64(expr) {
+        return Some(CostFormula::Constant(value));
+    }
+    let (name, args) = expr.match_list()?.split_first()?;
+    let a = as_u64(args.get(1)?)?;
+    let b = as_u64(args.get(2)?)?;
+    match name.match_atom()?.as_str() {
+        "linear" => Some(CostFormula::Linear(a, b)),
+        "logn" => Some(CostFormula::LogN(a, b)),
+        "nlogn" => Some(CostFormula::NLogN(a, b)),
+        _ => None,
+    }
+}
+
+fn parse_cost_specification(body: &SymbolicExpression) -> Option<CostSpecification> {
+    let mut specification = CostSpecification {
+        runtime: CostFormula::Constant(0),
+        read_count: CostFormula::Constant(0),
+        read_length: CostFormula::Constant(0),
+        write_count: CostFormula::Constant(0),
+        write_length: CostFormula::Constant(0),
+    };
+
+    let (name, args) = body.match_list()?.split_first()?;
+    match name.match_atom()?.as_str() {
+        "runtime" => specification.runtime = parse_cost_formula(args.first()?)?,
+        "tuple" => {
+            for entry in args {
+                let entry = entry.match_list()?;
+                let formula = parse_cost_formula(entry.get(1)?)?;
+                match entry.first()?.match_atom()?.as_str() {
+                    "runtime" => specification.runtime = formula,
+                    "read_count" => specification.read_count = formula,
+                    "read_length" => specification.read_length = formula,
+                    "write_count" => specification.write_count = formula,
+                    "write_length" => specification.write_length = formula,
+                    _ => return None,
+                }
+            }
+        }
+        _ => return None,
+    }
+    Some(specification)
+}
+
+// the formulas are read from the `costs-3` boot contract rather than being hardcoded,
+// so that they stay in sync with the cost functions used by the network
+fn parse_cost_functions(source: &str) -> HashMap<String, CostSpecification> {
+    let mut cost_functions = HashMap::new();
+    let ast = match build_ast_with_rules(
+        &QualifiedContractIdentifier::transient(),
+        source,
+        &mut (),
+        ClarityVersion::Clarity2,
+        StacksEpochId::Epoch21,
+        ASTRules::Typical,
+    ) {
+        Ok(ast) => ast,
+        Err(_) => return cost_functions,
+    };
+
+    for expr in ast.expressions.iter() {
+        let (name, args) = match expr.match_list().and_then(|l| l.split_first()) {
+            Some(list) => list,
+            None => continue,
+        };
+        if name.match_atom().map(|n| n.as_str()) != Some("define-read-only") {
+            continue;
+        }
+        let function_name = args
+            .first()
+            .and_then(|signature| signature.match_list())
+            .and_then(|signature| signature.first())
+            .and_then(|name| name.match_atom());
+        let specification = args.get(1).and_then(parse_cost_specification);
+        if let (Some(function_name), Some(specification)) = (function_name, specification) {
+            cost_functions.insert(function_name.to_string(), specification);
+        }
+    }
+    cost_functions
+}
+
+lazy_static! {
+    static ref COST_FUNCTIONS: HashMap<String, CostSpecification> =
+        parse_cost_functions(BOOT_CODE_COSTS_3);
+    static ref NATIVE_COST_FUNCTIONS: HashMap<&'static str, &'static str> = HashMap::from([
+        ("+", "cost_add"),
+        ("-", "cost_sub"),
+        ("*", "cost_mul"),
+        ("/", "cost_div"),
+        (">=", "cost_geq"),
+        ("<=", "cost_leq"),
+        ("<", "cost_le"),
+        (">", "cost_ge"),
+        ("to-int", "cost_int_cast"),
+        ("to-uint", "cost_int_cast"),
+        ("mod", "cost_mod"),
+        ("pow", "cost_pow"),
+        ("sqrti", "cost_sqrti"),
+        ("log2", "cost_log2"),
+        ("xor", "cost_xor"),
+        ("not", "cost_not"),
+        ("is-eq", "cost_eq"),
+        ("begin", "cost_begin"),
+        ("hash160", "cost_hash160"),
+        ("sha256", "cost_sha256"),
+        ("sha512", "cost_sha512"),
+        ("sha512/256", "cost_sha512t256"),
+        ("keccak256", "cost_keccak256"),
+        ("secp256k1-recover?", "cost_secp256k1recover"),
+        ("secp256k1-verify", "cost_secp256k1verify"),
+        ("print", "cost_print"),
+        ("some", "cost_some_cons"),
+        ("ok", "cost_ok_cons"),
+        ("err", "cost_err_cons"),
+        ("default-to", "cost_default_to"),
+        ("unwrap!", "cost_unwrap_ret"),
+        ("unwrap-err!", "cost_unwrap_err_or_ret"),
+        ("is-ok", "cost_is_okay"),
+        ("is-none", "cost_is_none"),
+        ("is-err", "cost_is_err"),
+        ("is-some", "cost_is_some"),
+        ("unwrap-panic", "cost_unwrap"),
+        ("unwrap-err-panic", "cost_unwrap_err"),
+        ("try!", "cost_try_ret"),
+        ("asserts!", "cost_asserts"),
+        ("or", "cost_or"),
+        ("and", "cost_and"),
+        ("append", "cost_append"),
+        ("concat", "cost_concat"),
+        ("as-max-len?", "cost_as_max_len"),
+        ("len", "cost_len"),
+        ("element-at", "cost_element_at"),
+        ("element-at?", "cost_element_at"),
+        ("index-of", "cost_index_of"),
+        ("index-of?", "cost_index_of"),
+        ("list", "cost_list_cons"),
+        ("get", "cost_tuple_get"),
+        ("merge", "cost_tuple_merge"),
+        ("tuple", "cost_tuple_cons"),
+        ("contract-of", "cost_contract_of"),
+        ("principal-of?", "cost_principal_of"),
+        ("at-block", "cost_at_block"),
+        ("as-contract", "cost_as_contract"),
+        ("map-get?", "cost_fetch_entry"),
+        ("map-set", "cost_set_entry"),
+        ("map-insert", "cost_set_entry"),
+        ("map-delete", "cost_set_entry"),
+        ("var-get", "cost_fetch_var"),
+        ("var-set", "cost_set_var"),
+        ("get-block-info?", "cost_block_info"),
+        ("get-burn-block-info?", "cost_burn_block_info"),
+        ("stx-get-balance", "cost_stx_balance"),
+        ("stx-account", "cost_stx_account"),
+        ("stx-transfer?", "cost_stx_transfer"),
+        ("stx-transfer-memo?", "cost_stx_transfer_memo"),
+        ("stx-burn?", "cost_stx_transfer"),
+        ("ft-mint?", "cost_ft_mint"),
+        ("ft-transfer?", "cost_ft_transfer"),
+        ("ft-get-balance", "cost_ft_balance"),
+        ("ft-get-supply", "cost_ft_get_supply"),
+        ("ft-burn?", "cost_ft_burn"),
+        ("nft-mint?", "cost_nft_mint"),
+        ("nft-transfer?", "cost_nft_transfer"),
+        ("nft-get-owner?", "cost_nft_owner"),
+        ("nft-burn?", "cost_nft_burn"),
+        ("buff-to-int-le", "cost_buff_to_int_le"),
+        ("buff-to-uint-le", "cost_buff_to_uint_le"),
+        ("buff-to-int-be", "cost_buff_to_int_be"),
+        ("buff-to-uint-be", "cost_buff_to_uint_be"),
+        ("is-standard", "cost_is_standard"),
+        ("principal-destruct?", "cost_principal_destruct"),
+        ("principal-construct?", "cost_principal_construct"),
+        ("string-to-int?", "cost_string_to_int"),
+        ("string-to-uint?", "cost_string_to_uint"),
+        ("int-to-ascii", "cost_int_to_ascii"),
+        ("int-to-utf8", "cost_int_to_utf8"),
+        ("slice?", "cost_slice"),
+        ("to-consensus-buff?", "cost_to_consensus_buff"),
+        ("from-consensus-buff?", "cost_from_consensus_buff"),
+        ("replace-at?", "cost_replace_at"),
+        ("bit-and", "cost_bitwise_and"),
+        ("bit-or", "cost_bitwise_or"),
+        ("bit-not", "cost_bitwise_not"),
+        ("bit-xor", "cost_xor"),
+        ("bit-shift-left", "cost_bitwise_left_shift"),
+        ("bit-shift-right", "cost_bitwise_right_shift"),
+    ]);
+}
+
+fn eval_cost_function(cost_function: &str, n: u64) -> ExecutionCost {
+    COST_FUNCTIONS
+        .get(cost_function)
+        .map(|specification| specification.eval(n))
+        .unwrap_or_else(ExecutionCost::zero)
+}
+
+fn add_cost(total: &mut ExecutionCost, cost: &ExecutionCost) {
+    total.runtime = total.runtime.saturating_add(cost.runtime);
+    total.read_count = total.read_count.saturating_add(cost.read_count);
+    total.read_length = total.read_length.saturating_add(cost.read_length);
+    total.write_count = total.write_count.saturating_add(cost.write_count);
+    total.write_length = total.write_length.saturating_add(cost.write_length);
+}
+
+fn multiply_cost(cost: &ExecutionCost, times: u64) -> ExecutionCost {
+    ExecutionCost {
+        runtime: cost.runtime.saturating_mul(times),
+        read_count: cost.read_count.saturating_mul(times),
+        read_length: cost.read_length.saturating_mul(times),
+        write_count: cost.write_count.saturating_mul(times),
+        write_length: cost.write_length.saturating_mul(times),
+    }
+}
+
+fn max_cost(a: &ExecutionCost, b: &ExecutionCost) -> ExecutionCost {
+    ExecutionCost {
+        runtime: a.runtime.max(b.runtime),
+        read_count: a.read_count.max(b.read_count),
+        read_length: a.read_length.max(b.read_length),
+        write_count: a.write_count.max(b.write_count),
+        write_length: a.write_length.max(b.write_length),
+    }
+}
+
+// returns the largest dimension of the cost, as a fraction of the block limit
+pub fn get_block_limit_fraction(cost: &ExecutionCost) -> (f64, &'static str) {
+    [
+        ("runtime", cost.runtime, BLOCK_LIMIT.runtime),
+        ("read_count", cost.read_count, BLOCK_LIMIT.read_count),
+        ("read_length", cost.read_length, BLOCK_LIMIT.read_length),
+        ("write_count", cost.write_count, BLOCK_LIMIT.write_count),
+        ("write_length", cost.write_length, BLOCK_LIMIT.write_length),
+    ]
+    .iter()
+    .map(|(dimension, value, limit)| (*value as f64 / *limit as f64, *dimension))
+    .fold((0.0, "runtime"), |max, current| {
+        if current.0 > max.0 {
+            current
+        } else {
+            max
+        }
+    })
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct FunctionCost {
+    pub cost: ExecutionCost,
+    // span of the function name, where the hints and warnings are displayed
+    pub span: Span,
+}
+
+struct FunctionBody<'a> {
+    body: &'a SymbolicExpression,
+    parameters: HashMap<ClarityName, TypeSignature>,
+    span: Span,
+}
+
+// Static worst-case estimation of the cost of the functions of a contract.
+// The cost of each native function is computed with the cost functions of the
+// `costs-3` boot contract, using the number of arguments or the size of the data types as input.
+// Only the most expensive branch of `if` and `match` expressions is taken into account,
+// and iterations over sequences are multiplied by their max length when it is known statically.
+// The cost of the functions called with `contract-call?` is not included.
+pub struct CostEstimator<'a> {
+    analysis: &'a ContractAnalysis,
+    functions: HashMap<ClarityName, FunctionBody<'a>>,
+    costs: HashMap<ClarityName, ExecutionCost>,
+}
+
+impl<'a> CostEstimator<'a> {
+    pub fn new(expressions: &'a [SymbolicExpression], analysis: &'a ContractAnalysis) -> Self {
+        let mut functions = HashMap::new();
+        for expr in expressions {
+            let (define, args) = match expr.match_list().and_then(|l| l.split_first()) {
+                Some(list) => list,
+                None => continue,
+            };
+            let function_types = match define
+                .match_atom()
+                .and_then(|name| DefineFunctions::lookup_by_name(name))
+            {
+                Some(DefineFunctions::PublicFunction) => &analysis.public_function_types,
+                Some(DefineFunctions::ReadOnlyFunction) => &analysis.read_only_function_types,
+                Some(DefineFunctions::PrivateFunction) => &analysis.private_function_types,
+                _ => continue,
+            };
+            let name_expr = args
+                .first()
+                .and_then(|signature| signature.match_list())
+                .and_then(|signature| signature.first());
+            let (name_expr, name, body) = match (name_expr, args.get(1)) {
+                (Some(name_expr), Some(body)) => match name_expr.match_atom() {
+                    Some(name) => (name_expr, name, body),
+                    None => continue,
+                },
+                _ => continue,
+            };
+
+            let parameters = match function_types.get(name) {
+                Some(FunctionType::Fixed(function)) => function
+                    .args
+                    .iter()
+                    .map(|arg| (arg.name.clone(), arg.signature.clone()))
+                    .collect(),
+                _ => HashMap::new(),
+            };
+            functions.insert(
+                name.clone(),
+                FunctionBody {
+                    body,
+                    parameters,
+                    span: name_expr.span.clone(),
+                },
+            );
+        }
+
+        Self {
+            analysis,
+            functions,
+            costs: HashMap::new(),
+        }
+    }
+
+    pub fn run(mut self) -> HashMap<ClarityName, FunctionCost> {
+        let names = self.functions.keys().cloned().collect::<Vec<_>>();
+        names
+            .into_iter()
+            .filter_map(|name| {
+                let cost = self.get_function_cost(&name);
+                let span = self.functions.get(&name)?.span.clone();
+                Some((name, FunctionCost { cost, span }))
+            })
+            .collect()
+    }
+
+    fn get_function_cost(&mut self, name: &ClarityName) -> ExecutionCost {
+        if let Some(cost) = self.costs.get(name) {
+            return cost.clone();
+        }
+        // recursion isn't allowed in clarity, this entry only guards against
+        // looping indefinitely on contracts that failed the analysis
+        self.costs.insert(name.clone(), ExecutionCost::zero());
+
+        let cost = match self.functions.get(name) {
+            Some(function) => {
+                let (body, parameters) = (function.body, function.parameters.clone());
+                self.get_expression_cost(body, &parameters)
+            }
+            None => ExecutionCost::zero(),
+        };
+        self.costs.insert(name.clone(), cost.clone());
+        cost
+    }
+
+    fn get_expressions_cost(
+        &mut self,
+        expressions: &[SymbolicExpression],
+        parameters: &HashMap<ClarityName, TypeSignature>,
+    ) -> ExecutionCost {
+        let mut total = ExecutionCost::zero();
+        for expr in expressions {
+            add_cost(&mut total, &self.get_expression_cost(expr, parameters));
+        }
+        total
+    }
+
+    fn get_max_expressions_cost(
+        &mut self,
+        expressions: &[&SymbolicExpression],
+        parameters: &HashMap<ClarityName, TypeSignature>,
+    ) -> ExecutionCost {
+        let mut max = ExecutionCost::zero();
+        for expr in expressions {
+            max = max_cost(&max, &self.get_expression_cost(expr, parameters));
+        }
+        max
+    }
+
+    fn get_sequence_max_len(
+        &self,
+        expr: &SymbolicExpression,
+        parameters: &HashMap<ClarityName, TypeSignature>,
+    ) -> u64 {
+        if let Some(name) = expr.match_atom() {
+            if let Some(TypeSignature::SequenceType(SequenceSubtype::ListType(list))) =
+                parameters.get(name)
+            {
+                return u64::from(list.get_max_len());
+            }
+        }
+        if let Some((name, items)) = expr.match_list().and_then(|l| l.split_first()) {
+            if name.match_atom().map(|n| n.as_str()) == Some("list") {
+                return items.len() as u64;
+            }
+        }
+        1
+    }
+
+    // input of the cost function of natives whose cost depends on the size of stored data
+    fn get_data_size(&self, function_name: &str, args: &[SymbolicExpression]) -> Option<u64> {
+        let name = args.first()?.match_atom()?;
+        let size = match function_name {
+            "var-get" | "var-set" => self.analysis.persisted_variable_types.get(name)?.size(),
+            "map-get?" | "map-set" | "map-insert" | "map-delete" => {
+                let (key, value) = self.analysis.map_types.get(name)?;
+                key.size() + value.size()
+            }
+            "nft-mint?" | "nft-transfer?" | "nft-get-owner?" | "nft-burn?" => {
+                self.analysis.non_fungible_tokens.get(name)?.size()
+            }
+            _ => return None,
+        };
+        Some(u64::from(size))
+    }
+
+    fn get_iteration_cost(
+        &mut self,
+        function_name: &ClarityName,
+        arguments_count: usize,
+    ) -> ExecutionCost {
+        if self.functions.contains_key(function_name) {
+            let mut cost =
+                eval_cost_function("cost_user_function_application", arguments_count as u64);
+            add_cost(&mut cost, &self.get_function_cost(function_name));
+            return cost;
+        }
+        match NATIVE_COST_FUNCTIONS.get(function_name.as_str()) {
+            Some(cost_function) => eval_cost_function(cost_function, arguments_count as u64),
+            None => ExecutionCost::zero(),
+        }
+    }
+
+    fn get_expression_cost(
+        &mut self,
+        expr: &SymbolicExpression,
+        parameters: &HashMap<ClarityName, TypeSignature>,
+    ) -> ExecutionCost {
+        let (function_name, args) = match expr.match_list().and_then(|l| l.split_first()) {
+            Some(list) => list,
+            None => return ExecutionCost::zero(),
+        };
+        let function_name = match function_name.match_atom() {
+            Some(function_name) => function_name,
+            None => {
+                return self.get_expressions_cost(expr.match_list().unwrap_or_default(), parameters)
+            }
+        };
+
+        let mut cost = ExecutionCost::zero();
+        match function_name.as_str() {
+            "if" => {
+                add_cost(&mut cost, &eval_cost_function("cost_if", 0));
+                if let Some((condition, branches)) = args.split_first() {
+                    add_cost(&mut cost, &self.get_expression_cost(condition, parameters));
+                    let branches = branches.iter().collect::<Vec<_>>();
+                    add_cost(
+                        &mut cost,
+                        &self.get_max_expressions_cost(&branches, parameters),
+                    );
+                }
+            }
+            "match" => {
+                add_cost(&mut cost, &eval_cost_function("cost_match", 0));
+                if let Some((input, branches)) = args.split_first() {
+                    add_cost(&mut cost, &self.get_expression_cost(input, parameters));
+                    // (match opt some-name some-branch none-branch)
+                    // (match resp ok-name ok-branch err-name err-branch)
+                    let branches = match branches.len() {
+                        3 => vec![&branches[1], &branches[2]],
+                        4 => vec![&branches[1], &branches[3]],
+                        _ => branches.iter().collect(),
+                    };
+                    add_cost(
+                        &mut cost,
+                        &self.get_max_expressions_cost(&branches, parameters),
+                    );
+                }
+            }
+            "let" => {
+                if let Some((bindings, body)) = args.split_first() {
+                    let bindings = bindings.match_list().unwrap_or_default();
+                    add_cost(
+                        &mut cost,
+                        &eval_cost_function("cost_let", bindings.len() as u64),
+                    );
+                    for binding in bindings {
+                        if let Some(value) = binding.match_list().and_then(|b| b.get(1)) {
+                            add_cost(&mut cost, &self.get_expression_cost(value, parameters));
+                        }
+                    }
+                    add_cost(&mut cost, &self.get_expressions_cost(body, parameters));
+                }
+            }
+            "map" | "filter" | "fold" => {
+                let (iterated_function, sequences) = match args.split_first() {
+                    Some(args) => args,
+                    None => return cost,
+                };
+                let (cost_function, sequences, arguments_count) = match function_name.as_str() {
+                    "map" => ("cost_map", sequences, sequences.len()),
+                    "filter" => ("cost_filter", sequences, 1),
+                    _ => ("cost_fold", &sequences[..sequences.len().min(1)], 2),
+                };
+                add_cost(
+                    &mut cost,
+                    &eval_cost_function(cost_function, sequences.len() as u64),
+                );
+                add_cost(&mut cost, &self.get_expressions_cost(sequences, parameters));
+                if function_name.as_str() == "fold" {
+                    add_cost(
+                        &mut cost,
+                        &self.get_expressions_cost(args.get(2..).unwrap_or_default(), parameters),
+                    );
+                }
+
+                let iterations = sequences
+                    .iter()
+                    .map(|sequence| self.get_sequence_max_len(sequence, parameters))
+                    .max()
+                    .unwrap_or(0);
+                if let Some(iterated_function) = iterated_function.match_atom() {
+                    let iteration_cost =
+                        self.get_iteration_cost(iterated_function, arguments_count);
+                    add_cost(&mut cost, &multiply_cost(&iteration_cost, iterations));
+                }
+            }
+            "contract-call?" => {
+                add_cost(&mut cost, &eval_cost_function("cost_contract_call", 0));
+                add_cost(
+                    &mut cost,
+                    &self.get_expressions_cost(args.get(2..).unwrap_or_default(), parameters),
+                );
+            }
+            _ if self.functions.contains_key(function_name) => {
+                add_cost(
+                    &mut cost,
+                    &eval_cost_function("cost_user_function_application", args.len() as u64),
+                );
+                add_cost(&mut cost, &self.get_function_cost(function_name));
+                add_cost(&mut cost, &self.get_expressions_cost(args, parameters));
+            }
+            name => {
+                if let Some(cost_function) = NATIVE_COST_FUNCTIONS.get(name) {
+                    let n = self.get_data_size(name, args).unwrap_or(args.len() as u64);
+                    add_cost(&mut cost, &eval_cost_function(cost_function, n));
+                }
+                add_cost(&mut cost, &self.get_expressions_cost(args, parameters));
+            }
+        }
+        cost
+    }
+}
+
+pub fn get_functions_costs(
+    expressions: &[SymbolicExpression],
+    analysis: &ContractAnalysis,
+) -> HashMap<ClarityName, FunctionCost> {
+    CostEstimator::new(expressions, analysis).run()
+}
+
+fn format_percentage(fraction: f64) -> String {
+    format!("{:.2}%", fraction * 100.0)
+}
+
+pub fn get_cost_documentation(function_cost: &FunctionCost) -> String {
+    let cost = &function_cost.cost;
+    let rows = [
+        ("runtime", cost.runtime, BLOCK_LIMIT.runtime),
+        ("read_count", cost.read_count, BLOCK_LIMIT.read_count),
+        ("read_length", cost.read_length, BLOCK_LIMIT.read_length),
+        ("write_count", cost.write_count, BLOCK_LIMIT.write_count),
+        ("write_length", cost.write_length, BLOCK_LIMIT.write_length),
+    ]
+    .iter()
+    .map(|(dimension, value, limit)| {
+        format!(
+            "| {} | {} | {} |",
+            dimension,
+            value,
+            format_percentage(*value as f64 / *limit as f64)
+        )
+    })
+    .collect::<Vec<_>>();
+
+    [
+        "**Estimated worst-case cost**".to_string(),
+        String::new(),
+        "| | cost | block limit |".to_string(),
+        "|---|---:|---:|".to_string(),
+        rows.join("\n"),
+    ]
+    .join("\n")
+}
+
+pub fn get_cost_inlay_hints(
+    functions_costs: &HashMap<ClarityName, FunctionCost>,
+) -> Vec<InlayHint> {
+    let mut functions_costs = functions_costs.values().collect::<Vec<_>>();
+    functions_costs.sort_by_key(|function_cost| span_to_range(&function_cost.span).start);
+
+    functions_costs
+        .into_iter()
+        .map(|function_cost| {
+            let (fraction, dimension) = get_block_limit_fraction(&function_cost.cost);
+            InlayHint {
+                position: span_to_range(&function_cost.span).end,
+                label: InlayHintLabel::String(format!(
+                    "{} of block ({})",
+                    format_percentage(fraction),
+                    dimension
+                )),
+                kind: None,
+                text_edits: None,
+                tooltip: Some(InlayHintTooltip::MarkupContent(MarkupContent {
+                    kind: MarkupKind::Markdown,
+                    value: get_cost_documentation(function_cost),
+                })),
+                padding_left: Some(true),
+                padding_right: None,
+                data: None,
+            }
+        })
+        .collect()
+}
+
+pub fn get_cost_at_position<'a>(
+    functions_costs: &'a HashMap<ClarityName, FunctionCost>,
+    position: &Position,
+) -> Option<&'a FunctionCost> {
+    functions_costs.values().find(|function_cost| {
+        is_position_within_range(position, &span_to_range(&function_cost.span))
+    })
+}
+
+pub fn get_cost_warnings(
+    functions_costs: &HashMap<ClarityName, FunctionCost>,
+    threshold: f64,
+) -> Vec<Diagnostic> {
+    let mut warnings = functions_costs
+        .iter()
+        .filter_map(|(name, function_cost)| {
+            let (fraction, dimension) = get_block_limit_fraction(&function_cost.cost);
+            if fraction <= threshold {
+                return None;
+            }
+            Some(Diagnostic {
+                level: Level::Warning,
+                message: format!(
+                    "function '{}' can use up to {} of the block limit ({})",
+                    name,
+                    format_percentage(fraction),
+                    dimension
+                ),
+                spans: vec![function_cost.span.clone()],
+                suggestion: None,
+            })
+        })
+        .collect::<Vec<_>>();
+    warnings.sort_by_key(|diagnostic| {
+        diagnostic
+            .spans
+            .first()
+            .map(|span| (span.start_line, span.start_column))
+    });
+    warnings
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use clarity_repl::clarity::costs::ExecutionCost;
+    use clarity_repl::clarity::diagnostic::Level;
+    use clarity_repl::clarity::representations::Span;
+    use clarity_repl::clarity::vm::types::StandardPrincipalData;
+    use clarity_repl::clarity::{ClarityName, ClarityVersion, StacksEpochId};
+    use clarity_repl::repl::{
+        ClarityCodeSource, ClarityContract, ClarityInterpreter, ContractDeployer, Settings,
+    };
+
+    use super::{
+        add_cost, eval_cost_function, get_cost_warnings, get_functions_costs, multiply_cost,
+        CostFormula, FunctionCost, BLOCK_LIMIT, COST_FUNCTIONS,
+    };
+
+    fn get_costs(source: &str) -> HashMap<ClarityName, FunctionCost> {
+        let contract = ClarityContract {
+            code_source: ClarityCodeSource::ContractInMemory(source.to_string()),
+            name: "contract".to_string(),
+            deployer: ContractDeployer::Transient,
+            clarity_version: ClarityVersion::Clarity2,
+            epoch: StacksEpochId::Epoch21,
+        };
+        let mut interpreter =
+            ClarityInterpreter::new(StandardPrincipalData::transient(), Settings::default());
+        let (mut ast, _, success) = interpreter.build_ast(&contract);
+        assert!(success);
+        let (analysis, _) = interpreter
+            .run_analysis(&contract, &mut ast, &vec![])
+            .unwrap();
+        get_functions_costs(&ast.expressions, &analysis)
+    }
+
+    fn get_cost(costs: &HashMap<ClarityName, FunctionCost>, name: &str) -> ExecutionCost {
+        costs
+            .iter()
+            .find(|(function_name, _)| function_name.as_str() == name)
+            .map(|(_, function_cost)| function_cost.cost.clone())
+            .unwrap()
+    }
+
+    #[test]
+    fn parse_costs_3_boot_contract() {
+        let add = COST_FUNCTIONS.get("cost_add").unwrap();
+        assert_eq!(add.runtime, CostFormula::Linear(11, 125));
+        assert_eq!(add.runtime.eval(2), 147);
+
+        let set_entry = COST_FUNCTIONS.get("cost_set_entry").unwrap();
+        assert_eq!(set_entry.runtime, CostFormula::Linear(4, 1899));
+        assert_eq!(set_entry.write_count, CostFormula::Constant(1));
+        assert_eq!(set_entry.read_length, CostFormula::Constant(0));
+
+        let tuple_get = COST_FUNCTIONS.get("cost_tuple_get").unwrap();
+        assert_eq!(tuple_get.runtime, CostFormula::NLogN(4, 1736));
+        assert_eq!(tuple_get.runtime.eval(4), 4 * 4 * 2 + 1736);
+    }
+
+    #[test]
+    fn take_the_most_expensive_branch() {
+        let costs = get_costs(
+            &[
+                "(define-read-only (add-4) (+ u1 u2 u3 u4))",
+                "(define-read-only (with-if (flag bool)) (if flag (+ u1 u2) (+ u1 u2 u3 u4)))",
+                "(define-read-only (with-match (value (optional uint)))",
+                "  (match value v (+ v u1 u2 u3) u0))",
+            ]
+            .join("\n"),
+        );
+        let add_4 = get_cost(&costs, "add-4");
+
+        let mut expected = eval_cost_function("cost_if", 0);
+        add_cost(&mut expected, &add_4);
+        assert_eq!(get_cost(&costs, "with-if"), expected);
+
+        let mut expected = eval_cost_function("cost_match", 0);
+        add_cost(&mut expected, &add_4);
+        assert_eq!(get_cost(&costs, "with-match"), expected);
+    }
+
+    #[test]
+    fn multiply_iterations_by_the_list_length() {
+        let costs = get_costs(
+            &[
+                "(define-private (incr (n uint)) (+ n u1))",
+                "(define-read-only (incr-all (l (list 10 uint))) (map incr l))",
+                "(define-read-only (sum (l (list 10 uint))) (fold + l u0))",
+            ]
+            .join("\n"),
+        );
+
+        let mut iteration = eval_cost_function("cost_user_function_application", 1);
+        add_cost(&mut iteration, &get_cost(&costs, "incr"));
+        let mut expected = eval_cost_function("cost_map", 1);
+        add_cost(&mut expected, &multiply_cost(&iteration, 10));
+        assert_eq!(get_cost(&costs, "incr-all"), expected);
+
+        let mut expected = eval_cost_function("cost_fold", 1);
+        add_cost(
+            &mut expected,
+            &multiply_cost(&eval_cost_function("cost_add", 2), 10),
+        );
+        assert_eq!(get_cost(&costs, "sum"), expected);
+    }
+
+    #[test]
+    fn inline_the_cost_of_private_functions() {
+        let costs = get_costs(
+            &[
+                "(define-private (incr (n uint)) (+ n u1))",
+                "(define-read-only (call-incr) (incr u1))",
+            ]
+            .join("\n"),
+        );
+
+        let incr = get_cost(&costs, "incr");
+        assert_eq!(incr, eval_cost_function("cost_add", 2));
+
+        let mut expected = eval_cost_function("cost_user_function_application", 1);
+        add_cost(&mut expected, &incr);
+        assert_eq!(get_cost(&costs, "call-incr"), expected);
+    }
+
+    #[test]
+    fn warn_above_the_threshold() {
+        let mut cost = ExecutionCost::zero();
+        cost.runtime = BLOCK_LIMIT.runtime / 2;
+        let costs = HashMap::from([(
+            ClarityName::from("expensive"),
+            FunctionCost {
+                cost,
+                span: Span::zero(),
+            },
+        )]);
+
+        assert!(get_cost_warnings(&costs, 0.5).is_empty());
+
+        let warnings = get_cost_warnings(&costs, 0.4);
+        assert_eq!(warnings.len(), 1);
+        assert_eq!(warnings[0].level, Level::Warning);
+        assert_eq!(
+            warnings[0].message,
+            "function 'expensive' can use up to 50.00% of the block limit (runtime)"
+        );
+    }
+}
@@ -0,0 +1,579 @@
+use std::collections::{HashMap, HashSet};
+
+use clarinet_deployments::types::{
+    ContractCallSpecification, DeploymentSpecificationFile, EmulatedContractCallSpecification,
+    EpochSpec, StxTransferSpecification, TransactionSpecificationFile,
+};
+use clarity_repl::clarity::analysis::ContractAnalysis;
+use clarity_repl::clarity::ast::{build_ast_with_rules, ASTRules};
+use clarity_repl::clarity::diagnostic::{Diagnostic, Level};
+use clarity_repl::clarity::vm::types::{FunctionType, PrincipalData, QualifiedContractIdentifier};
+use clarity_repl::clarity::{ClarityVersion, StacksEpochId, SymbolicExpressionType, Value};
+use lazy_static::lazy_static;
+use lsp_types::{CompletionItem, CompletionItemKind, Position};
+
+use super::helpers::get_line_span;
+
+lazy_static! {
+    static ref DEPLOYMENT_PLAN_DOCUMENTATION: HashMap<&'static str, &'static str> =
+        HashMap::from([
+            ("id", "Identifier of the deployment plan or of the batch."),
+            ("name", "Name of the deployment plan."),
+            ("network", "Network targeted by the plan: `simnet`, `devnet`, `testnet` or `mainnet`."),
+            ("stacks-node", "URL of the Stacks node used to broadcast the transactions."),
+            ("bitcoin-node", "URL of the Bitcoin node used to broadcast the transactions."),
+            ("genesis", "Wallets and boot contracts available in simnet."),
+            ("plan", "Batches of transactions to execute."),
+            ("batches", "Transactions are grouped in batches, a batch is included in a single block."),
+            ("transactions", "Transactions of the batch."),
+            ("epoch", "Stacks epoch in which the batch is executed (`2.0`, `2.05`, `2.1`, ..., `3.0`)."),
+            ("contract-publish", "Publish a contract, signed by `expected-sender`."),
+            ("contract-call", "Call a public function of a contract, signed by `expected-sender`."),
+            ("emulated-contract-publish", "Publish a contract in simnet, on behalf of `emulated-sender`."),
+            ("emulated-contract-call", "Call a public function of a contract in simnet, on behalf of `emulated-sender`."),
+            ("requirement-publish", "Publish a copy of a contract required by the project, with its principals remapped to `remap-sender`."),
+            ("stx-transfer", "Transfer STX from `expected-sender` to `recipient`."),
+            ("btc-transfer", "Transfer BTC from `expected-sender` to `recipient`."),
+            ("contract-id", "Identifier of the contract, formatted as `<principal>.<contract-name>`."),
+            ("contract-name", "Name of the contract to publish."),
+            ("expected-sender", "Address signing the transaction."),
+            ("emulated-sender", "Address on behalf of which the transaction is emulated."),
+            ("method", "Name of the public function to call."),
+            ("parameters", "Arguments of the function, as Clarity values."),
+            ("cost", "Fee of the transaction, in micro-STX."),
+            ("anchor-block-only", "Only include the transaction in anchor blocks (defaults to `true`)."),
+            ("post-condition-mode", "`deny` aborts the call if it sends assets not covered by a post-condition (defaults to `allow`)."),
+            ("post-conditions", "Post-conditions checked against the assets sent by the call."),
+            ("principal", "Principal sending the asset: `origin`, an address or a contract identifier."),
+            ("asset", "Asset of the post-condition, formatted as `<contract-id>::<asset-name>` (STX if omitted)."),
+            ("condition", "`sent-eq`, `sent-gt`, `sent-ge`, `sent-lt` or `sent-le` for STX and fungible tokens, `sent` or `not-sent` for non-fungible tokens."),
+            ("amount", "Amount compared by the post-condition, for STX and fungible tokens."),
+            ("value", "Non-fungible token checked by the post-condition, as a Clarity value."),
+            ("path", "Path of the contract source, relative to the project root."),
+            ("url", "URL of the contract source."),
+            ("clarity-version", "Version of Clarity used to publish the contract (`1` or `2`)."),
+            ("remap-sender", "Address publishing the copy of the required contract."),
+            ("remap-principals", "Principals to replace in the source of the required contract."),
+            ("recipient", "Recipient of the transfer."),
+            ("mstx-amount", "Amount of the transfer, in micro-STX."),
+            ("memo", "Memo of the transfer, hex encoded, up to 34 bytes."),
+            ("sats-amount", "Amount of the transfer, in satoshis."),
+            ("sats-per-byte", "Fee rate of the transfer, in satoshis per byte."),
+        ]);
+}
+
+const TRANSACTION_KEYS: [&str; 7] = [
+    "contract-call",
+    "contract-publish",
+    "emulated-contract-call",
+    "emulated-contract-publish",
+    "requirement-publish",
+    "btc-transfer",
+    "stx-transfer",
+];
+
+const EPOCHS: [&str; 8] = ["2.0", "2.05", "2.1", "2.2", "2.3", "2.4", "2.5", "3.0"];
+
+fn get_transaction_key(transaction: &TransactionSpecificationFile) -> &'static str {
+    match transaction {
+        TransactionSpecificationFile::ContractCall(_) => "contract-call",
+        TransactionSpecificationFile::ContractPublish(_) => "contract-publish",
+        TransactionSpecificationFile::EmulatedContractCall(_) => "emulated-contract-call",
+        TransactionSpecificationFile::EmulatedContractPublish(_) => "emulated-contract-publish",
+        TransactionSpecificationFile::RequirementPublish(_) => "requirement-publish",
+        TransactionSpecificationFile::BtcTransfer(_) => "btc-transfer",
+        TransactionSpecificationFile::StxTransfer(_) => "stx-transfer",
+    }
+}
+
+// the key of a yaml line, ignoring the indentation and the list item marker
+fn get_line_key(line: &str) -> Option<&str> {
+    let line = line.trim_start();
+    let line = line.strip_prefix("- ").unwrap_or(line).trim_start();
+    let (key, _) = line.split_once(':')?;
+    Some(key.trim())
+}
+
+fn find_key_line(lines: &[&str], start: usize, end: usize, key: &str) -> Option<usize> {
+    (start..end.min(lines.len())).find(|i| get_line_key(lines[*i]) == Some(key))
+}
+
+fn is_boot_contract(contract_id: &QualifiedContractIdentifier) -> bool {
+    let issuer = contract_id.issuer.to_address();
+    issuer == "SP000000000000000000002Q6VF78" || issuer == "ST000000000000000000002AMW42H"
+}
+
+fn get_published_contracts(
+    deployment: &DeploymentSpecificationFile,
+) -> HashSet<QualifiedContractIdentifier> {
+    let mut published = HashSet::new();
+    let batches = match &deployment.plan {
+        Some(plan) => &plan.batches,
+        None => return published,
+    };
+    for transaction in batches.iter().flat_map(|batch| batch.transactions.iter()) {
+        let contract_id = match transaction {
+            TransactionSpecificationFile::ContractPublish(spec) => {
+                format!("{}.{}", spec.expected_sender, spec.contract_name)
+            }
+            TransactionSpecificationFile::EmulatedContractPublish(spec) => {
+                format!("{}.{}", spec.emulated_sender, spec.contract_name)
+            }
+            TransactionSpecificationFile::RequirementPublish(spec) => {
+                if let Ok(contract_id) = QualifiedContractIdentifier::parse(&spec.contract_id) {
+                    published.insert(
+                        QualifiedContractIdentifier::parse(&format!(
+                            "{}.{}",
+                            spec.remap_sender, contract_id.name
+                        ))
+                        .unwrap_or(contract_id),
+                    );
+                }
+                spec.contract_id.clone()
+            }
+            _ => continue,
+        };
+        if let Ok(contract_id) = QualifiedContractIdentifier::parse(&contract_id) {
+            published.insert(contract_id);
+        }
+    }
+    published
+}
+
+fn check_parameter(parameter: &str) -> Result<Option<Value>, String> {
+    let ast = build_ast_with_rules(
+        &QualifiedContractIdentifier::transient(),
+        parameter,
+        &mut (),
+        ClarityVersion::Clarity2,
+        StacksEpochId::Epoch21,
+        ASTRules::Typical,
+    )
+    .map_err(|err| err.diagnostic.message)?;
+    match ast.expressions.as_slice() {
+        [expr] => match &expr.expr {
+            SymbolicExpressionType::LiteralValue(value) => Ok(Some(value.clone())),
+            _ => Ok(None),
+        },
+        _ => Err("a parameter must be a single clarity expression".to_string()),
+    }
+}
+
+fn check_contract_call(
+    contract_id: &QualifiedContractIdentifier,
+    method: &str,
+    parameters: &[String],
+    known_contracts: &HashMap<QualifiedContractIdentifier, Option<&ContractAnalysis>>,
+    published_contracts: &HashSet<QualifiedContractIdentifier>,
+) -> Vec<(&'static str, String)> {
+    let mut errors = vec![];
+    let analysis = match known_contracts.get(contract_id) {
+        Some(analysis) => analysis,
+        None => {
+            if !published_contracts.contains(contract_id) && !is_boot_contract(contract_id) {
+                errors.push(("contract-id", format!("unknown contract '{}'", contract_id)));
+            }
+            return errors;
+        }
+    };
+    let analysis = match analysis {
+        Some(analysis) => analysis,
+        None => return errors,
+    };
+
+    let function = match analysis
+        .public_function_types
+        .iter()
+        .find(|(name, _)| name.as_str() == method)
+    {
+        Some((_, FunctionType::Fixed(function))) => function,
+        Some(_) => return errors,
+        None => {
+            errors.push((
+                "method",
+                format!(
+                    "contract '{}' has no public function '{}'",
+                    contract_id, method
+                ),
+            ));
+            return errors;
+        }
+    };
+
+    if function.args.len() != parameters.len() {
+        errors.push((
+            "parameters",
+            format!(
+                "'{}' expects {} parameters, {} provided",
+                method,
+                function.args.len(),
+                parameters.len()
+            ),
+        ));
+        return errors;
+    }
+    for (arg, parameter) in function.args.iter().zip(parameters.iter()) {
+        match check_parameter(parameter) {
+            Ok(Some(value)) => {
+                if !arg
+                    .signature
+                    .admits(&StacksEpochId::Epoch21, &value)
+                    .unwrap_or(false)
+                {
+                    errors.push((
+                        "parameters",
+                        format!(
+                            "parameter '{}' expects a value of type {}, found '{}'",
+                            arg.name, arg.signature, parameter
+                        ),
+                    ));
+                }
+            }
+            Ok(None) => {}
+            Err(message) => errors.push((
+                "parameters",
+                format!("unable to parse parameter '{}': {}", parameter, message),
+            )),
+        }
+    }
+    errors
+}
+
+fn check_clarity_version(
+    clarity_version: Option<u8>,
+    epoch: Option<EpochSpec>,
+) -> Option<(&'static str, String)> {
+    match (clarity_version?, epoch) {
+        (1, _) => None,
+        (2, Some(epoch)) if epoch < EpochSpec::Epoch2_1 => Some((
+            "clarity-version",
+            "clarity 2 contracts can not be published before epoch 2.1".to_string(),
+        )),
+        (2, _) => None,
+        (version, _) => Some((
+            "clarity-version",
+            format!(
+                "clarity-version {} invalid (value supported: 1, 2)",
+                version
+            ),
+        )),
+    }
+}
+
+fn check_transaction(
+    transaction: &TransactionSpecificationFile,
+    epoch: Option<EpochSpec>,
+    known_contracts: &HashMap<QualifiedContractIdentifier, Option<&ContractAnalysis>>,
+    published_contracts: &HashSet<QualifiedContractIdentifier>,
+) -> Vec<(&'static str, String)> {
+    match transaction {
+        TransactionSpecificationFile::ContractCall(spec) => {
+            match ContractCallSpecification::from_specifications(spec) {
+                Ok(call) => check_contract_call(
+                    &call.contract_id,
+                    call.method.as_str(),
+                    &call.parameters,
+                    known_contracts,
+                    published_contracts,
+                ),
+                Err(message) => vec![("contract-call", message)],
+            }
+        }
+        TransactionSpecificationFile::EmulatedContractCall(spec) => {
+            match EmulatedContractCallSpecification::from_specifications(spec) {
+                Ok(call) => check_contract_call(
+                    &call.contract_id,
+                    call.method.as_str(),
+                    &call.parameters,
+                    known_contracts,
+                    published_contracts,
+                ),
+                Err(message) => vec![("emulated-contract-call", message)],
+            }
+        }
+        TransactionSpecificationFile::StxTransfer(spec) => {
+            match StxTransferSpecification::from_specifications(spec) {
+                Ok(_) => vec![],
+                Err(message) => vec![("stx-transfer", message)],
+            }
+        }
+        TransactionSpecificationFile::ContractPublish(spec) => {
+            let mut errors = vec![];
+            if PrincipalData::parse_standard_principal(&spec.expected_sender).is_err() {
+                errors.push((
+                    "expected-sender",
+                    format!(
+                        "unable to parse '{}' as a valid Stacks address",
+                        spec.expected_sender
+                    ),
+                ));
+            }
+            errors.extend(check_clarity_version(spec.clarity_version, epoch));
+            errors
+        }
+        TransactionSpecificationFile::EmulatedContractPublish(spec) => {
+            let mut errors = vec![];
+            if PrincipalData::parse_standard_principal(&spec.emulated_sender).is_err() {
+                errors.push((
+                    "emulated-sender",
+                    format!(
+                        "unable to parse '{}' as a valid Stacks address",
+                        spec.emulated_sender
+                    ),
+                ));
+            }
+            errors.extend(check_clarity_version(spec.clarity_version, epoch));
+            errors
+        }
+        TransactionSpecificationFile::RequirementPublish(spec) => {
+            let mut errors = vec![];
+            if QualifiedContractIdentifier::parse(&spec.contract_id).is_err() {
+                errors.push((
+                    "contract-id",
+                    format!(
+                        "unable to parse '{}' as a valid contract identifier",
+                        spec.contract_id
+                    ),
+                ));
+            }
+            errors.extend(check_clarity_version(spec.clarity_version, epoch));
+            errors
+        }
+        TransactionSpecificationFile::BtcTransfer(_) => vec![],
+    }
+}
+
+pub fn get_deployment_plan_diagnostics(
+    source: &str,
+    known_contracts: &HashMap<QualifiedContractIdentifier, Option<&ContractAnalysis>>,
+) -> Vec<Diagnostic> {
+    let deployment: DeploymentSpecificationFile = match serde_yaml::from_str(source) {
+        Ok(deployment) => deployment,
+        Err(err) => {
+            let line = err
+                .location()
+                .map(|location| location.line().saturating_sub(1))
+                .unwrap_or(0);
+            return vec![Diagnostic {
+                level: Level::Error,
+                message: format!("unable to parse deployment plan: {}", err),
+                spans: vec![get_line_span(source, line)],
+                suggestion: None,
+            }];
+        }
+    };
+
+    let published_contracts = get_published_contracts(&deployment);
+    let lines = source.lines().collect::<Vec<_>>();
+    let batches = match &deployment.plan {
+        Some(plan) => &plan.batches,
+        None => return vec![],
+    };
+
+    // the yaml parser doesn't keep track of the position of the values, so the
+    // transactions are located by looking for their keys, in the order of the plan
+    let mut diagnostics = vec![];
+    let mut cursor = 0;
+    for batch in batches.iter() {
+        for transaction in batch.transactions.iter() {
+            let transaction_key = get_transaction_key(transaction);
+            let start =
+                find_key_line(&lines, cursor, lines.len(), transaction_key).unwrap_or(cursor);
+            let end = (start + 1..lines.len())
+                .find(|i| {
+                    get_line_key(lines[*i]).map_or(false, |key| TRANSACTION_KEYS.contains(&key))
+                })
+                .unwrap_or(lines.len());
+            cursor = start + 1;
+
+            for (key, message) in check_transaction(
+                transaction,
+                batch.epoch,
+                known_contracts,
+                &published_contracts,
+            ) {
+                let line = find_key_line(&lines, start, end, key).unwrap_or(start);
+                diagnostics.push(Diagnostic {
+                    level: Level::Error,
+                    message,
+                    spans: vec![get_line_span(source, line)],
+                    suggestion: None,
+                });
+            }
+        }
+    }
+    diagnostics
+}
+
+fn get_contract_id_above(lines: &[&str], line: usize) -> Option<QualifiedContractIdentifier> {
+    for i in (0..line).rev() {
+        match get_line_key(lines[i]) {
+            Some("contract-id") => {
+                let (_, value) = lines[i].split_once(':')?;
+                let value = value.trim().trim_matches(|c| c == '"' || c == '\'');
+                return QualifiedContractIdentifier::parse(value).ok();
+            }
+            Some("contract-call") | Some("emulated-contract-call") => return None,
+            _ => {}
+        }
+    }
+    None
+}
+
+fn get_function_parameters_detail(signature: &FunctionType) -> Option<String> {
+    match signature {
+        FunctionType::Fixed(function) => Some(
+            function
+                .args
+                .iter()
+                .map(|arg| format!("({} {})", arg.name, arg.signature))
+                .collect::<Vec<_>>()
+                .join(" "),
+        ),
+        _ => None,
+    }
+}
+
+pub fn get_deployment_plan_completion_items(
+    source: &str,
+    position: &Position,
+    known_contracts: &HashMap<QualifiedContractIdentifier, Option<&ContractAnalysis>>,
+) -> Vec<CompletionItem> {
+    let lines = source.lines().collect::<Vec<_>>();
+    let line = match lines.get(position.line as usize) {
+        Some(line) => line,
+        None => return vec![],
+    };
+    let prefix = line
+        .char_indices()
+        .nth(position.character as usize)
+        .map(|(i, _)| &line[..i])
+        .unwrap_or(line);
+
+    let mut items = match get_line_key(prefix) {
+        Some("contract-id") => known_contracts
+            .keys()
+            .map(|contract_id| CompletionItem {
+                label: contract_id.to_string(),
+                kind: Some(CompletionItemKind::MODULE),
+                ..Default::default()
+            })
+            .collect::<Vec<_>>(),
+        Some("method") => {
+            let analysis = get_contract_id_above(&lines, position.line as usize)
+                .and_then(|contract_id| known_contracts.get(&contract_id).cloned().flatten());
+            match analysis {
+                Some(analysis) => analysis
+                    .public_function_types
+                    .iter()
+                    .map(|(name, signature)| CompletionItem {
+                        label: name.to_string(),
+                        kind: Some(CompletionItemKind::FUNCTION),
+                        detail: get_function_parameters_detail(signature),
+                        ..Default::default()
+                    })
+                    .collect(),
+                None => vec![],
+            }
+        }
+        Some("epoch") => EPOCHS
+            .iter()
+            .map(|epoch| CompletionItem {
+                label: format!("\"{}\"", epoch),
+                kind: Some(CompletionItemKind::ENUM_MEMBER),
+                ..Default::default()
+            })
+            .collect(),
+        Some("clarity-version") => ["1", "2"]
+            .iter()
+            .map(|version| CompletionItem {
+                label: version.to_string(),
+                kind: Some(CompletionItemKind::ENUM_MEMBER),
+                ..Default::default()
+            })
+            .collect(),
+        _ => vec![],
+    };
+    items.sort_by(|a, b| a.label.cmp(&b.label));
+    items
+}
+
+pub fn get_deployment_plan_documentation(source: &str, position: &Position) -> Option<String> {
+    let line = source.lines().nth(position.line as usize)?;
+    let key = get_line_key(line)?;
+    let key_start = line.find(key)?;
+    let character = position.character as usize;
+    if character < key_start || character > key_start + key.len() {
+        return None;
+    }
+    DEPLOYMENT_PLAN_DOCUMENTATION
+        .get(key)
+        .map(|documentation| format!("**{}**\n\n{}", key, documentation))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use lsp_types::Position;
+
+    use super::{get_deployment_plan_diagnostics, get_deployment_plan_documentation};
+
+    const PLAN: &str = r#"---
+id: 0
+name: "Simulated deployment, used as a default for `clarinet console`, `clarinet test` and `clarinet check`"
+network: simnet
+genesis:
+  wallets:
+    - name: deployer
+      address: ST1PQHQKV0RJXZFY1DGX8MNSNYVE3VGZJSRTPGZGM
+      balance: "100000000000000"
+  contracts:
+    - costs
+plan:
+  batches:
+    - id: 0
+      transactions:
+        - emulated-contract-publish:
+            contract-name: counter
+            emulated-sender: ST1PQHQKV0RJXZFY1DGX8MNSNYVE3VGZJSRTPGZGM
+            path: contracts/counter.clar
+            clarity-version: 3
+        - emulated-contract-call:
+            contract-id: ST1PQHQKV0RJXZFY1DGX8MNSNYVE3VGZJSRTPGZGM.unknown
+            emulated-sender: ST1PQHQKV0RJXZFY1DGX8MNSNYVE3VGZJSRTPGZGM
+            method: increment
+            parameters: []
+      epoch: "2.1"
+"#;
+
+    #[test]
+    fn report_invalid_transactions() {
+        let diagnostics = get_deployment_plan_diagnostics(PLAN, &HashMap::new());
+        assert_eq!(diagnostics.len(), 2);
+        assert_eq!(
+            diagnostics[0].message,
+            "clarity-version 3 invalid (value supported: 1, 2)"
+        );
+        assert_eq!(diagnostics[0].spans[0].start_line, 20);
+        assert_eq!(
+            diagnostics[1].message,
+            "unknown contract 'ST1PQHQKV0RJXZFY1DGX8MNSNYVE3VGZJSRTPGZGM.unknown'"
+        );
+        assert_eq!(diagnostics[1].spans[0].start_line, 22);
+    }
+
+    #[test]
+    fn report_malformed_plan() {
+        let diagnostics = get_deployment_plan_diagnostics("id: 0\nname: [", &HashMap::new());
+        assert_eq!(diagnostics.len(), 1);
+    }
+
+    #[test]
+    fn document_transaction_fields() {
+        let documentation =
+            get_deployment_plan_documentation(PLAN, &Position::new(15, 14)).unwrap();
+        assert!(documentation.starts_with("**emulated-contract-publish**"));
+        assert!(get_deployment_plan_documentation(PLAN, &Position::new(17, 40)).is_none());
+    }
+}
@@ -16,6 +16,19 @@ pub fn span_to_range(span: &Span) -> Range {
     }
 }
 
+// span of the content of a line (0-based index) of a non-clarity file, such as a manifest or a deployment plan
+pub fn get_line_span(source: &str, line_index: usize) -> Span {
+    let line = source.lines().nth(line_index).unwrap_or_default();
+    let start = line.len() - line.trim_start().len();
+    let end = line.trim_end().len();
+    Span {
+        start_line: line_index as u32 + 1,
+        start_column: start as u32 + 1,
+        end_line: line_index as u32 + 1,
+        end_column: end.max(start + 1) as u32,
+    }
+}
+
 // end_offset is usded to include the end position of a keyword, for go to definition in particular
 pub fn is_position_within_span(position: &Position, span: &Span, end_offset: u32) -> bool {
     if position.line < span.start_line || position.line > span.end_line {
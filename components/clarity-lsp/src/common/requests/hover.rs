@@ -1,8 +1,21 @@
+use clarity_repl::analysis::doc_comments::DefinitionDoc;
 use clarity_repl::clarity::{ClarityVersion, SymbolicExpression};
 use lsp_types::Position;
 
 use super::{api_ref::API_REF, helpers::get_expression_name_at_position};
 
+// documentation of the functions, constants and data defined in the contract
+pub fn get_definition_documentation(
+    position: &Position,
+    expressions: &Vec<SymbolicExpression>,
+    docs: &[DefinitionDoc],
+) -> Option<String> {
+    let expression_name = get_expression_name_at_position(position, expressions)?;
+    docs.iter()
+        .find(|doc| doc.name == expression_name.as_str())
+        .map(|doc| doc.to_markdown())
+}
+
 pub fn get_expression_documentation(
     position: &Position,
     clarity_version: ClarityVersion,
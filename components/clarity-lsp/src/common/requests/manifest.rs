@@ -0,0 +1,146 @@
+use clarinet_files::{get_epoch_and_clarity_version, ProjectManifestFile};
+use clarity_repl::clarity::diagnostic::{Diagnostic, Level};
+use toml::Value;
+
+use super::helpers::get_line_span;
+
+fn get_key(line: &str) -> Option<&str> {
+    let (key, _) = line.split_once('=')?;
+    Some(key.trim().trim_matches('"'))
+}
+
+fn find_contract_section(lines: &[&str], contract_name: &str) -> Option<usize> {
+    let headers = [
+        format!("[contracts.{}]", contract_name),
+        format!("[contracts.\"{}\"]", contract_name),
+    ];
+    lines
+        .iter()
+        .position(|line| headers.iter().any(|header| line.trim() == header))
+}
+
+fn find_section_key(lines: &[&str], section: usize, key: &str) -> Option<usize> {
+    lines
+        .iter()
+        .enumerate()
+        .skip(section + 1)
+        .take_while(|(_, line)| !line.trim_start().starts_with('['))
+        .find(|(_, line)| get_key(line) == Some(key))
+        .map(|(i, _)| i)
+}
+
+fn new_diagnostic(source: &str, line: usize, level: Level, message: String) -> Diagnostic {
+    Diagnostic {
+        level,
+        message,
+        spans: vec![get_line_span(source, line)],
+        suggestion: None,
+    }
+}
+
+pub fn get_manifest_diagnostics(source: &str) -> Vec<Diagnostic> {
+    if let Err(err) = toml::from_str::<ProjectManifestFile>(source) {
+        let line = err.line_col().map(|(line, _)| line).unwrap_or(0);
+        return vec![new_diagnostic(
+            source,
+            line,
+            Level::Error,
+            format!("unable to parse manifest: {}", err),
+        )];
+    }
+
+    let manifest = match toml::from_str::<Value>(source) {
+        Ok(manifest) => manifest,
+        Err(_) => return vec![],
+    };
+    let contracts = match manifest.get("contracts") {
+        Some(Value::Table(contracts)) => contracts,
+        _ => return vec![],
+    };
+
+    let lines = source.lines().collect::<Vec<_>>();
+    let mut diagnostics = vec![];
+    for (contract_name, settings) in contracts.iter() {
+        let section = find_contract_section(&lines, contract_name);
+        let header_line = section.unwrap_or(0);
+        let settings = match settings {
+            Value::Table(settings) => settings,
+            _ => {
+                diagnostics.push(new_diagnostic(
+                    source,
+                    header_line,
+                    Level::Error,
+                    format!("invalid settings for contract '{}'", contract_name),
+                ));
+                continue;
+            }
+        };
+
+        if !settings.contains_key("path") {
+            diagnostics.push(new_diagnostic(
+                source,
+                header_line,
+                Level::Warning,
+                format!(
+                    "contract '{}' has no path and will be ignored",
+                    contract_name
+                ),
+            ));
+        }
+
+        if let Err(message) =
+            get_epoch_and_clarity_version(settings.get("epoch"), settings.get("clarity_version"))
+        {
+            // incompatible versions are reported on the clarity_version field
+            let key = if message.starts_with("epoch") {
+                "epoch"
+            } else {
+                "clarity_version"
+            };
+            let line = section
+                .and_then(|section| find_section_key(&lines, section, key))
+                .unwrap_or(header_line);
+            diagnostics.push(new_diagnostic(source, line, Level::Error, message));
+        }
+    }
+    diagnostics
+}
+
+#[cfg(test)]
+mod tests {
+    use clarity_repl::clarity::diagnostic::Level;
+
+    use super::get_manifest_diagnostics;
+
+    #[test]
+    fn report_invalid_contract_settings() {
+        let manifest = [
+            "[project]",
+            "name = \"counter\"",
+            "",
+            "[contracts.counter]",
+            "path = \"contracts/counter.clar\"",
+            "clarity_version = 2",
+            "epoch = 2.05",
+            "",
+            "[contracts.token]",
+            "epoch = \"1.0\"",
+        ]
+        .join("\n");
+
+        let diagnostics = get_manifest_diagnostics(&manifest);
+        assert_eq!(diagnostics.len(), 3);
+        assert_eq!(diagnostics[0].spans[0].start_line, 6);
+        assert_eq!(diagnostics[1].level, Level::Warning);
+        assert_eq!(diagnostics[1].spans[0].start_line, 9);
+        assert!(diagnostics[2].message.starts_with("epoch field invalid"));
+        assert_eq!(diagnostics[2].spans[0].start_line, 10);
+    }
+
+    #[test]
+    fn report_malformed_manifest() {
+        let diagnostics = get_manifest_diagnostics("[project]\nname = ");
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].spans[0].start_line, 2);
+    }
+}
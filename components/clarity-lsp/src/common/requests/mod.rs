@@ -1,8 +1,13 @@
 mod api_ref;
+pub mod call_hierarchy;
 pub mod capabilities;
+pub mod code_lens;
 pub mod completion;
+pub mod costs;
 pub mod definitions;
+pub mod deployment_plan;
 pub mod document_symbols;
 pub mod helpers;
 pub mod hover;
+pub mod manifest;
 pub mod signature_help;
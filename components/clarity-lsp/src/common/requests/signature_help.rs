@@ -1,5 +1,9 @@
+use clarity_repl::analysis::doc_comments::DefinitionDoc;
 use clarity_repl::clarity::docs::FunctionAPI;
-use lsp_types::{ParameterInformation, ParameterLabel, Position, SignatureInformation};
+use lsp_types::{
+    Documentation, MarkupContent, MarkupKind, ParameterInformation, ParameterLabel, Position,
+    SignatureInformation,
+};
 
 use crate::state::ActiveContractData;
 
@@ -28,7 +32,16 @@ pub fn get_signatures(
         return None;
     }
 
-    let (version, _, reference) = API_REF.get(&function_name.to_string())?;
+    let (version, _, reference) = match API_REF.get(&function_name.to_string()) {
+        Some(reference) => reference,
+        None => {
+            let doc = contract
+                .docs
+                .iter()
+                .find(|doc| doc.is_function() && doc.name == function_name.as_str())?;
+            return Some(vec![get_definition_signature(doc, active_parameter)]);
+        }
+    };
     let FunctionAPI {
         signature,
         output_type,
@@ -83,11 +96,51 @@ pub fn get_signatures(
     Some(signatures)
 }
 
+fn get_definition_signature(
+    doc: &DefinitionDoc,
+    active_parameter: Option<u32>,
+) -> SignatureInformation {
+    let mut documentation = vec![];
+    if !doc.comment.description.is_empty() {
+        documentation.push(doc.comment.description.clone());
+    }
+    if let Some(returns) = &doc.comment.returns {
+        documentation.push(format!("**Returns** {}", returns));
+    }
+
+    let parameters = doc
+        .parameters
+        .iter()
+        .map(|(name, type_signature)| ParameterInformation {
+            label: ParameterLabel::Simple(format!("({} {})", name, type_signature)),
+            documentation: doc
+                .comment
+                .get_param(name)
+                .map(|text| Documentation::String(text.to_string())),
+        })
+        .collect::<Vec<_>>();
+
+    SignatureInformation {
+        label: doc.get_signature(),
+        documentation: match documentation.is_empty() {
+            true => None,
+            false => Some(Documentation::MarkupContent(MarkupContent {
+                kind: MarkupKind::Markdown,
+                value: documentation.join("\n\n"),
+            })),
+        },
+        parameters: Some(parameters),
+        active_parameter,
+    }
+}
+
 #[cfg(test)]
 mod definitions_visitor_tests {
     use clarity_repl::clarity::functions::NativeFunctions;
     use clarity_repl::clarity::{ClarityVersion::Clarity2, StacksEpochId::Epoch21};
-    use lsp_types::{ParameterInformation, ParameterLabel::Simple, Position, SignatureInformation};
+    use lsp_types::{
+        Documentation, ParameterInformation, ParameterLabel::Simple, Position, SignatureInformation,
+    };
 
     use crate::state::ActiveContractData;
 
@@ -137,6 +190,37 @@ mod definitions_visitor_tests {
         );
     }
 
+    #[test]
+    fn get_user_defined_function_signature() {
+        let signatures = get_source_signature(
+            [
+                ";; Add two numbers",
+                ";; @param a the first number",
+                "(define-private (add (a int) (b int)) (+ a b))",
+                "(add 1 )",
+            ]
+            .join("\n")
+            .as_str(),
+            &Position {
+                line: 4,
+                character: 8,
+            },
+        )
+        .unwrap();
+
+        assert_eq!(signatures.len(), 1);
+        let signature = &signatures[0];
+        assert_eq!(signature.label, "(define-private (add (a int) (b int)))");
+        assert_eq!(signature.active_parameter, Some(1));
+        assert_eq!(
+            signature.parameters.as_ref().unwrap()[0],
+            ParameterInformation {
+                label: Simple("(a int)".to_string()),
+                documentation: Some(Documentation::String("the first number".to_string())),
+            }
+        );
+    }
+
     #[test]
     fn ensure_all_native_function_have_valid_signature() {
         for method in NativeFunctions::ALL_NAMES {
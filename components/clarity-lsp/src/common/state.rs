@@ -1,12 +1,13 @@
 use crate::common::requests::completion::check_if_should_wrap;
 use clarinet_deployments::{
-    generate_default_deployment, initiate_session_from_deployment,
+    generate_default_deployment, initiate_session_from_deployment, setup_session_with_deployment,
     update_session_with_contracts_executions,
 };
 use clarinet_files::chainhook_types::StacksNetwork;
 use clarinet_files::ProjectManifest;
 use clarinet_files::{FileAccessor, FileLocation};
 use clarity_repl::analysis::ast_dependency_detector::DependencySet;
+use clarity_repl::analysis::doc_comments::{get_definitions_docs, DefinitionDoc};
 use clarity_repl::clarity::analysis::ContractAnalysis;
 use clarity_repl::clarity::ast::{build_ast_with_rules, ASTRules};
 use clarity_repl::clarity::diagnostic::{Diagnostic as ClarityDiagnostic, Level as ClarityLevel};
@@ -15,24 +16,38 @@ use clarity_repl::clarity::vm::types::{QualifiedContractIdentifier, StandardPrin
 use clarity_repl::clarity::vm::EvaluationResult;
 use clarity_repl::clarity::{ClarityName, ClarityVersion, StacksEpochId, SymbolicExpression};
 use clarity_repl::repl::{ContractDeployer, DEFAULT_CLARITY_VERSION};
+use clarity_repl::utils::{serialize_event, value_to_string};
 use lsp_types::{
-    CompletionItem, DocumentSymbol, Hover, Location, MessageType, Position, Range, SignatureHelp,
-    Url,
+    CallHierarchyIncomingCall, CallHierarchyItem, CallHierarchyOutgoingCall, CodeLens,
+    CompletionItem, DocumentSymbol, Hover, InlayHint, Location, MessageType, Position, Range,
+    SignatureHelp, SymbolInformation, SymbolKind, Url,
 };
 use std::borrow::BorrowMut;
 use std::collections::{BTreeMap, HashMap, HashSet};
 use std::vec;
 
+use super::requests::call_hierarchy::{
+    get_call_graph, is_position_within_range, CallTarget, FunctionDefinition,
+};
 use super::requests::capabilities::InitializationOptions;
+use super::requests::code_lens::{get_code_lenses, FunctionCallArguments};
 use super::requests::completion::{
     build_completion_item_list, get_contract_calls, ContractDefinedData,
 };
+use super::requests::costs::{
+    get_cost_at_position, get_cost_documentation, get_cost_inlay_hints, get_cost_warnings,
+    get_functions_costs, FunctionCost,
+};
 use super::requests::definitions::{
     get_definitions, get_public_function_definitions, DefinitionLocation,
 };
+use super::requests::deployment_plan::{
+    get_deployment_plan_completion_items, get_deployment_plan_diagnostics,
+    get_deployment_plan_documentation,
+};
 use super::requests::document_symbols::ASTSymbols;
 use super::requests::helpers::get_atom_start_at_position;
-use super::requests::hover::get_expression_documentation;
+use super::requests::hover::{get_definition_documentation, get_expression_documentation};
 use super::requests::signature_help::get_signatures;
 
 #[derive(Debug, Clone, PartialEq)]
@@ -43,6 +58,7 @@ pub struct ActiveContractData {
     pub expressions: Option<Vec<SymbolicExpression>>,
     pub definitions: Option<HashMap<(u32, u32), DefinitionLocation>>,
     pub diagnostic: Option<ClarityDiagnostic>,
+    pub docs: Vec<DefinitionDoc>,
     source: String,
 }
 
@@ -68,6 +84,7 @@ impl ActiveContractData {
                 expressions: Some(ast.expressions.clone()),
                 definitions: Some(get_definitions(&ast.expressions, issuer)),
                 diagnostic: None,
+                docs: get_definitions_docs(source, &ast.expressions),
                 source: source.to_string(),
             },
             Err(err) => ActiveContractData {
@@ -77,6 +94,7 @@ impl ActiveContractData {
                 expressions: None,
                 definitions: None,
                 diagnostic: Some(err.diagnostic),
+                docs: vec![],
                 source: source.to_string(),
             },
         }
@@ -94,6 +112,7 @@ impl ActiveContractData {
             ASTRules::PrecheckSize,
         ) {
             Ok(ast) => {
+                self.docs = get_definitions_docs(source, &ast.expressions);
                 self.expressions = Some(ast.expressions);
                 self.diagnostic = None;
                 if with_definitions {
@@ -124,6 +143,12 @@ impl ActiveContractData {
     }
 }
 
+#[derive(Debug, Clone, PartialEq)]
+pub struct ActiveDeploymentData {
+    pub manifest_location: FileLocation,
+    pub source: String,
+}
+
 #[derive(Debug, Clone, PartialEq)]
 pub struct ContractState {
     contract_calls: Vec<CompletionItem>,
@@ -133,6 +158,10 @@ pub struct ContractState {
     contract_id: QualifiedContractIdentifier,
     analysis: Option<ContractAnalysis>,
     definitions: HashMap<ClarityName, Range>,
+    functions: HashMap<ClarityName, FunctionDefinition>,
+    costs: HashMap<ClarityName, FunctionCost>,
+    docs: Vec<DefinitionDoc>,
+    symbols: Vec<DocumentSymbol>,
     location: FileLocation,
     clarity_version: ClarityVersion,
 }
@@ -140,11 +169,12 @@ pub struct ContractState {
 impl ContractState {
     pub fn new(
         contract_id: QualifiedContractIdentifier,
-        _ast: ContractAST,
+        ast: ContractAST,
         _deps: DependencySet,
         mut diags: Vec<ClarityDiagnostic>,
         analysis: Option<ContractAnalysis>,
         definitions: HashMap<ClarityName, Range>,
+        docs: Vec<DefinitionDoc>,
         location: FileLocation,
         clarity_version: ClarityVersion,
     ) -> ContractState {
@@ -167,10 +197,17 @@ impl ContractState {
         }
 
         let contract_calls = match analysis {
-            Some(ref analysis) => get_contract_calls(analysis),
+            Some(ref analysis) => get_contract_calls(analysis, &docs),
             None => vec![],
         };
 
+        let functions = get_call_graph(&ast.expressions);
+        let costs = match analysis {
+            Some(ref analysis) => get_functions_costs(&ast.expressions, analysis),
+            None => HashMap::new(),
+        };
+        let symbols = ASTSymbols::new().get_symbols(&ast.expressions);
+
         ContractState {
             contract_id,
             contract_calls,
@@ -179,6 +216,10 @@ impl ContractState {
             notes,
             analysis,
             definitions,
+            functions,
+            costs,
+            docs,
+            symbols,
             location,
             clarity_version,
         }
@@ -199,6 +240,7 @@ pub struct EditorState {
     pub protocols: HashMap<FileLocation, ProtocolState>,
     pub contracts_lookup: HashMap<FileLocation, ContractMetadata>,
     pub active_contracts: HashMap<FileLocation, ActiveContractData>,
+    pub active_deployments: HashMap<FileLocation, ActiveDeploymentData>,
     pub settings: InitializationOptions,
 }
 
@@ -208,6 +250,7 @@ impl EditorState {
             protocols: HashMap::new(),
             contracts_lookup: HashMap::new(),
             active_contracts: HashMap::new(),
+            active_deployments: HashMap::new(),
             settings: InitializationOptions::default(),
         }
     }
@@ -310,8 +353,9 @@ impl EditorState {
             .unwrap_or_default();
 
         let expressions = active_contract.expressions.as_ref();
-        let active_contract_defined_data =
+        let mut active_contract_defined_data =
             ContractDefinedData::new(expressions.unwrap_or(&vec![]), position);
+        active_contract_defined_data.set_functions_documentation(&active_contract.docs);
         let should_wrap = match self.settings.completion_smart_parenthesis_wrap {
             true => check_if_should_wrap(&active_contract.source, position),
             false => true,
@@ -349,6 +393,192 @@ impl EditorState {
         ast_symbols.get_symbols(expressions)
     }
 
+    pub fn get_workspace_symbols(&self, query: &str) -> Vec<SymbolInformation> {
+        let query = query.to_lowercase();
+        let mut workspace_symbols = vec![];
+
+        for protocol in self.protocols.values() {
+            for (contract_location, contract_state) in protocol.contracts.iter() {
+                let uri = match contract_location
+                    .to_url_string()
+                    .and_then(|url| Url::parse(&url).map_err(|e| e.to_string()))
+                {
+                    Ok(uri) => uri,
+                    Err(_) => continue,
+                };
+
+                for symbol in contract_state.symbols.iter() {
+                    if !symbol.name.to_lowercase().contains(&query) {
+                        continue;
+                    }
+                    #[allow(deprecated)]
+                    workspace_symbols.push(SymbolInformation {
+                        name: symbol.name.clone(),
+                        kind: symbol.kind,
+                        tags: None,
+                        deprecated: None,
+                        location: Location {
+                            uri: uri.clone(),
+                            range: symbol.range,
+                        },
+                        container_name: Some(contract_state.contract_id.name.to_string()),
+                    });
+                }
+            }
+        }
+
+        workspace_symbols
+    }
+
+    pub fn get_call_hierarchy_items(
+        &self,
+        contract_location: &FileLocation,
+        position: &Position,
+    ) -> Option<Vec<CallHierarchyItem>> {
+        let metadata = self.contracts_lookup.get(contract_location)?;
+        let protocol = self.protocols.get(&metadata.manifest_location)?;
+        let contract_state = protocol.contracts.get(contract_location)?;
+
+        let function = contract_state
+            .functions
+            .values()
+            .find(|function| is_position_within_range(position, &function.range))?;
+
+        // if the position is on a function call, the hierarchy starts from the called function
+        let item = match function.get_call_at_position(position) {
+            Some(target) => protocol.get_call_hierarchy_item_for_target(contract_location, target),
+            None => protocol.get_call_hierarchy_item(contract_location, &function.name),
+        }?;
+
+        Some(vec![item])
+    }
+
+    pub fn get_incoming_calls(&self, item: &CallHierarchyItem) -> Vec<CallHierarchyIncomingCall> {
+        let (protocol, contract_location) = match self.get_protocol_for_item(item) {
+            Some(protocol) => protocol,
+            None => return vec![],
+        };
+        protocol.get_incoming_calls(&contract_location, &item.name)
+    }
+
+    pub fn get_outgoing_calls(&self, item: &CallHierarchyItem) -> Vec<CallHierarchyOutgoingCall> {
+        let (protocol, contract_location) = match self.get_protocol_for_item(item) {
+            Some(protocol) => protocol,
+            None => return vec![],
+        };
+        protocol.get_outgoing_calls(&contract_location, &item.name)
+    }
+
+    fn get_protocol_for_item(
+        &self,
+        item: &CallHierarchyItem,
+    ) -> Option<(&ProtocolState, FileLocation)> {
+        let contract_location = FileLocation::try_parse(item.uri.as_str(), None)?;
+        let metadata = self.contracts_lookup.get(&contract_location)?;
+        let protocol = self.protocols.get(&metadata.manifest_location)?;
+        Some((protocol, contract_location))
+    }
+
+    pub fn get_code_lenses(&self, contract_location: &FileLocation) -> Vec<CodeLens> {
+        let metadata = match self.contracts_lookup.get(contract_location) {
+            Some(metadata) => metadata,
+            None => return vec![],
+        };
+        let contract_state = match self
+            .protocols
+            .get(&metadata.manifest_location)
+            .and_then(|p| p.contracts.get(contract_location))
+        {
+            Some(contract_state) => contract_state,
+            None => return vec![],
+        };
+        let analysis = match &contract_state.analysis {
+            Some(analysis) => analysis,
+            None => return vec![],
+        };
+
+        get_code_lenses(
+            &metadata.manifest_location,
+            &contract_state.contract_id,
+            &contract_state.functions,
+            analysis,
+        )
+    }
+
+    pub fn get_cost_inlay_hints(&self, contract_location: &FileLocation) -> Vec<InlayHint> {
+        match self.get_contract_state(contract_location) {
+            Some(contract_state) => get_cost_inlay_hints(&contract_state.costs),
+            None => vec![],
+        }
+    }
+
+    fn get_contract_state(&self, contract_location: &FileLocation) -> Option<&ContractState> {
+        let metadata = self.contracts_lookup.get(contract_location)?;
+        self.protocols
+            .get(&metadata.manifest_location)?
+            .contracts
+            .get(contract_location)
+    }
+
+    // contracts of the protocol that can be referenced in a deployment plan
+    fn get_deployment_known_contracts(
+        &self,
+        manifest_location: &FileLocation,
+    ) -> HashMap<QualifiedContractIdentifier, Option<&ContractAnalysis>> {
+        match self.protocols.get(manifest_location) {
+            Some(protocol) => protocol
+                .contracts
+                .values()
+                .map(|contract| (contract.contract_id.clone(), contract.analysis.as_ref()))
+                .collect(),
+            None => HashMap::new(),
+        }
+    }
+
+    pub fn get_deployment_diagnostics(
+        &self,
+        deployment_location: &FileLocation,
+    ) -> Vec<ClarityDiagnostic> {
+        match self.active_deployments.get(deployment_location) {
+            Some(deployment) => get_deployment_plan_diagnostics(
+                &deployment.source,
+                &self.get_deployment_known_contracts(&deployment.manifest_location),
+            ),
+            None => vec![],
+        }
+    }
+
+    pub fn get_deployment_completion_items(
+        &self,
+        deployment_location: &FileLocation,
+        position: &Position,
+    ) -> Vec<CompletionItem> {
+        match self.active_deployments.get(deployment_location) {
+            Some(deployment) => get_deployment_plan_completion_items(
+                &deployment.source,
+                position,
+                &self.get_deployment_known_contracts(&deployment.manifest_location),
+            ),
+            None => vec![],
+        }
+    }
+
+    pub fn get_deployment_hover_data(
+        &self,
+        deployment_location: &FileLocation,
+        position: &Position,
+    ) -> Option<Hover> {
+        let deployment = self.active_deployments.get(deployment_location)?;
+        let documentation = get_deployment_plan_documentation(&deployment.source, position)?;
+        Some(Hover {
+            contents: lsp_types::HoverContents::Markup(lsp_types::MarkupContent {
+                kind: lsp_types::MarkupKind::Markdown,
+                value: documentation,
+            }),
+            range: None,
+        })
+    }
+
     pub fn get_definition_location(
         &self,
         contract_location: &FileLocation,
@@ -409,15 +639,38 @@ impl EditorState {
         position: &lsp_types::Position,
     ) -> Option<Hover> {
         let contract = self.active_contracts.get(contract_location)?;
-        let position = Position {
+        let expressions = contract.expressions.as_ref()?;
+        let position_in_source = Position {
             line: position.line + 1,
             character: position.character + 1,
         };
-        let documentation = get_expression_documentation(
-            &position,
+        let cost_documentation = self
+            .get_contract_state(contract_location)
+            .and_then(|contract_state| get_cost_at_position(&contract_state.costs, position))
+            .map(get_cost_documentation);
+
+        let documentation = match get_expression_documentation(
+            &position_in_source,
             contract.clarity_version,
-            contract.expressions.as_ref()?,
-        )?;
+            expressions,
+        ) {
+            Some(documentation) => documentation,
+            None => {
+                let definition_documentation =
+                    get_definition_documentation(&position_in_source, expressions, &contract.docs)
+                        .or_else(|| {
+                            self.get_external_definition_documentation(
+                                contract_location,
+                                &position_in_source,
+                            )
+                        });
+                match (definition_documentation, cost_documentation) {
+                    (Some(documentation), Some(cost)) => format!("{}\n\n{}", documentation, cost),
+                    (Some(documentation), None) => documentation,
+                    (None, cost) => cost?,
+                }
+            }
+        };
 
         Some(Hover {
             contents: lsp_types::HoverContents::Markup(lsp_types::MarkupContent {
@@ -428,6 +681,38 @@ impl EditorState {
         })
     }
 
+    // documentation of a function called with contract-call?
+    fn get_external_definition_documentation(
+        &self,
+        contract_location: &FileLocation,
+        position: &Position,
+    ) -> Option<String> {
+        let contract = self.active_contracts.get(contract_location)?;
+        let expressions = contract.expressions.as_ref()?;
+        let position_hash = get_atom_start_at_position(position, expressions)?;
+        let definitions = match &contract.definitions {
+            Some(definitions) => definitions.to_owned(),
+            None => get_definitions(expressions, contract.issuer.clone()),
+        };
+        let (contract_identifier, function_name) = match definitions.get(&position_hash)? {
+            DefinitionLocation::External(contract_identifier, function_name) => {
+                (contract_identifier, function_name)
+            }
+            DefinitionLocation::Internal(_) => return None,
+        };
+
+        let metadata = self.contracts_lookup.get(contract_location)?;
+        let protocol = self.protocols.get(&metadata.manifest_location)?;
+        let definition_contract_location = protocol.locations_lookup.get(contract_identifier)?;
+        let docs = match self.active_contracts.get(definition_contract_location) {
+            Some(active_contract) => &active_contract.docs,
+            None => &protocol.contracts.get(definition_contract_location)?.docs,
+        };
+        docs.iter()
+            .find(|doc| doc.name == function_name.as_str())
+            .map(|doc| doc.to_markdown())
+    }
+
     pub fn get_signature_help(
         &self,
         contract_location: &FileLocation,
@@ -488,10 +773,28 @@ impl EditorState {
                 for note in state.notes.iter() {
                     diags.push(note.clone());
                 }
+
+                // Collect the functions that can exceed the configured fraction of the block limit
+                if self.settings.cost_hints {
+                    let mut cost_warnings =
+                        get_cost_warnings(&state.costs, self.settings.cost_warning_threshold);
+                    if !cost_warnings.is_empty() {
+                        warning_files.insert(relative_path.clone());
+                        diags.append(&mut cost_warnings);
+                    }
+                }
                 contracts.push((contract_url.clone(), diags));
             }
         }
 
+        // deployment plans are checked against the contracts of the protocol
+        for deployment_location in self.active_deployments.keys() {
+            contracts.push((
+                deployment_location.clone(),
+                self.get_deployment_diagnostics(deployment_location),
+            ));
+        }
+
         let tldr = match (erroring_files.len(), warning_files.len()) {
             (0, 0) => None,
             (0, _warnings) => Some((
@@ -532,6 +835,21 @@ impl EditorState {
         self.active_contracts.insert(contract_location, contract);
     }
 
+    pub fn insert_active_deployment(
+        &mut self,
+        deployment_location: FileLocation,
+        manifest_location: FileLocation,
+        source: &str,
+    ) {
+        self.active_deployments.insert(
+            deployment_location,
+            ActiveDeploymentData {
+                manifest_location,
+                source: source.to_string(),
+            },
+        );
+    }
+
     pub fn update_active_contract(
         &mut self,
         contract_location: &FileLocation,
@@ -565,6 +883,7 @@ impl ProtocolState {
         deps: &mut BTreeMap<QualifiedContractIdentifier, DependencySet>,
         diags: &mut HashMap<QualifiedContractIdentifier, Vec<ClarityDiagnostic>>,
         definitions: &mut HashMap<QualifiedContractIdentifier, HashMap<ClarityName, Range>>,
+        docs: &mut HashMap<QualifiedContractIdentifier, Vec<DefinitionDoc>>,
         analyses: &mut HashMap<QualifiedContractIdentifier, Option<ContractAnalysis>>,
         clarity_versions: &mut HashMap<QualifiedContractIdentifier, ClarityVersion>,
     ) {
@@ -597,6 +916,7 @@ impl ProtocolState {
                 Some(definitions) => definitions,
                 None => HashMap::new(),
             };
+            let docs = docs.remove(&contract_id).unwrap_or_default();
 
             let contract_state = ContractState::new(
                 contract_id.clone(),
@@ -605,6 +925,7 @@ impl ProtocolState {
                 diags,
                 analysis,
                 definitions,
+                docs,
                 contract_location.clone(),
                 clarity_version,
             );
@@ -628,6 +949,117 @@ impl ProtocolState {
         }
         contract_calls
     }
+
+    fn get_call_hierarchy_item(
+        &self,
+        contract_location: &FileLocation,
+        function_name: &str,
+    ) -> Option<CallHierarchyItem> {
+        let contract_state = self.contracts.get(contract_location)?;
+        let function = contract_state
+            .functions
+            .values()
+            .find(|function| function.name.as_str() == function_name)?;
+
+        Some(CallHierarchyItem {
+            name: function.name.to_string(),
+            kind: SymbolKind::FUNCTION,
+            tags: None,
+            detail: Some(format!(
+                "{} ({})",
+                contract_state.contract_id.name, function.kind
+            )),
+            uri: Url::parse(&contract_location.to_url_string().ok()?).ok()?,
+            range: function.range,
+            selection_range: function.selection_range,
+            data: None,
+        })
+    }
+
+    fn get_call_hierarchy_item_for_target(
+        &self,
+        contract_location: &FileLocation,
+        target: &CallTarget,
+    ) -> Option<CallHierarchyItem> {
+        match target {
+            CallTarget::Internal(function_name) => {
+                self.get_call_hierarchy_item(contract_location, function_name)
+            }
+            CallTarget::External(contract_id, function_name) => {
+                let target_location = self.locations_lookup.get(contract_id)?;
+                self.get_call_hierarchy_item(target_location, function_name)
+            }
+        }
+    }
+
+    pub fn get_incoming_calls(
+        &self,
+        contract_location: &FileLocation,
+        function_name: &str,
+    ) -> Vec<CallHierarchyIncomingCall> {
+        let contract_id = match self.contracts.get(contract_location) {
+            Some(contract_state) => &contract_state.contract_id,
+            None => return vec![],
+        };
+
+        let mut incoming_calls = vec![];
+        for (caller_location, caller_state) in self.contracts.iter() {
+            for caller in caller_state.functions.values() {
+                let from_ranges: Vec<Range> = caller
+                    .calls
+                    .iter()
+                    .filter(|(target, _)| match target {
+                        CallTarget::Internal(name) => {
+                            caller_location == contract_location && name.as_str() == function_name
+                        }
+                        CallTarget::External(id, name) => {
+                            id == contract_id && name.as_str() == function_name
+                        }
+                    })
+                    .map(|(_, range)| *range)
+                    .collect();
+                if from_ranges.is_empty() {
+                    continue;
+                }
+                if let Some(from) = self.get_call_hierarchy_item(caller_location, &caller.name) {
+                    incoming_calls.push(CallHierarchyIncomingCall { from, from_ranges });
+                }
+            }
+        }
+        incoming_calls
+    }
+
+    pub fn get_outgoing_calls(
+        &self,
+        contract_location: &FileLocation,
+        function_name: &str,
+    ) -> Vec<CallHierarchyOutgoingCall> {
+        let function = match self.contracts.get(contract_location).and_then(|c| {
+            c.functions
+                .values()
+                .find(|function| function.name.as_str() == function_name)
+        }) {
+            Some(function) => function,
+            None => return vec![],
+        };
+
+        // group the calls by target, keeping the order in which they first appear
+        let mut targets: Vec<(&CallTarget, Vec<Range>)> = vec![];
+        for (target, range) in function.calls.iter() {
+            match targets.iter_mut().find(|(t, _)| *t == target) {
+                Some((_, ranges)) => ranges.push(*range),
+                None => targets.push((target, vec![*range])),
+            }
+        }
+
+        targets
+            .into_iter()
+            .filter_map(|(target, from_ranges)| {
+                let to = self.get_call_hierarchy_item_for_target(contract_location, target)?;
+                Some(CallHierarchyOutgoingCall { to, from_ranges })
+            })
+            .collect()
+    }
 }
 
 pub async fn build_state(
@@ -638,6 +1070,7 @@ pub async fn build_state(
     let mut locations = HashMap::new();
     let mut analyses = HashMap::new();
     let mut definitions = HashMap::new();
+    let mut docs = HashMap::new();
     let mut clarity_versions = HashMap::new();
 
     // In the LSP use case, trying to load an existing deployment
@@ -670,7 +1103,7 @@ pub async fn build_state(
         Some(StacksEpochId::Epoch21),
     );
     for (contract_id, mut result) in results.into_iter() {
-        let (_, contract_location) = match deployment.contracts.get(&contract_id) {
+        let (source, contract_location) = match deployment.contracts.get(&contract_id) {
             Some(entry) => entry,
             None => continue,
         };
@@ -691,6 +1124,10 @@ pub async fn build_state(
                             contract_id.clone(),
                             get_public_function_definitions(&ast.expressions),
                         );
+                        docs.insert(
+                            contract_id.clone(),
+                            get_definitions_docs(source, &ast.expressions),
+                        );
                     }
                     analyses.insert(contract_id.clone(), Some(contract_result.contract.analysis));
                 };
@@ -710,9 +1147,72 @@ pub async fn build_state(
         &mut artifacts.deps,
         &mut artifacts.diags,
         &mut definitions,
+        &mut docs,
         &mut analyses,
         &mut clarity_versions,
     );
 
     Ok(())
 }
+
+pub async fn run_function(
+    function_call: &FunctionCallArguments,
+    file_accessor: Option<&dyn FileAccessor>,
+) -> Result<String, String> {
+    function_call.check_args()?;
+    let manifest_location = FileLocation::try_parse(&function_call.manifest, None).ok_or(
+        format!("unable to parse manifest {}", function_call.manifest),
+    )?;
+    let manifest = match file_accessor {
+        None => ProjectManifest::from_location(&manifest_location)?,
+        Some(file_accessor) => {
+            ProjectManifest::from_file_accessor(&manifest_location, file_accessor).await?
+        }
+    };
+
+    let (deployment, artifacts) = generate_default_deployment(
+        &manifest,
+        &StacksNetwork::Simnet,
+        false,
+        file_accessor,
+        Some(StacksEpochId::Epoch21),
+    )
+    .await?;
+
+    let mut session =
+        setup_session_with_deployment(&manifest, &deployment, Some(&artifacts.asts)).session;
+    let sender = match &function_call.sender {
+        Some(sender) => sender.clone(),
+        None => session.get_tx_sender(),
+    };
+
+    let (execution, _) = session
+        .invoke_contract_call(
+            &function_call.contract_id,
+            &function_call.function_name,
+            &function_call.args,
+            &sender,
+            "code-lens".to_string(),
+        )
+        .map_err(|diagnostics| {
+            diagnostics
+                .iter()
+                .map(|d| d.message.clone())
+                .collect::<Vec<_>>()
+                .join("\n")
+        })?;
+
+    let result = match &execution.result {
+        EvaluationResult::Snippet(snippet) => value_to_string(&snippet.result),
+        EvaluationResult::Contract(_) => return Err("unexpected contract result".to_string()),
+    };
+
+    let mut output = vec![
+        format!("{} (sender: {})", function_call.get_expression(), sender),
+        result,
+    ];
+    for event in execution.events.iter() {
+        output.push(serialize_event(event).to_string());
+    }
+    Ok(output.join("\n"))
+}
@@ -72,3 +72,11 @@ pub fn get_contract_location(text_document_uri: &Url) -> Option<FileLocation> {
     }
     FileLocation::try_parse(&file_location, None)
 }
+
+pub fn get_deployment_location(text_document_uri: &Url) -> Option<FileLocation> {
+    let file_location = text_document_uri.to_string();
+    if !file_location.contains("/deployments/") || !file_location.ends_with(".yaml") {
+        return None;
+    }
+    FileLocation::try_parse(&file_location, None)
+}
@@ -1,10 +1,14 @@
 extern crate console_error_panic_hook;
 use crate::backend::{
     process_mutating_request, process_notification, process_request, EditorStateInput,
-    LspNotification, LspRequest, LspRequestResponse,
+    FunctionCallArguments, LspNotification, LspRequest, LspRequestResponse, DEBUG_FUNCTION_COMMAND,
+    RUN_FUNCTION_COMMAND,
 };
 use crate::state::EditorState;
-use crate::utils::{clarity_diagnostics_to_lsp_type, get_contract_location, get_manifest_location};
+use crate::utils::{
+    clarity_diagnostics_to_lsp_type, get_contract_location, get_deployment_location,
+    get_manifest_location,
+};
 use clarinet_files::{FileAccessor, WASMFileSystemAccessor};
 use js_sys::{Function as JsFunction, Promise};
 use lsp_types::notification::{
@@ -12,12 +16,13 @@ use lsp_types::notification::{
     Initialized, Notification,
 };
 use lsp_types::request::{
-    Completion, DocumentSymbolRequest, GotoDefinition, HoverRequest, Initialize, Request,
-    SignatureHelpRequest,
+    CallHierarchyIncomingCalls, CallHierarchyOutgoingCalls, CallHierarchyPrepare, CodeLensRequest,
+    Completion, DocumentSymbolRequest, ExecuteCommand, GotoDefinition, HoverRequest, Initialize,
+    InlayHintRequest, Request, SignatureHelpRequest, WorkspaceSymbolRequest,
 };
 use lsp_types::{
     DidChangeTextDocumentParams, DidCloseTextDocumentParams, DidOpenTextDocumentParams,
-    DidSaveTextDocumentParams, MessageType, PublishDiagnosticsParams, Url,
+    DidSaveTextDocumentParams, ExecuteCommandParams, MessageType, PublishDiagnosticsParams, Url,
 };
 use serde::Serialize;
 use serde_wasm_bindgen::{from_value as decode_from_js, to_value as encode_to_js, Serializer};
@@ -72,6 +77,8 @@ impl LspVscodeBridge {
                     LspNotification::ContractOpened(contract_location.clone())
                 } else if let Some(manifest_location) = get_manifest_location(uri) {
                     LspNotification::ManifestOpened(manifest_location)
+                } else if let Some(deployment_location) = get_deployment_location(uri) {
+                    LspNotification::DeploymentOpened(deployment_location)
                 } else {
                     return Promise::reject(&JsValue::from_str("Unsupported file opened"));
                 }
@@ -105,6 +112,11 @@ impl LspVscodeBridge {
                         contract_location,
                         params.content_changes[0].text.to_string(),
                     )
+                } else if let Some(deployment_location) = get_deployment_location(uri) {
+                    LspNotification::DeploymentChanged(
+                        deployment_location,
+                        params.content_changes[0].text.to_string(),
+                    )
                 } else {
                     return Promise::resolve(&JsValue::FALSE);
                 }
@@ -119,11 +131,21 @@ impl LspVscodeBridge {
 
                 if let Some(contract_location) = get_contract_location(uri) {
                     LspNotification::ContractClosed(contract_location)
+                } else if let Some(deployment_location) = get_deployment_location(uri) {
+                    LspNotification::DeploymentClosed(deployment_location)
                 } else {
                     return Promise::resolve(&JsValue::FALSE);
                 }
             }
 
+            "clarity/runFunction" => {
+                let function_call: FunctionCallArguments = match decode_from_js(js_params) {
+                    Ok(params) => params,
+                    Err(err) => return Promise::reject(&JsValue::from(format!("error: {}", err))),
+                };
+                LspNotification::RunFunction(function_call)
+            }
+
             _ => {
                 #[cfg(debug_assertions)]
                 log!("unexpected notification ({})", method);
@@ -143,6 +165,7 @@ impl LspVscodeBridge {
                 process_notification(command, &mut editor_state_lock, Some(&*file_accessor)).await;
 
             let mut aggregated_diagnostics = vec![];
+            let mut notification = None;
             if let Err(err) = result {
                 if err.starts_with("No Clarinet.toml is associated to the contract") {
                     let _ = send_notification.call2(
@@ -159,6 +182,15 @@ impl LspVscodeBridge {
             }
             if let Ok(ref mut response) = result {
                 aggregated_diagnostics.append(&mut response.aggregated_diagnostics);
+                notification = response.notification.take();
+            }
+
+            if let Some((typ, message)) = notification {
+                send_notification.call2(
+                    &JsValue::NULL,
+                    &encode_to_js(&lsp_types::notification::ShowMessage::METHOD)?,
+                    &encode_to_js(&lsp_types::ShowMessageParams { typ, message })?,
+                )?;
             }
 
             for (location, diags) in aggregated_diagnostics.into_iter() {
@@ -235,6 +267,96 @@ impl LspVscodeBridge {
                 }
             }
 
+            WorkspaceSymbolRequest::METHOD => {
+                let lsp_response = process_request(
+                    LspRequest::WorkspaceSymbol(decode_from_js(js_params)?),
+                    &EditorStateInput::RwLock(self.editor_state_lock.clone()),
+                );
+                if let Ok(LspRequestResponse::WorkspaceSymbol(response)) = lsp_response {
+                    return response.serialize(&serializer).map_err(|_| JsValue::NULL);
+                }
+            }
+
+            CallHierarchyPrepare::METHOD => {
+                let lsp_response = process_request(
+                    LspRequest::PrepareCallHierarchy(decode_from_js(js_params)?),
+                    &EditorStateInput::RwLock(self.editor_state_lock.clone()),
+                );
+                if let Ok(LspRequestResponse::PrepareCallHierarchy(response)) = lsp_response {
+                    return response.serialize(&serializer).map_err(|_| JsValue::NULL);
+                }
+            }
+
+            CallHierarchyIncomingCalls::METHOD => {
+                let lsp_response = process_request(
+                    LspRequest::IncomingCalls(decode_from_js(js_params)?),
+                    &EditorStateInput::RwLock(self.editor_state_lock.clone()),
+                );
+                if let Ok(LspRequestResponse::IncomingCalls(response)) = lsp_response {
+                    return response.serialize(&serializer).map_err(|_| JsValue::NULL);
+                }
+            }
+
+            CallHierarchyOutgoingCalls::METHOD => {
+                let lsp_response = process_request(
+                    LspRequest::OutgoingCalls(decode_from_js(js_params)?),
+                    &EditorStateInput::RwLock(self.editor_state_lock.clone()),
+                );
+                if let Ok(LspRequestResponse::OutgoingCalls(response)) = lsp_response {
+                    return response.serialize(&serializer).map_err(|_| JsValue::NULL);
+                }
+            }
+
+            CodeLensRequest::METHOD => {
+                let lsp_response = process_request(
+                    LspRequest::CodeLens(decode_from_js(js_params)?),
+                    &EditorStateInput::RwLock(self.editor_state_lock.clone()),
+                );
+                if let Ok(LspRequestResponse::CodeLens(response)) = lsp_response {
+                    return response.serialize(&serializer).map_err(|_| JsValue::NULL);
+                }
+            }
+
+            InlayHintRequest::METHOD => {
+                let lsp_response = process_request(
+                    LspRequest::InlayHint(decode_from_js(js_params)?),
+                    &EditorStateInput::RwLock(self.editor_state_lock.clone()),
+                );
+                if let Ok(LspRequestResponse::InlayHint(response)) = lsp_response {
+                    return response.serialize(&serializer).map_err(|_| JsValue::NULL);
+                }
+            }
+
+            ExecuteCommand::METHOD => {
+                let params: ExecuteCommandParams = decode_from_js(js_params)?;
+                let function_call: FunctionCallArguments = match params.arguments.first() {
+                    Some(argument) => serde_json::from_value(argument.clone())
+                        .map_err(|err| JsValue::from(format!("error: {}", err)))?,
+                    None => return Ok(JsValue::NULL),
+                };
+                // the arguments are prompted by the client before it sends the command
+                function_call.check_args().map_err(JsValue::from)?;
+
+                match params.command.as_str() {
+                    DEBUG_FUNCTION_COMMAND => {
+                        return function_call
+                            .get_debug_configuration()
+                            .serialize(&serializer)
+                            .map_err(|_| JsValue::NULL);
+                    }
+                    // running the function is asynchronous, the result is sent as a message
+                    RUN_FUNCTION_COMMAND => {
+                        return Ok(self
+                            .notification_handler(
+                                "clarity/runFunction".to_string(),
+                                encode_to_js(&function_call)?,
+                            )
+                            .into());
+                    }
+                    _ => {}
+                }
+            }
+
             HoverRequest::METHOD => {
                 let lsp_response = process_request(
                     LspRequest::Hover(decode_from_js(js_params)?),
@@ -0,0 +1,329 @@
+use clarity::vm::representations::Span;
+use clarity::vm::SymbolicExpression;
+use serde::{Deserialize, Serialize};
+
+// Doc comments are `;;` blocks written directly above a definition:
+//
+// ;; Transfer tokens to a recipient
+// ;; @param amount the number of tokens to transfer
+// ;; @param recipient the principal receiving the tokens
+// ;; @returns true if the transfer succeeded
+// (define-public (transfer (amount uint) (recipient principal)) ...)
+
+const DEFINE_KEYWORDS: [&str; 9] = [
+    "define-public",
+    "define-read-only",
+    "define-private",
+    "define-constant",
+    "define-data-var",
+    "define-map",
+    "define-fungible-token",
+    "define-non-fungible-token",
+    "define-trait",
+];
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct DocComment {
+    pub description: String,
+    pub params: Vec<(String, String)>,
+    pub returns: Option<String>,
+}
+
+impl DocComment {
+    pub fn parse(lines: &[&str]) -> DocComment {
+        let mut description: Vec<String> = vec![];
+        let mut params: Vec<(String, String)> = vec![];
+        let mut returns: Option<String> = None;
+
+        // lines following a tag are a continuation of this tag
+        let mut current_tag = None;
+        for line in lines {
+            let line = line.trim();
+            if let Some(param) = line.strip_prefix("@param") {
+                let param = param.trim();
+                let (name, text) = param.split_once(char::is_whitespace).unwrap_or((param, ""));
+                params.push((name.to_string(), text.trim().to_string()));
+                current_tag = Some("param");
+            } else if let Some(text) = line
+                .strip_prefix("@returns")
+                .or_else(|| line.strip_prefix("@return"))
+            {
+                returns = Some(text.trim().to_string());
+                current_tag = Some("returns");
+            } else {
+                match (current_tag, params.last_mut(), returns.as_mut()) {
+                    (Some("param"), Some((_, text)), _) if !line.is_empty() => {
+                        text.push(' ');
+                        text.push_str(line);
+                    }
+                    (Some("returns"), _, Some(text)) if !line.is_empty() => {
+                        text.push(' ');
+                        text.push_str(line);
+                    }
+                    _ => description.push(line.to_string()),
+                }
+            }
+        }
+
+        DocComment {
+            description: description.join("\n").trim().to_string(),
+            params,
+            returns,
+        }
+    }
+
+    pub fn get_param(&self, name: &str) -> Option<&str> {
+        self.params
+            .iter()
+            .find(|(param, _)| param == name)
+            .map(|(_, text)| text.as_str())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DefinitionDoc {
+    pub name: String,
+    pub kind: String,
+    // name and type of the parameters, for functions
+    pub parameters: Vec<(String, String)>,
+    pub comment: DocComment,
+    pub span: Span,
+}
+
+impl DefinitionDoc {
+    pub fn is_function(&self) -> bool {
+        ["define-public", "define-read-only", "define-private"].contains(&self.kind.as_str())
+    }
+
+    pub fn get_signature(&self) -> String {
+        if !self.is_function() {
+            return format!("({} {})", self.kind, self.name);
+        }
+        let mut signature = format!("({} ({}", self.kind, self.name);
+        for (name, type_signature) in self.parameters.iter() {
+            signature.push_str(&format!(" ({} {})", name, type_signature));
+        }
+        signature.push_str("))");
+        signature
+    }
+
+    pub fn to_markdown(&self) -> String {
+        let mut doc = vec![format!("```clarity\n{}\n```", self.get_signature())];
+        if !self.comment.description.is_empty() {
+            doc.push(self.comment.description.clone());
+        }
+        if !self.parameters.is_empty() {
+            let params = self
+                .parameters
+                .iter()
+                .map(
+                    |(name, type_signature)| match self.comment.get_param(name) {
+                        Some(text) if !text.is_empty() => {
+                            format!("- `{}` `{}`: {}", name, type_signature, text)
+                        }
+                        _ => format!("- `{}` `{}`", name, type_signature),
+                    },
+                )
+                .collect::<Vec<_>>();
+            doc.push(format!("**Parameters**\n\n{}", params.join("\n")));
+        }
+        if let Some(returns) = &self.comment.returns {
+            doc.push(format!("**Returns** {}", returns));
+        }
+        doc.join("\n\n")
+    }
+}
+
+fn get_span_text(lines: &[&str], span: &Span) -> String {
+    if span.start_line == 0 {
+        return String::new();
+    }
+    let mut text = vec![];
+    for line_number in span.start_line..=span.end_line {
+        let line = match lines.get(line_number as usize - 1) {
+            Some(line) => line,
+            None => break,
+        };
+        let start = match line_number == span.start_line {
+            true => span.start_column as usize - 1,
+            false => 0,
+        };
+        let end = match line_number == span.end_line {
+            true => span.end_column as usize,
+            false => line.chars().count(),
+        };
+        text.push(
+            line.chars()
+                .skip(start)
+                .take(end.saturating_sub(start))
+                .collect::<String>(),
+        );
+    }
+    text.join("\n")
+}
+
+// the comment lines directly above a line (1-based), ignoring annotations
+fn get_comment_above(lines: &[&str], line_number: u32) -> Vec<String> {
+    let mut comment = vec![];
+    for line in lines
+        .iter()
+        .take(line_number.saturating_sub(1) as usize)
+        .rev()
+    {
+        let text = match line.trim().strip_prefix(";;") {
+            Some(text) => text.trim_start_matches(';'),
+            None => break,
+        };
+        if text.trim().starts_with("#[") {
+            continue;
+        }
+        comment.push(text.strip_prefix(' ').unwrap_or(text).to_string());
+    }
+    comment.reverse();
+    comment
+}
+
+pub fn get_definitions_docs(
+    source: &str,
+    expressions: &[SymbolicExpression],
+) -> Vec<DefinitionDoc> {
+    let lines = source.lines().collect::<Vec<_>>();
+    let mut docs = vec![];
+    for expr in expressions {
+        let list = match expr.match_list() {
+            Some(list) => list,
+            None => continue,
+        };
+        let kind = match list.first().and_then(|keyword| keyword.match_atom()) {
+            Some(keyword) if DEFINE_KEYWORDS.contains(&keyword.as_str()) => keyword.to_string(),
+            _ => continue,
+        };
+        let signature = match list.get(1) {
+            Some(signature) => signature,
+            None => continue,
+        };
+        let (name, parameters) = match signature.match_atom() {
+            Some(name) => (name.to_string(), vec![]),
+            None => match signature.match_list().and_then(|s| s.split_first()) {
+                Some((name, parameters)) => {
+                    let name = match name.match_atom() {
+                        Some(name) => name.to_string(),
+                        None => continue,
+                    };
+                    let parameters = parameters
+                        .iter()
+                        .filter_map(|parameter| match parameter.match_list()? {
+                            [name, type_signature] => Some((
+                                name.match_atom()?.to_string(),
+                                get_span_text(&lines, &type_signature.span),
+                            )),
+                            _ => None,
+                        })
+                        .collect();
+                    (name, parameters)
+                }
+                None => continue,
+            },
+        };
+
+        let comment = get_comment_above(&lines, expr.span.start_line);
+        docs.push(DefinitionDoc {
+            name,
+            kind,
+            parameters,
+            comment: DocComment::parse(&comment.iter().map(|l| l.as_str()).collect::<Vec<_>>()),
+            span: expr.span.clone(),
+        });
+    }
+    docs
+}
+
+#[cfg(test)]
+mod tests {
+    use clarity::types::StacksEpochId;
+    use clarity::vm::ast::{build_ast_with_rules, ASTRules};
+    use clarity::vm::types::QualifiedContractIdentifier;
+    use clarity::vm::ClarityVersion;
+
+    use super::*;
+
+    fn get_docs(source: &str) -> Vec<DefinitionDoc> {
+        let ast = build_ast_with_rules(
+            &QualifiedContractIdentifier::transient(),
+            source,
+            &mut (),
+            ClarityVersion::Clarity2,
+            StacksEpochId::Epoch21,
+            ASTRules::Typical,
+        )
+        .unwrap();
+        get_definitions_docs(source, &ast.expressions)
+    }
+
+    #[test]
+    fn parse_function_doc_comment() {
+        let docs = get_docs(
+            [
+                ";; not part of the doc comment",
+                "",
+                ";; Transfer tokens",
+                ";; to a recipient",
+                ";; #[allow(unchecked_data)]",
+                ";; @param amount the number of tokens",
+                ";; @param recipient the principal",
+                ";;   receiving the tokens",
+                ";; @returns true if the transfer succeeded",
+                "(define-public (transfer (amount uint) (recipient principal))",
+                "  (ok true))",
+            ]
+            .join("\n")
+            .as_str(),
+        );
+
+        assert_eq!(docs.len(), 1);
+        let transfer = &docs[0];
+        assert_eq!(transfer.name, "transfer");
+        assert_eq!(transfer.kind, "define-public");
+        assert_eq!(
+            transfer.get_signature(),
+            "(define-public (transfer (amount uint) (recipient principal)))"
+        );
+        assert_eq!(
+            transfer.comment.description,
+            "Transfer tokens\nto a recipient"
+        );
+        assert_eq!(
+            transfer.comment.params,
+            vec![
+                ("amount".to_string(), "the number of tokens".to_string()),
+                (
+                    "recipient".to_string(),
+                    "the principal receiving the tokens".to_string()
+                ),
+            ]
+        );
+        assert_eq!(
+            transfer.comment.returns,
+            Some("true if the transfer succeeded".to_string())
+        );
+    }
+
+    #[test]
+    fn document_all_definitions() {
+        let docs = get_docs(
+            [
+                ";; the owner of the contract",
+                "(define-constant owner tx-sender)",
+                "(define-data-var count uint u0)",
+                "(define-read-only (get-count) (var-get count))",
+            ]
+            .join("\n")
+            .as_str(),
+        );
+
+        assert_eq!(docs.len(), 3);
+        assert_eq!(docs[0].comment.description, "the owner of the contract");
+        assert_eq!(docs[1].comment, DocComment::default());
+        assert_eq!(docs[2].get_signature(), "(define-read-only (get-count))");
+    }
+}
@@ -6,6 +6,7 @@ pub mod check_checker;
 pub mod coverage;
 #[cfg(test)]
 mod coverage_tests;
+pub mod doc_comments;
 
 use serde::Serialize;
 
@@ -20,9 +20,7 @@ use clarity::util::secp256k1::{
     MessageSignature, Secp256k1PrivateKey, Secp256k1PublicKey, MESSAGE_SIGNATURE_ENCODED_SIZE,
 };
 use clarity::util::vrf::VRFProof;
-use clarity::vm::types::{
-    PrincipalData, QualifiedContractIdentifier, StandardPrincipalData, Value,
-};
+use clarity::vm::types::{PrincipalData, QualifiedContractIdentifier, Value};
 use clarity::vm::ClarityVersion;
 use clarity::vm::{ClarityName, ContractName};
 use clarity::{
@@ -1489,163 +1487,11 @@ pub enum TransactionPayloadID {
     NakamotoCoinbase = 8,
 }
 
-/// Encoding of an asset type identifier
-#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
-pub struct AssetInfo {
-    pub contract_address: StacksAddress,
-    pub contract_name: ContractName,
-    pub asset_name: ClarityName,
-}
-
-/// numeric wire-format ID of an asset info type variant
-#[repr(u8)]
-#[derive(Debug, Clone, PartialEq, Copy, Serialize, Deserialize)]
-pub enum AssetInfoID {
-    STX = 0,
-    FungibleAsset = 1,
-    NonfungibleAsset = 2,
-}
-
-impl AssetInfoID {
-    pub fn from_u8(b: u8) -> Option<AssetInfoID> {
-        match b {
-            0 => Some(AssetInfoID::STX),
-            1 => Some(AssetInfoID::FungibleAsset),
-            2 => Some(AssetInfoID::NonfungibleAsset),
-            _ => None,
-        }
-    }
-}
-
-#[repr(u8)]
-#[derive(Debug, Clone, PartialEq, Copy, Serialize, Deserialize)]
-pub enum FungibleConditionCode {
-    SentEq = 0x01,
-    SentGt = 0x02,
-    SentGe = 0x03,
-    SentLt = 0x04,
-    SentLe = 0x05,
-}
-
-impl FungibleConditionCode {
-    pub fn from_u8(b: u8) -> Option<FungibleConditionCode> {
-        match b {
-            0x01 => Some(FungibleConditionCode::SentEq),
-            0x02 => Some(FungibleConditionCode::SentGt),
-            0x03 => Some(FungibleConditionCode::SentGe),
-            0x04 => Some(FungibleConditionCode::SentLt),
-            0x05 => Some(FungibleConditionCode::SentLe),
-            _ => None,
-        }
-    }
-
-    pub fn check(&self, amount_sent_condition: u128, amount_sent: u128) -> bool {
-        match *self {
-            FungibleConditionCode::SentEq => amount_sent == amount_sent_condition,
-            FungibleConditionCode::SentGt => amount_sent > amount_sent_condition,
-            FungibleConditionCode::SentGe => amount_sent >= amount_sent_condition,
-            FungibleConditionCode::SentLt => amount_sent < amount_sent_condition,
-            FungibleConditionCode::SentLe => amount_sent <= amount_sent_condition,
-        }
-    }
-}
-
-#[repr(u8)]
-#[derive(Debug, Clone, PartialEq, Copy, Serialize, Deserialize)]
-pub enum NonfungibleConditionCode {
-    Sent = 0x10,
-    NotSent = 0x11,
-}
-
-impl NonfungibleConditionCode {
-    pub fn from_u8(b: u8) -> Option<NonfungibleConditionCode> {
-        match b {
-            0x10 => Some(NonfungibleConditionCode::Sent),
-            0x11 => Some(NonfungibleConditionCode::NotSent),
-            _ => None,
-        }
-    }
-
-    pub fn was_sent(nft_sent_condition: &Value, nfts_sent: &[Value]) -> bool {
-        for asset_sent in nfts_sent.iter() {
-            if *asset_sent == *nft_sent_condition {
-                // asset was sent, and is no longer owned by this principal
-                return true;
-            }
-        }
-        false
-    }
-
-    pub fn check(&self, nft_sent_condition: &Value, nfts_sent: &[Value]) -> bool {
-        match *self {
-            NonfungibleConditionCode::Sent => {
-                NonfungibleConditionCode::was_sent(nft_sent_condition, nfts_sent)
-            }
-            NonfungibleConditionCode::NotSent => {
-                !NonfungibleConditionCode::was_sent(nft_sent_condition, nfts_sent)
-            }
-        }
-    }
-}
-
-/// Post-condition principal.
-#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
-pub enum PostConditionPrincipal {
-    Origin,
-    Standard(StacksAddress),
-    Contract(StacksAddress, ContractName),
-}
-
-impl PostConditionPrincipal {
-    pub fn to_principal_data(&self, origin_principal: &PrincipalData) -> PrincipalData {
-        match *self {
-            PostConditionPrincipal::Origin => origin_principal.clone(),
-            PostConditionPrincipal::Standard(ref addr) => {
-                PrincipalData::Standard(StandardPrincipalData::from(*addr))
-            }
-            PostConditionPrincipal::Contract(ref addr, ref contract_name) => {
-                PrincipalData::Contract(QualifiedContractIdentifier::new(
-                    StandardPrincipalData::from(*addr),
-                    contract_name.clone(),
-                ))
-            }
-        }
-    }
-}
-
-#[repr(u8)]
-#[derive(Debug, Clone, PartialEq, Copy, Serialize, Deserialize)]
-pub enum PostConditionPrincipalID {
-    Origin = 0x01,
-    Standard = 0x02,
-    Contract = 0x03,
-}
-
-/// Post-condition on a transaction
-#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
-pub enum TransactionPostCondition {
-    STX(PostConditionPrincipal, FungibleConditionCode, u64),
-    Fungible(
-        PostConditionPrincipal,
-        AssetInfo,
-        FungibleConditionCode,
-        u64,
-    ),
-    Nonfungible(
-        PostConditionPrincipal,
-        AssetInfo,
-        Value,
-        NonfungibleConditionCode,
-    ),
-}
-
-/// Post-condition modes for unspecified assets
-#[repr(u8)]
-#[derive(Debug, Clone, PartialEq, Copy, Serialize, Deserialize)]
-pub enum TransactionPostConditionMode {
-    Allow = 0x01, // allow any other changes not specified
-    Deny = 0x02,  // deny any other changes not specified
-}
+pub use crate::repl::post_conditions::{
+    AssetInfo, AssetInfoID, FungibleConditionCode, NonfungibleConditionCode,
+    PostConditionPrincipal, PostConditionPrincipalID, TransactionPostCondition,
+    TransactionPostConditionMode,
+};
 
 /// Stacks transaction versions
 #[repr(u8)]
@@ -2621,164 +2467,6 @@ impl StacksMessageCodec for TransactionPayload {
     }
 }
 
-impl StacksMessageCodec for AssetInfo {
-    fn consensus_serialize<W: Write>(&self, fd: &mut W) -> Result<(), CodecError> {
-        write_next(fd, &self.contract_address)?;
-        write_next(fd, &self.contract_name)?;
-        write_next(fd, &self.asset_name)?;
-        Ok(())
-    }
-
-    fn consensus_deserialize<R: Read>(fd: &mut R) -> Result<AssetInfo, CodecError> {
-        let contract_address: StacksAddress = read_next(fd)?;
-        let contract_name: ContractName = read_next(fd)?;
-        let asset_name: ClarityName = read_next(fd)?;
-        Ok(AssetInfo {
-            contract_address,
-            contract_name,
-            asset_name,
-        })
-    }
-}
-
-impl StacksMessageCodec for PostConditionPrincipal {
-    fn consensus_serialize<W: Write>(&self, fd: &mut W) -> Result<(), CodecError> {
-        match *self {
-            PostConditionPrincipal::Origin => {
-                write_next(fd, &(PostConditionPrincipalID::Origin as u8))?;
-            }
-            PostConditionPrincipal::Standard(ref address) => {
-                write_next(fd, &(PostConditionPrincipalID::Standard as u8))?;
-                write_next(fd, address)?;
-            }
-            PostConditionPrincipal::Contract(ref address, ref contract_name) => {
-                write_next(fd, &(PostConditionPrincipalID::Contract as u8))?;
-                write_next(fd, address)?;
-                write_next(fd, contract_name)?;
-            }
-        }
-        Ok(())
-    }
-
-    fn consensus_deserialize<R: Read>(fd: &mut R) -> Result<PostConditionPrincipal, CodecError> {
-        let principal_id: u8 = read_next(fd)?;
-        let principal = match principal_id {
-            x if x == PostConditionPrincipalID::Origin as u8 => PostConditionPrincipal::Origin,
-            x if x == PostConditionPrincipalID::Standard as u8 => {
-                let addr: StacksAddress = read_next(fd)?;
-                PostConditionPrincipal::Standard(addr)
-            }
-            x if x == PostConditionPrincipalID::Contract as u8 => {
-                let addr: StacksAddress = read_next(fd)?;
-                let contract_name: ContractName = read_next(fd)?;
-                PostConditionPrincipal::Contract(addr, contract_name)
-            }
-            _ => {
-                return Err(CodecError::DeserializeError(format!(
-                    "Failed to parse transaction: unknown post condition principal ID {}",
-                    principal_id
-                )));
-            }
-        };
-        Ok(principal)
-    }
-}
-
-impl StacksMessageCodec for TransactionPostCondition {
-    fn consensus_serialize<W: Write>(&self, fd: &mut W) -> Result<(), CodecError> {
-        match *self {
-            TransactionPostCondition::STX(ref principal, ref fungible_condition, ref amount) => {
-                write_next(fd, &(AssetInfoID::STX as u8))?;
-                write_next(fd, principal)?;
-                write_next(fd, &(*fungible_condition as u8))?;
-                write_next(fd, amount)?;
-            }
-            TransactionPostCondition::Fungible(
-                ref principal,
-                ref asset_info,
-                ref fungible_condition,
-                ref amount,
-            ) => {
-                write_next(fd, &(AssetInfoID::FungibleAsset as u8))?;
-                write_next(fd, principal)?;
-                write_next(fd, asset_info)?;
-                write_next(fd, &(*fungible_condition as u8))?;
-                write_next(fd, amount)?;
-            }
-            TransactionPostCondition::Nonfungible(
-                ref principal,
-                ref asset_info,
-                ref asset_value,
-                ref nonfungible_condition,
-            ) => {
-                write_next(fd, &(AssetInfoID::NonfungibleAsset as u8))?;
-                write_next(fd, principal)?;
-                write_next(fd, asset_info)?;
-                write_next(fd, asset_value)?;
-                write_next(fd, &(*nonfungible_condition as u8))?;
-            }
-        };
-        Ok(())
-    }
-
-    fn consensus_deserialize<R: Read>(fd: &mut R) -> Result<TransactionPostCondition, CodecError> {
-        let asset_info_id: u8 = read_next(fd)?;
-        let postcond = match asset_info_id {
-            x if x == AssetInfoID::STX as u8 => {
-                let principal: PostConditionPrincipal = read_next(fd)?;
-                let condition_u8: u8 = read_next(fd)?;
-                let amount: u64 = read_next(fd)?;
-
-                let condition_code = FungibleConditionCode::from_u8(condition_u8).ok_or(
-                    CodecError::DeserializeError(format!(
-                    "Failed to parse transaction: Failed to parse STX fungible condition code {}",
-                    condition_u8
-                )),
-                )?;
-
-                TransactionPostCondition::STX(principal, condition_code, amount)
-            }
-            x if x == AssetInfoID::FungibleAsset as u8 => {
-                let principal: PostConditionPrincipal = read_next(fd)?;
-                let asset: AssetInfo = read_next(fd)?;
-                let condition_u8: u8 = read_next(fd)?;
-                let amount: u64 = read_next(fd)?;
-
-                let condition_code = FungibleConditionCode::from_u8(condition_u8).ok_or(
-                    CodecError::DeserializeError(format!(
-                    "Failed to parse transaction: Failed to parse FungibleAsset condition code {}",
-                    condition_u8
-                )),
-                )?;
-
-                TransactionPostCondition::Fungible(principal, asset, condition_code, amount)
-            }
-            x if x == AssetInfoID::NonfungibleAsset as u8 => {
-                let principal: PostConditionPrincipal = read_next(fd)?;
-                let asset: AssetInfo = read_next(fd)?;
-                let asset_value: Value = read_next(fd)?;
-                let condition_u8: u8 = read_next(fd)?;
-
-                let condition_code = NonfungibleConditionCode::from_u8(condition_u8)
-                    .ok_or(CodecError::DeserializeError(format!(
-                        "Failed to parse transaction: Failed to parse NonfungibleAsset condition code {}",
-                        condition_u8
-                    )))?;
-
-                TransactionPostCondition::Nonfungible(principal, asset, asset_value, condition_code)
-            }
-            _ => {
-                return Err(CodecError::DeserializeError(format!(
-                    "Failed to parse transaction: unknown asset info ID {}",
-                    asset_info_id
-                )));
-            }
-        };
-
-        Ok(postcond)
-    }
-}
-
 impl StacksMessageCodec for TransactionAuth {
     fn consensus_serialize<W: Write>(&self, fd: &mut W) -> Result<(), CodecError> {
         match *self {
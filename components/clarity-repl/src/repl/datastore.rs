@@ -7,6 +7,7 @@ use clarity::types::chainstate::SortitionId;
 use clarity::types::chainstate::StacksAddress;
 use clarity::types::chainstate::StacksBlockId;
 use clarity::types::chainstate::VRFSeed;
+use clarity::types::Address;
 use clarity::types::StacksEpochId;
 use clarity::util::hash::Sha512Trunc256Sum;
 use clarity::vm::analysis::AnalysisDatabase;
@@ -16,7 +17,8 @@ use clarity::vm::errors::InterpreterResult as Result;
 use clarity::vm::types::QualifiedContractIdentifier;
 use clarity::vm::types::TupleData;
 use clarity::vm::StacksEpoch;
-use std::collections::HashMap;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
 
 #[derive(Clone, Debug)]
 pub struct Datastore {
@@ -29,6 +31,32 @@ pub struct Datastore {
     height_at_chain_tip: HashMap<StacksBlockId, u32>,
 }
 
+// Serializable copy of a datastore. Every block id is derived from a height, so blocks
+// are keyed by their height rather than by their id
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct DatastoreSnapshot {
+    pub chain_height: u32,
+    pub blocks: BTreeMap<u32, BTreeMap<String, String>>,
+    pub block_lookups: BTreeMap<u32, u32>,
+    pub metadata: Vec<(String, String, String)>,
+}
+
+// Serializable copy of a burn datastore. Only the info that can be overridden with a
+// `BlockContext` is kept, the rest is derived from the height when the blocks are mined again
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct BurnDatastoreSnapshot {
+    pub genesis_time: u64,
+    pub blocks: BTreeMap<u32, BurnBlockSnapshot>,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct BurnBlockSnapshot {
+    pub burn_block_time: u64,
+    pub burn_block_height: u32,
+    pub miner: String,
+    pub vrf_seed: String,
+}
+
 #[derive(Clone, Debug)]
 pub struct BlockInfo {
     block_header_hash: BlockHeaderHash,
@@ -44,6 +72,16 @@ pub struct BlockInfo {
     pox_payout_addrs: (Vec<TupleData>, u128),
 }
 
+// Overrides of the synthetic info of the next mined block. The blocks mined after it are
+// derived from it: their time and burn block height keep increasing from the overridden values
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct BlockContext {
+    pub time: Option<u64>,
+    pub burn_block_height: Option<u32>,
+    pub miner: Option<StacksAddress>,
+    pub vrf_seed: Option<VRFSeed>,
+}
+
 #[derive(Clone, Debug)]
 pub struct StacksConstants {
     pub burn_start_height: u32,
@@ -177,6 +215,81 @@ impl Datastore {
         self.current_chain_tip = self.open_chain_tip;
         self.chain_height
     }
+
+    pub fn get_chain_height(&self) -> u32 {
+        self.chain_height
+    }
+
+    fn height_of(&self, id: &StacksBlockId) -> u32 {
+        *self
+            .height_at_chain_tip
+            .get(id)
+            .expect("Block id missing in height lookup table")
+    }
+
+    pub fn snapshot(&self) -> DatastoreSnapshot {
+        let blocks = self
+            .store
+            .iter()
+            .map(|(id, entries)| {
+                let entries = entries
+                    .iter()
+                    .map(|(k, v)| (k.clone(), v.clone()))
+                    .collect();
+                (self.height_of(id), entries)
+            })
+            .collect();
+        let block_lookups = self
+            .block_id_lookup
+            .iter()
+            .map(|(id, lookup_id)| (self.height_of(id), self.height_of(lookup_id)))
+            .collect();
+        let mut metadata: Vec<(String, String, String)> = self
+            .metadata
+            .iter()
+            .map(|((contract, key), value)| (contract.clone(), key.clone(), value.clone()))
+            .collect();
+        metadata.sort();
+
+        DatastoreSnapshot {
+            chain_height: self.chain_height,
+            blocks,
+            block_lookups,
+            metadata,
+        }
+    }
+
+    pub fn from_snapshot(snapshot: DatastoreSnapshot) -> Self {
+        let store = snapshot
+            .blocks
+            .into_iter()
+            .map(|(height, entries)| (height_to_id(height), entries.into_iter().collect()))
+            .collect();
+        let block_id_lookup = snapshot
+            .block_lookups
+            .into_iter()
+            .map(|(height, lookup_height)| (height_to_id(height), height_to_id(lookup_height)))
+            .collect();
+        let metadata = snapshot
+            .metadata
+            .into_iter()
+            .map(|(contract, key, value)| ((contract, key), value))
+            .collect();
+        let height_at_chain_tip = (0..=snapshot.chain_height)
+            .map(|height| (height_to_id(height), height))
+            .collect();
+        let chain_tip = height_to_id(snapshot.chain_height);
+
+        Self {
+            store,
+            block_id_lookup,
+            metadata,
+            open_chain_tip: chain_tip,
+            current_chain_tip: chain_tip,
+            chain_height: snapshot.chain_height,
+            height_at_chain_tip,
+        }
+    }
 }
 
 impl ClarityBackingStore for Datastore {
@@ -342,7 +455,7 @@ impl BurnDatastore {
         }
     }
 
-    pub fn advance_chain_tip(&mut self, count: u32) {
+    pub fn advance_chain_tip(&mut self, count: u32, context: &BlockContext) {
         let cur_height = self.chain_height;
         let current_lookup_id = *self
             .block_id_lookup
@@ -355,7 +468,25 @@ impl BurnDatastore {
             let bytes = height_to_hashed_bytes(height);
             let id = StacksBlockId(bytes);
             let sortition_id = SortitionId(bytes);
-            let block_info = height_to_block(height, Some(genesis_time));
+            let mut block_info = height_to_block(height, Some(genesis_time));
+            if let Some(parent) = self.store.get(&height_to_id(height - 1)) {
+                block_info.burn_block_time = parent.burn_block_time + 1800;
+                block_info.burn_block_height = parent.burn_block_height + 1;
+            }
+            if i == 1 {
+                if let Some(time) = context.time {
+                    block_info.burn_block_time = time;
+                }
+                if let Some(burn_block_height) = context.burn_block_height {
+                    block_info.burn_block_height = burn_block_height;
+                }
+                if let Some(miner) = context.miner {
+                    block_info.miner = miner;
+                }
+                if let Some(vrf_seed) = context.vrf_seed {
+                    block_info.vrf_seed = vrf_seed;
+                }
+            }
             self.block_id_lookup.insert(id, current_lookup_id);
             self.height_at_chain_tip.insert(id, height);
             self.sortition_lookup.insert(sortition_id, id);
@@ -368,6 +499,60 @@ impl BurnDatastore {
         self.open_chain_tip = height_to_id(self.chain_height);
         self.current_chain_tip = self.open_chain_tip;
     }
+
+    pub fn snapshot(&self) -> BurnDatastoreSnapshot {
+        let blocks = (1..=self.chain_height)
+            .filter_map(|height| {
+                let block_info = self.store.get(&height_to_id(height))?;
+                Some((
+                    height,
+                    BurnBlockSnapshot {
+                        burn_block_time: block_info.burn_block_time,
+                        burn_block_height: block_info.burn_block_height,
+                        miner: block_info.miner.to_string(),
+                        vrf_seed: block_info.vrf_seed.to_hex(),
+                    },
+                ))
+            })
+            .collect();
+        BurnDatastoreSnapshot {
+            genesis_time: self.genesis_time,
+            blocks,
+        }
+    }
+
+    // the blocks are mined again one by one, with the info they had when the snapshot was taken
+    pub fn restore_snapshot(
+        &mut self,
+        snapshot: BurnDatastoreSnapshot,
+    ) -> std::result::Result<(), String> {
+        let mut burn_datastore = BurnDatastore::new(self.constants.clone());
+        burn_datastore.genesis_time = snapshot.genesis_time;
+        let genesis_id = height_to_id(0);
+        if let Some(genesis_block) = burn_datastore.store.get_mut(&genesis_id) {
+            genesis_block.burn_block_time = snapshot.genesis_time;
+        }
+
+        for (height, block) in snapshot.blocks {
+            if height != burn_datastore.chain_height + 1 {
+                return Err(format!("missing burn block {} in snapshot", height - 1));
+            }
+            let miner = StacksAddress::from_string(&block.miner)
+                .ok_or(format!("invalid miner in snapshot: {}", block.miner))?;
+            let vrf_seed = VRFSeed::from_hex(&block.vrf_seed)
+                .map_err(|e| format!("invalid vrf seed in snapshot: {}", e))?;
+            let context = BlockContext {
+                time: Some(block.burn_block_time),
+                burn_block_height: Some(block.burn_block_height),
+                miner: Some(miner),
+                vrf_seed: Some(vrf_seed),
+            };
+            burn_datastore.advance_chain_tip(1, &context);
+        }
+
+        *self = burn_datastore;
+        Ok(())
+    }
 }
 
 impl HeadersDB for BurnDatastore {
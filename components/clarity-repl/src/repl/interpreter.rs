@@ -1,10 +1,18 @@
 use std::collections::{btree_map::Entry, BTreeMap, BTreeSet};
+use std::convert::TryFrom;
 
 use crate::analysis::annotation::{Annotation, AnnotationKind};
 use crate::analysis::ast_dependency_detector::{ASTDependencyDetector, Dependency};
 use crate::analysis::{self};
+use crate::repl::datastore::BlockContext;
 use crate::repl::datastore::BurnDatastore;
+use crate::repl::datastore::BurnDatastoreSnapshot;
 use crate::repl::datastore::Datastore;
+use crate::repl::datastore::DatastoreSnapshot;
+use crate::repl::post_conditions::{
+    check_post_conditions, TransactionPostCondition, TransactionPostConditionMode,
+};
+use crate::repl::stubs::{FunctionStub, StubCall, StubCallRecorder, STUB_EXPR_ID_BASE};
 use crate::repl::Settings;
 use clarity::consts::CHAIN_ID_TESTNET;
 use clarity::vm::analysis::ContractAnalysis;
@@ -20,13 +28,15 @@ use clarity::vm::events::*;
 use clarity::vm::representations::SymbolicExpressionType::{Atom, List};
 use clarity::vm::representations::{Span, SymbolicExpression};
 use clarity::vm::types::{
-    PrincipalData, QualifiedContractIdentifier, StandardPrincipalData, Value,
+    FunctionType, PrincipalData, QualifiedContractIdentifier, StandardPrincipalData, Value,
 };
 use clarity::vm::{analysis::AnalysisDatabase, database::ClarityBackingStore};
 use clarity::vm::{eval, eval_all, EvaluationResult, SnippetEvaluationResult};
-use clarity::vm::{ContractEvaluationResult, EvalHook};
+use clarity::vm::{ClarityName, ClarityVersion, ContractEvaluationResult, EvalHook};
 use clarity::vm::{CostSynthesis, ExecutionResult, ParsedContract};
 
+use serde::{Deserialize, Serialize};
+
 use super::datastore::StacksConstants;
 use super::{ClarityContract, DEFAULT_EPOCH};
 
@@ -44,8 +54,35 @@ pub struct ClarityInterpreter {
     pub burn_datastore: BurnDatastore,
     pub repl_settings: Settings,
     tx_sender: StandardPrincipalData,
+    tx_sponsor: Option<StandardPrincipalData>,
     accounts: BTreeSet<String>,
     tokens: BTreeMap<String, BTreeMap<String, u128>>,
+    stubs: BTreeMap<u64, FunctionStub>,
+    stub_calls: Vec<StubCall>,
+    next_stub_expr_id: u64,
+    next_transaction: TransactionOptions,
+}
+
+// Conditions applied to the next transaction run by the interpreter, they are consumed by it.
+// Like on a node, the fee is paid as soon as the transaction runs, and a transaction that
+// doesn't meet its post-conditions is aborted
+#[derive(Clone, Debug, Default)]
+pub struct TransactionOptions {
+    pub fee: Option<u64>,
+    pub post_conditions: Option<(TransactionPostConditionMode, Vec<TransactionPostCondition>)>,
+}
+
+// State of the interpreter that outlives a single transaction. The stubbed bodies are stored in
+// the datastore, the stubs registry is kept with it so that they can be removed after a restore
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct InterpreterSnapshot {
+    pub datastore: DatastoreSnapshot,
+    pub burn_datastore: BurnDatastoreSnapshot,
+    pub tx_sender: String,
+    pub accounts: BTreeSet<String>,
+    pub tokens: BTreeMap<String, BTreeMap<String, u128>>,
+    pub stubs: BTreeMap<u64, FunctionStub>,
+    pub next_stub_expr_id: u64,
 }
 
 #[derive(Debug)]
@@ -108,10 +145,15 @@ impl ClarityInterpreter {
         };
         Self {
             tx_sender,
+            tx_sponsor: None,
             repl_settings,
             datastore: Datastore::new(),
             accounts: BTreeSet::new(),
             tokens: BTreeMap::new(),
+            stubs: BTreeMap::new(),
+            stub_calls: vec![],
+            next_stub_expr_id: STUB_EXPR_ID_BASE,
+            next_transaction: TransactionOptions::default(),
             burn_datastore: BurnDatastore::new(constants),
         }
     }
@@ -123,14 +165,48 @@ impl ClarityInterpreter {
         cost_track: bool,
         eval_hooks: Option<Vec<&mut dyn EvalHook>>,
     ) -> Result<ExecutionResult, Vec<Diagnostic>> {
+        let options = std::mem::take(&mut self.next_transaction);
         #[cfg(feature = "cli")]
         if self.repl_settings.clarity_wasm_mode {
-            self.run_wasm(&contract.clone(), &mut ast.clone(), cost_track, None)
+            self.run_wasm(
+                &contract.clone(),
+                &mut ast.clone(),
+                cost_track,
+                None,
+                options,
+            )
         } else {
-            self.run_interpreter(&contract.clone(), &mut ast.clone(), cost_track, eval_hooks)
+            self.run_interpreter(
+                &contract.clone(),
+                &mut ast.clone(),
+                cost_track,
+                eval_hooks,
+                options,
+            )
         }
         #[cfg(not(feature = "cli"))]
-        self.run_interpreter(&contract.clone(), &mut ast.clone(), cost_track, eval_hooks)
+        self.run_interpreter(
+            &contract.clone(),
+            &mut ast.clone(),
+            cost_track,
+            eval_hooks,
+            options,
+        )
+    }
+
+    pub fn clear_next_transaction(&mut self) {
+        self.next_transaction = TransactionOptions::default();
+    }
+
+    pub fn set_next_transaction_fee(&mut self, fee: Option<u64>) {
+        self.next_transaction.fee = fee;
+    }
+
+    pub fn set_next_transaction_post_conditions(
+        &mut self,
+        post_conditions: Option<(TransactionPostConditionMode, Vec<TransactionPostCondition>)>,
+    ) {
+        self.next_transaction.post_conditions = post_conditions;
     }
 
     fn run_interpreter(
@@ -139,6 +215,7 @@ impl ClarityInterpreter {
         ast: &mut Option<ContractAST>,
         cost_track: bool,
         eval_hooks: Option<Vec<&mut dyn EvalHook>>,
+        options: TransactionOptions,
     ) -> Result<ExecutionResult, Vec<Diagnostic>> {
         let (mut ast, mut diagnostics, success) = match ast.take() {
             Some(ast) => (ast.clone(), vec![], true),
@@ -164,7 +241,9 @@ impl ClarityInterpreter {
             return Err(diagnostics.to_vec());
         }
 
-        let mut result = match self.execute(contract, &mut ast, analysis, cost_track, eval_hooks) {
+        let mut result = match self.execute(
+            contract, &mut ast, analysis, cost_track, eval_hooks, options,
+        ) {
             Ok(result) => result,
             Err(e) => {
                 diagnostics.push(Diagnostic {
@@ -188,6 +267,7 @@ impl ClarityInterpreter {
         ast: &mut Option<ContractAST>,
         cost_track: bool,
         eval_hooks: Option<Vec<&mut dyn EvalHook>>,
+        options: TransactionOptions,
     ) -> Result<ExecutionResult, Vec<Diagnostic>> {
         use clar2wasm::compile_contract;
 
@@ -235,6 +315,7 @@ impl ClarityInterpreter {
             &mut module,
             cost_track,
             eval_hooks,
+            options,
         ) {
             Ok(result) => result,
             Err(e) => {
@@ -463,11 +544,23 @@ impl ClarityInterpreter {
         analysis: ContractAnalysis,
         cost_track: bool,
         eval_hooks: Option<Vec<&mut dyn EvalHook>>,
+        options: TransactionOptions,
     ) -> Result<ExecutionResult, String> {
         let contract_id = contract.expect_resolved_contract_identifier(Some(&self.tx_sender));
         let snippet = contract.expect_in_memory_code_source();
         let mut contract_context =
             ContractContext::new(contract_id.clone(), contract.clarity_version);
+        let mut stub_call_recorder = StubCallRecorder::new(self.stubs.clone());
+
+        // the fee is committed on its own, it's paid even if the transaction is aborted.
+        // the issuer of the contract is the sender of the transaction, or its deployer
+        if let Some(fee) = options.fee.filter(|fee| *fee > 0) {
+            let payer: PrincipalData = match &self.tx_sponsor {
+                Some(sponsor) => sponsor.clone().into(),
+                None => contract_id.issuer.clone().into(),
+            };
+            self.charge_fee(&payer, fee)?;
+        }
 
         let mut conn = ClarityDatabase::new(
             &mut self.datastore,
@@ -475,6 +568,8 @@ impl ClarityInterpreter {
             &self.burn_datastore,
         );
         let tx_sender: PrincipalData = self.tx_sender.clone().into();
+        let tx_origin = tx_sender.clone();
+        let tx_sponsor: Option<PrincipalData> = self.tx_sponsor.clone().map(|s| s.into());
         conn.begin();
         conn.set_clarity_epoch_version(contract.epoch);
         conn.commit();
@@ -493,11 +588,17 @@ impl ClarityInterpreter {
         let mut global_context =
             GlobalContext::new(false, CHAIN_ID_TESTNET, conn, cost_tracker, contract.epoch);
 
+        let mut hooks: Vec<&mut dyn EvalHook> = Vec::new();
         if let Some(mut in_hooks) = eval_hooks {
-            let mut hooks: Vec<&mut dyn EvalHook> = Vec::new();
             for hook in in_hooks.drain(..) {
                 hooks.push(hook);
             }
+        }
+        // the calls to the stubbed functions are recorded along the execution
+        if !self.stubs.is_empty() {
+            hooks.push(&mut stub_call_recorder);
+        }
+        if !hooks.is_empty() {
             global_context.eval_hooks = Some(hooks);
         }
 
@@ -515,7 +616,7 @@ impl ClarityInterpreter {
                     &mut call_stack,
                     Some(tx_sender.clone()),
                     Some(tx_sender.clone()),
-                    None,
+                    tx_sponsor.clone(),
                 );
 
                 // call a function
@@ -596,6 +697,20 @@ impl ClarityInterpreter {
             .flat_map(|b| b.events.clone())
             .collect::<Vec<_>>();
 
+        // the transaction is aborted by leaving its outer nesting level uncommitted
+        if let Some((mode, post_conditions)) = &options.post_conditions {
+            if let Err(err) =
+                check_post_conditions(*mode, post_conditions, &tx_origin, &emitted_events)
+            {
+                if let Some(mut eval_hooks) = global_context.eval_hooks.take() {
+                    for hook in eval_hooks.iter_mut() {
+                        hook.did_complete(Err(err.clone()));
+                    }
+                }
+                return Err(err);
+            }
+        }
+
         let contract_saved =
             !contract_context.functions.is_empty() || !contract_context.defined_traits.is_empty();
 
@@ -678,6 +793,8 @@ impl ClarityInterpreter {
                 .expect("Unable to save data");
         }
 
+        self.stub_calls.append(&mut stub_call_recorder.calls);
+
         Ok(execution_result)
     }
 
@@ -690,18 +807,31 @@ impl ClarityInterpreter {
         wasm_module: &mut clar2wasm::Module,
         cost_track: bool,
         eval_hooks: Option<Vec<&mut dyn EvalHook>>,
+        options: TransactionOptions,
     ) -> Result<ExecutionResult, String> {
         let contract_id = contract.expect_resolved_contract_identifier(Some(&self.tx_sender));
         let snippet = contract.expect_in_memory_code_source();
         let mut contract_context =
             ContractContext::new(contract_id.clone(), contract.clarity_version);
 
+        // the fee is committed on its own, it's paid even if the transaction is aborted.
+        // the issuer of the contract is the sender of the transaction, or its deployer
+        if let Some(fee) = options.fee.filter(|fee| *fee > 0) {
+            let payer: PrincipalData = match &self.tx_sponsor {
+                Some(sponsor) => sponsor.clone().into(),
+                None => contract_id.issuer.clone().into(),
+            };
+            self.charge_fee(&payer, fee)?;
+        }
+
         let mut conn = ClarityDatabase::new(
             &mut self.datastore,
             &self.burn_datastore,
             &self.burn_datastore,
         );
         let tx_sender: PrincipalData = self.tx_sender.clone().into();
+        let tx_origin = tx_sender.clone();
+        let tx_sponsor: Option<PrincipalData> = self.tx_sponsor.clone().map(|s| s.into());
         conn.begin();
         conn.set_clarity_epoch_version(contract.epoch);
         conn.commit();
@@ -741,7 +871,7 @@ impl ClarityInterpreter {
                     &mut call_stack,
                     Some(tx_sender.clone()),
                     Some(tx_sender.clone()),
-                    None,
+                    tx_sponsor.clone(),
                 );
 
                 // call a function
@@ -777,7 +907,7 @@ impl ClarityInterpreter {
                                 &mut call_stack,
                                 Some(tx_sender.clone()),
                                 Some(tx_sender),
-                                None,
+                                tx_sponsor,
                             ) {
                                 Ok(res) => res,
                                 Err(e) => {
@@ -834,6 +964,20 @@ impl ClarityInterpreter {
             .flat_map(|b| b.events.clone())
             .collect::<Vec<_>>();
 
+        // the transaction is aborted by leaving its outer nesting level uncommitted
+        if let Some((mode, post_conditions)) = &options.post_conditions {
+            if let Err(err) =
+                check_post_conditions(*mode, post_conditions, &tx_origin, &emitted_events)
+            {
+                if let Some(mut eval_hooks) = global_context.eval_hooks.take() {
+                    for hook in eval_hooks.iter_mut() {
+                        hook.did_complete(Err(err.clone()));
+                    }
+                }
+                return Err(err);
+            }
+        }
+
         let contract_saved =
             !contract_context.functions.is_empty() || !contract_context.defined_traits.is_empty();
 
@@ -1055,6 +1199,351 @@ impl ClarityInterpreter {
         Ok(format!("→ {}: {} µSTX", recipient, final_balance))
     }
 
+    // simnet has no miners, the fee is burnt
+    pub fn charge_fee(&mut self, payer: &PrincipalData, fee: u64) -> Result<(), String> {
+        {
+            let conn = ClarityDatabase::new(
+                &mut self.datastore,
+                &self.burn_datastore,
+                &self.burn_datastore,
+            );
+
+            let mut global_context = GlobalContext::new(
+                false,
+                CHAIN_ID_TESTNET,
+                conn,
+                LimitedCostTracker::new_free(),
+                DEFAULT_EPOCH,
+            );
+            global_context.begin();
+            let mut cur_balance = global_context.database.get_stx_balance_snapshot(payer);
+            let available = cur_balance.get_available_balance();
+            if available < fee as u128 {
+                global_context.roll_back();
+                return Err(format!(
+                    "unable to pay fee of {} µSTX: {} only has {} µSTX",
+                    fee, payer, available
+                ));
+            }
+            cur_balance.debit(fee as u128);
+            cur_balance.save();
+            // the debit is rolled back if the liquid supply can't be updated
+            if let Err(e) = global_context
+                .database
+                .decrement_ustx_liquid_supply(fee as u128)
+            {
+                global_context.roll_back();
+                return Err(format!("unable to pay fee of {} µSTX: {}", fee, e));
+            }
+            global_context.commit().unwrap();
+        }
+        self.debit_token(payer.to_string(), "STX".to_string(), fee.into());
+        Ok(())
+    }
+
+    // Replaces the body of a function of a deployed contract. The stub is evaluated in the
+    // context of the contract, so it can use the arguments of the function and the contract
+    // constants, variables and maps. The contract is type-checked with the stub, which has to
+    // return a type admitted by the original function
+    pub fn stub_function(
+        &mut self,
+        contract_id: &QualifiedContractIdentifier,
+        function: &str,
+        body: &str,
+    ) -> Result<(), String> {
+        if self.repl_settings.clarity_wasm_mode {
+            return Err("functions can not be stubbed in clarity-wasm mode".into());
+        }
+
+        // the evaluation hooks identify the expressions by their id, the ones of the stub are
+        // taken above the ids of the contracts expressions
+        let mut next_id = self.next_stub_expr_id;
+        let (stub_id, stub, define) = self.with_global_context(|g| {
+            Self::build_stub(g, contract_id, function, body, &mut next_id)
+        })?;
+
+        self.check_stub(contract_id, function, &define)?;
+        self.redefine_function(contract_id, function, &define)?;
+
+        self.next_stub_expr_id = next_id;
+        self.stubs
+            .retain(|_, s| !(&s.contract_id == contract_id && s.function == function));
+        self.stubs.insert(stub_id, stub);
+        Ok(())
+    }
+
+    // Restores the original definition of a stubbed function
+    pub fn remove_stub(
+        &mut self,
+        contract_id: &QualifiedContractIdentifier,
+        function: &str,
+    ) -> Result<(), String> {
+        let stub_id = match self
+            .stubs
+            .iter()
+            .find(|(_, s)| &s.contract_id == contract_id && s.function == function)
+        {
+            Some((stub_id, _)) => *stub_id,
+            None => return Err(format!("{}::{} is not stubbed", contract_id, function)),
+        };
+
+        let (source, clarity_version) =
+            self.with_global_context(|g| Self::get_contract_source(g, contract_id))?;
+        // the contract is parsed again, its expressions get the ids they had when it was deployed
+        let (ast, _, success) = build_ast_with_diagnostics(
+            contract_id,
+            &source,
+            &mut (),
+            clarity_version,
+            DEFAULT_EPOCH,
+        );
+        let index = match success {
+            true => find_function_definition(&ast.expressions, function),
+            false => None,
+        };
+        let define = match index {
+            Some(index) => &ast.expressions[index],
+            None => return Err(format!("unable to find the definition of {}", function)),
+        };
+        self.redefine_function(contract_id, function, define)?;
+
+        self.stubs.remove(&stub_id);
+        Ok(())
+    }
+
+    fn with_global_context<F, R>(&mut self, f: F) -> R
+    where
+        F: FnOnce(&mut GlobalContext) -> R,
+    {
+        let conn = ClarityDatabase::new(
+            &mut self.datastore,
+            &self.burn_datastore,
+            &self.burn_datastore,
+        );
+        let mut global_context = GlobalContext::new(
+            false,
+            CHAIN_ID_TESTNET,
+            conn,
+            LimitedCostTracker::new_free(),
+            DEFAULT_EPOCH,
+        );
+        global_context.begin();
+        let result = f(&mut global_context);
+        global_context.commit().unwrap();
+        result
+    }
+
+    fn get_contract_source(
+        global_context: &mut GlobalContext,
+        contract_id: &QualifiedContractIdentifier,
+    ) -> Result<(String, ClarityVersion), String> {
+        let contract = global_context
+            .database
+            .get_contract(contract_id)
+            .map_err(|_| format!("contract {} not found", contract_id))?;
+        match global_context.database.get_contract_src(contract_id) {
+            Some(source) => Ok((source, contract.contract_context.clarity_version)),
+            None => Err(format!("unable to retrieve the source of {}", contract_id)),
+        }
+    }
+
+    // builds the definition of the stub, with the signature of the original function. the id of
+    // its body identifies the stub
+    fn build_stub(
+        global_context: &mut GlobalContext,
+        contract_id: &QualifiedContractIdentifier,
+        function: &str,
+        body: &str,
+        next_id: &mut u64,
+    ) -> Result<(u64, FunctionStub, SymbolicExpression), String> {
+        let contract = global_context
+            .database
+            .get_contract(contract_id)
+            .map_err(|_| format!("contract {} not found", contract_id))?;
+        let contract_context = &contract.contract_context;
+
+        let name = ClarityName::try_from(function.to_string())
+            .map_err(|_| format!("invalid function name {}", function))?;
+        let defined_function = contract_context.functions.get(&name).ok_or(format!(
+            "contract {} has no function {}",
+            contract_id, function
+        ))?;
+        let define = if defined_function.is_read_only() {
+            "define-read-only"
+        } else if defined_function.is_public() {
+            "define-public"
+        } else {
+            "define-private"
+        };
+        let arguments: Vec<String> = defined_function
+            .get_arguments()
+            .iter()
+            .map(|n| n.to_string())
+            .collect();
+        let signature: Vec<String> = arguments
+            .iter()
+            .zip(defined_function.get_arg_types().iter())
+            .map(|(n, t)| format!("({} {})", n, t))
+            .collect();
+        let snippet = format!(
+            "({} ({} {}) {})",
+            define,
+            function,
+            signature.join(" "),
+            body
+        );
+
+        let (mut ast, diagnostics, success) = build_ast_with_diagnostics(
+            contract_id,
+            &snippet,
+            &mut (),
+            contract_context.clarity_version,
+            DEFAULT_EPOCH,
+        );
+        if !success || ast.expressions.len() != 1 {
+            let message = diagnostics
+                .last()
+                .map(|d| d.message.clone())
+                .unwrap_or_else(|| "the stub must be a single expression".into());
+            return Err(format!("invalid stub for {}: {}", function, message));
+        }
+        let mut define = ast.expressions.remove(0);
+        renumber_expressions(&mut define, next_id);
+        let stub_id = match &define.expr {
+            List(define) if define.len() == 3 => define[2].id,
+            _ => {
+                return Err(format!(
+                    "the stub of {} must be a single expression",
+                    function
+                ))
+            }
+        };
+
+        let stub = FunctionStub {
+            contract_id: contract_id.clone(),
+            function: function.to_string(),
+            arguments,
+        };
+        Ok((stub_id, stub, define))
+    }
+
+    // the contract is type-checked with the stub in place of the original definition, like it
+    // would be on deployment. read-only functions can't be stubbed with a body writing data
+    fn check_stub(
+        &mut self,
+        contract_id: &QualifiedContractIdentifier,
+        function: &str,
+        define: &SymbolicExpression,
+    ) -> Result<(), String> {
+        let (source, clarity_version) =
+            self.with_global_context(|g| Self::get_contract_source(g, contract_id))?;
+        let (ast, _, success) = build_ast_with_diagnostics(
+            contract_id,
+            &source,
+            &mut (),
+            clarity_version,
+            DEFAULT_EPOCH,
+        );
+        let index = match success {
+            true => find_function_definition(&ast.expressions, function),
+            false => None,
+        };
+        let index = match index {
+            Some(index) => index,
+            None => return Err(format!("unable to find the definition of {}", function)),
+        };
+        let mut expressions = ast.expressions;
+        let original = self
+            .type_check(contract_id, expressions.clone(), clarity_version)
+            .map_err(|message| format!("unable to analyze {}: {}", contract_id, message))?;
+        expressions[index] = define.clone();
+        let stubbed = self
+            .type_check(contract_id, expressions, clarity_version)
+            .map_err(|message| format!("invalid stub for {}: {}", function, message))?;
+
+        match (
+            get_function_type(&original, function),
+            get_function_type(&stubbed, function),
+        ) {
+            (Some(FunctionType::Fixed(original)), Some(FunctionType::Fixed(stub))) => {
+                let admitted = original.returns == stub.returns
+                    || original
+                        .returns
+                        .admits_type(&DEFAULT_EPOCH, &stub.returns)
+                        .unwrap_or(false);
+                match admitted {
+                    true => Ok(()),
+                    false => Err(format!(
+                        "invalid stub for {}: expecting {} to be returned, found {}",
+                        function, original.returns, stub.returns
+                    )),
+                }
+            }
+            _ => Err(format!("unable to type-check the stub of {}", function)),
+        }
+    }
+
+    fn type_check(
+        &mut self,
+        contract_id: &QualifiedContractIdentifier,
+        mut expressions: Vec<SymbolicExpression>,
+        clarity_version: ClarityVersion,
+    ) -> Result<ContractAnalysis, String> {
+        let mut analysis_db = AnalysisDatabase::new(&mut self.datastore);
+        clarity::vm::analysis::run_analysis(
+            contract_id,
+            &mut expressions,
+            &mut analysis_db,
+            false,
+            LimitedCostTracker::new_free(),
+            DEFAULT_EPOCH,
+            clarity_version,
+        )
+        .map_err(|(error, _)| error.diagnostic.message)
+    }
+
+    // the original definition has to be removed for the name to be defined again
+    fn redefine_function(
+        &mut self,
+        contract_id: &QualifiedContractIdentifier,
+        function: &str,
+        define: &SymbolicExpression,
+    ) -> Result<(), String> {
+        self.with_global_context(|global_context| {
+            let mut contract = global_context
+                .database
+                .get_contract(contract_id)
+                .map_err(|_| format!("contract {} not found", contract_id))?;
+            let contract_context = &mut contract.contract_context;
+            let name = ClarityName::try_from(function.to_string())
+                .map_err(|_| format!("invalid function name {}", function))?;
+            contract_context.functions.remove(&name);
+            eval_all(&[define.clone()], contract_context, global_context, None)
+                .map_err(|e| format!("unable to define {}: {:?}", function, e))?;
+            global_context
+                .database
+                .insert_contract(contract_id, contract);
+            Ok(())
+        })
+    }
+
+    // calls made to the stubbed functions since the last time they were taken
+    pub fn take_stub_calls(&mut self) -> Vec<StubCall> {
+        std::mem::take(&mut self.stub_calls)
+    }
+
+    pub fn get_stub_calls(&self) -> &[StubCall] {
+        &self.stub_calls
+    }
+
+    pub fn set_tx_sponsor(&mut self, tx_sponsor: Option<StandardPrincipalData>) {
+        self.tx_sponsor = tx_sponsor;
+    }
+
+    pub fn get_tx_sponsor(&self) -> Option<StandardPrincipalData> {
+        self.tx_sponsor.clone()
+    }
+
     pub fn set_tx_sender(&mut self, tx_sender: StandardPrincipalData) {
         self.tx_sender = tx_sender;
     }
@@ -1064,7 +1553,11 @@ impl ClarityInterpreter {
     }
 
     pub fn advance_chain_tip(&mut self, count: u32) -> u32 {
-        self.burn_datastore.advance_chain_tip(count);
+        self.advance_chain_tip_with_context(count, &BlockContext::default())
+    }
+
+    pub fn advance_chain_tip_with_context(&mut self, count: u32, context: &BlockContext) -> u32 {
+        self.burn_datastore.advance_chain_tip(count, context);
         self.datastore.advance_chain_tip(count)
     }
 
@@ -1072,6 +1565,39 @@ impl ClarityInterpreter {
         self.datastore.get_current_block_height()
     }
 
+    pub fn snapshot(&self) -> InterpreterSnapshot {
+        InterpreterSnapshot {
+            datastore: self.datastore.snapshot(),
+            burn_datastore: self.burn_datastore.snapshot(),
+            tx_sender: self.tx_sender.to_address(),
+            accounts: self.accounts.clone(),
+            tokens: self.tokens.clone(),
+            stubs: self.stubs.clone(),
+            next_stub_expr_id: self.next_stub_expr_id,
+        }
+    }
+
+    pub fn restore_snapshot(&mut self, snapshot: InterpreterSnapshot) -> Result<(), String> {
+        let tx_sender = PrincipalData::parse_standard_principal(&snapshot.tx_sender)
+            .map_err(|e| format!("invalid tx-sender in snapshot: {}", e))?;
+        let datastore = Datastore::from_snapshot(snapshot.datastore);
+        if snapshot.burn_datastore.blocks.len() != datastore.get_chain_height() as usize {
+            return Err("the burn blocks of the snapshot don't match its chain height".into());
+        }
+
+        let mut fresh = ClarityInterpreter::new(tx_sender, self.repl_settings.clone());
+        fresh.datastore = datastore;
+        fresh
+            .burn_datastore
+            .restore_snapshot(snapshot.burn_datastore)?;
+        fresh.accounts = snapshot.accounts;
+        fresh.tokens = snapshot.tokens;
+        fresh.stubs = snapshot.stubs;
+        fresh.next_stub_expr_id = snapshot.next_stub_expr_id;
+        *self = fresh;
+        Ok(())
+    }
+
     fn credit_token(&mut self, account: String, token: String, value: u128) {
         self.accounts.insert(account.clone());
         match self.tokens.entry(token) {
@@ -1131,6 +1657,49 @@ impl ClarityInterpreter {
     }
 }
 
+// the expressions are numbered in depth-first order
+fn renumber_expressions(expr: &mut SymbolicExpression, next_id: &mut u64) {
+    expr.id = *next_id;
+    *next_id += 1;
+    if let List(list) = &mut expr.expr {
+        for expr in list.iter_mut() {
+            renumber_expressions(expr, next_id);
+        }
+    }
+}
+
+// index of the top level `(define-* (function ...) ...)` expression
+fn find_function_definition(expressions: &[SymbolicExpression], function: &str) -> Option<usize> {
+    expressions.iter().position(|expr| {
+        let define = match &expr.expr {
+            List(define) if define.len() == 3 => define,
+            _ => return false,
+        };
+        let is_function_definition = matches!(
+            define[0].match_atom().map(|name| name.as_str()),
+            Some("define-public" | "define-read-only" | "define-private")
+        );
+        let name = define[1]
+            .match_list()
+            .and_then(|signature| signature.first())
+            .and_then(|name| name.match_atom());
+        is_function_definition && name.map(|name| name.as_str()) == Some(function)
+    })
+}
+
+fn get_function_type<'a>(
+    analysis: &'a ContractAnalysis,
+    function: &str,
+) -> Option<&'a FunctionType> {
+    analysis
+        .public_function_types
+        .iter()
+        .chain(analysis.read_only_function_types.iter())
+        .chain(analysis.private_function_types.iter())
+        .find(|(name, _)| name.as_str() == function)
+        .map(|(_, function_type)| function_type)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -1162,6 +1731,71 @@ mod tests {
         assert_eq!(interpreter.get_tx_sender(), tx_sender);
     }
 
+    #[test]
+    fn test_charge_tx_fee() {
+        let sender = StandardPrincipalData::from(
+            StacksAddress::from_string("ST1SJ3DTE5DN7X54YDH5D64R3BCB6A2AG2ZQ8YPD5").unwrap(),
+        );
+        let sponsor = StandardPrincipalData::from(
+            StacksAddress::from_string("ST2CY5V39NHDPWSXMW9QDT3HC3GD6Q6XX4CFRK9AG").unwrap(),
+        );
+        let mut interpreter = ClarityInterpreter::new(sender.clone(), Settings::default());
+        let _ = interpreter.mint_stx_balance(sender.clone().into(), 5000);
+        let _ = interpreter.mint_stx_balance(sponsor.clone().into(), 500);
+
+        let snippet = ClarityContractBuilder::default()
+            .code_source("(+ 1 2)".into())
+            .build();
+        interpreter.set_next_transaction_fee(Some(1000));
+        assert!(interpreter.run(&snippet, &mut None, false, None).is_ok());
+        assert_eq!(
+            interpreter.get_balance_for_account(&sender.to_string(), "STX"),
+            4000
+        );
+
+        // the fee only applies to the next transaction
+        assert!(interpreter.run(&snippet, &mut None, false, None).is_ok());
+        assert_eq!(
+            interpreter.get_balance_for_account(&sender.to_string(), "STX"),
+            4000
+        );
+
+        // a transaction that doesn't parse doesn't run and isn't charged
+        let invalid = ClarityContractBuilder::default()
+            .code_source("(+ 1 2".into())
+            .build();
+        interpreter.set_next_transaction_fee(Some(1000));
+        assert!(interpreter.run(&invalid, &mut None, false, None).is_err());
+        assert_eq!(
+            interpreter.get_balance_for_account(&sender.to_string(), "STX"),
+            4000
+        );
+
+        // a runtime error aborts the transaction, but the fee is paid
+        let failing = ClarityContractBuilder::default()
+            .code_source("(unwrap-panic none)".into())
+            .build();
+        interpreter.set_next_transaction_fee(Some(1000));
+        assert!(interpreter.run(&failing, &mut None, false, None).is_err());
+        assert_eq!(
+            interpreter.get_balance_for_account(&sender.to_string(), "STX"),
+            3000
+        );
+
+        // the sponsor pays the fee and can not afford it
+        interpreter.set_tx_sponsor(Some(sponsor.clone()));
+        interpreter.set_next_transaction_fee(Some(1000));
+        assert!(interpreter.run(&snippet, &mut None, false, None).is_err());
+        assert_eq!(
+            interpreter.get_balance_for_account(&sponsor.to_string(), "STX"),
+            500
+        );
+        assert_eq!(
+            interpreter.get_balance_for_account(&sender.to_string(), "STX"),
+            3000
+        );
+    }
+
     #[test]
     fn test_get_block_time() {
         let mut interpreter =
@@ -1187,6 +1821,33 @@ mod tests {
         assert_eq!(interpreter.get_block_height(), initial_block_height + count);
     }
 
+    #[test]
+    fn test_restore_snapshot_keeps_burn_blocks() {
+        let mut interpreter =
+            ClarityInterpreter::new(StandardPrincipalData::transient(), Settings::default());
+        interpreter.advance_chain_tip(2);
+        let context = BlockContext {
+            time: Some(4_000_000_000),
+            burn_block_height: Some(100),
+            miner: Some(StacksAddress::burn_address(false)),
+            vrf_seed: None,
+        };
+        interpreter.advance_chain_tip_with_context(3, &context);
+        let snapshot = interpreter.snapshot();
+        assert_eq!(snapshot.burn_datastore.blocks.len(), 5);
+        assert_eq!(
+            snapshot.burn_datastore.blocks[&3].burn_block_time,
+            4_000_000_000
+        );
+        assert_eq!(snapshot.burn_datastore.blocks[&5].burn_block_height, 102);
+
+        let mut restored =
+            ClarityInterpreter::new(StandardPrincipalData::transient(), Settings::default());
+        restored.restore_snapshot(snapshot.clone()).unwrap();
+        assert_eq!(restored.get_block_height(), 5);
+        assert_eq!(restored.snapshot(), snapshot);
+    }
+
     #[test]
     fn test_get_assets_maps() {
         let mut interpreter =
@@ -1272,7 +1933,13 @@ mod tests {
         let mut interpreter =
             ClarityInterpreter::new(StandardPrincipalData::transient(), Settings::default());
         let contract = ClarityContract::fixture();
-        let result = interpreter.run_interpreter(&contract, &mut None, false, None);
+        let result = interpreter.run_interpreter(
+            &contract,
+            &mut None,
+            false,
+            None,
+            TransactionOptions::default(),
+        );
         assert!(result.is_ok());
         assert!(result.unwrap().diagnostics.is_empty());
     }
@@ -1287,7 +1954,13 @@ mod tests {
         let contract = ClarityContractBuilder::default()
             .code_source(snippet.into())
             .build();
-        let result = interpreter.run_interpreter(&contract, &mut None, false, None);
+        let result = interpreter.run_interpreter(
+            &contract,
+            &mut None,
+            false,
+            None,
+            TransactionOptions::default(),
+        );
         assert!(result.is_err());
         let diagnostics = result.unwrap_err();
         assert_eq!(diagnostics.len(), 1);
@@ -1302,7 +1975,13 @@ mod tests {
         let contract = ClarityContractBuilder::default()
             .code_source(snippet.into())
             .build();
-        let result = interpreter.run_interpreter(&contract, &mut None, false, None);
+        let result = interpreter.run_interpreter(
+            &contract,
+            &mut None,
+            false,
+            None,
+            TransactionOptions::default(),
+        );
         assert!(result.is_err());
 
         let diagnostics = result.unwrap_err();
@@ -1344,7 +2023,14 @@ mod tests {
             .run_analysis(&contract, &mut ast, &annotations)
             .unwrap();
 
-        let result = interpreter.execute(&contract, &mut ast, analysis, false, None);
+        let result = interpreter.execute(
+            &contract,
+            &mut ast,
+            analysis,
+            false,
+            None,
+            TransactionOptions::default(),
+        );
         assert!(result.is_ok());
         let ExecutionResult {
             diagnostics,
@@ -1369,6 +2055,59 @@ mod tests {
         let _ = interpreter.run(&call_contract, &mut None, false, None);
     }
 
+    #[test]
+    fn test_stub_function() {
+        let mut interpreter =
+            ClarityInterpreter::new(StandardPrincipalData::transient(), Settings::default());
+        let contract = ClarityContract::fixture();
+        let _ = interpreter.run(&contract, &mut None, false, None);
+        let contract_id = contract.expect_resolved_contract_identifier(None);
+
+        interpreter
+            .stub_function(&contract_id, "get-x", "(+ (var-get x) u42)")
+            .unwrap();
+        assert_eq!(interpreter.stubs.len(), 1);
+        assert!(interpreter
+            .stubs
+            .keys()
+            .all(|stub_id| *stub_id >= STUB_EXPR_ID_BASE));
+
+        // the stub has to return the type of the original function
+        assert!(interpreter
+            .stub_function(&contract_id, "get-x", "(ok u1)")
+            .is_err());
+        // and a read-only function can't be stubbed with a body writing data
+        assert!(interpreter
+            .stub_function(&contract_id, "get-x", "(begin (var-set x u1) u1)")
+            .is_err());
+        assert_eq!(interpreter.stubs.len(), 1);
+
+        interpreter.remove_stub(&contract_id, "get-x").unwrap();
+        assert!(interpreter.stubs.is_empty());
+        assert!(interpreter.remove_stub(&contract_id, "get-x").is_err());
+    }
+
+    #[test]
+    fn test_remove_stub_after_restoring_snapshot() {
+        let mut interpreter =
+            ClarityInterpreter::new(StandardPrincipalData::transient(), Settings::default());
+        let contract = ClarityContract::fixture();
+        let _ = interpreter.run(&contract, &mut None, false, None);
+        let contract_id = contract.expect_resolved_contract_identifier(None);
+
+        interpreter
+            .stub_function(&contract_id, "get-x", "(+ (var-get x) u42)")
+            .unwrap();
+        let snapshot = interpreter.snapshot();
+        interpreter.remove_stub(&contract_id, "get-x").unwrap();
+
+        // the stubbed body is restored with the datastore, and can still be removed
+        interpreter.restore_snapshot(snapshot).unwrap();
+        assert_eq!(interpreter.stubs.len(), 1);
+        interpreter.remove_stub(&contract_id, "get-x").unwrap();
+        assert!(interpreter.stubs.is_empty());
+    }
+
     #[test]
     fn test_get_data_var() {
         let mut interpreter =
@@ -1463,7 +2202,14 @@ mod tests {
         let balance = interpreter.get_balance_for_account(&account.to_string(), "STX");
         assert_eq!(balance, 100000);
 
-        let result = interpreter.execute(&contract, &mut ast, analysis, false, None);
+        let result = interpreter.execute(
+            &contract,
+            &mut ast,
+            analysis,
+            false,
+            None,
+            TransactionOptions::default(),
+        );
         assert!(result.is_ok());
 
         let ExecutionResult {
@@ -1517,7 +2263,14 @@ mod tests {
             .run_analysis(&contract, &mut ast, &annotations)
             .unwrap();
 
-        let result = interpreter.execute(&contract, &mut ast, analysis, false, None);
+        let result = interpreter.execute(
+            &contract,
+            &mut ast,
+            analysis,
+            false,
+            None,
+            TransactionOptions::default(),
+        );
         assert!(result.is_ok());
         let ExecutionResult {
             diagnostics,
@@ -1569,7 +2322,14 @@ mod tests {
             .run_analysis(&contract, &mut ast, &annotations)
             .unwrap();
 
-        let result = interpreter.execute(&contract, &mut ast, analysis, false, None);
+        let result = interpreter.execute(
+            &contract,
+            &mut ast,
+            analysis,
+            false,
+            None,
+            TransactionOptions::default(),
+        );
         assert!(result.is_ok());
         let ExecutionResult {
             diagnostics,
@@ -0,0 +1,299 @@
+use std::collections::HashMap;
+
+use super::post_conditions::{TransactionPostCondition, TransactionPostConditionMode};
+
+/// Order in which the pending transactions are picked when a block is mined
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum MempoolOrdering {
+    // highest fee first, transactions paying the same fee are mined in their arrival order
+    #[default]
+    FeePriority,
+    Fifo,
+    // the pending transactions are shuffled, the same seed always produces the same blocks
+    Random(u64),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum MempoolTxPayload {
+    ContractCall {
+        contract: String,
+        method: String,
+        args: Vec<String>,
+    },
+    StxTransfer {
+        amount: u64,
+        recipient: String,
+    },
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct MempoolTransaction {
+    pub sender: String,
+    pub nonce: u64,
+    pub fee: u64,
+    // the sponsor pays the fee of the transaction instead of the sender
+    pub sponsor: Option<String>,
+    pub post_conditions: Option<(TransactionPostConditionMode, Vec<TransactionPostCondition>)>,
+    pub payload: MempoolTxPayload,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct PendingTransaction {
+    pub txid: u64,
+    pub tx: MempoolTransaction,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct MempoolAdmission {
+    pub txid: u64,
+    // txid of the pending transaction with the same nonce that was replaced
+    pub replaced: Option<u64>,
+}
+
+#[derive(Debug, Clone)]
+pub struct MinedTransaction<R> {
+    pub txid: u64,
+    pub tx: MempoolTransaction,
+    pub result: R,
+}
+
+#[derive(Debug, Clone)]
+pub struct MinedBlock<R> {
+    pub transactions: Vec<MinedTransaction<R>>,
+    // transactions that could not be executed (e.g. the sender can't pay the fee), they are
+    // removed from the mempool without consuming their nonce
+    pub dropped: Vec<PendingTransaction>,
+}
+
+// Pending transactions waiting to be mined. Like a node, a transaction is only mined once all
+// the previous nonces of its sender have been mined, and a pending transaction can be replaced
+// by one with the same nonce paying a higher fee
+#[derive(Debug, Clone, Default)]
+pub struct Mempool {
+    ordering: MempoolOrdering,
+    random_state: u64,
+    pending: Vec<PendingTransaction>,
+    nonces: HashMap<String, u64>,
+    next_txid: u64,
+}
+
+impl Mempool {
+    pub fn new(ordering: MempoolOrdering) -> Self {
+        let mut mempool = Mempool::default();
+        mempool.set_ordering(ordering);
+        mempool
+    }
+
+    pub fn set_ordering(&mut self, ordering: MempoolOrdering) {
+        if let MempoolOrdering::Random(seed) = ordering {
+            self.random_state = seed;
+        }
+        self.ordering = ordering;
+    }
+
+    pub fn get_ordering(&self) -> MempoolOrdering {
+        self.ordering
+    }
+
+    // nonce expected for the next transaction of the account
+    pub fn get_nonce(&self, sender: &str) -> u64 {
+        self.nonces.get(sender).copied().unwrap_or(0)
+    }
+
+    pub fn get_pending_transactions(&self) -> &[PendingTransaction] {
+        &self.pending
+    }
+
+    pub fn submit(&mut self, tx: MempoolTransaction) -> Result<MempoolAdmission, String> {
+        let expected_nonce = self.get_nonce(&tx.sender);
+        if tx.nonce < expected_nonce {
+            return Err(format!(
+                "transaction rejected: nonce {} of {} is too low, expected at least {}",
+                tx.nonce, tx.sender, expected_nonce
+            ));
+        }
+
+        let conflicting = self
+            .pending
+            .iter()
+            .position(|p| p.tx.sender == tx.sender && p.tx.nonce == tx.nonce);
+        let replaced = match conflicting {
+            Some(index) if self.pending[index].tx.fee >= tx.fee => {
+                return Err(format!(
+                    "transaction rejected: conflicting nonce {} of {}, the fee must be higher than {} µSTX to replace the pending transaction",
+                    tx.nonce, tx.sender, self.pending[index].tx.fee
+                ));
+            }
+            Some(index) => Some(self.pending.remove(index).txid),
+            None => None,
+        };
+
+        let txid = self.next_txid;
+        self.next_txid += 1;
+        self.pending.push(PendingTransaction { txid, tx });
+        Ok(MempoolAdmission { txid, replaced })
+    }
+
+    // Mines the ready transactions. `execute` returns None if the transaction can't be
+    // included in the block, otherwise its nonce is consumed, whatever the result
+    pub fn mine_block<R, F>(&mut self, mut execute: F) -> MinedBlock<R>
+    where
+        F: FnMut(&MempoolTransaction) -> Option<R>,
+    {
+        let order = self.block_order();
+        let mut block = MinedBlock {
+            transactions: vec![],
+            dropped: vec![],
+        };
+
+        loop {
+            let next = order.iter().find_map(|txid| {
+                self.pending
+                    .iter()
+                    .position(|p| p.txid == *txid && p.tx.nonce == self.get_nonce(&p.tx.sender))
+            });
+            let index = match next {
+                Some(index) => index,
+                None => break,
+            };
+
+            let pending = self.pending.remove(index);
+            match execute(&pending.tx) {
+                Some(result) => {
+                    *self.nonces.entry(pending.tx.sender.clone()).or_insert(0) += 1;
+                    block.transactions.push(MinedTransaction {
+                        txid: pending.txid,
+                        tx: pending.tx,
+                        result,
+                    });
+                }
+                None => block.dropped.push(pending),
+            }
+        }
+        block
+    }
+
+    fn block_order(&mut self) -> Vec<u64> {
+        let mut order: Vec<&PendingTransaction> = self.pending.iter().collect();
+        match self.ordering {
+            MempoolOrdering::Fifo => {}
+            // the sort is stable, the arrival order is kept for equal fees
+            MempoolOrdering::FeePriority => order.sort_by(|a, b| b.tx.fee.cmp(&a.tx.fee)),
+            MempoolOrdering::Random(_) => {
+                for i in (1..order.len()).rev() {
+                    let j = (next_random(&mut self.random_state) % (i as u64 + 1)) as usize;
+                    order.swap(i, j);
+                }
+            }
+        }
+        order.iter().map(|p| p.txid).collect()
+    }
+}
+
+// splitmix64, good enough to shuffle transactions and reproducible across platforms
+fn next_random(state: &mut u64) -> u64 {
+    *state = state.wrapping_add(0x9e3779b97f4a7c15);
+    let mut z = *state;
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d049bb133111eb);
+    z ^ (z >> 31)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn transfer(sender: &str, nonce: u64, fee: u64) -> MempoolTransaction {
+        MempoolTransaction {
+            sender: sender.to_string(),
+            nonce,
+            fee,
+            sponsor: None,
+            post_conditions: None,
+            payload: MempoolTxPayload::StxTransfer {
+                amount: 100,
+                recipient: "ST2CY5V39NHDPWSXMW9QDT3HC3GD6Q6XX4CFRK9AG".to_string(),
+            },
+        }
+    }
+
+    fn mined_txids(mempool: &mut Mempool) -> Vec<u64> {
+        let block = mempool.mine_block(|_| Some(()));
+        block.transactions.iter().map(|t| t.txid).collect()
+    }
+
+    #[test]
+    fn it_orders_transactions() {
+        let mut mempool = Mempool::new(MempoolOrdering::FeePriority);
+        mempool.submit(transfer("alice", 0, 100)).unwrap();
+        mempool.submit(transfer("bob", 0, 300)).unwrap();
+        mempool.submit(transfer("carol", 0, 200)).unwrap();
+        assert_eq!(mined_txids(&mut mempool), vec![1, 2, 0]);
+
+        let mut mempool = Mempool::new(MempoolOrdering::Fifo);
+        mempool.submit(transfer("alice", 0, 100)).unwrap();
+        mempool.submit(transfer("bob", 0, 300)).unwrap();
+        mempool.submit(transfer("carol", 0, 200)).unwrap();
+        assert_eq!(mined_txids(&mut mempool), vec![0, 1, 2]);
+
+        let random_block = |seed| {
+            let mut mempool = Mempool::new(MempoolOrdering::Random(seed));
+            for (i, sender) in ["alice", "bob", "carol", "dave"].iter().enumerate() {
+                mempool.submit(transfer(sender, 0, i as u64)).unwrap();
+            }
+            mined_txids(&mut mempool)
+        };
+        assert_eq!(random_block(42), random_block(42));
+        let mut txids = random_block(42);
+        txids.sort();
+        assert_eq!(txids, vec![0, 1, 2, 3]);
+    }
+
+    #[test]
+    fn it_handles_nonces() {
+        let mut mempool = Mempool::default();
+        // the nonce 1 is missing, the transaction with the nonce 2 stays in the mempool
+        mempool.submit(transfer("alice", 2, 500)).unwrap();
+        mempool.submit(transfer("alice", 0, 100)).unwrap();
+        assert_eq!(mined_txids(&mut mempool), vec![1]);
+        assert_eq!(mempool.get_nonce("alice"), 1);
+        assert_eq!(mempool.get_pending_transactions().len(), 1);
+
+        assert!(mempool.submit(transfer("alice", 0, 100)).is_err());
+
+        // once the gap is filled, both transactions are mined in the nonce order
+        mempool.submit(transfer("alice", 1, 100)).unwrap();
+        assert_eq!(mined_txids(&mut mempool), vec![2, 0]);
+        assert_eq!(mempool.get_nonce("alice"), 3);
+    }
+
+    #[test]
+    fn it_replaces_transactions() {
+        let mut mempool = Mempool::default();
+        mempool.submit(transfer("alice", 0, 100)).unwrap();
+        assert!(mempool.submit(transfer("alice", 0, 100)).is_err());
+
+        let admission = mempool.submit(transfer("alice", 0, 150)).unwrap();
+        assert_eq!(
+            admission,
+            MempoolAdmission {
+                txid: 1,
+                replaced: Some(0)
+            }
+        );
+        assert_eq!(mined_txids(&mut mempool), vec![1]);
+    }
+
+    #[test]
+    fn it_drops_transactions_that_can_not_be_executed() {
+        let mut mempool = Mempool::default();
+        mempool.submit(transfer("alice", 0, 100)).unwrap();
+        mempool.submit(transfer("alice", 1, 100)).unwrap();
+
+        let block = mempool.mine_block(|tx| if tx.nonce == 0 { None } else { Some(()) });
+        assert!(block.transactions.is_empty());
+        assert_eq!(block.dropped.len(), 1);
+        assert_eq!(mempool.get_nonce("alice"), 0);
+        assert_eq!(mempool.get_pending_transactions().len(), 1);
+    }
+}
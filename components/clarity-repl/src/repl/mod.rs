@@ -2,8 +2,11 @@ pub mod boot;
 pub mod datastore;
 pub mod diagnostic;
 pub mod interpreter;
+pub mod mempool;
+pub mod post_conditions;
 pub mod session;
 pub mod settings;
+pub mod stubs;
 pub mod tracer;
 
 #[cfg(not(feature = "wasm"))]
@@ -0,0 +1,649 @@
+use std::collections::{BTreeMap, HashMap};
+use std::io::{Read, Write};
+
+use clarity::codec::{read_next, write_next, Error as CodecError, StacksMessageCodec};
+use clarity::types::chainstate::StacksAddress;
+use clarity::vm::events::{FTEventType, NFTEventType, STXEventType, StacksTransactionEvent};
+use clarity::vm::types::{
+    AssetIdentifier, PrincipalData, QualifiedContractIdentifier, StandardPrincipalData, Value,
+};
+use clarity::vm::{ClarityName, ContractName};
+use serde::{Deserialize, Serialize};
+
+/// Encoding of an asset type identifier
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AssetInfo {
+    pub contract_address: StacksAddress,
+    pub contract_name: ContractName,
+    pub asset_name: ClarityName,
+}
+
+/// numeric wire-format ID of an asset info type variant
+#[repr(u8)]
+#[derive(Debug, Clone, PartialEq, Copy, Serialize, Deserialize)]
+pub enum AssetInfoID {
+    STX = 0,
+    FungibleAsset = 1,
+    NonfungibleAsset = 2,
+}
+
+impl AssetInfoID {
+    pub fn from_u8(b: u8) -> Option<AssetInfoID> {
+        match b {
+            0 => Some(AssetInfoID::STX),
+            1 => Some(AssetInfoID::FungibleAsset),
+            2 => Some(AssetInfoID::NonfungibleAsset),
+            _ => None,
+        }
+    }
+}
+
+#[repr(u8)]
+#[derive(Debug, Clone, PartialEq, Copy, Serialize, Deserialize)]
+pub enum FungibleConditionCode {
+    SentEq = 0x01,
+    SentGt = 0x02,
+    SentGe = 0x03,
+    SentLt = 0x04,
+    SentLe = 0x05,
+}
+
+impl FungibleConditionCode {
+    pub fn from_u8(b: u8) -> Option<FungibleConditionCode> {
+        match b {
+            0x01 => Some(FungibleConditionCode::SentEq),
+            0x02 => Some(FungibleConditionCode::SentGt),
+            0x03 => Some(FungibleConditionCode::SentGe),
+            0x04 => Some(FungibleConditionCode::SentLt),
+            0x05 => Some(FungibleConditionCode::SentLe),
+            _ => None,
+        }
+    }
+
+    pub fn check(&self, amount_sent_condition: u128, amount_sent: u128) -> bool {
+        match *self {
+            FungibleConditionCode::SentEq => amount_sent == amount_sent_condition,
+            FungibleConditionCode::SentGt => amount_sent > amount_sent_condition,
+            FungibleConditionCode::SentGe => amount_sent >= amount_sent_condition,
+            FungibleConditionCode::SentLt => amount_sent < amount_sent_condition,
+            FungibleConditionCode::SentLe => amount_sent <= amount_sent_condition,
+        }
+    }
+}
+
+#[repr(u8)]
+#[derive(Debug, Clone, PartialEq, Copy, Serialize, Deserialize)]
+pub enum NonfungibleConditionCode {
+    Sent = 0x10,
+    NotSent = 0x11,
+}
+
+impl NonfungibleConditionCode {
+    pub fn from_u8(b: u8) -> Option<NonfungibleConditionCode> {
+        match b {
+            0x10 => Some(NonfungibleConditionCode::Sent),
+            0x11 => Some(NonfungibleConditionCode::NotSent),
+            _ => None,
+        }
+    }
+
+    pub fn was_sent(nft_sent_condition: &Value, nfts_sent: &[Value]) -> bool {
+        for asset_sent in nfts_sent.iter() {
+            if *asset_sent == *nft_sent_condition {
+                // asset was sent, and is no longer owned by this principal
+                return true;
+            }
+        }
+        false
+    }
+
+    pub fn check(&self, nft_sent_condition: &Value, nfts_sent: &[Value]) -> bool {
+        match *self {
+            NonfungibleConditionCode::Sent => {
+                NonfungibleConditionCode::was_sent(nft_sent_condition, nfts_sent)
+            }
+            NonfungibleConditionCode::NotSent => {
+                !NonfungibleConditionCode::was_sent(nft_sent_condition, nfts_sent)
+            }
+        }
+    }
+}
+
+/// Post-condition principal.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum PostConditionPrincipal {
+    Origin,
+    Standard(StacksAddress),
+    Contract(StacksAddress, ContractName),
+}
+
+impl PostConditionPrincipal {
+    pub fn to_principal_data(&self, origin_principal: &PrincipalData) -> PrincipalData {
+        match *self {
+            PostConditionPrincipal::Origin => origin_principal.clone(),
+            PostConditionPrincipal::Standard(ref addr) => {
+                PrincipalData::Standard(StandardPrincipalData::from(*addr))
+            }
+            PostConditionPrincipal::Contract(ref addr, ref contract_name) => {
+                PrincipalData::Contract(QualifiedContractIdentifier::new(
+                    StandardPrincipalData::from(*addr),
+                    contract_name.clone(),
+                ))
+            }
+        }
+    }
+}
+
+#[repr(u8)]
+#[derive(Debug, Clone, PartialEq, Copy, Serialize, Deserialize)]
+pub enum PostConditionPrincipalID {
+    Origin = 0x01,
+    Standard = 0x02,
+    Contract = 0x03,
+}
+
+/// Post-condition on a transaction
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum TransactionPostCondition {
+    STX(PostConditionPrincipal, FungibleConditionCode, u64),
+    Fungible(
+        PostConditionPrincipal,
+        AssetInfo,
+        FungibleConditionCode,
+        u64,
+    ),
+    Nonfungible(
+        PostConditionPrincipal,
+        AssetInfo,
+        Value,
+        NonfungibleConditionCode,
+    ),
+}
+
+/// Post-condition modes for unspecified assets
+#[repr(u8)]
+#[derive(Debug, Clone, PartialEq, Copy, Serialize, Deserialize)]
+pub enum TransactionPostConditionMode {
+    Allow = 0x01, // allow any other changes not specified
+    Deny = 0x02,  // deny any other changes not specified
+}
+
+impl StacksMessageCodec for AssetInfo {
+    fn consensus_serialize<W: Write>(&self, fd: &mut W) -> Result<(), CodecError> {
+        write_next(fd, &self.contract_address)?;
+        write_next(fd, &self.contract_name)?;
+        write_next(fd, &self.asset_name)?;
+        Ok(())
+    }
+
+    fn consensus_deserialize<R: Read>(fd: &mut R) -> Result<AssetInfo, CodecError> {
+        let contract_address: StacksAddress = read_next(fd)?;
+        let contract_name: ContractName = read_next(fd)?;
+        let asset_name: ClarityName = read_next(fd)?;
+        Ok(AssetInfo {
+            contract_address,
+            contract_name,
+            asset_name,
+        })
+    }
+}
+
+impl StacksMessageCodec for PostConditionPrincipal {
+    fn consensus_serialize<W: Write>(&self, fd: &mut W) -> Result<(), CodecError> {
+        match *self {
+            PostConditionPrincipal::Origin => {
+                write_next(fd, &(PostConditionPrincipalID::Origin as u8))?;
+            }
+            PostConditionPrincipal::Standard(ref address) => {
+                write_next(fd, &(PostConditionPrincipalID::Standard as u8))?;
+                write_next(fd, address)?;
+            }
+            PostConditionPrincipal::Contract(ref address, ref contract_name) => {
+                write_next(fd, &(PostConditionPrincipalID::Contract as u8))?;
+                write_next(fd, address)?;
+                write_next(fd, contract_name)?;
+            }
+        }
+        Ok(())
+    }
+
+    fn consensus_deserialize<R: Read>(fd: &mut R) -> Result<PostConditionPrincipal, CodecError> {
+        let principal_id: u8 = read_next(fd)?;
+        let principal = match principal_id {
+            x if x == PostConditionPrincipalID::Origin as u8 => PostConditionPrincipal::Origin,
+            x if x == PostConditionPrincipalID::Standard as u8 => {
+                let addr: StacksAddress = read_next(fd)?;
+                PostConditionPrincipal::Standard(addr)
+            }
+            x if x == PostConditionPrincipalID::Contract as u8 => {
+                let addr: StacksAddress = read_next(fd)?;
+                let contract_name: ContractName = read_next(fd)?;
+                PostConditionPrincipal::Contract(addr, contract_name)
+            }
+            _ => {
+                return Err(CodecError::DeserializeError(format!(
+                    "Failed to parse transaction: unknown post condition principal ID {}",
+                    principal_id
+                )));
+            }
+        };
+        Ok(principal)
+    }
+}
+
+impl StacksMessageCodec for TransactionPostCondition {
+    fn consensus_serialize<W: Write>(&self, fd: &mut W) -> Result<(), CodecError> {
+        match *self {
+            TransactionPostCondition::STX(ref principal, ref fungible_condition, ref amount) => {
+                write_next(fd, &(AssetInfoID::STX as u8))?;
+                write_next(fd, principal)?;
+                write_next(fd, &(*fungible_condition as u8))?;
+                write_next(fd, amount)?;
+            }
+            TransactionPostCondition::Fungible(
+                ref principal,
+                ref asset_info,
+                ref fungible_condition,
+                ref amount,
+            ) => {
+                write_next(fd, &(AssetInfoID::FungibleAsset as u8))?;
+                write_next(fd, principal)?;
+                write_next(fd, asset_info)?;
+                write_next(fd, &(*fungible_condition as u8))?;
+                write_next(fd, amount)?;
+            }
+            TransactionPostCondition::Nonfungible(
+                ref principal,
+                ref asset_info,
+                ref asset_value,
+                ref nonfungible_condition,
+            ) => {
+                write_next(fd, &(AssetInfoID::NonfungibleAsset as u8))?;
+                write_next(fd, principal)?;
+                write_next(fd, asset_info)?;
+                write_next(fd, asset_value)?;
+                write_next(fd, &(*nonfungible_condition as u8))?;
+            }
+        };
+        Ok(())
+    }
+
+    fn consensus_deserialize<R: Read>(fd: &mut R) -> Result<TransactionPostCondition, CodecError> {
+        let asset_info_id: u8 = read_next(fd)?;
+        let postcond = match asset_info_id {
+            x if x == AssetInfoID::STX as u8 => {
+                let principal: PostConditionPrincipal = read_next(fd)?;
+                let condition_u8: u8 = read_next(fd)?;
+                let amount: u64 = read_next(fd)?;
+
+                let condition_code = FungibleConditionCode::from_u8(condition_u8).ok_or(
+                    CodecError::DeserializeError(format!(
+                    "Failed to parse transaction: Failed to parse STX fungible condition code {}",
+                    condition_u8
+                )),
+                )?;
+
+                TransactionPostCondition::STX(principal, condition_code, amount)
+            }
+            x if x == AssetInfoID::FungibleAsset as u8 => {
+                let principal: PostConditionPrincipal = read_next(fd)?;
+                let asset: AssetInfo = read_next(fd)?;
+                let condition_u8: u8 = read_next(fd)?;
+                let amount: u64 = read_next(fd)?;
+
+                let condition_code = FungibleConditionCode::from_u8(condition_u8).ok_or(
+                    CodecError::DeserializeError(format!(
+                    "Failed to parse transaction: Failed to parse FungibleAsset condition code {}",
+                    condition_u8
+                )),
+                )?;
+
+                TransactionPostCondition::Fungible(principal, asset, condition_code, amount)
+            }
+            x if x == AssetInfoID::NonfungibleAsset as u8 => {
+                let principal: PostConditionPrincipal = read_next(fd)?;
+                let asset: AssetInfo = read_next(fd)?;
+                let asset_value: Value = read_next(fd)?;
+                let condition_u8: u8 = read_next(fd)?;
+
+                let condition_code = NonfungibleConditionCode::from_u8(condition_u8)
+                    .ok_or(CodecError::DeserializeError(format!(
+                        "Failed to parse transaction: Failed to parse NonfungibleAsset condition code {}",
+                        condition_u8
+                    )))?;
+
+                TransactionPostCondition::Nonfungible(principal, asset, asset_value, condition_code)
+            }
+            _ => {
+                return Err(CodecError::DeserializeError(format!(
+                    "Failed to parse transaction: unknown asset info ID {}",
+                    asset_info_id
+                )));
+            }
+        };
+
+        Ok(postcond)
+    }
+}
+
+// Assets sent (transferred or burned) by each principal during a transaction,
+// equivalent to the asset map maintained by a stacks node
+#[derive(Debug, Default)]
+struct AssetsSent {
+    stx: HashMap<PrincipalData, u128>,
+    fungible: HashMap<PrincipalData, BTreeMap<String, u128>>,
+    nonfungible: HashMap<PrincipalData, BTreeMap<String, Vec<Value>>>,
+}
+
+impl AssetsSent {
+    fn from_events(events: &[StacksTransactionEvent]) -> Self {
+        let mut assets = AssetsSent::default();
+        for event in events.iter() {
+            match event {
+                StacksTransactionEvent::STXEvent(STXEventType::STXTransferEvent(data)) => {
+                    assets.add_stx(&data.sender, data.amount)
+                }
+                StacksTransactionEvent::STXEvent(STXEventType::STXBurnEvent(data)) => {
+                    assets.add_stx(&data.sender, data.amount)
+                }
+                StacksTransactionEvent::FTEvent(FTEventType::FTTransferEvent(data)) => {
+                    assets.add_fungible(&data.sender, &data.asset_identifier, data.amount)
+                }
+                StacksTransactionEvent::FTEvent(FTEventType::FTBurnEvent(data)) => {
+                    assets.add_fungible(&data.sender, &data.asset_identifier, data.amount)
+                }
+                StacksTransactionEvent::NFTEvent(NFTEventType::NFTTransferEvent(data)) => {
+                    assets.add_nonfungible(&data.sender, &data.asset_identifier, &data.value)
+                }
+                StacksTransactionEvent::NFTEvent(NFTEventType::NFTBurnEvent(data)) => {
+                    assets.add_nonfungible(&data.sender, &data.asset_identifier, &data.value)
+                }
+                _ => {}
+            }
+        }
+        assets
+    }
+
+    fn add_stx(&mut self, sender: &PrincipalData, amount: u128) {
+        *self.stx.entry(sender.clone()).or_insert(0) += amount;
+    }
+
+    fn add_fungible(&mut self, sender: &PrincipalData, asset: &AssetIdentifier, amount: u128) {
+        *self
+            .fungible
+            .entry(sender.clone())
+            .or_default()
+            .entry(asset.to_string())
+            .or_insert(0) += amount;
+    }
+
+    fn add_nonfungible(&mut self, sender: &PrincipalData, asset: &AssetIdentifier, value: &Value) {
+        self.nonfungible
+            .entry(sender.clone())
+            .or_default()
+            .entry(asset.to_string())
+            .or_default()
+            .push(value.clone());
+    }
+
+    fn get_stx(&self, principal: &PrincipalData) -> u128 {
+        self.stx.get(principal).copied().unwrap_or(0)
+    }
+
+    fn get_fungible(&self, principal: &PrincipalData, asset: &str) -> u128 {
+        match self.fungible.get(principal) {
+            Some(assets) => assets.get(asset).copied().unwrap_or(0),
+            None => 0,
+        }
+    }
+
+    fn get_nonfungible(&self, principal: &PrincipalData, asset: &str) -> &[Value] {
+        match self
+            .nonfungible
+            .get(principal)
+            .and_then(|assets| assets.get(asset))
+        {
+            Some(values) => values,
+            None => &[],
+        }
+    }
+}
+
+fn asset_info_to_string(asset_info: &AssetInfo) -> String {
+    let contract_identifier = QualifiedContractIdentifier::new(
+        StandardPrincipalData::from(asset_info.contract_address),
+        asset_info.contract_name.clone(),
+    );
+    format!("{}::{}", contract_identifier, asset_info.asset_name)
+}
+
+// Checks the post-conditions of a transaction against the asset events it emitted.
+// Just like a node, the transaction must be aborted if an error is returned:
+// every post-condition has to be met and, in deny mode, every asset sent has to be
+// covered by a post-condition
+pub fn check_post_conditions(
+    mode: TransactionPostConditionMode,
+    post_conditions: &[TransactionPostCondition],
+    origin: &PrincipalData,
+    events: &[StacksTransactionEvent],
+) -> Result<(), String> {
+    let assets_sent = AssetsSent::from_events(events);
+
+    let mut checked_stx: Vec<PrincipalData> = vec![];
+    let mut checked_fungible: Vec<(PrincipalData, String)> = vec![];
+    let mut checked_nonfungible: Vec<(PrincipalData, String, Value)> = vec![];
+
+    for post_condition in post_conditions.iter() {
+        match post_condition {
+            TransactionPostCondition::STX(principal, condition_code, amount) => {
+                let principal = principal.to_principal_data(origin);
+                let sent = assets_sent.get_stx(&principal);
+                if !condition_code.check(u128::from(*amount), sent) {
+                    return Err(format!(
+                        "post-condition check failure: {} sent {} uSTX, expected {:?} {}",
+                        principal, sent, condition_code, amount
+                    ));
+                }
+                checked_stx.push(principal);
+            }
+            TransactionPostCondition::Fungible(principal, asset_info, condition_code, amount) => {
+                let principal = principal.to_principal_data(origin);
+                let asset = asset_info_to_string(asset_info);
+                let sent = assets_sent.get_fungible(&principal, &asset);
+                if !condition_code.check(u128::from(*amount), sent) {
+                    return Err(format!(
+                        "post-condition check failure: {} sent {} {}, expected {:?} {}",
+                        principal, sent, asset, condition_code, amount
+                    ));
+                }
+                checked_fungible.push((principal, asset));
+            }
+            TransactionPostCondition::Nonfungible(principal, asset_info, value, condition_code) => {
+                let principal = principal.to_principal_data(origin);
+                let asset = asset_info_to_string(asset_info);
+                let sent = assets_sent.get_nonfungible(&principal, &asset);
+                if !condition_code.check(value, sent) {
+                    return Err(format!(
+                        "post-condition check failure: {} {} {}, expected {:?}",
+                        asset, value, principal, condition_code
+                    ));
+                }
+                checked_nonfungible.push((principal, asset, value.clone()));
+            }
+        }
+    }
+
+    if mode == TransactionPostConditionMode::Allow {
+        return Ok(());
+    }
+
+    for (principal, amount) in assets_sent.stx.iter() {
+        if *amount > 0 && !checked_stx.contains(principal) {
+            return Err(format!(
+                "post-condition check failure: {} sent {} uSTX without a post-condition (deny mode)",
+                principal, amount
+            ));
+        }
+    }
+    for (principal, assets) in assets_sent.fungible.iter() {
+        for (asset, amount) in assets.iter() {
+            if *amount > 0 && !checked_fungible.contains(&(principal.clone(), asset.clone())) {
+                return Err(format!(
+                    "post-condition check failure: {} sent {} {} without a post-condition (deny mode)",
+                    principal, amount, asset
+                ));
+            }
+        }
+    }
+    for (principal, assets) in assets_sent.nonfungible.iter() {
+        for (asset, values) in assets.iter() {
+            for value in values.iter() {
+                let key = (principal.clone(), asset.clone(), value.clone());
+                if !checked_nonfungible.contains(&key) {
+                    return Err(format!(
+                        "post-condition check failure: {} sent {} {} without a post-condition (deny mode)",
+                        principal, asset, value
+                    ));
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use clarity::vm::events::{FTTransferEventData, NFTTransferEventData, STXTransferEventData};
+    use clarity::vm::types::BuffData;
+
+    fn origin() -> PrincipalData {
+        PrincipalData::parse("ST1SJ3DTE5DN7X54YDH5D64R3BCB6A2AG2ZQ8YPD5").unwrap()
+    }
+
+    fn recipient() -> PrincipalData {
+        PrincipalData::parse("ST2CY5V39NHDPWSXMW9QDT3HC3GD6Q6XX4CFRK9AG").unwrap()
+    }
+
+    fn token_contract() -> QualifiedContractIdentifier {
+        QualifiedContractIdentifier::parse("ST1PQHQKV0RJXZFY1DGX8MNSNYVE3VGZJSRTPGZGM.token")
+            .unwrap()
+    }
+
+    fn asset_info(asset_name: &str) -> AssetInfo {
+        let contract = token_contract();
+        AssetInfo {
+            contract_address: StacksAddress::from(contract.issuer),
+            contract_name: contract.name,
+            asset_name: asset_name.into(),
+        }
+    }
+
+    fn asset_identifier(asset_name: &str) -> AssetIdentifier {
+        AssetIdentifier {
+            contract_identifier: token_contract(),
+            asset_name: asset_name.into(),
+        }
+    }
+
+    fn stx_transfer(amount: u128) -> StacksTransactionEvent {
+        StacksTransactionEvent::STXEvent(STXEventType::STXTransferEvent(STXTransferEventData {
+            sender: origin(),
+            recipient: recipient(),
+            amount,
+            memo: BuffData::empty(),
+        }))
+    }
+
+    fn ft_transfer(amount: u128) -> StacksTransactionEvent {
+        StacksTransactionEvent::FTEvent(FTEventType::FTTransferEvent(FTTransferEventData {
+            asset_identifier: asset_identifier("ft"),
+            sender: origin(),
+            recipient: recipient(),
+            amount,
+        }))
+    }
+
+    fn nft_transfer(id: u128) -> StacksTransactionEvent {
+        StacksTransactionEvent::NFTEvent(NFTEventType::NFTTransferEvent(NFTTransferEventData {
+            asset_identifier: asset_identifier("nft"),
+            sender: origin(),
+            recipient: recipient(),
+            value: Value::UInt(id),
+        }))
+    }
+
+    #[test]
+    fn it_checks_stx_post_conditions() {
+        let events = vec![stx_transfer(100)];
+        let post_condition = TransactionPostCondition::STX(
+            PostConditionPrincipal::Origin,
+            FungibleConditionCode::SentLe,
+            100,
+        );
+        let mode = TransactionPostConditionMode::Deny;
+        assert!(check_post_conditions(mode, &[post_condition], &origin(), &events).is_ok());
+
+        let post_condition = TransactionPostCondition::STX(
+            PostConditionPrincipal::Origin,
+            FungibleConditionCode::SentLt,
+            100,
+        );
+        assert!(check_post_conditions(mode, &[post_condition], &origin(), &events).is_err());
+    }
+
+    #[test]
+    fn it_checks_fungible_and_nonfungible_post_conditions() {
+        let events = vec![ft_transfer(10), nft_transfer(1)];
+        let post_conditions = vec![
+            TransactionPostCondition::Fungible(
+                PostConditionPrincipal::Origin,
+                asset_info("ft"),
+                FungibleConditionCode::SentEq,
+                10,
+            ),
+            TransactionPostCondition::Nonfungible(
+                PostConditionPrincipal::Origin,
+                asset_info("nft"),
+                Value::UInt(1),
+                NonfungibleConditionCode::Sent,
+            ),
+        ];
+        let mode = TransactionPostConditionMode::Deny;
+        assert!(check_post_conditions(mode, &post_conditions, &origin(), &events).is_ok());
+
+        let post_condition = TransactionPostCondition::Nonfungible(
+            PostConditionPrincipal::Origin,
+            asset_info("nft"),
+            Value::UInt(1),
+            NonfungibleConditionCode::NotSent,
+        );
+        let mode = TransactionPostConditionMode::Allow;
+        assert!(check_post_conditions(mode, &[post_condition], &origin(), &events).is_err());
+    }
+
+    #[test]
+    fn it_denies_unchecked_assets_in_deny_mode() {
+        let events = vec![stx_transfer(100), ft_transfer(10)];
+        let post_conditions = vec![TransactionPostCondition::STX(
+            PostConditionPrincipal::Origin,
+            FungibleConditionCode::SentEq,
+            100,
+        )];
+        assert!(check_post_conditions(
+            TransactionPostConditionMode::Allow,
+            &post_conditions,
+            &origin(),
+            &events
+        )
+        .is_ok());
+        assert!(check_post_conditions(
+            TransactionPostConditionMode::Deny,
+            &post_conditions,
+            &origin(),
+            &events
+        )
+        .is_err());
+    }
+}
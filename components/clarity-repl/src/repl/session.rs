@@ -1,5 +1,11 @@
 use super::boot::{STACKS_BOOT_CODE_MAINNET, STACKS_BOOT_CODE_TESTNET};
+use super::datastore::BlockContext;
 use super::diagnostic::output_diagnostic;
+use super::interpreter::InterpreterSnapshot;
+use super::mempool::{Mempool, MempoolAdmission, MempoolTransaction, MempoolTxPayload, MinedBlock};
+use super::post_conditions::{TransactionPostCondition, TransactionPostConditionMode};
+use super::stubs::StubCall;
+use super::tracer::{CallFrame, CallTracer};
 use super::{ClarityCodeSource, ClarityContract, ClarityInterpreter, ContractDeployer};
 use crate::analysis::coverage::TestCoverageReport;
 use crate::repl::Settings;
@@ -17,6 +23,7 @@ use clarity::vm::types::{
 };
 use clarity::vm::variables::NativeVariables;
 use clarity::vm::{ClarityVersion, CostSynthesis, EvalHook, EvaluationResult, ExecutionResult};
+use serde::{Deserialize, Serialize};
 use std::collections::{BTreeMap, HashMap};
 use std::fmt;
 use std::num::ParseIntError;
@@ -91,6 +98,28 @@ pub struct CostsReport {
     pub cost_result: CostSynthesis,
 }
 
+// Serializable state of a session, used to start new sessions from an already deployed chain
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct SessionSnapshot {
+    pub interpreter: InterpreterSnapshot,
+    pub epoch: String,
+    pub contracts: BTreeMap<String, BTreeMap<String, Vec<String>>>,
+}
+
+pub fn parse_epoch(epoch: &str) -> Option<StacksEpochId> {
+    match epoch {
+        "2.0" => Some(StacksEpochId::Epoch20),
+        "2.05" => Some(StacksEpochId::Epoch2_05),
+        "2.1" => Some(StacksEpochId::Epoch21),
+        "2.2" => Some(StacksEpochId::Epoch22),
+        "2.3" => Some(StacksEpochId::Epoch23),
+        "2.4" => Some(StacksEpochId::Epoch24),
+        "2.5" => Some(StacksEpochId::Epoch25),
+        "3.0" => Some(StacksEpochId::Epoch30),
+        _ => None,
+    }
+}
+
 #[derive(Clone, Debug)]
 pub struct Session {
     pub settings: SessionSettings,
@@ -103,6 +132,10 @@ pub struct Session {
     pub show_costs: bool,
     pub executed: Vec<String>,
     pub current_epoch: StacksEpochId,
+    pub mempool: Mempool,
+    // when enabled, the trace of the last contract call is kept, see `take_call_trace`
+    trace_calls: bool,
+    last_call_trace: Option<CallFrame>,
     keywords_reference: HashMap<String, String>,
 }
 
@@ -128,6 +161,9 @@ impl Session {
             settings,
             executed: Vec::new(),
             current_epoch: StacksEpochId::Epoch2_05,
+            mempool: Mempool::default(),
+            trace_calls: false,
+            last_call_trace: None,
             keywords_reference: clarity_keywords(),
         }
     }
@@ -204,6 +240,13 @@ impl Session {
             cmd if cmd.starts_with("::set_tx_sender") => {
                 self.parse_and_set_tx_sender(&mut output, cmd)
             }
+            cmd if cmd.starts_with("::set_tx_sponsor") => {
+                self.parse_and_set_tx_sponsor(&mut output, cmd)
+            }
+            cmd if cmd.starts_with("::set_tx_fee") => self.parse_and_set_tx_fee(&mut output, cmd),
+            cmd if cmd.starts_with("::stub") => self.parse_and_stub_function(&mut output, cmd),
+            cmd if cmd.starts_with("::unstub") => self.parse_and_remove_stub(&mut output, cmd),
+            cmd if cmd.starts_with("::calls") => self.display_stub_calls(&mut output),
             cmd if cmd.starts_with("::get_assets_maps") => self.get_accounts(&mut output),
             cmd if cmd.starts_with("::get_costs") => self.get_costs(&mut output, cmd),
             cmd if cmd.starts_with("::get_contracts") => self.get_contracts(&mut output),
@@ -233,6 +276,7 @@ impl Session {
             }
 
             snippet => {
+                self.charge_tx_fee_on_next_transaction();
                 let execution_result = self.run_snippet(&mut output, self.show_costs, snippet);
                 return (false, output, execution_result);
             }
@@ -511,6 +555,8 @@ impl Session {
                 spans: vec![],
                 suggestion: None,
             };
+            // the transaction doesn't run, its options must not apply to the next one
+            self.interpreter.clear_next_transaction();
             return Err(vec![diagnostic]);
         }
         let mut hooks: Vec<&mut dyn EvalHook> = Vec::new();
@@ -568,6 +614,15 @@ impl Session {
         let mut hooks: Vec<&mut dyn EvalHook> = vec![];
         let mut coverage = TestCoverageReport::new(test_name.clone());
         hooks.push(&mut coverage);
+        let mut call_tracer = match self.trace_calls {
+            true => QualifiedContractIdentifier::parse(&contract_id)
+                .ok()
+                .map(|contract_id| CallTracer::new(contract_id, method)),
+            false => None,
+        };
+        if let Some(call_tracer) = call_tracer.as_mut() {
+            hooks.push(call_tracer);
+        }
 
         let contract_call = format!(
             "(contract-call? '{} {} {})",
@@ -584,17 +639,13 @@ impl Session {
         };
 
         self.set_tx_sender(sender.into());
-        let execution = match self
+        let execution = self
             .interpreter
-            .run(&contract_call, &mut None, true, Some(hooks))
-        {
-            Ok(result) => result,
-            Err(e) => {
-                self.set_tx_sender(initial_tx_sender);
-                return Err(e);
-            }
-        };
+            .run(&contract_call, &mut None, true, Some(hooks));
         self.set_tx_sender(initial_tx_sender);
+        // the trace is also kept when the call fails, it helps understanding runtime errors
+        self.last_call_trace = call_tracer.map(CallTracer::into_trace);
+        let execution = execution?;
         self.coverage_reports.push(coverage);
 
         let contract_identifier = QualifiedContractIdentifier::parse(&contract_id).unwrap();
@@ -611,6 +662,160 @@ impl Session {
         Ok((execution, contract_identifier))
     }
 
+    // Like `invoke_contract_call`, but the post-conditions are checked against the assets
+    // sent by the call. Just like a node, the transaction is aborted if they are not met and
+    // its effects are discarded
+    pub fn invoke_contract_call_with_post_conditions(
+        &mut self,
+        contract: &str,
+        method: &str,
+        args: &[String],
+        sender: &str,
+        test_name: String,
+        post_condition_mode: TransactionPostConditionMode,
+        post_conditions: &[TransactionPostCondition],
+    ) -> Result<(ExecutionResult, QualifiedContractIdentifier), Vec<Diagnostic>> {
+        self.interpreter.set_next_transaction_post_conditions(Some((
+            post_condition_mode,
+            post_conditions.to_vec(),
+        )));
+        self.invoke_contract_call(contract, method, args, sender, test_name)
+    }
+
+    pub fn stx_transfer_with_post_conditions(
+        &mut self,
+        amount: u64,
+        recipient: &str,
+        post_condition_mode: TransactionPostConditionMode,
+        post_conditions: &[TransactionPostCondition],
+    ) -> Result<ExecutionResult, Vec<Diagnostic>> {
+        self.interpreter.set_next_transaction_post_conditions(Some((
+            post_condition_mode,
+            post_conditions.to_vec(),
+        )));
+        self.stx_transfer(amount, recipient)
+    }
+
+    // Like `invoke_contract_call`, but the transaction fee is paid by the sponsor, which is
+    // also exposed to the contract as `tx-sponsor?`
+    pub fn invoke_sponsored_contract_call(
+        &mut self,
+        contract: &str,
+        method: &str,
+        args: &[String],
+        sender: &str,
+        sponsor: &str,
+        test_name: String,
+    ) -> Result<(ExecutionResult, QualifiedContractIdentifier), Vec<Diagnostic>> {
+        let initial_tx_sponsor = self.interpreter.get_tx_sponsor();
+        if let Err(message) = self.set_tx_sponsor(Some(sponsor.to_string())) {
+            return Err(vec![Diagnostic {
+                level: Level::Error,
+                message,
+                spans: vec![],
+                suggestion: None,
+            }]);
+        }
+        self.charge_tx_fee_on_next_transaction();
+        let result = self.invoke_contract_call(contract, method, args, sender, test_name);
+        self.interpreter.set_tx_sponsor(initial_tx_sponsor);
+        result
+    }
+
+    // The configured fee is debited from the sponsor, or from the sender if the transaction
+    // isn't sponsored, once the next transaction runs. It's paid even if it is aborted
+    pub fn charge_tx_fee_on_next_transaction(&mut self) {
+        self.interpreter.set_next_transaction_fee(self.get_tx_fee());
+    }
+
+    pub fn submit_transaction(
+        &mut self,
+        tx: MempoolTransaction,
+    ) -> Result<MempoolAdmission, String> {
+        self.mempool.submit(tx)
+    }
+
+    // Mines the pending transactions of the mempool in a new block, in the order defined by
+    // the mempool ordering policy. Like on chain, the fee of a transaction aborted by a runtime
+    // error is paid and its nonce is consumed, but its effects are discarded
+    pub fn mine_mempool_block(
+        &mut self,
+        test_name: &str,
+        context: &BlockContext,
+    ) -> MinedBlock<Result<ExecutionResult, Vec<Diagnostic>>> {
+        let mut mempool = std::mem::take(&mut self.mempool);
+        let block = mempool.mine_block(|tx| self.execute_mempool_transaction(tx, test_name));
+        self.mempool = mempool;
+        self.advance_chain_tip_with_context(1, context);
+        block
+    }
+
+    // returns None if the transaction is invalid and can't be included in a block
+    fn execute_mempool_transaction(
+        &mut self,
+        tx: &MempoolTransaction,
+        test_name: &str,
+    ) -> Option<Result<ExecutionResult, Vec<Diagnostic>>> {
+        if PrincipalData::parse_standard_principal(&tx.sender).is_err() {
+            return None;
+        }
+        let sponsor = match &tx.sponsor {
+            Some(sponsor) => match PrincipalData::parse_standard_principal(sponsor) {
+                Ok(sponsor) => Some(sponsor),
+                Err(_) => return None,
+            },
+            None => None,
+        };
+        // the fee is paid by the sponsor of the transaction, or by its sender
+        let payer = tx.sponsor.as_ref().unwrap_or(&tx.sender);
+        let balance = self.interpreter.get_balance_for_account(payer, "STX");
+        if balance < tx.fee as u128 {
+            return None;
+        }
+
+        // a transaction aborted by a runtime error or by its post-conditions pays the fee
+        // too, and its other effects are discarded. the sponsor is set directly rather than
+        // with `invoke_sponsored_contract_call`, the fee is the one of the transaction
+        let initial_tx_sponsor = self.interpreter.get_tx_sponsor();
+        self.interpreter.set_tx_sponsor(sponsor);
+        self.interpreter.set_next_transaction_fee(Some(tx.fee));
+        let (post_condition_mode, post_conditions) = match &tx.post_conditions {
+            Some((mode, post_conditions)) => (*mode, post_conditions.as_slice()),
+            None => (TransactionPostConditionMode::Allow, [].as_slice()),
+        };
+        let result = match &tx.payload {
+            MempoolTxPayload::ContractCall {
+                contract,
+                method,
+                args,
+            } => self
+                .invoke_contract_call_with_post_conditions(
+                    contract,
+                    method,
+                    args,
+                    &tx.sender,
+                    test_name.to_string(),
+                    post_condition_mode,
+                    post_conditions,
+                )
+                .map(|(execution, _)| execution),
+            MempoolTxPayload::StxTransfer { amount, recipient } => {
+                let initial_tx_sender = self.get_tx_sender();
+                self.set_tx_sender(tx.sender.clone());
+                let result = self.stx_transfer_with_post_conditions(
+                    *amount,
+                    recipient,
+                    post_condition_mode,
+                    post_conditions,
+                );
+                self.set_tx_sender(initial_tx_sender);
+                result
+            }
+        };
+        self.interpreter.set_tx_sponsor(initial_tx_sponsor);
+        Some(result)
+    }
+
     pub fn eval(
         &mut self,
         snippet: String,
@@ -709,6 +914,17 @@ impl Session {
             "{}",
             help_colour.paint("::set_tx_sender <principal>\t\tSet tx-sender variable to principal")
         ));
+        output.push(format!(
+            "{}",
+            help_colour.paint(
+                "::set_tx_sponsor <principal> | none\tSet the sponsor paying the transaction fees"
+            )
+        ));
+        output.push(format!(
+            "{}",
+            help_colour
+                .paint("::set_tx_fee <amount> | none\t\tSet the fee charged for each transaction")
+        ));
         output.push(format!(
             "{}",
             help_colour.paint("::get_assets_maps\t\t\tGet assets maps for active accounts")
@@ -717,6 +933,21 @@ impl Session {
             "{}",
             help_colour.paint("::get_costs <expr>\t\t\tDisplay the cost analysis")
         ));
+        output.push(format!(
+            "{}",
+            help_colour.paint(
+                "::stub <contract> <function> <expr>\tReplace the body of a contract function"
+            )
+        ));
+        output.push(format!(
+            "{}",
+            help_colour
+                .paint("::unstub <contract> <function>\t\tRestore the body of a stubbed function")
+        ));
+        output.push(format!(
+            "{}",
+            help_colour.paint("::calls\t\t\t\tDisplay the calls made to the stubbed functions")
+        ));
         output.push(format!(
             "{}",
             help_colour.paint("::get_contracts\t\t\t\tGet contracts")
@@ -729,6 +960,14 @@ impl Session {
             "{}",
             help_colour.paint("::advance_chain_tip <count>\t\tSimulate mining of <count> blocks")
         ));
+        output.push(format!(
+            "{}",
+            help_colour.paint("  [time=<timestamp>] [burn_block_height=<height>]\tOverride the info of the first mined block")
+        ));
+        output.push(format!(
+            "{}",
+            help_colour.paint("  [miner=<address>] [vrf_seed=<0x...>]")
+        ));
         output.push(format!(
             "{}",
             help_colour.paint("::set_epoch <2.0> | <2.05> | <2.1>\tUpdate the current epoch")
@@ -777,10 +1016,10 @@ impl Session {
 
     #[cfg(feature = "cli")]
     fn parse_and_advance_chain_tip(&mut self, output: &mut Vec<String>, command: &str) {
-        let args: Vec<_> = command.split(' ').collect();
+        let args: Vec<_> = command.split_whitespace().collect();
 
-        if args.len() != 2 {
-            output.push(red!("Usage: ::advance_chain_tip <count>"));
+        if args.len() < 2 {
+            output.push(red!("Usage: ::advance_chain_tip <count> [time=<timestamp>] [burn_block_height=<height>] [miner=<address>] [vrf_seed=<0x...>]"));
             return;
         }
 
@@ -792,7 +1031,15 @@ impl Session {
             }
         };
 
-        let new_height = self.advance_chain_tip(count);
+        let context = match parse_block_context(&args[2..]) {
+            Ok(context) => context,
+            Err(message) => {
+                output.push(red!(message));
+                return;
+            }
+        };
+
+        let new_height = self.advance_chain_tip_with_context(count, &context);
         output.push(green!(format!(
             "{} blocks simulated, new height: {}",
             count, new_height
@@ -803,6 +1050,11 @@ impl Session {
         self.interpreter.advance_chain_tip(count)
     }
 
+    pub fn advance_chain_tip_with_context(&mut self, count: u32, context: &BlockContext) -> u32 {
+        self.interpreter
+            .advance_chain_tip_with_context(count, context)
+    }
+
     #[cfg(feature = "cli")]
     fn parse_and_set_tx_sender(&mut self, output: &mut Vec<String>, command: &str) {
         let args: Vec<_> = command.split(' ').collect();
@@ -834,6 +1086,211 @@ impl Session {
         self.interpreter.get_tx_sender().to_address()
     }
 
+    #[cfg(feature = "cli")]
+    fn parse_and_set_tx_sponsor(&mut self, output: &mut Vec<String>, command: &str) {
+        let args: Vec<_> = command.split(' ').collect();
+
+        if args.len() != 2 {
+            output.push(red!("Usage: ::set_tx_sponsor <address> | none"));
+            return;
+        }
+
+        if args[1] == "none" {
+            self.interpreter.set_tx_sponsor(None);
+            output.push(green!("tx-sponsor unset"));
+            return;
+        }
+
+        match self.set_tx_sponsor(Some(args[1].to_string())) {
+            Ok(()) => output.push(green!(format!("tx-sponsor switched to {}", args[1]))),
+            Err(_) => output.push(red!("Unable to parse the address")),
+        }
+    }
+
+    pub fn set_tx_sponsor(&mut self, address: Option<String>) -> Result<(), String> {
+        let tx_sponsor = match address {
+            Some(address) => Some(
+                PrincipalData::parse_standard_principal(&address)
+                    .map_err(|e| format!("invalid sponsor {}: {}", address, e))?,
+            ),
+            None => None,
+        };
+        self.interpreter.set_tx_sponsor(tx_sponsor);
+        Ok(())
+    }
+
+    pub fn get_tx_sponsor(&self) -> Option<String> {
+        self.interpreter
+            .get_tx_sponsor()
+            .map(|sponsor| sponsor.to_address())
+    }
+
+    #[cfg(feature = "cli")]
+    fn parse_and_set_tx_fee(&mut self, output: &mut Vec<String>, command: &str) {
+        let args: Vec<_> = command.split(' ').collect();
+
+        if args.len() != 2 {
+            output.push(red!("Usage: ::set_tx_fee <amount> | none"));
+            return;
+        }
+
+        if args[1] == "none" {
+            self.set_tx_fee(None);
+            output.push(green!("Transactions are free"));
+            return;
+        }
+
+        let fee: u64 = match args[1].parse() {
+            Ok(fee) => fee,
+            _ => {
+                output.push(red!("Unable to parse the fee"));
+                return;
+            }
+        };
+
+        self.set_tx_fee(Some(fee));
+        output.push(green!(format!("Transaction fee set to {} µSTX", fee)));
+    }
+
+    pub fn set_tx_fee(&mut self, fee: Option<u64>) {
+        self.interpreter.repl_settings.tx_fee = fee;
+    }
+
+    pub fn get_tx_fee(&self) -> Option<u64> {
+        self.interpreter.repl_settings.tx_fee
+    }
+
+    #[cfg(feature = "cli")]
+    fn parse_and_stub_function(&mut self, output: &mut Vec<String>, command: &str) {
+        let args: Vec<_> = command.splitn(4, ' ').collect();
+
+        if args.len() != 4 {
+            output.push(red!("Usage: ::stub <contract> <function> <expr>"));
+            return;
+        }
+
+        match self.stub_function(args[1], args[2], args[3]) {
+            Ok(()) => output.push(green!(format!("{}::{} stubbed", args[1], args[2]))),
+            Err(message) => output.push(red!(message)),
+        };
+    }
+
+    #[cfg(feature = "cli")]
+    fn parse_and_remove_stub(&mut self, output: &mut Vec<String>, command: &str) {
+        let args: Vec<_> = command.split_whitespace().collect();
+
+        if args.len() != 3 {
+            output.push(red!("Usage: ::unstub <contract> <function>"));
+            return;
+        }
+
+        match self.remove_stub(args[1], args[2]) {
+            Ok(()) => output.push(green!(format!("{}::{} restored", args[1], args[2]))),
+            Err(message) => output.push(red!(message)),
+        };
+    }
+
+    #[cfg(feature = "cli")]
+    fn display_stub_calls(&mut self, output: &mut Vec<String>) {
+        let calls = self.get_stub_calls();
+        if calls.is_empty() {
+            output.push("No calls to stubbed functions".to_string());
+        }
+        for call in calls {
+            let args: Vec<String> = call.args.iter().map(|a| a.to_string()).collect();
+            let caller = match &call.caller {
+                Some(caller) => caller.to_string(),
+                None => "-".to_string(),
+            };
+            output.push(format!(
+                "{}::{}({}) called by {}",
+                call.contract_id,
+                call.function,
+                args.join(", "),
+                caller
+            ));
+        }
+    }
+
+    // Replaces the body of a function of a deployed contract with `body`, a clarity
+    // expression evaluated in the context of the contract
+    pub fn stub_function(
+        &mut self,
+        contract: &str,
+        function: &str,
+        body: &str,
+    ) -> Result<(), String> {
+        let contract_id = self.parse_stubbed_contract_id(contract)?;
+        self.interpreter.stub_function(&contract_id, function, body)
+    }
+
+    // Restores the original body of a function stubbed with `stub_function`
+    pub fn remove_stub(&mut self, contract: &str, function: &str) -> Result<(), String> {
+        let contract_id = self.parse_stubbed_contract_id(contract)?;
+        self.interpreter.remove_stub(&contract_id, function)
+    }
+
+    fn parse_stubbed_contract_id(
+        &self,
+        contract: &str,
+    ) -> Result<QualifiedContractIdentifier, String> {
+        // Handle fully qualified contract_id and sugared syntax
+        let contract_id = if contract.starts_with('S') {
+            contract.to_string()
+        } else {
+            format!("{}.{}", self.get_tx_sender(), contract)
+        };
+        QualifiedContractIdentifier::parse(&contract_id)
+            .map_err(|e| format!("invalid contract identifier {}: {}", contract_id, e))
+    }
+
+    pub fn set_call_tracing(&mut self, enabled: bool) {
+        self.trace_calls = enabled;
+        if !enabled {
+            self.last_call_trace = None;
+        }
+    }
+
+    pub fn take_call_trace(&mut self) -> Option<CallFrame> {
+        self.last_call_trace.take()
+    }
+
+    // the asts are not part of the snapshot, the caller already has them from the deployment
+    pub fn snapshot(&self) -> SessionSnapshot {
+        SessionSnapshot {
+            interpreter: self.interpreter.snapshot(),
+            epoch: self.current_epoch.to_string(),
+            contracts: self.contracts.clone(),
+        }
+    }
+
+    pub fn restore_snapshot(&mut self, snapshot: SessionSnapshot) -> Result<(), String> {
+        let epoch = match parse_epoch(&snapshot.epoch) {
+            Some(epoch) => epoch,
+            None => return Err(format!("unknown epoch in snapshot: {}", snapshot.epoch)),
+        };
+        self.interpreter.restore_snapshot(snapshot.interpreter)?;
+        self.update_epoch(epoch);
+        // the state filled before the restore is replaced, not merged with the snapshot
+        self.asts
+            .retain(|contract_id, _| snapshot.contracts.contains_key(&contract_id.to_string()));
+        self.contracts = snapshot.contracts;
+        self.coverage_reports.clear();
+        self.costs_reports.clear();
+        self.last_call_trace = None;
+        self.mempool = Mempool::new(self.mempool.get_ordering());
+        Ok(())
+    }
+
+    // calls made to the stubbed functions, in the order of execution
+    pub fn get_stub_calls(&self) -> Vec<StubCall> {
+        self.interpreter.get_stub_calls().to_vec()
+    }
+
+    pub fn clear_stub_calls(&mut self) {
+        self.interpreter.take_stub_calls();
+    }
+
     #[cfg(feature = "cli")]
     fn get_block_height(&mut self, output: &mut Vec<String>) {
         let height = self.interpreter.get_block_height();
@@ -872,16 +1329,12 @@ impl Session {
     }
 
     pub fn set_epoch(&mut self, output: &mut Vec<String>, cmd: &str) {
-        let epoch = match cmd.split_once(' ').map(|(_, epoch)| epoch) {
-            Some("2.0") => StacksEpochId::Epoch20,
-            Some("2.05") => StacksEpochId::Epoch2_05,
-            Some("2.1") => StacksEpochId::Epoch21,
-            Some("2.2") => StacksEpochId::Epoch22,
-            Some("2.3") => StacksEpochId::Epoch23,
-            Some("2.4") => StacksEpochId::Epoch24,
-            Some("2.5") => StacksEpochId::Epoch25,
-            Some("3.0") => StacksEpochId::Epoch30,
-            _ => {
+        let epoch = match cmd
+            .split_once(' ')
+            .and_then(|(_, epoch)| parse_epoch(epoch))
+        {
+            Some(epoch) => epoch,
+            None => {
                 return output.push(red!(
                     "Usage: ::set_epoch 2.0 | 2.05 | 2.1 | 2.2 | 2.3 | 2.4 | 2.5 | 3.0"
                 ))
@@ -1191,6 +1644,47 @@ fn decode_hex(byte_string: &str) -> Result<Vec<u8>, DecodeHexError> {
     }
 }
 
+// Parses the `key=value` options of `::advance_chain_tip`
+#[cfg(feature = "cli")]
+fn parse_block_context(options: &[&str]) -> Result<BlockContext, String> {
+    let mut context = BlockContext::default();
+    for option in options.iter() {
+        let (key, value) = match option.split_once('=') {
+            Some(option) => option,
+            None => return Err(format!("Invalid option {}, expected <key>=<value>", option)),
+        };
+        match key {
+            "time" => {
+                let time = value
+                    .parse::<u64>()
+                    .map_err(|_| format!("Unable to parse time {}", value))?;
+                context.time = Some(time);
+            }
+            "burn_block_height" => {
+                let height = value
+                    .parse::<u32>()
+                    .map_err(|_| format!("Unable to parse burn block height {}", value))?;
+                context.burn_block_height = Some(height);
+            }
+            "miner" => {
+                let address = PrincipalData::parse_standard_principal(value)
+                    .map_err(|_| format!("Unable to parse miner address {}", value))?;
+                context.miner = Some(StacksAddress::from(address));
+            }
+            "vrf_seed" => {
+                let bytes = decode_hex(value)
+                    .map_err(|e| format!("Unable to parse vrf seed {}: {}", value, e))?;
+                let seed: [u8; 32] = bytes
+                    .try_into()
+                    .map_err(|_| "The vrf seed must be 32 bytes long".to_string())?;
+                context.vrf_seed = Some(clarity::types::chainstate::VRFSeed(seed));
+            }
+            _ => return Err(format!("Unknown option {}", key)),
+        }
+    }
+    Ok(context)
+}
+
 fn build_api_reference() -> HashMap<String, String> {
     let mut api_reference = HashMap::new();
     for func in NativeFunctions::ALL.iter() {
@@ -1244,6 +1738,7 @@ fn clarity_keywords() -> HashMap<String, String> {
 
 #[cfg(test)]
 mod tests {
+    use crate::repl::post_conditions::{FungibleConditionCode, PostConditionPrincipal};
     use crate::repl::{self, settings::Account};
 
     use super::*;
@@ -1457,6 +1952,624 @@ mod tests {
         );
         assert_eq!(session.handle_command("(at-block (unwrap-panic (get-block-info? id-header-hash u10000)) (contract-call? .contract get-x))").1[0], green!("u1"));
     }
+
+    #[test]
+    fn advance_chain_tip_with_block_context() {
+        let mut session = Session::new(SessionSettings::default());
+        let seed = format!("0x{}", "01".repeat(32));
+        let (_, output, _) = session.handle_command(&format!(
+            "::advance_chain_tip 2 time=1700000000 miner=ST1PQHQKV0RJXZFY1DGX8MNSNYVE3VGZJSRTPGZGM vrf_seed={}",
+            seed
+        ));
+        assert!(output[0].contains("2 blocks simulated"));
+        assert_eq!(
+            session
+                .handle_command("(get-block-info? time (- block-height u1))")
+                .1[0],
+            green!("(some u1700000000)")
+        );
+        assert_eq!(
+            session
+                .handle_command("(get-block-info? miner-address (- block-height u1))")
+                .1[0],
+            green!("(some ST1PQHQKV0RJXZFY1DGX8MNSNYVE3VGZJSRTPGZGM)")
+        );
+        assert_eq!(
+            session
+                .handle_command("(get-block-info? vrf-seed (- block-height u1))")
+                .1[0],
+            green!(format!("(some {})", seed))
+        );
+
+        // the following blocks are derived from the overridden one
+        session.advance_chain_tip(1);
+        assert_eq!(
+            session
+                .handle_command("(get-block-info? time (- block-height u1))")
+                .1[0],
+            green!("(some u1700001800)")
+        );
+
+        let (_, output, _) = session.handle_command("::advance_chain_tip 1 vrf_seed=0x01");
+        assert_eq!(output[0], red!("The vrf seed must be 32 bytes long"));
+    }
+
+    #[test]
+    fn invoke_contract_call_with_post_conditions() {
+        let sender = "ST1SJ3DTE5DN7X54YDH5D64R3BCB6A2AG2ZQ8YPD5";
+        let mut session = Session::new(SessionSettings {
+            initial_accounts: vec![Account {
+                address: sender.to_owned(),
+                balance: 1000000,
+                name: "wallet_1".to_owned(),
+            }],
+            ..Default::default()
+        });
+        session.start().expect("session could not start");
+
+        let snippet = "(define-public (send (amount uint))
+            (stx-transfer? amount tx-sender 'ST2CY5V39NHDPWSXMW9QDT3HC3GD6Q6XX4CFRK9AG))";
+        let contract = ClarityContract {
+            code_source: ClarityCodeSource::ContractInMemory(snippet.to_string()),
+            name: "sender".to_string(),
+            deployer: ContractDeployer::Address(sender.into()),
+            clarity_version: ClarityVersion::Clarity2,
+            epoch: repl::DEFAULT_EPOCH,
+        };
+        let _ = session.deploy_contract(&contract, None, false, None, &mut None);
+
+        let post_conditions = vec![TransactionPostCondition::STX(
+            PostConditionPrincipal::Origin,
+            FungibleConditionCode::SentLe,
+            100,
+        )];
+        let contract_id = format!("{}.sender", sender);
+
+        let result = session.invoke_contract_call_with_post_conditions(
+            &contract_id,
+            "send",
+            &["u100".into()],
+            sender,
+            "test".into(),
+            TransactionPostConditionMode::Deny,
+            &post_conditions,
+        );
+        assert!(result.is_ok());
+        let balance = session.interpreter.get_balance_for_account(sender, "STX");
+        assert_eq!(balance, 999900);
+
+        // the transfer exceeds the post-condition, the call is aborted and rolled back
+        let result = session.invoke_contract_call_with_post_conditions(
+            &contract_id,
+            "send",
+            &["u200".into()],
+            sender,
+            "test".into(),
+            TransactionPostConditionMode::Deny,
+            &post_conditions,
+        );
+        assert!(result.is_err());
+        let balance = session.interpreter.get_balance_for_account(sender, "STX");
+        assert_eq!(balance, 999900);
+        assert_eq!(
+            session
+                .handle_command(&format!("(stx-get-balance '{})", sender))
+                .1[0],
+            green!("u999900")
+        );
+
+        // in deny mode, every asset sent must be covered by a post-condition
+        let result = session.invoke_contract_call_with_post_conditions(
+            &contract_id,
+            "send",
+            &["u100".into()],
+            sender,
+            "test".into(),
+            TransactionPostConditionMode::Deny,
+            &[],
+        );
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn mine_mempool_block() {
+        let sender = "ST1SJ3DTE5DN7X54YDH5D64R3BCB6A2AG2ZQ8YPD5";
+        let mut session = Session::new(SessionSettings {
+            initial_accounts: vec![Account {
+                address: sender.to_owned(),
+                balance: 1000000,
+                name: "wallet_1".to_owned(),
+            }],
+            ..Default::default()
+        });
+        session.start().expect("session could not start");
+
+        let snippet = "(define-data-var last uint u0)
+            (define-read-only (get-last) (var-get last))
+            (define-public (set-last (v uint)) (ok (var-set last v)))
+            (define-public (fail) (begin (var-set last u999) (ok (unwrap-panic (element-at (list u1) u5)))))";
+        let contract = ClarityContract {
+            code_source: ClarityCodeSource::ContractInMemory(snippet.to_string()),
+            name: "mempool".to_string(),
+            deployer: ContractDeployer::Address(sender.into()),
+            clarity_version: ClarityVersion::Clarity2,
+            epoch: repl::DEFAULT_EPOCH,
+        };
+        let _ = session.deploy_contract(&contract, None, false, None, &mut None);
+        let contract_id = format!("{}.mempool", sender);
+
+        let call = |nonce, fee, method: &str, args: Vec<String>| MempoolTransaction {
+            sender: sender.to_string(),
+            nonce,
+            fee,
+            sponsor: None,
+            post_conditions: None,
+            payload: MempoolTxPayload::ContractCall {
+                contract: contract_id.clone(),
+                method: method.to_string(),
+                args,
+            },
+        };
+        session
+            .submit_transaction(call(0, 100, "set-last", vec!["u1".into()]))
+            .unwrap();
+        session
+            .submit_transaction(call(1, 100, "fail", vec![]))
+            .unwrap();
+        session
+            .submit_transaction(call(3, 100, "set-last", vec!["u3".into()]))
+            .unwrap();
+
+        let block_height = session.interpreter.get_block_height();
+        let block = session.mine_mempool_block("test", &BlockContext::default());
+        assert_eq!(session.interpreter.get_block_height(), block_height + 1);
+        assert_eq!(block.transactions.len(), 2);
+        assert!(block.transactions[0].result.is_ok());
+        // the runtime error aborts the transaction, its fee is paid and its nonce consumed
+        assert!(block.transactions[1].result.is_err());
+        assert_eq!(session.mempool.get_nonce(sender), 2);
+        assert_eq!(
+            session.interpreter.get_balance_for_account(sender, "STX"),
+            999800
+        );
+        assert_eq!(
+            session
+                .handle_command(&format!("(contract-call? '{} get-last)", contract_id))
+                .1[0],
+            green!("u1")
+        );
+
+        // the transaction with the nonce 3 waits for the nonce 2
+        assert_eq!(session.mempool.get_pending_transactions().len(), 1);
+    }
+
+    #[test]
+    fn mine_mempool_block_with_post_conditions() {
+        let sender = "ST1SJ3DTE5DN7X54YDH5D64R3BCB6A2AG2ZQ8YPD5";
+        let sponsor = "ST2CY5V39NHDPWSXMW9QDT3HC3GD6Q6XX4CFRK9AG";
+        let mut session = Session::new(SessionSettings {
+            initial_accounts: vec![
+                Account {
+                    address: sender.to_owned(),
+                    balance: 1000000,
+                    name: "wallet_1".to_owned(),
+                },
+                Account {
+                    address: sponsor.to_owned(),
+                    balance: 1000000,
+                    name: "wallet_2".to_owned(),
+                },
+            ],
+            ..Default::default()
+        });
+        session.start().expect("session could not start");
+
+        let snippet = "(define-public (send (amount uint))
+            (stx-transfer? amount tx-sender 'ST2JHG361ZXG51QTKY2NQCVBPPRRE2KZB1HR05NNC))";
+        let contract = ClarityContract {
+            code_source: ClarityCodeSource::ContractInMemory(snippet.to_string()),
+            name: "sender".to_string(),
+            deployer: ContractDeployer::Address(sender.into()),
+            clarity_version: ClarityVersion::Clarity2,
+            epoch: repl::DEFAULT_EPOCH,
+        };
+        let _ = session.deploy_contract(&contract, None, false, None, &mut None);
+
+        let post_conditions = Some((
+            TransactionPostConditionMode::Deny,
+            vec![TransactionPostCondition::STX(
+                PostConditionPrincipal::Origin,
+                FungibleConditionCode::SentLe,
+                100,
+            )],
+        ));
+        session
+            .submit_transaction(MempoolTransaction {
+                sender: sender.to_string(),
+                nonce: 0,
+                fee: 100,
+                sponsor: Some(sponsor.to_string()),
+                post_conditions,
+                payload: MempoolTxPayload::ContractCall {
+                    contract: format!("{}.sender", sender),
+                    method: "send".to_string(),
+                    args: vec!["u200".into()],
+                },
+            })
+            .unwrap();
+
+        let block = session.mine_mempool_block("test", &BlockContext::default());
+        assert_eq!(block.transactions.len(), 1);
+        // the transfer exceeds the post-condition, the transaction is aborted and rolled back
+        assert!(block.transactions[0].result.is_err());
+        assert_eq!(
+            session.interpreter.get_balance_for_account(sender, "STX"),
+            1000000
+        );
+        // the fee is paid by the sponsor
+        assert_eq!(
+            session.interpreter.get_balance_for_account(sponsor, "STX"),
+            999900
+        );
+    }
+
+    #[test]
+    fn stub_function() {
+        let sender = "ST1SJ3DTE5DN7X54YDH5D64R3BCB6A2AG2ZQ8YPD5";
+        let mut session = Session::new(SessionSettings::default());
+        session.start().expect("session could not start");
+
+        let oracle = "(define-read-only (get-price (asset (string-ascii 8))) (ok u100))";
+        let consumer = "(define-public (buy (amount uint))
+            (let ((price (try! (contract-call? .oracle get-price \"BTC\"))))
+                (ok (* price amount))))";
+        for (name, snippet) in [("oracle", oracle), ("consumer", consumer)] {
+            let contract = ClarityContract {
+                code_source: ClarityCodeSource::ContractInMemory(snippet.to_string()),
+                name: name.to_string(),
+                deployer: ContractDeployer::Address(sender.into()),
+                clarity_version: ClarityVersion::Clarity2,
+                epoch: repl::DEFAULT_EPOCH,
+            };
+            session
+                .deploy_contract(&contract, None, false, None, &mut None)
+                .unwrap();
+        }
+
+        let oracle_id = format!("{}.oracle", sender);
+        session
+            .stub_function(
+                &oracle_id,
+                "get-price",
+                "(ok (if (is-eq asset \"BTC\") u42 u0))",
+            )
+            .unwrap();
+        assert!(session
+            .stub_function(&oracle_id, "get-volume", "(ok u1)")
+            .is_err());
+
+        let (execution, _) = session
+            .invoke_contract_call(
+                &format!("{}.consumer", sender),
+                "buy",
+                &["u2".into()],
+                sender,
+                "test".into(),
+            )
+            .unwrap();
+        let res = match execution.result {
+            EvaluationResult::Contract(_) => unreachable!(),
+            EvaluationResult::Snippet(res) => res,
+        };
+        assert_eq!(res.result, Value::okay(Value::UInt(84)).unwrap());
+
+        let calls = session.get_stub_calls();
+        assert_eq!(calls.len(), 1);
+        assert_eq!(calls[0].function, "get-price");
+        assert_eq!(
+            calls[0].args,
+            vec![Value::string_ascii_from_bytes("BTC".as_bytes().to_vec()).unwrap()]
+        );
+        assert_eq!(
+            calls[0].caller,
+            Some(PrincipalData::parse(&format!("{}.consumer", sender)).unwrap())
+        );
+
+        session.remove_stub(&oracle_id, "get-price").unwrap();
+        let (execution, _) = session
+            .invoke_contract_call(
+                &format!("{}.consumer", sender),
+                "buy",
+                &["u2".into()],
+                sender,
+                "test".into(),
+            )
+            .unwrap();
+        let res = match execution.result {
+            EvaluationResult::Contract(_) => unreachable!(),
+            EvaluationResult::Snippet(res) => res,
+        };
+        assert_eq!(res.result, Value::okay(Value::UInt(200)).unwrap());
+    }
+
+    #[test]
+    fn trace_contract_call() {
+        let sender = "ST1SJ3DTE5DN7X54YDH5D64R3BCB6A2AG2ZQ8YPD5";
+        let mut session = Session::new(SessionSettings::default());
+        session.start().expect("session could not start");
+
+        let oracle = "(define-read-only (get-price (asset (string-ascii 8)))
+            (begin (print asset) (ok u100)))";
+        let consumer = "(define-private (double (n uint)) (* n u2))
+            (define-public (buy (amount uint))
+                (let ((price (try! (contract-call? .oracle get-price \"BTC\"))))
+                    (ok (double (* price amount)))))";
+        for (name, snippet) in [("oracle", oracle), ("consumer", consumer)] {
+            let contract = ClarityContract {
+                code_source: ClarityCodeSource::ContractInMemory(snippet.to_string()),
+                name: name.to_string(),
+                deployer: ContractDeployer::Address(sender.into()),
+                clarity_version: ClarityVersion::Clarity2,
+                epoch: repl::DEFAULT_EPOCH,
+            };
+            session
+                .deploy_contract(&contract, None, false, None, &mut None)
+                .unwrap();
+        }
+
+        let consumer_id = format!("{}.consumer", sender);
+        session
+            .invoke_contract_call(&consumer_id, "buy", &["u2".into()], sender, "test".into())
+            .unwrap();
+        assert!(session.take_call_trace().is_none());
+
+        session.set_call_tracing(true);
+        session
+            .invoke_contract_call(&consumer_id, "buy", &["u2".into()], sender, "test".into())
+            .unwrap();
+        let trace = session.take_call_trace().unwrap();
+        assert_eq!(trace.contract_id.to_string(), consumer_id);
+        assert_eq!(trace.function, "buy");
+        assert_eq!(trace.args, vec![Value::UInt(2)]);
+        assert_eq!(trace.result, Some(Value::okay(Value::UInt(400)).unwrap()));
+        assert!(trace.cost.runtime > 0);
+        assert!(trace.events.is_empty());
+
+        let calls = trace
+            .calls
+            .iter()
+            .map(|call| {
+                (
+                    call.function.as_str(),
+                    call.args.clone(),
+                    call.result.clone(),
+                )
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            calls,
+            vec![
+                (
+                    "get-price",
+                    vec![Value::string_ascii_from_bytes("BTC".as_bytes().to_vec()).unwrap()],
+                    Some(Value::okay(Value::UInt(100)).unwrap())
+                ),
+                ("double", vec![Value::UInt(200)], Some(Value::UInt(400))),
+            ]
+        );
+        assert_eq!(
+            trace.calls[0].contract_id.to_string(),
+            format!("{}.oracle", sender)
+        );
+        assert_eq!(trace.calls[0].events.len(), 1);
+        assert!(trace.calls[0].cost.runtime > 0);
+    }
+
+    #[test]
+    fn trace_contract_call_does_not_change_its_cost() {
+        let sender = "ST1SJ3DTE5DN7X54YDH5D64R3BCB6A2AG2ZQ8YPD5";
+        let mut session = Session::new(SessionSettings::default());
+        session.start().expect("session could not start");
+
+        let price_trait = "(define-trait price-trait
+            ((get-price ((string-ascii 8)) (response uint uint))))";
+        let oracle = "(impl-trait .price-trait.price-trait)
+            (define-read-only (get-price (asset (string-ascii 8))) (ok u100))";
+        let consumer = "(use-trait price-trait .price-trait.price-trait)
+            (define-public (buy (oracle <price-trait>) (amount uint))
+                (let ((price (try! (contract-call? oracle get-price \"BTC\"))))
+                    (ok (* price amount))))";
+        for (name, snippet) in [
+            ("price-trait", price_trait),
+            ("oracle", oracle),
+            ("consumer", consumer),
+        ] {
+            let contract = ClarityContract {
+                code_source: ClarityCodeSource::ContractInMemory(snippet.to_string()),
+                name: name.to_string(),
+                deployer: ContractDeployer::Address(sender.into()),
+                clarity_version: ClarityVersion::Clarity2,
+                epoch: repl::DEFAULT_EPOCH,
+            };
+            session
+                .deploy_contract(&contract, None, false, None, &mut None)
+                .unwrap();
+        }
+
+        let consumer_id = format!("{}.consumer", sender);
+        let args = [format!("'{}.oracle", sender), "u2".to_string()];
+        let (untraced, _) = session
+            .invoke_contract_call(&consumer_id, "buy", &args, sender, "test".into())
+            .unwrap();
+
+        session.set_call_tracing(true);
+        let (traced, _) = session
+            .invoke_contract_call(&consumer_id, "buy", &args, sender, "test".into())
+            .unwrap();
+        let trace = session.take_call_trace().unwrap();
+        assert_eq!(
+            trace.calls[0].contract_id.to_string(),
+            format!("{}.oracle", sender)
+        );
+
+        assert_eq!(untraced.cost.unwrap().total, traced.cost.unwrap().total);
+    }
+
+    #[test]
+    fn restore_session_snapshot() {
+        let sender = "ST1SJ3DTE5DN7X54YDH5D64R3BCB6A2AG2ZQ8YPD5";
+        let mut session = Session::new(SessionSettings::default());
+        session.start().expect("session could not start");
+        session.update_epoch(StacksEpochId::Epoch21);
+
+        let snippet = "(define-data-var count uint u0)
+            (define-public (incr) (ok (var-set count (+ (var-get count) u1))))
+            (define-read-only (get-count) (var-get count))";
+        let contract = ClarityContract {
+            code_source: ClarityCodeSource::ContractInMemory(snippet.to_string()),
+            name: "counter".to_string(),
+            deployer: ContractDeployer::Address(sender.into()),
+            clarity_version: ClarityVersion::Clarity2,
+            epoch: StacksEpochId::Epoch21,
+        };
+        session
+            .deploy_contract(&contract, None, false, None, &mut None)
+            .unwrap();
+        let contract_id = format!("{}.counter", sender);
+        session
+            .invoke_contract_call(&contract_id, "incr", &[], sender, "test".into())
+            .unwrap();
+        session.advance_chain_tip(3);
+
+        let snapshot = session.snapshot();
+        let serialized = serde_json::to_string(&snapshot).unwrap();
+        let snapshot: SessionSnapshot = serde_json::from_str(&serialized).unwrap();
+        assert_eq!(snapshot, session.snapshot());
+
+        // the contracts deployed before the restore are discarded
+        let mut restored = Session::new(SessionSettings::default());
+        restored.start().expect("session could not start");
+        restored.update_epoch(StacksEpochId::Epoch21);
+        let other = ClarityContract {
+            name: "other".to_string(),
+            ..contract.clone()
+        };
+        restored
+            .deploy_contract(&other, None, false, None, &mut None)
+            .unwrap();
+        restored.restore_snapshot(snapshot).unwrap();
+        assert_eq!(restored.current_epoch, StacksEpochId::Epoch21);
+        assert!(!restored
+            .asts
+            .keys()
+            .any(|contract_id| contract_id.name.as_str() == "other"));
+        assert_eq!(
+            restored.interpreter.get_block_height(),
+            session.interpreter.get_block_height()
+        );
+        assert_eq!(restored.contracts, session.contracts);
+
+        restored
+            .invoke_contract_call(&contract_id, "incr", &[], sender, "test".into())
+            .unwrap();
+        let (execution, _) = restored
+            .invoke_contract_call(&contract_id, "get-count", &[], sender, "test".into())
+            .unwrap();
+        let res = match execution.result {
+            EvaluationResult::Contract(_) => unreachable!(),
+            EvaluationResult::Snippet(res) => res,
+        };
+        assert_eq!(res.result, Value::UInt(2));
+    }
+
+    #[test]
+    fn invoke_sponsored_contract_call() {
+        let sender = "ST1SJ3DTE5DN7X54YDH5D64R3BCB6A2AG2ZQ8YPD5";
+        let sponsor = "ST2CY5V39NHDPWSXMW9QDT3HC3GD6Q6XX4CFRK9AG";
+        let mut settings = SessionSettings {
+            initial_accounts: vec![
+                Account {
+                    address: sender.to_owned(),
+                    balance: 1000000,
+                    name: "wallet_1".to_owned(),
+                },
+                Account {
+                    address: sponsor.to_owned(),
+                    balance: 1000000,
+                    name: "wallet_2".to_owned(),
+                },
+            ],
+            ..Default::default()
+        };
+        settings.repl_settings.tx_fee = Some(1000);
+        let mut session = Session::new(settings);
+        session.start().expect("session could not start");
+
+        let snippet = "(define-public (get-sponsor) (ok tx-sponsor?))";
+        let contract = ClarityContract {
+            code_source: ClarityCodeSource::ContractInMemory(snippet.to_string()),
+            name: "sponsored".to_string(),
+            deployer: ContractDeployer::Address(sender.into()),
+            clarity_version: ClarityVersion::Clarity2,
+            epoch: repl::DEFAULT_EPOCH,
+        };
+        let _ = session.deploy_contract(&contract, None, false, None, &mut None);
+        let contract_id = format!("{}.sponsored", sender);
+
+        let (execution, _) = session
+            .invoke_sponsored_contract_call(
+                &contract_id,
+                "get-sponsor",
+                &[],
+                sender,
+                sponsor,
+                "test".into(),
+            )
+            .unwrap();
+        let res = match execution.result {
+            EvaluationResult::Contract(_) => unreachable!(),
+            EvaluationResult::Snippet(res) => res,
+        };
+        let expected_sponsor = Value::Principal(PrincipalData::parse(sponsor).unwrap());
+        assert_eq!(
+            res.result,
+            Value::okay(Value::some(expected_sponsor).unwrap()).unwrap()
+        );
+        assert_eq!(session.get_tx_sponsor(), None);
+        assert_eq!(
+            session.interpreter.get_balance_for_account(sponsor, "STX"),
+            999000
+        );
+        assert_eq!(
+            session.interpreter.get_balance_for_account(sender, "STX"),
+            1000000
+        );
+
+        // without a sponsor, the sender pays the fee
+        session.charge_tx_fee_on_next_transaction();
+        let _ = session
+            .invoke_contract_call(&contract_id, "get-sponsor", &[], sender, "test".into())
+            .unwrap();
+        assert_eq!(
+            session.interpreter.get_balance_for_account(sender, "STX"),
+            999000
+        );
+
+        // an invalid sponsor is reported and the call doesn't run
+        let result = session.invoke_sponsored_contract_call(
+            &contract_id,
+            "get-sponsor",
+            &[],
+            sender,
+            "not-a-principal",
+            "test".into(),
+        );
+        assert!(result.is_err());
+        assert_eq!(
+            session.interpreter.get_balance_for_account(sender, "STX"),
+            999000
+        );
+    }
 }
 
 #[cfg(not(feature = "wasm"))]
@@ -56,11 +56,14 @@ pub struct Settings {
     pub analysis: analysis::Settings,
     pub clarity_wasm_mode: bool,
     pub show_timings: bool,
+    // fee charged to the sender (or the sponsor) of each transaction, simnet is free when unset
+    pub tx_fee: Option<u64>,
 }
 
 #[derive(Debug, Default, Clone, Deserialize, Serialize)]
 pub struct SettingsFile {
     pub analysis: Option<analysis::SettingsFile>,
+    pub tx_fee: Option<u64>,
 }
 
 impl From<SettingsFile> for Settings {
@@ -74,6 +77,7 @@ impl From<SettingsFile> for Settings {
             analysis,
             clarity_wasm_mode: false,
             show_timings: false,
+            tx_fee: file.tx_fee,
         }
     }
 }
@@ -0,0 +1,77 @@
+use std::collections::BTreeMap;
+
+use clarity::vm::contexts::{Environment, LocalContext};
+use clarity::vm::errors::Error;
+use clarity::vm::types::{PrincipalData, QualifiedContractIdentifier};
+use clarity::vm::{EvalHook, ExecutionResult, SymbolicExpression, Value};
+use serde::{Deserialize, Serialize};
+
+// the bodies of the stubs are identified by the id of their expression, taken from the top of
+// the range so that they can't collide with the ids of the contracts expressions
+pub const STUB_EXPR_ID_BASE: u64 = u64::MAX - u32::MAX as u64;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FunctionStub {
+    pub contract_id: QualifiedContractIdentifier,
+    pub function: String,
+    pub arguments: Vec<String>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct StubCall {
+    pub contract_id: QualifiedContractIdentifier,
+    pub function: String,
+    pub caller: Option<PrincipalData>,
+    pub args: Vec<Value>,
+}
+
+// Records the calls to the stubbed functions, along with the value of their arguments
+pub struct StubCallRecorder {
+    stubs: BTreeMap<u64, FunctionStub>,
+    pub calls: Vec<StubCall>,
+}
+
+impl StubCallRecorder {
+    pub fn new(stubs: BTreeMap<u64, FunctionStub>) -> Self {
+        Self {
+            stubs,
+            calls: vec![],
+        }
+    }
+}
+
+impl EvalHook for StubCallRecorder {
+    fn will_begin_eval(
+        &mut self,
+        env: &mut Environment,
+        context: &LocalContext,
+        expr: &SymbolicExpression,
+    ) {
+        let stub = match self.stubs.get(&expr.id) {
+            Some(stub) if stub.contract_id == env.contract_context.contract_identifier => stub,
+            _ => return,
+        };
+        let args = stub
+            .arguments
+            .iter()
+            .filter_map(|name| context.lookup_variable(name).cloned())
+            .collect();
+        self.calls.push(StubCall {
+            contract_id: stub.contract_id.clone(),
+            function: stub.function.clone(),
+            caller: env.caller.clone(),
+            args,
+        });
+    }
+
+    fn did_finish_eval(
+        &mut self,
+        _env: &mut Environment,
+        _context: &LocalContext,
+        _expr: &SymbolicExpression,
+        _res: &Result<Value, Error>,
+    ) {
+    }
+
+    fn did_complete(&mut self, _result: Result<&mut ExecutionResult, String>) {}
+}
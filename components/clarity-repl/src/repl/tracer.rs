@@ -1,14 +1,18 @@
 use crate::repl::interpreter::Txid;
 use crate::repl::tracer::SymbolicExpressionType::List;
+use crate::utils::{serialize_event, value_to_string};
+use clarity::vm::costs::ExecutionCost;
 use clarity::vm::errors::Error;
+use clarity::vm::events::StacksTransactionEvent;
 use clarity::vm::functions::define::DefineFunctions;
 use clarity::vm::functions::NativeFunctions;
+use clarity::vm::types::{PrincipalData, QualifiedContractIdentifier};
 use clarity::vm::{
     contexts::{Environment, LocalContext},
     types::Value,
     EvalHook, SymbolicExpression, SymbolicExpressionType,
 };
-use clarity::vm::{eval, ClarityVersion, EvaluationResult};
+use clarity::vm::{ClarityVersion, EvaluationResult};
 
 pub struct Tracer {
     stack: Vec<u64>,
@@ -65,10 +69,9 @@ impl EvalHook for Tracer {
 
                             let mut lines = Vec::new();
                             if args[0].match_atom().is_some() {
-                                let callee = if let Ok(value) = eval(&args[0], env, context) {
-                                    value.to_string()
-                                } else {
-                                    "?".to_string()
+                                let callee = match resolve_callee(&args[0], context) {
+                                    Some(contract_id) => contract_id.to_string(),
+                                    None => "?".to_string(),
                                 };
                                 lines.push(format!(
                                     "{}│ {}",
@@ -223,3 +226,301 @@ impl EvalHook for Tracer {
         }
     }
 }
+
+// A call made during the execution of a contract call, with its arguments, its result, the
+// events it emitted, its cost and the calls it made itself
+#[derive(Debug, Clone)]
+pub struct CallFrame {
+    pub contract_id: QualifiedContractIdentifier,
+    pub function: String,
+    pub args: Vec<Value>,
+    pub result: Option<Value>,
+    pub error: Option<String>,
+    pub events: Vec<StacksTransactionEvent>,
+    pub cost: ExecutionCost,
+    pub calls: Vec<CallFrame>,
+}
+
+impl CallFrame {
+    pub fn new(contract_id: QualifiedContractIdentifier, function: &str) -> Self {
+        CallFrame {
+            contract_id,
+            function: function.to_string(),
+            args: vec![],
+            result: None,
+            error: None,
+            events: vec![],
+            cost: ExecutionCost::zero(),
+            calls: vec![],
+        }
+    }
+
+    pub fn to_json(&self) -> serde_json::Value {
+        json!({
+            "contract_id": self.contract_id.to_string(),
+            "function": self.function,
+            "args": self.args.iter().map(value_to_string).collect::<Vec<_>>(),
+            "result": self.result.as_ref().map(value_to_string),
+            "error": self.error,
+            "events": self.events.iter().map(serialize_event).collect::<Vec<_>>(),
+            "cost": self.cost,
+            "calls": self.calls.iter().map(|call| call.to_json()).collect::<Vec<_>>(),
+        })
+    }
+}
+
+struct TracedCall {
+    // id of the call expression, and contract in which it is evaluated
+    expr_id: u64,
+    caller: QualifiedContractIdentifier,
+    arg_ids: Vec<u64>,
+    // set once the arguments are evaluated and the function is actually called
+    start_cost: Option<ExecutionCost>,
+    frame: CallFrame,
+}
+
+// Builds the structured trace of a contract call. The root call itself isn't evaluated as an
+// expression, its arguments are read from the context of the function once it's called
+pub struct CallTracer {
+    root: CallFrame,
+    root_args_collected: bool,
+    stack: Vec<TracedCall>,
+    nb_of_emitted_events: usize,
+}
+
+impl CallTracer {
+    pub fn new(contract_id: QualifiedContractIdentifier, function: &str) -> CallTracer {
+        CallTracer {
+            root: CallFrame::new(contract_id, function),
+            root_args_collected: false,
+            stack: vec![],
+            nb_of_emitted_events: 0,
+        }
+    }
+
+    pub fn into_trace(mut self) -> CallFrame {
+        // the calls interrupted by a runtime error are kept without result
+        while let Some(call) = self.stack.pop() {
+            if call.start_cost.is_some() {
+                self.current_frame().calls.push(call.frame);
+            }
+        }
+        self.root
+    }
+
+    // innermost call being executed
+    fn current_frame(&mut self) -> &mut CallFrame {
+        let root = &mut self.root;
+        match self
+            .stack
+            .iter_mut()
+            .rev()
+            .find(|call| call.start_cost.is_some())
+        {
+            Some(call) => &mut call.frame,
+            None => root,
+        }
+    }
+
+    fn collect_events(&mut self, env: &Environment) {
+        let emitted_events = env
+            .global_context
+            .event_batches
+            .iter()
+            .flat_map(|b| b.events.iter().cloned())
+            .collect::<Vec<_>>();
+        // the events of a call returning an err are rolled back
+        if emitted_events.len() < self.nb_of_emitted_events {
+            self.nb_of_emitted_events = emitted_events.len();
+        }
+        let nb_of_emitted_events = emitted_events.len();
+        let new_events = emitted_events
+            .into_iter()
+            .skip(self.nb_of_emitted_events)
+            .collect::<Vec<_>>();
+        self.current_frame().events.extend(new_events);
+        self.nb_of_emitted_events = nb_of_emitted_events;
+    }
+}
+
+// the callee of a contract-call? is a literal principal, or a trait reference bound in the
+// context. it's looked up rather than evaluated, evaluating it would charge its cost twice
+fn resolve_callee(
+    expr: &SymbolicExpression,
+    context: &LocalContext,
+) -> Option<QualifiedContractIdentifier> {
+    match &expr.expr {
+        SymbolicExpressionType::LiteralValue(Value::Principal(PrincipalData::Contract(
+            contract_id,
+        ))) => Some(contract_id.clone()),
+        SymbolicExpressionType::Atom(name) => match context.lookup_callable_contract(name) {
+            Some(callable) => Some(callable.contract_identifier.clone()),
+            None => match context.lookup_variable(name) {
+                Some(Value::CallableContract(callable)) => {
+                    Some(callable.contract_identifier.clone())
+                }
+                Some(Value::Principal(PrincipalData::Contract(contract_id))) => {
+                    Some(contract_id.clone())
+                }
+                _ => None,
+            },
+        },
+        _ => None,
+    }
+}
+
+fn cost_difference(end: &ExecutionCost, start: &ExecutionCost) -> ExecutionCost {
+    ExecutionCost {
+        write_length: end.write_length.saturating_sub(start.write_length),
+        write_count: end.write_count.saturating_sub(start.write_count),
+        read_length: end.read_length.saturating_sub(start.read_length),
+        read_count: end.read_count.saturating_sub(start.read_count),
+        runtime: end.runtime.saturating_sub(start.runtime),
+    }
+}
+
+impl EvalHook for CallTracer {
+    fn will_begin_eval(
+        &mut self,
+        env: &mut Environment,
+        context: &LocalContext,
+        expr: &SymbolicExpression,
+    ) {
+        if !self.root_args_collected
+            && self.stack.is_empty()
+            && env.contract_context.contract_identifier == self.root.contract_id
+        {
+            self.root_args_collected = true;
+            let function = env
+                .contract_context
+                .functions
+                .iter()
+                .find(|(name, _)| name.as_str() == self.root.function);
+            if let Some((_, function)) = function {
+                self.root.args = function
+                    .get_arguments()
+                    .iter()
+                    .filter_map(|name| context.lookup_variable(name).cloned())
+                    .collect();
+            }
+        }
+
+        let list = match &expr.expr {
+            List(list) => list,
+            _ => return,
+        };
+        let (function_name, args) = match list.split_first() {
+            Some((function_name, args)) => match function_name.match_atom() {
+                Some(function_name) => (function_name, args),
+                None => return,
+            },
+            None => return,
+        };
+        if DefineFunctions::lookup_by_name(function_name).is_some() {
+            return;
+        }
+
+        let (contract_id, function, args) = match NativeFunctions::lookup_by_name_at_version(
+            function_name,
+            &ClarityVersion::latest(),
+        ) {
+            Some(NativeFunctions::ContractCall) => {
+                if args.len() < 2 {
+                    return;
+                }
+                let contract_id = match resolve_callee(&args[0], context) {
+                    Some(contract_id) => contract_id,
+                    None => return,
+                };
+                let function = match args[1].match_atom() {
+                    Some(function) => function.to_string(),
+                    None => return,
+                };
+                (contract_id, function, &args[2..])
+            }
+            Some(_) => return,
+            None if env.contract_context.functions.contains_key(function_name) => (
+                env.contract_context.contract_identifier.clone(),
+                function_name.to_string(),
+                args,
+            ),
+            None => return,
+        };
+
+        let start_cost = match args.is_empty() {
+            true => Some(env.global_context.cost_track.get_total()),
+            false => None,
+        };
+        self.stack.push(TracedCall {
+            expr_id: expr.id,
+            caller: env.contract_context.contract_identifier.clone(),
+            arg_ids: args.iter().map(|arg| arg.id).collect(),
+            start_cost,
+            frame: CallFrame::new(contract_id, &function),
+        });
+    }
+
+    fn did_finish_eval(
+        &mut self,
+        env: &mut Environment,
+        _context: &LocalContext,
+        expr: &SymbolicExpression,
+        res: &Result<Value, Error>,
+    ) {
+        self.collect_events(env);
+        let caller = &env.contract_context.contract_identifier;
+
+        let returning = matches!(
+            self.stack.last(),
+            Some(call) if &call.caller == caller && call.expr_id == expr.id
+        );
+        if returning {
+            if let Some(mut call) = self.stack.pop() {
+                // without a start cost, an argument could not be evaluated and the function
+                // was not called
+                if let Some(start_cost) = &call.start_cost {
+                    call.frame.cost =
+                        cost_difference(&env.global_context.cost_track.get_total(), start_cost);
+                    match res {
+                        Ok(value) => call.frame.result = Some(value.clone()),
+                        Err(e) => call.frame.error = Some(format!("{:?}", e)),
+                    }
+                    self.current_frame().calls.push(call.frame);
+                }
+            }
+        }
+
+        // the returned value can be the argument of a pending call
+        if let Some(call) = self.stack.last_mut() {
+            if &call.caller != caller
+                || call.start_cost.is_some()
+                || call.arg_ids.get(call.frame.args.len()) != Some(&expr.id)
+            {
+                return;
+            }
+            if let Ok(value) = res {
+                call.frame.args.push(value.clone());
+            }
+            if call.frame.args.len() == call.arg_ids.len() {
+                call.start_cost = Some(env.global_context.cost_track.get_total());
+            }
+        }
+    }
+
+    fn did_complete(
+        &mut self,
+        result: core::result::Result<&mut clarity::vm::ExecutionResult, String>,
+    ) {
+        match result {
+            Ok(result) => {
+                if let EvaluationResult::Snippet(snippet_result) = &result.result {
+                    self.root.result = Some(snippet_result.result.clone());
+                }
+                if let Some(cost) = &result.cost {
+                    self.root.cost = cost.total.clone();
+                }
+            }
+            Err(e) => self.root.error = Some(e),
+        }
+    }
+}
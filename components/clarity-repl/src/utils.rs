@@ -1,6 +1,10 @@
 use ::clarity::vm::events::{FTEventType, NFTEventType, STXEventType, StacksTransactionEvent};
+use clarity::types::StacksEpochId;
 use clarity::util::hash;
-use clarity::vm::Value;
+use clarity::vm::ast::build_ast_with_diagnostics;
+use clarity::vm::diagnostic::Level;
+use clarity::vm::types::{QualifiedContractIdentifier, TupleData};
+use clarity::vm::{ClarityVersion, SymbolicExpression, SymbolicExpressionType, Value};
 use std::fmt::Write;
 
 pub fn serialize_event(event: &StacksTransactionEvent) -> serde_json::Value {
@@ -124,12 +128,125 @@ pub fn value_to_string(value: &Value) -> String {
     }
 }
 
+// Like `value_to_string`, but the value is formatted as clarity code, so that it can be passed
+// as an argument of a contract call
+pub fn value_to_clarity_code(value: &Value) -> String {
+    use clarity::vm::types::SequenceData;
+
+    match value {
+        Value::Principal(principal_data) => format!("'{}", principal_data),
+        Value::Tuple(tup_data) => {
+            let data: Vec<String> = tup_data
+                .data_map
+                .iter()
+                .map(|(name, value)| format!("{}: {}", &**name, value_to_clarity_code(value)))
+                .collect();
+            format!("{{{}}}", data.join(", "))
+        }
+        Value::Optional(opt_data) => match opt_data.data {
+            Some(ref x) => format!("(some {})", value_to_clarity_code(x)),
+            None => "none".to_string(),
+        },
+        Value::Response(res_data) => match res_data.committed {
+            true => format!("(ok {})", value_to_clarity_code(&res_data.data)),
+            false => format!("(err {})", value_to_clarity_code(&res_data.data)),
+        },
+        Value::Sequence(SequenceData::List(list_data)) => {
+            let data: Vec<String> = list_data.data.iter().map(value_to_clarity_code).collect();
+            format!("(list {})", data.join(" "))
+        }
+        _ => value_to_string(value),
+    }
+}
+
+// Parses a literal Clarity value, such as `(some {a: u1, b: "foo"})`, without evaluating it.
+// Only values and the `some`, `ok`, `err`, `list` and `tuple` constructors are accepted, the
+// principals must be fully qualified
+pub fn parse_clarity_value(snippet: &str, epoch: StacksEpochId) -> Result<Value, String> {
+    let (ast, diagnostics, success) = build_ast_with_diagnostics(
+        &QualifiedContractIdentifier::transient(),
+        snippet,
+        &mut (),
+        ClarityVersion::default_for_epoch(epoch),
+        epoch,
+    );
+    if !success {
+        let errors: Vec<String> = diagnostics
+            .iter()
+            .filter(|d| d.level == Level::Error)
+            .map(|d| d.message.clone())
+            .collect();
+        return Err(format!(
+            "unable to parse {}: {}",
+            snippet,
+            errors.join(", ")
+        ));
+    }
+    match &ast.expressions[..] {
+        [expression] => expression_to_value(expression),
+        _ => Err(format!("{} is not a single value", snippet)),
+    }
+}
+
+fn expression_to_value(expression: &SymbolicExpression) -> Result<Value, String> {
+    match &expression.expr {
+        SymbolicExpressionType::LiteralValue(value) | SymbolicExpressionType::AtomValue(value) => {
+            Ok(value.clone())
+        }
+        SymbolicExpressionType::Atom(name) => match name.as_str() {
+            "true" => Ok(Value::Bool(true)),
+            "false" => Ok(Value::Bool(false)),
+            "none" => Ok(Value::none()),
+            _ => Err(format!("{} is not a literal value", name)),
+        },
+        SymbolicExpressionType::List(list) => {
+            let (constructor, args) = match list.split_first() {
+                Some((constructor, args)) => (constructor, args),
+                None => return Err("() is not a literal value".to_string()),
+            };
+            let constructor = match constructor.match_atom() {
+                Some(name) => name.as_str(),
+                None => return Err(format!("{} is not a literal value", expression)),
+            };
+            let value = match (constructor, args) {
+                ("some", [arg]) => Value::some(expression_to_value(arg)?),
+                ("ok", [arg]) => Value::okay(expression_to_value(arg)?),
+                ("err", [arg]) => Value::error(expression_to_value(arg)?),
+                ("list", args) => Value::cons_list_unsanitized(
+                    args.iter()
+                        .map(expression_to_value)
+                        .collect::<Result<_, _>>()?,
+                ),
+                ("tuple", args) => {
+                    let mut data = vec![];
+                    for arg in args {
+                        match arg.match_list() {
+                            Some([name, value]) => match name.match_atom() {
+                                Some(name) => {
+                                    data.push((name.clone(), expression_to_value(value)?))
+                                }
+                                None => return Err(format!("invalid tuple key {}", name)),
+                            },
+                            _ => return Err(format!("invalid tuple entry {}", arg)),
+                        }
+                    }
+                    TupleData::from_data(data).map(Value::Tuple)
+                }
+                _ => return Err(format!("{} is not a literal value", expression)),
+            };
+            value.map_err(|e| format!("invalid value {}: {}", expression, e))
+        }
+        _ => Err(format!("{} is not a literal value", expression)),
+    }
+}
+
 #[cfg(test)]
 mod tests {
-    use super::value_to_string;
+    use super::{parse_clarity_value, value_to_clarity_code, value_to_string};
+    use crate::repl::DEFAULT_EPOCH;
     use clarity::vm::types::{
-        ASCIIData, CharType, ListData, ListTypeData, OptionalData, ResponseData, SequenceData,
-        SequencedValue, TupleData, TypeSignature, UTF8Data, NONE,
+        ASCIIData, CharType, ListData, ListTypeData, OptionalData, PrincipalData, ResponseData,
+        SequenceData, SequencedValue, TupleData, TypeSignature, UTF8Data, NONE,
     };
     use clarity::vm::{ClarityName, Value};
     use std::convert::TryFrom;
@@ -200,4 +317,61 @@ mod tests {
         })));
         assert_eq!(s, "[-321]");
     }
+
+    #[test]
+    fn test_parse_clarity_value() {
+        let value = parse_clarity_value(
+            "(some {a: u1, b: \"foo\", c: (list 1 2), d: (ok true)})",
+            DEFAULT_EPOCH,
+        )
+        .unwrap();
+        assert_eq!(
+            value_to_string(&value),
+            "(some {a: u1, b: \"foo\", c: [1, 2], d: (ok true)})"
+        );
+        let contract = "ST1SJ3DTE5DN7X54YDH5D64R3BCB6A2AG2ZQ8YPD5.nft";
+        assert_eq!(
+            parse_clarity_value(&format!("'{}", contract), DEFAULT_EPOCH).unwrap(),
+            Value::Principal(PrincipalData::parse(contract).unwrap())
+        );
+        assert_eq!(
+            parse_clarity_value("none", DEFAULT_EPOCH).unwrap(),
+            Value::none()
+        );
+        assert_eq!(
+            parse_clarity_value("(err 0x01)", DEFAULT_EPOCH).unwrap(),
+            Value::error(Value::buff_from(vec![1]).unwrap()).unwrap()
+        );
+
+        // expressions are not evaluated
+        assert!(parse_clarity_value("(+ u1 u2)", DEFAULT_EPOCH).is_err());
+        assert!(parse_clarity_value("(var-set x u1)", DEFAULT_EPOCH).is_err());
+        assert!(parse_clarity_value("block-height", DEFAULT_EPOCH).is_err());
+        assert!(parse_clarity_value("u1 u2", DEFAULT_EPOCH).is_err());
+        assert!(parse_clarity_value("(list u1 1)", DEFAULT_EPOCH).is_err());
+    }
+
+    #[test]
+    fn test_value_to_clarity_code() {
+        let principal = PrincipalData::parse("ST1PQHQKV0RJXZFY1DGX8MNSNYVE3VGZJSRTPGZGM").unwrap();
+        let tuple = Value::Tuple(
+            TupleData::from_data(vec![
+                ("owner".into(), Value::Principal(principal)),
+                (
+                    "amounts".into(),
+                    Value::cons_list_unsanitized(vec![Value::UInt(1), Value::UInt(2)]).unwrap(),
+                ),
+            ])
+            .unwrap(),
+        );
+        let code = value_to_clarity_code(&Value::some(tuple).unwrap());
+        assert_eq!(
+            code,
+            "(some {amounts: (list u1 u2), owner: 'ST1PQHQKV0RJXZFY1DGX8MNSNYVE3VGZJSRTPGZGM})"
+        );
+        assert_eq!(
+            parse_clarity_value(&code, DEFAULT_EPOCH).map(|v| value_to_clarity_code(&v)),
+            Ok(code)
+        );
+    }
 }
@@ -1,10 +1,14 @@
 import * as vscode from "vscode";
 import { ExtensionContext } from "vscode";
-import { LanguageClientOptions } from "vscode-languageclient";
+import { LanguageClientOptions, Middleware } from "vscode-languageclient";
 
 import { initVFS } from "./customVFS";
 import { InsightsViewProvider } from "./Views/InsightsViewProvider";
-import type { InsightsData, LanguageClient } from "./types";
+import type {
+  FunctionCallArguments,
+  InsightsData,
+  LanguageClient,
+} from "./types";
 
 const { window, workspace } = vscode;
 
@@ -22,14 +26,63 @@ function getConfig() {
   return config;
 }
 
+/* code lens commands */
+// the commands are executed by the language server, the arguments of the
+// function are prompted before sending them
+async function promptFunctionArgs(
+  fnCall: FunctionCallArguments,
+): Promise<FunctionCallArguments | null> {
+  const args: string[] = [];
+  for (const param of fnCall.parameters) {
+    const arg = await window.showInputBox({
+      title: `${fnCall.functionName}: ${param.name}`,
+      prompt: `Clarity value of type ${param.type}`,
+      ignoreFocusOut: true,
+    });
+    if (arg === undefined) return null;
+    args.push(arg);
+  }
+  return { ...fnCall, args };
+}
+
+const executeCommand: Middleware["executeCommand"] = async (
+  command,
+  args,
+  next,
+) => {
+  if (
+    command !== "clarity.runFunction" &&
+    command !== "clarity.debugFunction"
+  ) {
+    return next(command, args);
+  }
+  const fnCallWithArgs = await promptFunctionArgs(args[0]);
+  if (!fnCallWithArgs) return;
+  const result = await next(command, [fnCallWithArgs]);
+  if (command === "clarity.debugFunction" && result) {
+    // the server returns the launch configuration of the debugger
+    const debugConfig = result as vscode.DebugConfiguration;
+    if (debugConfig.manifest.includes("://")) {
+      debugConfig.manifest = vscode.Uri.parse(debugConfig.manifest).fsPath;
+    }
+    vscode.debug.startDebugging(undefined, debugConfig);
+  }
+  return result;
+};
+
 export const clientOpts: LanguageClientOptions = {
-  documentSelector: [{ language: "clarity" }, { language: "toml" }],
+  documentSelector: [
+    { language: "clarity" },
+    { language: "toml" },
+    { language: "yaml", pattern: "**/deployments/*.yaml" },
+  ],
   diagnosticCollectionName: "Clarity LSP",
   progressOnInitialization: false,
   traceOutputChannel: vscode.window.createOutputChannel(
     "Clarity Language Server Trace",
   ),
   initializationOptions: JSON.stringify(getConfig()),
+  middleware: { executeCommand },
 };
 
 export async function initClient(
@@ -67,6 +120,11 @@ export async function initClient(
       "completionIncludeNativePlaceholders",
       "hover",
       "documentSymbols",
+      "workspaceSymbols",
+      "callHierarchy",
+      "codeLens",
+      "costHints",
+      "costWarningThreshold",
       "goToDefinition",
     ].forEach((k) => {
       if (newConfig[k] !== config[k]) requireReload = true;
@@ -24,3 +24,12 @@ export type CursorMove = {
 export type FileEvent = {
   path: string;
 };
+
+export type FunctionCallArguments = {
+  manifest: string;
+  contractId: string;
+  functionName: string;
+  parameters: { name: string; type: string }[];
+  args?: string[];
+  sender?: string;
+};
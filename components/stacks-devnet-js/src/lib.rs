@@ -200,6 +200,7 @@ impl StacksDevnet {
                             &mut None,
                             Some(log_tx),
                             false,
+                            false,
                             stacks_network::Context::empty(),
                             termination_tx,
                             None,
@@ -819,6 +820,8 @@ impl StacksDevnet {
                     slots,
                     btc_address,
                     auto_extend: Some(false),
+                    increase_at_cycle: None,
+                    increase_slots: None,
                 });
             }
             overrides.pox_stacking_orders = Some(stacking_orders);
@@ -1,9 +1,15 @@
 use super::ChainsCoordinatorCommand;
 
+use crate::control_server::start_control_server;
 use crate::event::DevnetEvent;
+use crate::event::ProtocolDeployingData;
 use crate::event::ServiceStatusData;
 use crate::event::Status;
+use crate::explorer::{start_lite_explorer, LiteExplorer};
+use crate::faults::{start_fault_proxy, FaultInjector};
 use crate::orchestrator::ServicesMapHosts;
+use crate::scenario::{load_scenarios, ScenarioRunner};
+use crate::snapshot::{CoordinatorState, SnapshotConfig, SnapshotMetadata};
 
 use base58::FromBase58;
 use chainhook_sdk::chainhooks::types::ChainhookConfig;
@@ -23,12 +29,12 @@ use clarinet_deployments::onchain::{
     apply_on_chain_deployment, DeploymentCommand, DeploymentEvent,
 };
 use clarinet_deployments::types::DeploymentSpecification;
-use clarinet_files::PoxStackingOrder;
 use clarinet_files::DEFAULT_FIRST_BURN_HEADER_HEIGHT;
 use clarinet_files::{self, AccountConfig, DevnetConfig, NetworkManifest, ProjectManifest};
+use clarinet_files::{PoxDelegationOrder, PoxStackingOrder};
 use clarity_repl::clarity::address::AddressHashMode;
 use clarity_repl::clarity::util::hash::{hex_bytes, Hash160};
-use clarity_repl::clarity::vm::types::{BuffData, SequenceData, TupleData};
+use clarity_repl::clarity::vm::types::{BuffData, PrincipalData, SequenceData, TupleData};
 use clarity_repl::clarity::vm::ClarityName;
 use clarity_repl::clarity::vm::Value as ClarityValue;
 use clarity_repl::codec;
@@ -62,6 +68,8 @@ pub struct DevnetEventObserverConfig {
     pub deployment_fee_rate: u64,
     pub services_map_hosts: ServicesMapHosts,
     pub network_manifest: NetworkManifest,
+    pub snapshot: Option<SnapshotConfig>,
+    pub faults: Option<Arc<FaultInjector>>,
 }
 
 impl DevnetEventObserverConfig {
@@ -147,6 +155,8 @@ impl DevnetEventObserverConfig {
             deployment_fee_rate: network_manifest.network.deployment_fee_rate,
             services_map_hosts,
             network_manifest,
+            snapshot: None,
+            faults: None,
         }
     }
 }
@@ -169,28 +179,59 @@ pub async fn start_chains_coordinator(
     let (deployment_commands_tx, deployments_command_rx) = channel();
     let (deployment_events_tx, deployment_events_rx) = channel();
 
-    // Set-up the background task in charge of serializing / signing / publishing the contracts.
-    // This tasks can take several seconds to minutes, depending on the complexity of the project.
-    // We start this process as soon as possible, as a background task.
-    // This thread becomes dormant once the encoding is done, and proceed to the actual deployment once
-    // the event DeploymentCommand::Start is received.
-    perform_protocol_deployment(
-        &config.network_manifest,
-        &config.deployment,
-        deployment_events_tx,
-        deployments_command_rx,
-        Some(config.consolidated_bitcoin_rpc_url()),
-        Some(config.consolidated_stacks_rpc_url()),
-    );
-
-    // Set-up the background task in charge of monitoring contracts deployments.
-    // This thread will be waiting and relaying events emitted by the thread above.
-    relay_devnet_protocol_deployment(
-        deployment_events_rx,
-        &devnet_event_tx,
-        Some(mining_command_tx.clone()),
-        &boot_completed,
-    );
+    let scenario_runner = match load_scenarios(&config.manifest, &config.devnet_config) {
+        Ok(scenarios) if scenarios.is_empty() => None,
+        Ok(scenarios) => Some(ScenarioRunner::new(&config, scenarios)),
+        Err(e) => {
+            let _ = devnet_event_tx.send(DevnetEvent::error(format!(
+                "unable to load scenarios: {}",
+                e
+            )));
+            None
+        }
+    };
+
+    match config.snapshot.as_ref().and_then(|s| s.restored.clone()) {
+        Some(metadata) => {
+            // The chain state restored from the snapshot already includes the deployment
+            should_deploy_protocol = false;
+            boot_completed.store(true, Ordering::SeqCst);
+            wait_for_restored_snapshot(
+                metadata,
+                config.consolidated_stacks_rpc_url(),
+                &devnet_event_tx,
+                mining_command_tx.clone(),
+                scenario_runner,
+            );
+        }
+        None => {
+            // Set-up the background task in charge of serializing / signing / publishing the contracts.
+            // This tasks can take several seconds to minutes, depending on the complexity of the project.
+            // We start this process as soon as possible, as a background task.
+            // This thread becomes dormant once the encoding is done, and proceed to the actual deployment once
+            // the event DeploymentCommand::Start is received.
+            perform_protocol_deployment(
+                &config.network_manifest,
+                &config.deployment,
+                deployment_events_tx,
+                deployments_command_rx,
+                Some(config.consolidated_bitcoin_rpc_url()),
+                Some(config.consolidated_stacks_rpc_url()),
+            );
+
+            // Set-up the background task in charge of monitoring contracts deployments.
+            // This thread will be waiting and relaying events emitted by the thread above.
+            relay_devnet_protocol_deployment(
+                deployment_events_rx,
+                &devnet_event_tx,
+                Some(mining_command_tx.clone()),
+                &boot_completed,
+                config.snapshot.clone(),
+                config.consolidated_stacks_rpc_url(),
+                scenario_runner,
+            );
+        }
+    }
 
     if let Some(ref hooks) = config.event_observer_config.chainhook_config {
         let chainhooks_count = hooks.bitcoin_chainhooks.len() + hooks.stacks_chainhooks.len();
@@ -229,6 +270,54 @@ pub async fn start_chains_coordinator(
         hiro_system_kit::nestable_block_on(future);
     });
 
+    // Spawn control server
+    let devnet_event_tx_moved = devnet_event_tx.clone();
+    let control_server_config = config.clone();
+    let control_server_mining_command_tx = mining_command_tx.clone();
+    let _ = hiro_system_kit::thread_named("Control server").spawn(move || {
+        let future = start_control_server(
+            &control_server_config,
+            control_server_mining_command_tx,
+            &devnet_event_tx_moved,
+        );
+        if let Err(e) = hiro_system_kit::nestable_block_on(future) {
+            let _ = devnet_event_tx_moved.send(DevnetEvent::warning(e));
+        }
+    });
+
+    // Spawn fault injection proxy
+    if config.devnet_config.enable_fault_injection {
+        if let Some(faults) = config.faults.clone() {
+            let devnet_event_tx_moved = devnet_event_tx.clone();
+            let fault_proxy_config = config.clone();
+            let _ = hiro_system_kit::thread_named("Fault proxy").spawn(move || {
+                let future = start_fault_proxy(&fault_proxy_config, faults, &devnet_event_tx_moved);
+                if let Err(e) = hiro_system_kit::nestable_block_on(future) {
+                    let _ = devnet_event_tx_moved.send(DevnetEvent::warning(e));
+                }
+            });
+        }
+    }
+
+    // Spawn built-in explorer
+    let explorer = match config.devnet_config.enable_lite_explorer {
+        true => {
+            let explorer = Arc::new(LiteExplorer::new(&config));
+            let explorer_moved = explorer.clone();
+            let explorer_port = config.devnet_config.lite_explorer_port;
+            let devnet_event_tx_moved = devnet_event_tx.clone();
+            let _ = hiro_system_kit::thread_named("Explorer server").spawn(move || {
+                let future =
+                    start_lite_explorer(explorer_moved, explorer_port, &devnet_event_tx_moved);
+                if let Err(e) = hiro_system_kit::nestable_block_on(future) {
+                    let _ = devnet_event_tx_moved.send(DevnetEvent::warning(e));
+                }
+            });
+            Some(explorer)
+        }
+        false => None,
+    };
+
     // Loop over events being received from Bitcoin and Stacks,
     // and orchestrate the 2 chains + protocol.
     let mut deployment_commands_tx = Some(deployment_commands_tx);
@@ -350,6 +439,11 @@ pub async fn start_chains_coordinator(
 
                 let known_tip = match &chain_event {
                     StacksChainEvent::ChainUpdatedWithBlocks(block) => {
+                        if let Some(ref explorer) = explorer {
+                            for update in block.new_blocks.iter() {
+                                explorer.index_block(&update.block);
+                            }
+                        }
                         match block.new_blocks.last() {
                             Some(known_tip) => known_tip.clone(),
                             None => unreachable!(),
@@ -454,6 +548,9 @@ pub async fn start_chains_coordinator(
                         }
                     }
                     for tx in transactions.into_iter() {
+                        if let Some(ref explorer) = explorer {
+                            explorer.index_mempool_transaction(&tx);
+                        }
                         let _ = devnet_event_tx.send(DevnetEvent::MempoolAdmission(tx));
                     }
                 }
@@ -495,31 +592,66 @@ pub fn relay_devnet_protocol_deployment(
     devnet_event_tx: &Sender<DevnetEvent>,
     bitcoin_mining_tx: Option<Sender<BitcoinMiningCommand>>,
     boot_completed: &Arc<AtomicBool>,
+    snapshot: Option<SnapshotConfig>,
+    stacks_rpc_url: String,
+    scenario_runner: Option<ScenarioRunner>,
 ) {
     let devnet_event_tx = devnet_event_tx.clone();
     let boot_completed = boot_completed.clone();
     let _ = hiro_system_kit::thread_named("Deployment monitoring").spawn(move || {
+        let mut coordinator_state = CoordinatorState::default();
         loop {
             let event = match deployment_events_rx.recv() {
                 Ok(event) => event,
                 Err(_e) => break,
             };
             match event {
-                DeploymentEvent::TransactionUpdate(tracker) => {
-                    if let TransactionStatus::Error(ref message) = tracker.status {
+                DeploymentEvent::TransactionUpdate(tracker) => match tracker.status {
+                    TransactionStatus::Error(ref message) => {
                         let _ = devnet_event_tx.send(DevnetEvent::error(message.into()));
                         break;
                     }
-                }
+                    TransactionStatus::Confirmed => {
+                        coordinator_state
+                            .deployed_contracts
+                            .push(tracker.name.clone());
+                        let _ = devnet_event_tx.send(DevnetEvent::ProtocolDeployingProgress(
+                            ProtocolDeployingData {
+                                new_contracts_deployed: vec![tracker.name],
+                            },
+                        ));
+                    }
+                    _ => {}
+                },
                 DeploymentEvent::Interrupted(_) => {
                     // Terminate
                     break;
                 }
                 DeploymentEvent::DeploymentCompleted => {
+                    if let Some(ref snapshot) = snapshot {
+                        match snapshot.save(&stacks_rpc_url, std::mem::take(&mut coordinator_state))
+                        {
+                            Ok(metadata) => {
+                                let _ = devnet_event_tx.send(DevnetEvent::success(format!(
+                                    "Snapshot '{}' saved at stacks block #{}",
+                                    metadata.name, metadata.stacks_block_height
+                                )));
+                            }
+                            Err(e) => {
+                                let _ = devnet_event_tx.send(DevnetEvent::warning(format!(
+                                    "unable to save snapshot '{}': {}",
+                                    snapshot.name, e
+                                )));
+                            }
+                        }
+                    }
                     boot_completed.store(true, Ordering::SeqCst);
                     if let Some(bitcoin_mining_tx) = bitcoin_mining_tx {
                         let _ = devnet_event_tx.send(DevnetEvent::BootCompleted(bitcoin_mining_tx));
                     }
+                    if let Some(scenario_runner) = scenario_runner {
+                        scenario_runner.run(&devnet_event_tx);
+                    }
                     break;
                 }
             }
@@ -527,6 +659,46 @@ pub fn relay_devnet_protocol_deployment(
     });
 }
 
+// Waits for the stacks-node to reach the tip saved in the snapshot before unlocking the devnet
+fn wait_for_restored_snapshot(
+    metadata: SnapshotMetadata,
+    stacks_rpc_url: String,
+    devnet_event_tx: &Sender<DevnetEvent>,
+    bitcoin_mining_tx: Sender<BitcoinMiningCommand>,
+    scenario_runner: Option<ScenarioRunner>,
+) {
+    let devnet_event_tx = devnet_event_tx.clone();
+    let _ = hiro_system_kit::thread_named("Snapshot monitoring").spawn(move || {
+        let stacks_rpc = StacksRpc::new(&stacks_rpc_url);
+        for _ in 0..120 {
+            if let Ok(info) = stacks_rpc.get_info() {
+                if info.stacks_tip_height >= metadata.stacks_block_height {
+                    // the contracts were deployed when the snapshot was taken
+                    let _ = devnet_event_tx.send(DevnetEvent::ProtocolDeployingProgress(
+                        ProtocolDeployingData {
+                            new_contracts_deployed: metadata.coordinator.deployed_contracts.clone(),
+                        },
+                    ));
+                    let _ = devnet_event_tx.send(DevnetEvent::info(format!(
+                        "Devnet restored from snapshot '{}' (stacks block #{})",
+                        metadata.name, info.stacks_tip_height
+                    )));
+                    let _ = devnet_event_tx.send(DevnetEvent::BootCompleted(bitcoin_mining_tx));
+                    if let Some(scenario_runner) = scenario_runner {
+                        scenario_runner.run(&devnet_event_tx);
+                    }
+                    return;
+                }
+            }
+            std::thread::sleep(Duration::from_secs(1));
+        }
+        let _ = devnet_event_tx.send(DevnetEvent::warning(format!(
+            "stacks-node did not reach the tip of snapshot '{}'",
+            metadata.name
+        )));
+    });
+}
+
 fn should_publish_stacking_orders(
     current_cycle: &u32,
     pox_stacking_order: &PoxStackingOrder,
@@ -551,6 +723,30 @@ fn should_publish_stacking_orders(
     true
 }
 
+// Returns the reward cycles the pool operator has to commit during the current cycle
+fn get_delegation_cycles_to_commit(
+    current_cycle: &u32,
+    pox_delegation_order: &PoxDelegationOrder,
+) -> Vec<u32> {
+    let PoxDelegationOrder {
+        duration,
+        start_at_cycle,
+        auto_restack,
+        ..
+    } = pox_delegation_order;
+
+    if *current_cycle == start_at_cycle.saturating_sub(1) {
+        return (*start_at_cycle..(start_at_cycle + duration)).collect();
+    }
+
+    // once locked, the delegated STX are extended by one cycle, every cycle
+    if auto_restack.unwrap_or_default() && current_cycle >= start_at_cycle {
+        return vec![current_cycle + duration];
+    }
+
+    vec![]
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -563,6 +759,24 @@ mod tests {
             slots: 1,
             btc_address: "address_1".to_string(),
             auto_extend: Some(true),
+            increase_at_cycle: None,
+            increase_slots: None,
+        }
+    }
+
+    fn build_pox_delegation_order(
+        duration: u32,
+        start_at_cycle: u32,
+        auto_restack: bool,
+    ) -> PoxDelegationOrder {
+        PoxDelegationOrder {
+            duration,
+            start_at_cycle,
+            pool_operator: "wallet_1".to_string(),
+            delegators: vec!["wallet_2".to_string(), "wallet_3".to_string()],
+            slots: 1,
+            btc_address: "address_1".to_string(),
+            auto_restack: Some(auto_restack),
         }
     }
 
@@ -638,6 +852,34 @@ mod tests {
             true
         );
     }
+
+    #[test]
+    fn test_get_delegation_cycles_to_commit() {
+        let pox_delegation_order = build_pox_delegation_order(2, 4, false);
+        assert!(get_delegation_cycles_to_commit(&2, &pox_delegation_order).is_empty());
+        // the initial lock commits every cycle of the duration
+        assert_eq!(
+            get_delegation_cycles_to_commit(&3, &pox_delegation_order),
+            vec![4, 5]
+        );
+        assert!(get_delegation_cycles_to_commit(&4, &pox_delegation_order).is_empty());
+
+        let pox_delegation_order = build_pox_delegation_order(2, 4, true);
+        assert!(get_delegation_cycles_to_commit(&2, &pox_delegation_order).is_empty());
+        assert_eq!(
+            get_delegation_cycles_to_commit(&3, &pox_delegation_order),
+            vec![4, 5]
+        );
+        // then each cycle extends the lock by one cycle
+        assert_eq!(
+            get_delegation_cycles_to_commit(&4, &pox_delegation_order),
+            vec![6]
+        );
+        assert_eq!(
+            get_delegation_cycles_to_commit(&9, &pox_delegation_order),
+            vec![11]
+        );
+    }
 }
 
 pub async fn publish_stacking_orders(
@@ -677,26 +919,87 @@ pub async fn publish_stacking_orders(
     let pox_cycle_length = pox_info.reward_cycle_length;
     let pox_cycle_position = effective_height % pox_cycle_length;
 
-    let should_submit_pox_orders = pox_cycle_position == 1;
-    if !should_submit_pox_orders {
-        return None;
+    // stacking orders and delegations are submitted on the first block of the cycle,
+    // pool operators aggregate the delegated STX on the next one
+    let transactions = match pox_cycle_position {
+        1 => {
+            publish_solo_stacking_orders(
+                devnet_config,
+                devnet_event_tx,
+                accounts,
+                &stacks_node_rpc_url,
+                &pox_info,
+                fee_rate,
+                bitcoin_block_height,
+                current_cycle,
+            ) + publish_delegations(
+                devnet_config,
+                devnet_event_tx,
+                accounts,
+                &stacks_node_rpc_url,
+                &pox_info,
+                fee_rate,
+                current_cycle,
+            )
+        }
+        2 => publish_pool_commits(
+            devnet_config,
+            devnet_event_tx,
+            accounts,
+            &stacks_node_rpc_url,
+            &pox_info,
+            fee_rate,
+            bitcoin_block_height,
+            current_cycle,
+        ),
+        _ => return None,
+    };
+
+    if transactions > 0 {
+        Some(transactions)
+    } else {
+        None
     }
+}
+
+fn publish_solo_stacking_orders(
+    devnet_config: &DevnetConfig,
+    devnet_event_tx: &Sender<DevnetEvent>,
+    accounts: &[AccountConfig],
+    stacks_node_rpc_url: &str,
+    pox_info: &PoxInfo,
+    fee_rate: u64,
+    bitcoin_block_height: u32,
+    current_cycle: u32,
+) -> usize {
+    let pox_version = get_pox_version(&pox_info.contract_id);
 
     let mut transactions = 0;
     for (i, pox_stacking_order) in devnet_config.pox_stacking_orders.iter().enumerate() {
-        if !should_publish_stacking_orders(&current_cycle, pox_stacking_order) {
-            continue;
-        }
-
         let PoxStackingOrder {
             duration,
             start_at_cycle,
             ..
         } = pox_stacking_order;
 
+        let mut should_stack = should_publish_stacking_orders(&current_cycle, pox_stacking_order);
         // if the is not the first cycle of this stacker, then stacking order will be extended
         let extend_stacking = current_cycle != start_at_cycle - 1;
         if extend_stacking && !pox_stacking_order.auto_extend.unwrap_or_default() {
+            should_stack = false;
+        }
+
+        let increase_slots = match (
+            pox_stacking_order.increase_at_cycle,
+            pox_stacking_order.increase_slots,
+        ) {
+            (Some(increase_at_cycle), Some(slots)) if current_cycle + 1 == increase_at_cycle => {
+                Some(slots)
+            }
+            _ => None,
+        };
+
+        if !should_stack && increase_slots.is_none() {
             continue;
         }
 
@@ -709,123 +1012,413 @@ pub async fn publish_stacking_orders(
             _ => continue,
         };
 
-        transactions += 1;
-
         let stx_amount = pox_info.next_cycle.min_threshold_ustx * pox_stacking_order.slots;
-        let addr_bytes = pox_stacking_order
-            .btc_address
-            .from_base58()
-            .expect("Unable to get bytes from btc address");
-        let node_url = stacks_node_rpc_url.clone();
-        let pox_contract_id = pox_info.contract_id.clone();
-        let pox_version = pox_contract_id
-            .rsplit('-')
-            .next()
-            .and_then(|version| version.parse::<u32>().ok())
-            .unwrap();
-
+        let increase_amount =
+            increase_slots.map(|slots| pox_info.next_cycle.min_threshold_ustx * slots);
+        let pox_addr_arg = pox_address_argument(&pox_stacking_order.btc_address);
         let duration = *duration;
-        let stacking_result =
-            hiro_system_kit::thread_named("Stacking orders handler").spawn(move || {
-                let default_fee = fee_rate * 1000;
-                let stacks_rpc = StacksRpc::new(&node_url);
-                let nonce = stacks_rpc.get_nonce(&account.stx_address)?;
-
-                let (_, _, account_secret_key) = clarinet_files::compute_addresses(
-                    &account.mnemonic,
-                    &account.derivation,
-                    &StacksNetwork::Devnet.get_networks(),
-                );
 
-                let pox_addr_arg = ClarityValue::Tuple(
-                    TupleData::from_data(vec![
-                        (
-                            ClarityName::try_from("version".to_owned()).unwrap(),
-                            ClarityValue::buff_from_byte(AddressHashMode::SerializeP2PKH as u8),
+        let result = broadcast_pox_contract_calls(
+            stacks_node_rpc_url,
+            &pox_info.contract_id,
+            &account,
+            fee_rate,
+            move |nonce| {
+                let mut calls = vec![];
+                if should_stack {
+                    let (method, arguments) = match extend_stacking {
+                        false => (
+                            "stack-stx",
+                            vec![
+                                ClarityValue::UInt(stx_amount.into()),
+                                pox_addr_arg,
+                                ClarityValue::UInt((bitcoin_block_height - 1).into()),
+                                ClarityValue::UInt(duration.into()),
+                            ],
                         ),
-                        (
-                            ClarityName::try_from("hashbytes".to_owned()).unwrap(),
-                            ClarityValue::Sequence(SequenceData::Buffer(BuffData {
-                                data: Hash160::from_bytes(&addr_bytes[1..21])
-                                    .unwrap()
-                                    .as_bytes()
-                                    .to_vec(),
-                            })),
+                        true => (
+                            "stack-extend",
+                            vec![ClarityValue::UInt(duration.into()), pox_addr_arg],
                         ),
-                    ])
-                    .unwrap(),
-                );
+                    };
+                    calls.push((method, arguments));
+                }
+                if let Some(increase_amount) = increase_amount {
+                    calls.push((
+                        "stack-increase",
+                        vec![ClarityValue::UInt(increase_amount.into())],
+                    ));
+                }
+                if pox_version >= 4 {
+                    for (_, arguments) in calls.iter_mut() {
+                        arguments.push(signer_key_argument(i, nonce));
+                    }
+                }
+                calls
+            },
+        );
 
-                let (method, mut arguments) = match extend_stacking {
-                    false => (
-                        "stack-stx",
+        match result {
+            Ok(count) => {
+                transactions += count;
+                if should_stack {
+                    let _ = devnet_event_tx.send(DevnetEvent::success(format!(
+                        "Stacking order for {} STX submitted",
+                        stx_amount
+                    )));
+                }
+                if let Some(increase_amount) = increase_amount {
+                    let _ = devnet_event_tx.send(DevnetEvent::success(format!(
+                        "Stacking increase of {} STX submitted",
+                        increase_amount
+                    )));
+                }
+            }
+            Err(e) => {
+                let _ = devnet_event_tx.send(DevnetEvent::error(format!("Unable to stack: {}", e)));
+            }
+        }
+    }
+    transactions
+}
+
+fn publish_delegations(
+    devnet_config: &DevnetConfig,
+    devnet_event_tx: &Sender<DevnetEvent>,
+    accounts: &[AccountConfig],
+    stacks_node_rpc_url: &str,
+    pox_info: &PoxInfo,
+    fee_rate: u64,
+    current_cycle: u32,
+) -> usize {
+    let mut transactions = 0;
+    for delegation_order in devnet_config.pox_delegation_orders.iter() {
+        if current_cycle != delegation_order.start_at_cycle.saturating_sub(1) {
+            continue;
+        }
+
+        let pool_operator = match accounts
+            .iter()
+            .find(|e| e.label == delegation_order.pool_operator)
+        {
+            Some(account) => account,
+            None => continue,
+        };
+        let pool_operator_principal = match PrincipalData::parse(&pool_operator.stx_address) {
+            Ok(principal) => principal,
+            Err(e) => {
+                let _ = devnet_event_tx.send(DevnetEvent::error(format!(
+                    "Unable to delegate to {}: {}",
+                    pool_operator.label, e
+                )));
+                continue;
+            }
+        };
+
+        let stx_amount = pox_info.next_cycle.min_threshold_ustx * delegation_order.slots;
+        for delegator in delegation_order.delegators.iter() {
+            let account = match accounts.iter().find(|e| &e.label == delegator) {
+                Some(account) => account,
+                None => continue,
+            };
+            let pox_addr_arg = pox_address_argument(&delegation_order.btc_address);
+            let delegate_to = ClarityValue::Principal(pool_operator_principal.clone());
+            let result = broadcast_pox_contract_calls(
+                stacks_node_rpc_url,
+                &pox_info.contract_id,
+                account,
+                fee_rate,
+                move |_| {
+                    vec![(
+                        "delegate-stx",
                         vec![
                             ClarityValue::UInt(stx_amount.into()),
-                            pox_addr_arg,
-                            ClarityValue::UInt((bitcoin_block_height - 1).into()),
-                            ClarityValue::UInt(duration.into()),
+                            delegate_to,
+                            ClarityValue::none(),
+                            ClarityValue::some(pox_addr_arg).unwrap(),
                         ],
-                    ),
-                    true => (
-                        "stack-extend",
-                        vec![ClarityValue::UInt(duration.into()), pox_addr_arg],
-                    ),
-                };
+                    )]
+                },
+            );
+            match result {
+                Ok(count) => {
+                    transactions += count;
+                    let _ = devnet_event_tx.send(DevnetEvent::success(format!(
+                        "Delegation of {} STX from {} to {} submitted",
+                        stx_amount, account.label, pool_operator.label
+                    )));
+                }
+                Err(e) => {
+                    let _ = devnet_event_tx
+                        .send(DevnetEvent::error(format!("Unable to delegate: {}", e)));
+                }
+            }
+        }
+    }
+    transactions
+}
 
-                if pox_version >= 4 {
-                    let mut signer_key = vec![0; 33];
-                    signer_key[0] = i as u8;
-                    signer_key[1] = nonce as u8;
-                    arguments.push(ClarityValue::buff_from(signer_key).unwrap());
-                };
+fn publish_pool_commits(
+    devnet_config: &DevnetConfig,
+    devnet_event_tx: &Sender<DevnetEvent>,
+    accounts: &[AccountConfig],
+    stacks_node_rpc_url: &str,
+    pox_info: &PoxInfo,
+    fee_rate: u64,
+    bitcoin_block_height: u32,
+    current_cycle: u32,
+) -> usize {
+    let pox_version = get_pox_version(&pox_info.contract_id);
 
-                let tx = codec::build_contrat_call_transaction(
-                    pox_contract_id,
-                    method.into(),
-                    arguments,
-                    nonce,
-                    default_fee,
-                    &hex_bytes(&account_secret_key).unwrap(),
-                );
+    let mut transactions = 0;
+    for (i, delegation_order) in devnet_config.pox_delegation_orders.iter().enumerate() {
+        let cycles = get_delegation_cycles_to_commit(&current_cycle, delegation_order);
+        if cycles.is_empty() {
+            continue;
+        }
+        let restacking = current_cycle >= delegation_order.start_at_cycle;
 
-                stacks_rpc.post_transaction(&tx)
-            });
+        let pool_operator = match accounts
+            .iter()
+            .find(|e| e.label == delegation_order.pool_operator)
+        {
+            Some(account) => account,
+            None => continue,
+        };
 
-        match stacking_result {
-            Ok(result) => {
-                if let Ok(result) = result.join() {
-                    match result {
-                        Ok(_) => {
-                            let _ = devnet_event_tx.send(DevnetEvent::success(format!(
-                                "Stacking order for {} STX submitted",
-                                stx_amount
-                            )));
-                        }
-                        Err(e) => {
-                            let _ = devnet_event_tx
-                                .send(DevnetEvent::error(format!("Unable to stack: {}", e)));
-                        }
+        let mut stackers = vec![];
+        for delegator in delegation_order.delegators.iter() {
+            let principal = accounts
+                .iter()
+                .find(|e| &e.label == delegator)
+                .and_then(|account| PrincipalData::parse(&account.stx_address).ok());
+            if let Some(principal) = principal {
+                stackers.push(ClarityValue::Principal(principal));
+            }
+        }
+
+        let stx_amount = pox_info.next_cycle.min_threshold_ustx * delegation_order.slots;
+        let btc_address = delegation_order.btc_address.clone();
+        let duration = delegation_order.duration;
+        let signer_index = devnet_config.pox_stacking_orders.len() + i;
+        let commits = cycles.len();
+
+        let result = broadcast_pox_contract_calls(
+            stacks_node_rpc_url,
+            &pox_info.contract_id,
+            pool_operator,
+            fee_rate,
+            move |nonce| {
+                let mut calls = vec![];
+                for stacker in stackers.into_iter() {
+                    let pox_addr_arg = pox_address_argument(&btc_address);
+                    let (method, arguments) = match restacking {
+                        false => (
+                            "delegate-stack-stx",
+                            vec![
+                                stacker,
+                                ClarityValue::UInt(stx_amount.into()),
+                                pox_addr_arg,
+                                ClarityValue::UInt((bitcoin_block_height - 1).into()),
+                                ClarityValue::UInt(duration.into()),
+                            ],
+                        ),
+                        true => (
+                            "delegate-stack-extend",
+                            vec![stacker, pox_addr_arg, ClarityValue::UInt(1)],
+                        ),
+                    };
+                    calls.push((method, arguments));
+                }
+                for cycle in cycles.into_iter() {
+                    let mut arguments = vec![
+                        pox_address_argument(&btc_address),
+                        ClarityValue::UInt(cycle.into()),
+                    ];
+                    if pox_version >= 4 {
+                        arguments.push(signer_key_argument(signer_index, nonce));
                     }
-                };
+                    calls.push(("stack-aggregation-commit", arguments));
+                }
+                calls
+            },
+        );
+
+        match result {
+            Ok(count) => {
+                transactions += count;
+                let _ = devnet_event_tx.send(DevnetEvent::success(format!(
+                    "Pool operator {} committed delegated STX for {} cycle(s)",
+                    pool_operator.label, commits
+                )));
             }
             Err(e) => {
-                let _ = devnet_event_tx.send(DevnetEvent::error(format!("Unable to stack: {}", e)));
+                let _ = devnet_event_tx.send(DevnetEvent::error(format!(
+                    "Unable to commit delegated STX: {}",
+                    e
+                )));
             }
         }
     }
-    if transactions > 0 {
-        Some(transactions)
-    } else {
-        None
+    transactions
+}
+
+fn get_pox_version(pox_contract_id: &str) -> u32 {
+    pox_contract_id
+        .rsplit('-')
+        .next()
+        .and_then(|version| version.parse::<u32>().ok())
+        .unwrap()
+}
+
+fn pox_address_argument(btc_address: &str) -> ClarityValue {
+    let addr_bytes = btc_address
+        .from_base58()
+        .expect("Unable to get bytes from btc address");
+    ClarityValue::Tuple(
+        TupleData::from_data(vec![
+            (
+                ClarityName::try_from("version".to_owned()).unwrap(),
+                ClarityValue::buff_from_byte(AddressHashMode::SerializeP2PKH as u8),
+            ),
+            (
+                ClarityName::try_from("hashbytes".to_owned()).unwrap(),
+                ClarityValue::Sequence(SequenceData::Buffer(BuffData {
+                    data: Hash160::from_bytes(&addr_bytes[1..21])
+                        .unwrap()
+                        .as_bytes()
+                        .to_vec(),
+                })),
+            ),
+        ])
+        .unwrap(),
+    )
+}
+
+fn signer_key_argument(index: usize, nonce: u64) -> ClarityValue {
+    let mut signer_key = vec![0; 33];
+    signer_key[0] = index as u8;
+    signer_key[1] = nonce as u8;
+    ClarityValue::buff_from(signer_key).unwrap()
+}
+
+// Signs the contract calls with consecutive nonces and posts them to the stacks node
+fn broadcast_pox_contract_calls<F>(
+    stacks_node_rpc_url: &str,
+    pox_contract_id: &str,
+    account: &AccountConfig,
+    fee_rate: u64,
+    build_calls: F,
+) -> Result<usize, String>
+where
+    F: FnOnce(u64) -> Vec<(&'static str, Vec<ClarityValue>)> + Send + 'static,
+{
+    let node_url = stacks_node_rpc_url.to_string();
+    let pox_contract_id = pox_contract_id.to_string();
+    let account = account.clone();
+    let handle = hiro_system_kit::thread_named("Stacking orders handler")
+        .spawn(move || {
+            let default_fee = fee_rate * 1000;
+            let stacks_rpc = StacksRpc::new(&node_url);
+            let nonce = stacks_rpc
+                .get_nonce(&account.stx_address)
+                .map_err(|e| e.to_string())?;
+
+            let (_, _, account_secret_key) = clarinet_files::compute_addresses(
+                &account.mnemonic,
+                &account.derivation,
+                &StacksNetwork::Devnet.get_networks(),
+            );
+
+            let calls = build_calls(nonce);
+            let count = calls.len();
+            for (i, (method, arguments)) in calls.into_iter().enumerate() {
+                let tx = codec::build_contrat_call_transaction(
+                    pox_contract_id.clone(),
+                    method.into(),
+                    arguments,
+                    nonce + i as u64,
+                    default_fee,
+                    &hex_bytes(&account_secret_key).unwrap(),
+                );
+                stacks_rpc
+                    .post_transaction(&tx)
+                    .map_err(|e| e.to_string())?;
+            }
+            Ok(count)
+        })
+        .map_err(|e| e.to_string())?;
+
+    handle
+        .join()
+        .map_err(|_| "stacking orders handler panicked".to_string())?
+}
+
+pub async fn send_bitcoin_rpc_request(
+    bitcoin_node_host: &str,
+    bitcoin_node_username: &str,
+    bitcoin_node_password: &str,
+    method: &str,
+    params: Vec<serde_json::Value>,
+) -> Result<serde_json::Value, String> {
+    use reqwest::Client as HttpClient;
+
+    let response = HttpClient::builder()
+        .timeout(Duration::from_secs(5))
+        .build()
+        .expect("Unable to build http client")
+        .post(format!("http://{}", bitcoin_node_host))
+        .basic_auth(bitcoin_node_username, Some(bitcoin_node_password))
+        .header("Content-Type", "application/json")
+        .header("Host", bitcoin_node_host)
+        .json(&serde_json::json!({
+            "jsonrpc": "1.0",
+            "id": "stacks-network",
+            "method": method,
+            "params": params
+        }))
+        .send()
+        .await
+        .map_err(|e| format!("unable to send request ({})", e))?
+        .json::<serde_json::Value>()
+        .await
+        .map_err(|e| format!("unable to parse {} response ({})", method, e))?;
+
+    match response.get("error") {
+        Some(error) if !error.is_null() => Err(format!("{} failed ({})", method, error)),
+        _ => Ok(response
+            .get("result")
+            .cloned()
+            .unwrap_or(serde_json::Value::Null)),
     }
 }
 
-pub fn invalidate_bitcoin_chain_tip(
-    _bitcoin_node_host: &str,
-    _bitcoin_node_username: &str,
-    _bitcoin_node_password: &str,
-) {
-    unimplemented!()
+pub async fn invalidate_bitcoin_chain_tip(
+    bitcoin_node_host: &str,
+    bitcoin_node_username: &str,
+    bitcoin_node_password: &str,
+) -> Result<String, String> {
+    let chain_tip = send_bitcoin_rpc_request(
+        bitcoin_node_host,
+        bitcoin_node_username,
+        bitcoin_node_password,
+        "getbestblockhash",
+        vec![],
+    )
+    .await?;
+    let block_hash = match chain_tip.as_str() {
+        Some(block_hash) => block_hash.to_string(),
+        None => return Err("unable to retrieve bitcoin chain tip".to_string()),
+    };
+    send_bitcoin_rpc_request(
+        bitcoin_node_host,
+        bitcoin_node_username,
+        bitcoin_node_password,
+        "invalidateblock",
+        vec![serde_json::json!(block_hash)],
+    )
+    .await?;
+    Ok(block_hash)
 }
 
 pub async fn mine_bitcoin_block(
@@ -922,11 +1515,15 @@ async fn handle_bitcoin_mining(
                 }
             }
             BitcoinMiningCommand::InvalidateChainTip => {
-                invalidate_bitcoin_chain_tip(
+                let res = invalidate_bitcoin_chain_tip(
                     &config.services_map_hosts.bitcoin_node_host,
                     config.devnet_config.bitcoin_node_username.as_str(),
                     config.devnet_config.bitcoin_node_password.as_str(),
-                );
+                )
+                .await;
+                if let Err(e) = res {
+                    let _ = devnet_event_tx.send(DevnetEvent::error(e));
+                }
             }
         }
     }
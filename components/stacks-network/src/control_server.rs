@@ -0,0 +1,495 @@
+use crate::chains_coordinator::{
+    invalidate_bitcoin_chain_tip, mine_bitcoin_block, send_bitcoin_rpc_request,
+    BitcoinMiningCommand, DevnetEventObserverConfig,
+};
+use crate::event::DevnetEvent;
+use crate::faults::{FaultCommand, FaultInjector};
+
+use clarinet_files::DevnetConfig;
+use clarity_repl::clarity::vm::types::PrincipalData;
+use hyper::service::{make_service_fn, service_fn};
+use hyper::{Body, Method, Request, Response, Server, StatusCode};
+use serde_json::{json, Value as JsonValue};
+use stacks_rpc_client::clarity::codec::TransactionAnchorMode;
+use stacks_rpc_client::crypto::{encode_stx_transfer, Wallet};
+use stacks_rpc_client::StacksRpc;
+use std::convert::Infallible;
+use std::net::SocketAddr;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::Sender;
+use std::sync::{Arc, Mutex};
+
+// Local HTTP/JSON API used to drive a running devnet:
+//
+// GET  /v1/status                  status of the bitcoin and stacks nodes
+// POST /v1/mine                    mine bitcoin blocks, body: {"blocks": 3} (default: 1)
+// POST /v1/automining/pause        stop mining blocks automatically
+// POST /v1/automining/resume       resume mining blocks automatically
+// POST /v1/invalidate-tip          invalidate the bitcoin chain tip
+// POST /v1/faucet                  send STX from the faucet, body: {"address": "ST...", "amount": 1000000}
+// GET  /v1/faults                  faults currently injected
+// POST /v1/faults/latency          delay the stacks-node events deliveries, body: {"ms": 2000}
+// POST /v1/faults/drop-events      drop the stacks-node events deliveries, body: {"enabled": true}
+// POST /v1/faults/disconnect-bitcoin  refuse the bitcoin rpc calls of the stacks nodes, body: {"enabled": true}
+// POST /v1/faults/reset            clear the faults, resume the paused services
+// POST /v1/services/<name>/pause   pause a service (stacks-node, stacks-api, postgres, ...)
+// POST /v1/services/<name>/resume  resume a paused service
+// POST /v1/services/<name>/restart restart a service
+
+#[derive(Debug, PartialEq)]
+enum ControlRequest {
+    Status,
+    Mine(u64),
+    PauseAutomining,
+    ResumeAutomining,
+    InvalidateChainTip,
+    Fund {
+        recipient: PrincipalData,
+        amount: u64,
+    },
+    Faults,
+    InjectFault(FaultCommand),
+}
+
+#[derive(Deserialize)]
+struct MineRequest {
+    blocks: Option<u64>,
+}
+
+#[derive(Deserialize)]
+struct LatencyRequest {
+    ms: u64,
+}
+
+#[derive(Deserialize)]
+struct ToggleRequest {
+    enabled: Option<bool>,
+}
+
+#[derive(Deserialize)]
+struct FundRequest {
+    address: String,
+    amount: u64,
+}
+
+const ROUTES: [&str; 11] = [
+    "/v1/status",
+    "/v1/mine",
+    "/v1/automining/pause",
+    "/v1/automining/resume",
+    "/v1/invalidate-tip",
+    "/v1/faucet",
+    "/v1/faults",
+    "/v1/faults/latency",
+    "/v1/faults/drop-events",
+    "/v1/faults/disconnect-bitcoin",
+    "/v1/faults/reset",
+];
+
+impl ControlRequest {
+    fn parse(
+        method: &Method,
+        path: &str,
+        body: &[u8],
+    ) -> Result<ControlRequest, (StatusCode, String)> {
+        let bad_request =
+            |e: serde_json::Error| (StatusCode::BAD_REQUEST, format!("invalid body: {}", e));
+
+        match (method, path) {
+            (&Method::GET, "/v1/status") => Ok(ControlRequest::Status),
+            (&Method::POST, "/v1/mine") => {
+                let blocks = match body.is_empty() {
+                    true => 1,
+                    false => serde_json::from_slice::<MineRequest>(body)
+                        .map_err(bad_request)?
+                        .blocks
+                        .unwrap_or(1),
+                };
+                Ok(ControlRequest::Mine(blocks))
+            }
+            (&Method::POST, "/v1/automining/pause") => Ok(ControlRequest::PauseAutomining),
+            (&Method::POST, "/v1/automining/resume") => Ok(ControlRequest::ResumeAutomining),
+            (&Method::POST, "/v1/invalidate-tip") => Ok(ControlRequest::InvalidateChainTip),
+            (&Method::POST, "/v1/faucet") => {
+                let request = serde_json::from_slice::<FundRequest>(body).map_err(bad_request)?;
+                let recipient = PrincipalData::parse(&request.address).map_err(|e| {
+                    (
+                        StatusCode::BAD_REQUEST,
+                        format!("invalid address {}: {}", request.address, e),
+                    )
+                })?;
+                Ok(ControlRequest::Fund {
+                    recipient,
+                    amount: request.amount,
+                })
+            }
+            (&Method::GET, "/v1/faults") => Ok(ControlRequest::Faults),
+            (&Method::POST, "/v1/faults/latency") => {
+                let request =
+                    serde_json::from_slice::<LatencyRequest>(body).map_err(bad_request)?;
+                Ok(ControlRequest::InjectFault(FaultCommand::SetEventLatency(
+                    request.ms,
+                )))
+            }
+            (&Method::POST, "/v1/faults/drop-events") => {
+                let enabled = parse_toggle(body).map_err(bad_request)?;
+                Ok(ControlRequest::InjectFault(FaultCommand::DropEvents(
+                    enabled,
+                )))
+            }
+            (&Method::POST, "/v1/faults/disconnect-bitcoin") => {
+                let enabled = parse_toggle(body).map_err(bad_request)?;
+                Ok(ControlRequest::InjectFault(
+                    FaultCommand::DisconnectBitcoinNode(enabled),
+                ))
+            }
+            (&Method::POST, "/v1/faults/reset") => {
+                Ok(ControlRequest::InjectFault(FaultCommand::Reset))
+            }
+            (&Method::POST, path) if path.starts_with("/v1/services/") => {
+                let route = &path["/v1/services/".len()..];
+                let command = match route.split_once('/') {
+                    Some((service, "pause")) => FaultCommand::PauseService(service.into()),
+                    Some((service, "resume")) => FaultCommand::ResumeService(service.into()),
+                    Some((service, "restart")) => FaultCommand::RestartService(service.into()),
+                    _ => return Err((StatusCode::NOT_FOUND, format!("unknown route {}", path))),
+                };
+                Ok(ControlRequest::InjectFault(command))
+            }
+            (_, path) if ROUTES.contains(&path) => Err((
+                StatusCode::METHOD_NOT_ALLOWED,
+                format!("method {} not allowed on {}", method, path),
+            )),
+            (_, path) => Err((StatusCode::NOT_FOUND, format!("unknown route {}", path))),
+        }
+    }
+}
+
+// an empty body enables the fault
+fn parse_toggle(body: &[u8]) -> Result<bool, serde_json::Error> {
+    match body.is_empty() {
+        true => Ok(true),
+        false => Ok(serde_json::from_slice::<ToggleRequest>(body)?
+            .enabled
+            .unwrap_or(true)),
+    }
+}
+
+struct ControlServerState {
+    devnet_config: DevnetConfig,
+    bitcoin_node_host: String,
+    stacks_node_host: String,
+    fee_rate: u64,
+    mining_command_tx: Mutex<Sender<BitcoinMiningCommand>>,
+    automining: AtomicBool,
+    // the node only knows the nonce of confirmed transactions
+    faucet_nonce: Mutex<Option<u64>>,
+    faults: Option<Arc<FaultInjector>>,
+}
+
+impl ControlServerState {
+    fn send_mining_command(&self, command: BitcoinMiningCommand) -> Result<(), String> {
+        self.mining_command_tx
+            .lock()
+            .map_err(|e| e.to_string())?
+            .send(command)
+            .map_err(|e| format!("unable to send mining command: {}", e))
+    }
+
+    async fn get_status(&self) -> JsonValue {
+        let bitcoin_node = match send_bitcoin_rpc_request(
+            &self.bitcoin_node_host,
+            &self.devnet_config.bitcoin_node_username,
+            &self.devnet_config.bitcoin_node_password,
+            "getblockcount",
+            vec![],
+        )
+        .await
+        {
+            Ok(height) => json!({ "status": "running", "chain_tip": height }),
+            Err(e) => json!({ "status": "unreachable", "error": e }),
+        };
+
+        let stacks_node_info =
+            match reqwest::get(format!("http://{}/v2/info", self.stacks_node_host)).await {
+                Ok(response) => response
+                    .json::<JsonValue>()
+                    .await
+                    .map_err(|e| e.to_string()),
+                Err(e) => Err(e.to_string()),
+            };
+        let stacks_node = match stacks_node_info {
+            Ok(info) => json!({
+                "status": "running",
+                "chain_tip": info["stacks_tip_height"],
+                "burn_block_height": info["burn_block_height"],
+            }),
+            Err(e) => json!({ "status": "unreachable", "error": e }),
+        };
+
+        json!({
+            "automining": self.automining.load(Ordering::SeqCst),
+            "bitcoin_node": bitcoin_node,
+            "stacks_node": stacks_node,
+        })
+    }
+
+    // blocking, the stacks rpc client can't be used from the runtime
+    fn fund_address(&self, recipient: PrincipalData, amount: u64) -> Result<String, String> {
+        let stacks_rpc = StacksRpc::new(&format!("http://{}", self.stacks_node_host));
+        let mut faucet_nonce = self.faucet_nonce.lock().map_err(|e| e.to_string())?;
+        let nonce = stacks_rpc
+            .get_nonce(&self.devnet_config.faucet_stx_address)
+            .map_err(|e| format!("unable to retrieve faucet nonce: {}", e))?;
+        let nonce = match *faucet_nonce {
+            Some(pending_nonce) if pending_nonce > nonce => pending_nonce,
+            _ => nonce,
+        };
+
+        let wallet = Wallet {
+            mnemonic: self.devnet_config.faucet_mnemonic.clone(),
+            derivation: self.devnet_config.faucet_derivation_path.clone(),
+            mainnet: false,
+        };
+        let transaction = encode_stx_transfer(
+            recipient,
+            amount,
+            [0; 34],
+            &wallet,
+            nonce,
+            self.fee_rate * 1000,
+            TransactionAnchorMode::Any,
+        )?;
+        let result = stacks_rpc
+            .post_transaction(&transaction)
+            .map_err(|e| format!("unable to broadcast transaction: {}", e))?;
+        *faucet_nonce = Some(nonce + 1);
+        Ok(result.txid)
+    }
+}
+
+async fn execute_request(
+    request: ControlRequest,
+    state: Arc<ControlServerState>,
+) -> Result<JsonValue, String> {
+    match request {
+        ControlRequest::Status => Ok(state.get_status().await),
+        ControlRequest::Mine(blocks) => {
+            for _ in 0..blocks {
+                mine_bitcoin_block(
+                    &state.bitcoin_node_host,
+                    &state.devnet_config.bitcoin_node_username,
+                    &state.devnet_config.bitcoin_node_password,
+                    &state.devnet_config.miner_btc_address,
+                )
+                .await?;
+            }
+            Ok(json!({ "blocks": blocks }))
+        }
+        ControlRequest::PauseAutomining => {
+            if state.automining.swap(false, Ordering::SeqCst) {
+                state.send_mining_command(BitcoinMiningCommand::Pause)?;
+            }
+            Ok(json!({ "automining": false }))
+        }
+        ControlRequest::ResumeAutomining => {
+            if !state.automining.swap(true, Ordering::SeqCst) {
+                state.send_mining_command(BitcoinMiningCommand::Start)?;
+            }
+            Ok(json!({ "automining": true }))
+        }
+        ControlRequest::InvalidateChainTip => {
+            let block_hash = invalidate_bitcoin_chain_tip(
+                &state.bitcoin_node_host,
+                &state.devnet_config.bitcoin_node_username,
+                &state.devnet_config.bitcoin_node_password,
+            )
+            .await?;
+            Ok(json!({ "invalidated_block_hash": block_hash }))
+        }
+        ControlRequest::Fund { recipient, amount } => {
+            let txid = tokio::task::spawn_blocking(move || state.fund_address(recipient, amount))
+                .await
+                .map_err(|e| e.to_string())??;
+            Ok(json!({ "txid": txid }))
+        }
+        ControlRequest::Faults => match state.faults {
+            Some(ref faults) => Ok(json!(faults.state())),
+            None => Err("fault injection unavailable".into()),
+        },
+        ControlRequest::InjectFault(command) => match state.faults {
+            Some(ref faults) => Ok(json!(faults.execute(command).await?)),
+            None => Err("fault injection unavailable".into()),
+        },
+    }
+}
+
+fn json_response(status: StatusCode, value: JsonValue) -> Response<Body> {
+    Response::builder()
+        .status(status)
+        .header("Content-Type", "application/json")
+        .body(Body::from(value.to_string()))
+        .expect("unable to build response")
+}
+
+async fn handle_request(
+    request: Request<Body>,
+    state: Arc<ControlServerState>,
+) -> Result<Response<Body>, Infallible> {
+    let method = request.method().clone();
+    let path = request.uri().path().to_string();
+    let body = match hyper::body::to_bytes(request.into_body()).await {
+        Ok(body) => body,
+        Err(e) => {
+            return Ok(json_response(
+                StatusCode::BAD_REQUEST,
+                json!({ "error": e.to_string() }),
+            ))
+        }
+    };
+
+    let result = match ControlRequest::parse(&method, &path, &body) {
+        Ok(request) => execute_request(request, state)
+            .await
+            .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e)),
+        Err(e) => Err(e),
+    };
+    let response = match result {
+        Ok(value) => json_response(StatusCode::OK, value),
+        Err((status, message)) => json_response(status, json!({ "error": message })),
+    };
+    Ok(response)
+}
+
+pub async fn start_control_server(
+    config: &DevnetEventObserverConfig,
+    mining_command_tx: Sender<BitcoinMiningCommand>,
+    devnet_event_tx: &Sender<DevnetEvent>,
+) -> Result<(), String> {
+    let port = config.devnet_config.orchestrator_control_port;
+    let state = Arc::new(ControlServerState {
+        devnet_config: config.devnet_config.clone(),
+        bitcoin_node_host: config.services_map_hosts.bitcoin_node_host.clone(),
+        stacks_node_host: config.services_map_hosts.stacks_node_host.clone(),
+        fee_rate: config.deployment_fee_rate,
+        mining_command_tx: Mutex::new(mining_command_tx),
+        automining: AtomicBool::new(!config.devnet_config.bitcoin_controller_automining_disabled),
+        faucet_nonce: Mutex::new(None),
+        faults: config.faults.clone(),
+    });
+
+    let make_service = make_service_fn(move |_| {
+        let state = state.clone();
+        async move {
+            Ok::<_, Infallible>(service_fn(move |request| {
+                handle_request(request, state.clone())
+            }))
+        }
+    });
+
+    let address = SocketAddr::from(([127, 0, 0, 1], port));
+    let server = Server::try_bind(&address)
+        .map_err(|e| format!("unable to start control server on port {}: {}", port, e))?
+        .serve(make_service);
+
+    let _ = devnet_event_tx.send(DevnetEvent::info(format!(
+        "Control API listening on http://localhost:{}",
+        port
+    )));
+    server
+        .await
+        .map_err(|e| format!("control server stopped: {}", e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_control_requests() {
+        assert_eq!(
+            ControlRequest::parse(&Method::GET, "/v1/status", b""),
+            Ok(ControlRequest::Status)
+        );
+        assert_eq!(
+            ControlRequest::parse(&Method::POST, "/v1/mine", b""),
+            Ok(ControlRequest::Mine(1))
+        );
+        assert_eq!(
+            ControlRequest::parse(&Method::POST, "/v1/mine", br#"{"blocks": 5}"#),
+            Ok(ControlRequest::Mine(5))
+        );
+
+        let body = br#"{"address": "ST1PQHQKV0RJXZFY1DGX8MNSNYVE3VGZJSRTPGZGM", "amount": 1000}"#;
+        assert_eq!(
+            ControlRequest::parse(&Method::POST, "/v1/faucet", body),
+            Ok(ControlRequest::Fund {
+                recipient: PrincipalData::parse("ST1PQHQKV0RJXZFY1DGX8MNSNYVE3VGZJSRTPGZGM")
+                    .unwrap(),
+                amount: 1000
+            })
+        );
+    }
+
+    #[test]
+    fn parse_fault_requests() {
+        assert_eq!(
+            ControlRequest::parse(&Method::POST, "/v1/faults/latency", br#"{"ms": 2000}"#),
+            Ok(ControlRequest::InjectFault(FaultCommand::SetEventLatency(
+                2000
+            )))
+        );
+        assert_eq!(
+            ControlRequest::parse(&Method::POST, "/v1/faults/drop-events", b""),
+            Ok(ControlRequest::InjectFault(FaultCommand::DropEvents(true)))
+        );
+        assert_eq!(
+            ControlRequest::parse(
+                &Method::POST,
+                "/v1/faults/disconnect-bitcoin",
+                br#"{"enabled": false}"#
+            ),
+            Ok(ControlRequest::InjectFault(
+                FaultCommand::DisconnectBitcoinNode(false)
+            ))
+        );
+        assert_eq!(
+            ControlRequest::parse(&Method::POST, "/v1/services/stacks-api/restart", b""),
+            Ok(ControlRequest::InjectFault(FaultCommand::RestartService(
+                "stacks-api".into()
+            )))
+        );
+        assert_eq!(
+            ControlRequest::parse(&Method::POST, "/v1/services/postgres/stop", b"")
+                .unwrap_err()
+                .0,
+            StatusCode::NOT_FOUND
+        );
+    }
+
+    #[test]
+    fn reject_invalid_control_requests() {
+        let status = |result: Result<ControlRequest, (StatusCode, String)>| result.unwrap_err().0;
+
+        assert_eq!(
+            status(ControlRequest::parse(&Method::GET, "/v1/mine", b"")),
+            StatusCode::METHOD_NOT_ALLOWED
+        );
+        assert_eq!(
+            status(ControlRequest::parse(&Method::GET, "/v1/unknown", b"")),
+            StatusCode::NOT_FOUND
+        );
+        assert_eq!(
+            status(ControlRequest::parse(&Method::POST, "/v1/mine", b"{")),
+            StatusCode::BAD_REQUEST
+        );
+        assert_eq!(
+            status(ControlRequest::parse(
+                &Method::POST,
+                "/v1/faucet",
+                br#"{"address": "not-an-address", "amount": 1000}"#
+            )),
+            StatusCode::BAD_REQUEST
+        );
+    }
+}
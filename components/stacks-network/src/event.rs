@@ -2,8 +2,12 @@ use std::sync::mpsc::Sender;
 
 use chainhook_sdk::{
     observer::MempoolAdmissionData,
-    types::{BitcoinChainEvent, StacksChainEvent},
+    types::{
+        BitcoinBlockData, BitcoinChainEvent, StacksBlockData, StacksChainEvent,
+        StacksMicroblockData, StacksTransactionData,
+    },
 };
+use serde_json::{json, Value as JsonValue};
 
 use crate::{
     chains_coordinator::BitcoinMiningCommand,
@@ -22,6 +26,7 @@ pub enum DevnetEvent {
     StacksChainEvent(StacksChainEvent),
     BitcoinChainEvent(BitcoinChainEvent),
     MempoolAdmission(MempoolAdmissionData),
+    ScenarioCompleted(ScenarioReportData),
     FatalError(String),
     Terminate,
 }
@@ -68,6 +73,176 @@ impl DevnetEvent {
     pub fn log_debug(message: String) -> LogData {
         LogData::new(LogLevel::Debug, message)
     }
+
+    // Events emitted by `clarinet devnet start --json`, one JSON object per line:
+    // {"version": 1, "type": "<type>", "data": {...}}
+    // Fields can be added to `data`, but existing fields must not be renamed or removed
+    // without bumping JSON_EVENTS_VERSION.
+    pub fn to_json_events(&self) -> Vec<JsonValue> {
+        let events = match self {
+            DevnetEvent::Log(log) => vec![(
+                "log",
+                json!({
+                    "level": log_level_name(&log.level),
+                    "message": log.message,
+                    "occurred_at": log.occurred_at,
+                }),
+            )],
+            DevnetEvent::ServiceStatus(status) => vec![(
+                "service_status",
+                json!({
+                    "name": status.name,
+                    "status": match status.status {
+                        Status::Red => "red",
+                        Status::Yellow => "yellow",
+                        Status::Green => "green",
+                    },
+                    "comment": status.comment,
+                }),
+            )],
+            DevnetEvent::ProtocolDeployingProgress(progress) => vec![(
+                "deployment_progress",
+                json!({ "contracts_deployed": progress.new_contracts_deployed }),
+            )],
+            DevnetEvent::BootCompleted(_) => vec![("deployment_completed", json!({}))],
+            DevnetEvent::StacksChainEvent(StacksChainEvent::ChainUpdatedWithBlocks(update)) => {
+                update
+                    .new_blocks
+                    .iter()
+                    .map(|update| ("stacks_block", stacks_block_to_json(&update.block)))
+                    .collect()
+            }
+            DevnetEvent::StacksChainEvent(StacksChainEvent::ChainUpdatedWithMicroblocks(
+                update,
+            )) => update
+                .new_microblocks
+                .iter()
+                .map(|microblock| ("stacks_microblock", stacks_microblock_to_json(microblock)))
+                .collect(),
+            DevnetEvent::BitcoinChainEvent(BitcoinChainEvent::ChainUpdatedWithBlocks(update)) => {
+                update
+                    .new_blocks
+                    .iter()
+                    .map(|block| ("bitcoin_block", bitcoin_block_to_json(block)))
+                    .collect()
+            }
+            DevnetEvent::BitcoinChainEvent(BitcoinChainEvent::ChainUpdatedWithReorg(update)) => {
+                vec![(
+                    "bitcoin_reorg",
+                    json!({
+                        "blocks_to_rollback": update
+                            .blocks_to_rollback
+                            .iter()
+                            .map(bitcoin_block_to_json)
+                            .collect::<Vec<_>>(),
+                        "blocks_to_apply": update
+                            .blocks_to_apply
+                            .iter()
+                            .map(bitcoin_block_to_json)
+                            .collect::<Vec<_>>(),
+                    }),
+                )]
+            }
+            DevnetEvent::MempoolAdmission(tx) => vec![(
+                "mempool_admission",
+                json!({
+                    "tx_data": tx.tx_data,
+                    "tx_description": tx.tx_description,
+                }),
+            )],
+            DevnetEvent::ScenarioCompleted(report) => vec![(
+                "scenario_completed",
+                json!({
+                    "name": report.name,
+                    "passed": report.passed,
+                    "steps_executed": report.steps_executed,
+                    "error": report.error,
+                }),
+            )],
+            DevnetEvent::FatalError(message) => {
+                vec![("fatal_error", json!({ "message": message }))]
+            }
+            DevnetEvent::Terminate => vec![("terminate", json!({}))],
+            // stacks re-orgs are unreachable in the context of devnet, ui events are not exported
+            DevnetEvent::StacksChainEvent(_) | DevnetEvent::KeyEvent(_) | DevnetEvent::Tick => {
+                vec![]
+            }
+        };
+        events
+            .into_iter()
+            .map(|(event_type, data)| {
+                json!({
+                    "version": JSON_EVENTS_VERSION,
+                    "type": event_type,
+                    "data": data,
+                })
+            })
+            .collect()
+    }
+}
+
+pub const JSON_EVENTS_VERSION: u32 = 1;
+
+fn log_level_name(level: &LogLevel) -> &'static str {
+    match level {
+        LogLevel::Error => "error",
+        LogLevel::Warning => "warning",
+        LogLevel::Info => "info",
+        LogLevel::Success => "success",
+        LogLevel::Debug => "debug",
+    }
+}
+
+fn stacks_transaction_to_json(tx: &StacksTransactionData) -> JsonValue {
+    json!({
+        "txid": tx.transaction_identifier.hash,
+        "sender": tx.metadata.sender,
+        "success": tx.metadata.success,
+        "result": tx.metadata.result,
+        "description": tx.metadata.description,
+    })
+}
+
+fn stacks_block_to_json(block: &StacksBlockData) -> JsonValue {
+    json!({
+        "index": block.block_identifier.index,
+        "hash": block.block_identifier.hash,
+        "parent_hash": block.parent_block_identifier.hash,
+        "bitcoin_anchor_block_index": block.metadata.bitcoin_anchor_block_identifier.index,
+        "timestamp": block.timestamp,
+        "transactions": block
+            .transactions
+            .iter()
+            .map(stacks_transaction_to_json)
+            .collect::<Vec<_>>(),
+    })
+}
+
+fn stacks_microblock_to_json(microblock: &StacksMicroblockData) -> JsonValue {
+    json!({
+        "index": microblock.block_identifier.index,
+        "hash": microblock.block_identifier.hash,
+        "parent_hash": microblock.parent_block_identifier.hash,
+        "transactions": microblock
+            .transactions
+            .iter()
+            .map(stacks_transaction_to_json)
+            .collect::<Vec<_>>(),
+    })
+}
+
+fn bitcoin_block_to_json(block: &BitcoinBlockData) -> JsonValue {
+    json!({
+        "index": block.block_identifier.index,
+        "hash": block.block_identifier.hash,
+        "parent_hash": block.parent_block_identifier.hash,
+        "timestamp": block.timestamp,
+        "transactions": block
+            .transactions
+            .iter()
+            .map(|tx| tx.transaction_identifier.hash.clone())
+            .collect::<Vec<_>>(),
+    })
 }
 
 #[derive(Clone, Debug)]
@@ -90,7 +265,57 @@ pub struct ProtocolDeployingData {
     pub new_contracts_deployed: Vec<String>,
 }
 
+#[derive(Clone, Debug)]
+pub struct ScenarioReportData {
+    pub name: String,
+    pub passed: bool,
+    pub steps_executed: usize,
+    pub error: Option<String>,
+}
+
 #[derive(Clone, Debug)]
 pub struct BootCompletedData {
     pub contracts_deployed: Vec<String>,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn serialize_log_event() {
+        let events = DevnetEvent::info("Starting bitcoin-node".into()).to_json_events();
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0]["version"], json!(1));
+        assert_eq!(events[0]["type"], json!("log"));
+        assert_eq!(events[0]["data"]["level"], json!("info"));
+        assert_eq!(events[0]["data"]["message"], json!("Starting bitcoin-node"));
+    }
+
+    #[test]
+    fn serialize_service_status_event() {
+        let event = DevnetEvent::ServiceStatus(ServiceStatusData {
+            order: 1,
+            status: Status::Green,
+            name: "stacks-node".into(),
+            comment: "mining blocks (chaintip = #3)".into(),
+        });
+        assert_eq!(
+            event.to_json_events(),
+            vec![json!({
+                "version": 1,
+                "type": "service_status",
+                "data": {
+                    "name": "stacks-node",
+                    "status": "green",
+                    "comment": "mining blocks (chaintip = #3)",
+                },
+            })]
+        );
+    }
+
+    #[test]
+    fn skip_ui_events() {
+        assert!(DevnetEvent::Tick.to_json_events().is_empty());
+    }
+}
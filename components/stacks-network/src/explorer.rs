@@ -0,0 +1,598 @@
+use crate::chains_coordinator::DevnetEventObserverConfig;
+use crate::event::DevnetEvent;
+use crate::ui::transaction::decode_payload;
+use crate::MempoolAdmissionData;
+
+use chainhook_sdk::types::{StacksBlockData, StacksTransactionData};
+use clarinet_files::AccountConfig;
+use clarity_repl::clarity::codec::StacksMessageCodec;
+use clarity_repl::clarity::util::hash::hex_bytes;
+use clarity_repl::codec::{StacksTransaction, TransactionPayload};
+use hyper::service::{make_service_fn, service_fn};
+use hyper::{Body, Method, Request, Response, Server, StatusCode};
+use serde_json::Value as JsonValue;
+use std::collections::{BTreeMap, VecDeque};
+use std::convert::Infallible;
+use std::io::Cursor;
+use std::net::SocketAddr;
+use std::sync::mpsc::Sender;
+use std::sync::{Arc, RwLock};
+
+// Built-in web explorer, fed with the blocks and mempool events observed by the chains
+// coordinator, usable without the stacks-api, postgres and stacks-explorer containers:
+//
+// GET /                    latest blocks, pending transactions, contracts and accounts balances
+// GET /blocks/<height>     block and its transactions
+// GET /txs/<txid>          transaction details
+// GET /contracts/<id>      contract source
+// GET /address/<principal> balance and transactions of an address
+
+const MAX_INDEXED_BLOCKS: usize = 1000;
+const HOME_BLOCKS_COUNT: usize = 20;
+
+#[derive(Debug, PartialEq)]
+enum ExplorerPage {
+    Home,
+    Block(u64),
+    Transaction(String),
+    Contract(String),
+    Address(String),
+}
+
+impl ExplorerPage {
+    fn parse(method: &Method, path: &str) -> Result<ExplorerPage, (StatusCode, String)> {
+        if *method != Method::GET {
+            return Err((
+                StatusCode::METHOD_NOT_ALLOWED,
+                format!("method {} not allowed on {}", method, path),
+            ));
+        }
+        let not_found = || (StatusCode::NOT_FOUND, format!("unknown page {}", path));
+        let segments = path
+            .trim_matches('/')
+            .split('/')
+            .filter(|segment| !segment.is_empty())
+            .collect::<Vec<_>>();
+        match segments.as_slice() {
+            [] => Ok(ExplorerPage::Home),
+            ["blocks", height] => height
+                .parse::<u64>()
+                .map(ExplorerPage::Block)
+                .map_err(|_| not_found()),
+            ["txs", txid] => Ok(ExplorerPage::Transaction(normalize_txid(txid))),
+            ["contracts", contract_id] => Ok(ExplorerPage::Contract(contract_id.to_string())),
+            ["address", principal] => Ok(ExplorerPage::Address(principal.to_string())),
+            _ => Err(not_found()),
+        }
+    }
+}
+
+fn normalize_txid(txid: &str) -> String {
+    match txid.starts_with("0x") {
+        true => txid.to_string(),
+        false => format!("0x{}", txid),
+    }
+}
+
+#[derive(Default)]
+struct ExplorerIndex {
+    blocks: VecDeque<StacksBlockData>,
+    mempool: Vec<MempoolAdmissionData>,
+    contracts: BTreeMap<String, String>,
+}
+
+pub struct LiteExplorer {
+    index: RwLock<ExplorerIndex>,
+    stacks_node_host: String,
+    accounts: Vec<AccountConfig>,
+}
+
+impl LiteExplorer {
+    pub fn new(config: &DevnetEventObserverConfig) -> LiteExplorer {
+        let mut accounts = config.accounts.clone();
+        accounts.sort_by(|a, b| a.label.cmp(&b.label));
+        LiteExplorer {
+            index: RwLock::new(ExplorerIndex::default()),
+            stacks_node_host: config.services_map_hosts.stacks_node_host.clone(),
+            accounts,
+        }
+    }
+
+    pub fn index_block(&self, block: &StacksBlockData) {
+        let mut index = match self.index.write() {
+            Ok(index) => index,
+            Err(_) => return,
+        };
+        for tx in block.transactions.iter() {
+            if let Some((contract_name, source)) = decode_contract_deployment(&tx.metadata.raw_tx) {
+                let contract_id = format!("{}.{}", tx.metadata.sender, contract_name);
+                index.contracts.insert(contract_id, source);
+            }
+        }
+        let raw_txs = block
+            .transactions
+            .iter()
+            .map(|tx| tx.metadata.raw_tx.as_str())
+            .collect::<Vec<_>>();
+        index
+            .mempool
+            .retain(|item| !raw_txs.contains(&item.tx_data.as_str()));
+        index.blocks.push_back(block.clone());
+        if index.blocks.len() > MAX_INDEXED_BLOCKS {
+            index.blocks.pop_front();
+        }
+    }
+
+    pub fn index_mempool_transaction(&self, tx: &MempoolAdmissionData) {
+        if let Ok(mut index) = self.index.write() {
+            index.mempool.push(tx.clone());
+        }
+    }
+
+    async fn render(&self, page: ExplorerPage) -> Result<String, (StatusCode, String)> {
+        let not_found = |what: &str| (StatusCode::NOT_FOUND, format!("{} not found", what));
+        let body = match page {
+            ExplorerPage::Home => {
+                let mut balances = vec![];
+                for account in self.accounts.iter() {
+                    balances.push((account, self.get_balance(&account.stx_address).await));
+                }
+                let index = self.read_index()?;
+                render_home(&index, &balances)
+            }
+            ExplorerPage::Block(height) => {
+                let index = self.read_index()?;
+                let block = index
+                    .blocks
+                    .iter()
+                    .find(|block| block.block_identifier.index == height)
+                    .ok_or_else(|| not_found("block"))?;
+                render_block(block)
+            }
+            ExplorerPage::Transaction(txid) => {
+                let index = self.read_index()?;
+                let (block, tx) = index
+                    .blocks
+                    .iter()
+                    .rev()
+                    .find_map(|block| {
+                        block
+                            .transactions
+                            .iter()
+                            .find(|tx| tx.transaction_identifier.hash == txid)
+                            .map(|tx| (block, tx))
+                    })
+                    .ok_or_else(|| not_found("transaction"))?;
+                render_transaction(block, tx)
+            }
+            ExplorerPage::Contract(contract_id) => {
+                let index = self.read_index()?;
+                let source = index
+                    .contracts
+                    .get(&contract_id)
+                    .ok_or_else(|| not_found("contract"))?;
+                render_contract(&contract_id, source)
+            }
+            ExplorerPage::Address(principal) => {
+                let balance = self.get_balance(&principal).await;
+                let index = self.read_index()?;
+                render_address(&index, &principal, &balance)
+            }
+        };
+        Ok(body)
+    }
+
+    fn read_index(
+        &self,
+    ) -> Result<std::sync::RwLockReadGuard<ExplorerIndex>, (StatusCode, String)> {
+        self.index
+            .read()
+            .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))
+    }
+
+    async fn get_balance(&self, principal: &str) -> Result<AccountBalance, String> {
+        let url = format!(
+            "http://{}/v2/accounts/{}?proof=0",
+            self.stacks_node_host, principal
+        );
+        let response = reqwest::get(url)
+            .await
+            .map_err(|e| format!("unable to retrieve balance: {}", e))?
+            .json::<JsonValue>()
+            .await
+            .map_err(|e| format!("unable to parse balance: {}", e))?;
+        Ok(AccountBalance {
+            balance: parse_hex_amount(&response["balance"])?,
+            locked: parse_hex_amount(&response["locked"]).unwrap_or(0),
+            nonce: response["nonce"].as_u64().unwrap_or(0),
+        })
+    }
+}
+
+struct AccountBalance {
+    balance: u128,
+    locked: u128,
+    nonce: u64,
+}
+
+// the stacks node returns amounts as hex strings ("0x0000000000000000000000e8d4a51000")
+fn parse_hex_amount(value: &JsonValue) -> Result<u128, String> {
+    let amount = value
+        .as_str()
+        .ok_or_else(|| format!("invalid amount {}", value))?;
+    u128::from_str_radix(amount.trim_start_matches("0x"), 16)
+        .map_err(|e| format!("invalid amount {}: {}", amount, e))
+}
+
+fn decode_contract_deployment(raw_tx: &str) -> Option<(String, String)> {
+    let bytes = hex_bytes(raw_tx.trim_start_matches("0x")).ok()?;
+    let mut cursor = Cursor::new(&bytes);
+    let tx = StacksTransaction::consensus_deserialize(&mut cursor).ok()?;
+    match tx.payload {
+        TransactionPayload::SmartContract(contract, _) => {
+            Some((contract.name.to_string(), contract.code_body.to_string()))
+        }
+        _ => None,
+    }
+}
+
+fn escape_html(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    for c in value.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+fn format_stx(micro_stx: u128) -> String {
+    format!("{}.{:06} STX", micro_stx / 1_000_000, micro_stx % 1_000_000)
+}
+
+fn render_layout(title: &str, content: &str) -> String {
+    format!(
+        r#"<!DOCTYPE html>
+<html>
+<head>
+<meta charset="utf-8">
+<title>{title} - Devnet Explorer</title>
+<style>
+body {{ font-family: sans-serif; margin: 2em; color: #222; }}
+table {{ border-collapse: collapse; margin-bottom: 2em; }}
+td, th {{ border-bottom: 1px solid #ddd; padding: 4px 12px; text-align: left; font-family: monospace; }}
+pre {{ background: #f4f4f4; padding: 1em; overflow-x: auto; }}
+.failed {{ color: #c00; }}
+</style>
+</head>
+<body>
+<p><a href="/">Devnet Explorer</a></p>
+<h1>{title}</h1>
+{content}
+</body>
+</html>
+"#,
+        title = escape_html(title),
+    )
+}
+
+fn render_transactions_table(transactions: &[&StacksTransactionData]) -> String {
+    if transactions.is_empty() {
+        return "<p>No transactions</p>".into();
+    }
+    let mut rows = String::new();
+    for tx in transactions.iter() {
+        rows.push_str(&format!(
+            r#"<tr><td><a href="/txs/{txid}">{txid}</a></td><td><a href="/address/{sender}">{sender}</a></td><td>{description}</td><td class="{class}">{result}</td></tr>"#,
+            txid = escape_html(&tx.transaction_identifier.hash),
+            sender = escape_html(&tx.metadata.sender),
+            description = escape_html(&tx.metadata.description),
+            class = if tx.metadata.success { "success" } else { "failed" },
+            result = escape_html(&tx.metadata.result),
+        ));
+    }
+    format!(
+        "<table><tr><th>Transaction</th><th>Sender</th><th>Description</th><th>Result</th></tr>{}</table>",
+        rows
+    )
+}
+
+fn render_home(
+    index: &ExplorerIndex,
+    balances: &[(&AccountConfig, Result<AccountBalance, String>)],
+) -> String {
+    let mut content = String::from("<h2>Latest blocks</h2>");
+    if index.blocks.is_empty() {
+        content.push_str("<p>Waiting for the first block</p>");
+    } else {
+        content.push_str("<table><tr><th>Height</th><th>Hash</th><th>Bitcoin block</th><th>Transactions</th></tr>");
+        for block in index.blocks.iter().rev().take(HOME_BLOCKS_COUNT) {
+            content.push_str(&format!(
+                r#"<tr><td><a href="/blocks/{height}">#{height}</a></td><td>{hash}</td><td>#{anchor}</td><td>{count}</td></tr>"#,
+                height = block.block_identifier.index,
+                hash = escape_html(&block.block_identifier.hash),
+                anchor = block.metadata.bitcoin_anchor_block_identifier.index,
+                count = block.transactions.len(),
+            ));
+        }
+        content.push_str("</table>");
+    }
+
+    content.push_str("<h2>Mempool</h2>");
+    if index.mempool.is_empty() {
+        content.push_str("<p>No pending transactions</p>");
+    } else {
+        content.push_str("<table><tr><th>Description</th></tr>");
+        for tx in index.mempool.iter() {
+            content.push_str(&format!(
+                "<tr><td>{}</td></tr>",
+                escape_html(&tx.tx_description)
+            ));
+        }
+        content.push_str("</table>");
+    }
+
+    content.push_str("<h2>Contracts</h2>");
+    if index.contracts.is_empty() {
+        content.push_str("<p>No contracts deployed</p>");
+    } else {
+        content.push_str("<table><tr><th>Contract</th></tr>");
+        for contract_id in index.contracts.keys() {
+            content.push_str(&format!(
+                r#"<tr><td><a href="/contracts/{id}">{id}</a></td></tr>"#,
+                id = escape_html(contract_id),
+            ));
+        }
+        content.push_str("</table>");
+    }
+
+    content.push_str("<h2>Accounts</h2><table><tr><th>Account</th><th>Address</th><th>Balance</th><th>Locked</th></tr>");
+    for (account, balance) in balances.iter() {
+        let (balance, locked) = match balance {
+            Ok(balance) => (format_stx(balance.balance), format_stx(balance.locked)),
+            Err(e) => (escape_html(e), "-".to_string()),
+        };
+        content.push_str(&format!(
+            r#"<tr><td>{label}</td><td><a href="/address/{address}">{address}</a></td><td>{balance}</td><td>{locked}</td></tr>"#,
+            label = escape_html(&account.label),
+            address = escape_html(&account.stx_address),
+            balance = balance,
+            locked = locked,
+        ));
+    }
+    content.push_str("</table>");
+
+    render_layout("Devnet", &content)
+}
+
+fn render_block(block: &StacksBlockData) -> String {
+    let height = block.block_identifier.index;
+    let mut content = format!(
+        "<table><tr><th>Hash</th><td>{}</td></tr><tr><th>Parent hash</th><td>{}</td></tr><tr><th>Bitcoin block</th><td>#{}</td></tr><tr><th>Timestamp</th><td>{}</td></tr></table>",
+        escape_html(&block.block_identifier.hash),
+        escape_html(&block.parent_block_identifier.hash),
+        block.metadata.bitcoin_anchor_block_identifier.index,
+        block.timestamp,
+    );
+    if height > 0 {
+        content.push_str(&format!(
+            r#"<p><a href="/blocks/{}">Previous block</a></p>"#,
+            height - 1
+        ));
+    }
+    content.push_str("<h2>Transactions</h2>");
+    let transactions = block.transactions.iter().collect::<Vec<_>>();
+    content.push_str(&render_transactions_table(&transactions));
+    render_layout(&format!("Block #{}", height), &content)
+}
+
+fn render_transaction(block: &StacksBlockData, tx: &StacksTransactionData) -> String {
+    let payload = match decode_payload(&tx.metadata.raw_tx) {
+        Ok(payload) => payload,
+        Err(e) => vec![format!("unable to decode transaction: {}", e)],
+    };
+    let events = tx
+        .metadata
+        .receipt
+        .events
+        .iter()
+        .map(|event| serde_json::to_string(event).unwrap_or_default())
+        .collect::<Vec<_>>();
+    let content = format!(
+        r#"<table><tr><th>Block</th><td><a href="/blocks/{height}">#{height}</a></td></tr><tr><th>Sender</th><td><a href="/address/{sender}">{sender}</a></td></tr><tr><th>Status</th><td class="{class}">{status}</td></tr><tr><th>Result</th><td>{result}</td></tr></table><h2>Payload</h2><pre>{payload}</pre><h2>Events</h2><pre>{events}</pre>"#,
+        height = block.block_identifier.index,
+        sender = escape_html(&tx.metadata.sender),
+        class = if tx.metadata.success {
+            "success"
+        } else {
+            "failed"
+        },
+        status = if tx.metadata.success {
+            "success"
+        } else {
+            "failed"
+        },
+        result = escape_html(&tx.metadata.result),
+        payload = escape_html(&payload.join("\n")),
+        events = escape_html(&events.join("\n")),
+    );
+    render_layout(&tx.transaction_identifier.hash, &content)
+}
+
+fn render_contract(contract_id: &str, source: &str) -> String {
+    let content = format!("<pre>{}</pre>", escape_html(source));
+    render_layout(contract_id, &content)
+}
+
+fn render_address(
+    index: &ExplorerIndex,
+    principal: &str,
+    balance: &Result<AccountBalance, String>,
+) -> String {
+    let mut content = match balance {
+        Ok(balance) => format!(
+            "<table><tr><th>Balance</th><td>{}</td></tr><tr><th>Locked</th><td>{}</td></tr><tr><th>Nonce</th><td>{}</td></tr></table>",
+            format_stx(balance.balance),
+            format_stx(balance.locked),
+            balance.nonce,
+        ),
+        Err(e) => format!("<p>{}</p>", escape_html(e)),
+    };
+    let transactions = index
+        .blocks
+        .iter()
+        .rev()
+        .flat_map(|block| block.transactions.iter())
+        .filter(|tx| tx.metadata.sender == principal)
+        .collect::<Vec<_>>();
+    content.push_str("<h2>Transactions</h2>");
+    content.push_str(&render_transactions_table(&transactions));
+    render_layout(principal, &content)
+}
+
+fn html_response(status: StatusCode, body: String) -> Response<Body> {
+    Response::builder()
+        .status(status)
+        .header("Content-Type", "text/html; charset=utf-8")
+        .body(Body::from(body))
+        .expect("unable to build response")
+}
+
+async fn handle_request(
+    request: Request<Body>,
+    explorer: Arc<LiteExplorer>,
+) -> Result<Response<Body>, Infallible> {
+    let result = match ExplorerPage::parse(request.method(), request.uri().path()) {
+        Ok(page) => explorer.render(page).await,
+        Err(e) => Err(e),
+    };
+    let response = match result {
+        Ok(body) => html_response(StatusCode::OK, body),
+        Err((status, message)) => html_response(
+            status,
+            render_layout(
+                status.canonical_reason().unwrap_or("Error"),
+                &format!("<p>{}</p>", escape_html(&message)),
+            ),
+        ),
+    };
+    Ok(response)
+}
+
+pub async fn start_lite_explorer(
+    explorer: Arc<LiteExplorer>,
+    port: u16,
+    devnet_event_tx: &Sender<DevnetEvent>,
+) -> Result<(), String> {
+    let make_service = make_service_fn(move |_| {
+        let explorer = explorer.clone();
+        async move {
+            Ok::<_, Infallible>(service_fn(move |request| {
+                handle_request(request, explorer.clone())
+            }))
+        }
+    });
+
+    let address = SocketAddr::from(([127, 0, 0, 1], port));
+    let server = Server::try_bind(&address)
+        .map_err(|e| format!("unable to start explorer on port {}: {}", port, e))?
+        .serve(make_service);
+
+    let _ = devnet_event_tx.send(DevnetEvent::info(format!(
+        "Explorer available on http://localhost:{}",
+        port
+    )));
+    server.await.map_err(|e| format!("explorer stopped: {}", e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use clarinet_files::{DEFAULT_DERIVATION_PATH, DEFAULT_FAUCET_MNEMONIC};
+    use clarity_repl::clarity::util::hash::to_hex;
+    use clarity_repl::clarity::ContractName;
+    use clarity_repl::codec::TransactionAnchorMode;
+    use stacks_rpc_client::crypto::{encode_contract_publish, Wallet};
+    use std::convert::TryFrom;
+
+    #[test]
+    fn parse_explorer_pages() {
+        assert_eq!(
+            ExplorerPage::parse(&Method::GET, "/"),
+            Ok(ExplorerPage::Home)
+        );
+        assert_eq!(
+            ExplorerPage::parse(&Method::GET, "/blocks/12"),
+            Ok(ExplorerPage::Block(12))
+        );
+        assert_eq!(
+            ExplorerPage::parse(&Method::GET, "/txs/abcd"),
+            Ok(ExplorerPage::Transaction("0xabcd".into()))
+        );
+        assert_eq!(
+            ExplorerPage::parse(
+                &Method::GET,
+                "/contracts/ST1PQHQKV0RJXZFY1DGX8MNSNYVE3VGZJSRTPGZGM.counter"
+            ),
+            Ok(ExplorerPage::Contract(
+                "ST1PQHQKV0RJXZFY1DGX8MNSNYVE3VGZJSRTPGZGM.counter".into()
+            ))
+        );
+        assert_eq!(
+            ExplorerPage::parse(&Method::GET, "/blocks/tip")
+                .unwrap_err()
+                .0,
+            StatusCode::NOT_FOUND
+        );
+        assert_eq!(
+            ExplorerPage::parse(&Method::POST, "/").unwrap_err().0,
+            StatusCode::METHOD_NOT_ALLOWED
+        );
+    }
+
+    #[test]
+    fn decode_deployed_contracts() {
+        let wallet = Wallet {
+            mnemonic: DEFAULT_FAUCET_MNEMONIC.into(),
+            derivation: DEFAULT_DERIVATION_PATH.into(),
+            mainnet: false,
+        };
+        let source = "(define-read-only (get-count) u1)";
+        let tx = encode_contract_publish(
+            &ContractName::try_from("counter".to_string()).unwrap(),
+            source,
+            None,
+            &wallet,
+            0,
+            2000,
+            TransactionAnchorMode::Any,
+        )
+        .unwrap();
+        let raw_tx = format!("0x{}", to_hex(&tx.serialize_to_vec()));
+
+        assert_eq!(
+            decode_contract_deployment(&raw_tx),
+            Some(("counter".to_string(), source.to_string()))
+        );
+        assert_eq!(decode_contract_deployment("0x00"), None);
+    }
+
+    #[test]
+    fn format_amounts_and_html() {
+        assert_eq!(
+            parse_hex_amount(&JsonValue::from("0x0000000000000000000000e8d4a51000")),
+            Ok(1_000_000_000_000)
+        );
+        assert!(parse_hex_amount(&JsonValue::Null).is_err());
+        assert_eq!(format_stx(1_500_000), "1.500000 STX");
+        assert_eq!(
+            escape_html("(ok \"<script>\")"),
+            "(ok &quot;&lt;script&gt;&quot;)"
+        );
+    }
+}
@@ -0,0 +1,424 @@
+use crate::chains_coordinator::DevnetEventObserverConfig;
+use crate::event::DevnetEvent;
+use crate::runtime::ContainerRuntime;
+
+use clarinet_files::DevnetConfig;
+use hyper::service::{make_service_fn, service_fn};
+use hyper::{Body, Request, Response, Server, StatusCode};
+use std::convert::Infallible;
+use std::fmt;
+use std::net::SocketAddr;
+use std::sync::mpsc::Sender;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+// the services that can be paused or restarted on command
+const SERVICES: [&str; 6] = [
+    "bitcoin-node",
+    "stacks-node",
+    "stacks-api",
+    "postgres",
+    "stacks-explorer",
+    "bitcoin-explorer",
+];
+
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct FaultsState {
+    // delay applied to the deliveries of the stacks-node events
+    pub event_latency_ms: u64,
+    pub drop_events: bool,
+    pub bitcoin_disconnected: bool,
+    pub paused_services: Vec<String>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum FaultCommand {
+    SetEventLatency(u64),
+    DropEvents(bool),
+    DisconnectBitcoinNode(bool),
+    PauseService(String),
+    ResumeService(String),
+    RestartService(String),
+    Reset,
+}
+
+impl fmt::Display for FaultCommand {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            FaultCommand::SetEventLatency(0) => write!(f, "events delivered without latency"),
+            FaultCommand::SetEventLatency(ms) => {
+                write!(f, "events delivered with {}ms latency", ms)
+            }
+            FaultCommand::DropEvents(true) => write!(f, "events deliveries dropped"),
+            FaultCommand::DropEvents(false) => write!(f, "events deliveries restored"),
+            FaultCommand::DisconnectBitcoinNode(true) => write!(f, "bitcoin-node disconnected"),
+            FaultCommand::DisconnectBitcoinNode(false) => write!(f, "bitcoin-node reconnected"),
+            FaultCommand::PauseService(service) => write!(f, "{} paused", service),
+            FaultCommand::ResumeService(service) => write!(f, "{} resumed", service),
+            FaultCommand::RestartService(service) => write!(f, "{} restarted", service),
+            FaultCommand::Reset => write!(f, "faults cleared"),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct FaultInjector {
+    state: Mutex<FaultsState>,
+    proxy_enabled: bool,
+    network_name: String,
+    services: Vec<String>,
+    runtime: Option<Arc<dyn ContainerRuntime>>,
+}
+
+impl FaultInjector {
+    pub fn new(
+        devnet_config: &DevnetConfig,
+        network_name: &str,
+        runtime: Option<Arc<dyn ContainerRuntime>>,
+    ) -> FaultInjector {
+        let mut services: Vec<String> = SERVICES.iter().map(|s| s.to_string()).collect();
+        for node in devnet_config.stacks_nodes.iter() {
+            services.push(node.name.clone());
+        }
+        FaultInjector {
+            state: Mutex::new(FaultsState::default()),
+            proxy_enabled: devnet_config.enable_fault_injection,
+            network_name: network_name.to_string(),
+            services,
+            runtime,
+        }
+    }
+
+    // without the proxy, only the explicit service faults of the control api are available
+    pub fn is_enabled(&self) -> bool {
+        self.proxy_enabled
+    }
+
+    pub fn state(&self) -> FaultsState {
+        match self.state.lock() {
+            Ok(state) => state.clone(),
+            Err(_) => FaultsState::default(),
+        }
+    }
+
+    pub async fn execute(&self, command: FaultCommand) -> Result<FaultsState, String> {
+        match command {
+            FaultCommand::SetEventLatency(ms) => {
+                self.update_proxy_faults(|state| state.event_latency_ms = ms)
+            }
+            FaultCommand::DropEvents(drop_events) => {
+                self.update_proxy_faults(|state| state.drop_events = drop_events)
+            }
+            FaultCommand::DisconnectBitcoinNode(disconnected) => {
+                self.update_proxy_faults(|state| state.bitcoin_disconnected = disconnected)
+            }
+            FaultCommand::PauseService(service) => {
+                let container = self.service_container(&service)?;
+                self.get_runtime()?.pause_container(&container).await?;
+                self.update_state(|state| {
+                    if !state.paused_services.contains(&service) {
+                        state.paused_services.push(service)
+                    }
+                })
+            }
+            FaultCommand::ResumeService(service) => {
+                let container = self.service_container(&service)?;
+                self.get_runtime()?.unpause_container(&container).await?;
+                self.update_state(|state| state.paused_services.retain(|s| s != &service))
+            }
+            FaultCommand::RestartService(service) => {
+                let container = self.service_container(&service)?;
+                self.get_runtime()?.restart_container(&container).await?;
+                self.update_state(|state| state.paused_services.retain(|s| s != &service))
+            }
+            FaultCommand::Reset => {
+                let paused_services = self.state().paused_services;
+                for service in paused_services.iter() {
+                    let container = self.service_container(service)?;
+                    self.get_runtime()?.unpause_container(&container).await?;
+                }
+                self.update_state(|state| *state = FaultsState::default())
+            }
+        }
+    }
+
+    fn update_state<F>(&self, update: F) -> Result<FaultsState, String>
+    where
+        F: FnOnce(&mut FaultsState),
+    {
+        let mut state = self.state.lock().map_err(|e| e.to_string())?;
+        update(&mut state);
+        Ok(state.clone())
+    }
+
+    fn update_proxy_faults<F>(&self, update: F) -> Result<FaultsState, String>
+    where
+        F: FnOnce(&mut FaultsState),
+    {
+        if !self.proxy_enabled {
+            return Err(
+                "fault injection is disabled (enable_fault_injection = true in settings/Devnet.toml)"
+                    .into(),
+            );
+        }
+        self.update_state(update)
+    }
+
+    fn service_container(&self, service: &str) -> Result<String, String> {
+        if !self.services.iter().any(|s| s == service) {
+            return Err(format!(
+                "unknown service {} (expected one of {})",
+                service,
+                self.services.join(", ")
+            ));
+        }
+        Ok(format!("{}.{}", service, self.network_name))
+    }
+
+    fn get_runtime(&self) -> Result<&Arc<dyn ContainerRuntime>, String> {
+        match self.runtime {
+            Some(ref runtime) => Ok(runtime),
+            None => Err("the devnet services are not managed by clarinet".into()),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq)]
+enum ProxyAction {
+    Forward(Duration),
+    Drop,
+    Refuse,
+}
+
+// the bitcoin rpc calls are posted on `/`, the events on `/new_block`, `/new_burn_block`, etc.
+fn get_proxy_action(state: &FaultsState, path: &str) -> ProxyAction {
+    if path == "/" {
+        match state.bitcoin_disconnected {
+            true => ProxyAction::Refuse,
+            false => ProxyAction::Forward(Duration::ZERO),
+        }
+    } else if state.drop_events {
+        ProxyAction::Drop
+    } else {
+        ProxyAction::Forward(Duration::from_millis(state.event_latency_ms))
+    }
+}
+
+async fn forward_request(
+    request: Request<Body>,
+    ingestion_url: &str,
+) -> Result<Response<Body>, String> {
+    let method = reqwest::Method::from_bytes(request.method().as_str().as_bytes())
+        .map_err(|e| e.to_string())?;
+    let path = match request.uri().path_and_query() {
+        Some(path) => path.to_string(),
+        None => "/".to_string(),
+    };
+    let mut builder = reqwest::Client::new().request(method, format!("{}{}", ingestion_url, path));
+    for (name, value) in request.headers().iter() {
+        if name != hyper::header::HOST {
+            builder = builder.header(name.as_str(), value.as_bytes());
+        }
+    }
+    let body = hyper::body::to_bytes(request.into_body())
+        .await
+        .map_err(|e| e.to_string())?;
+    let response = builder
+        .body(body.to_vec())
+        .send()
+        .await
+        .map_err(|e| e.to_string())?;
+
+    let mut proxied = Response::builder().status(response.status().as_u16());
+    if let Some(content_type) = response.headers().get(reqwest::header::CONTENT_TYPE) {
+        proxied = proxied.header(hyper::header::CONTENT_TYPE, content_type.as_bytes());
+    }
+    let body = response.bytes().await.map_err(|e| e.to_string())?;
+    proxied.body(Body::from(body)).map_err(|e| e.to_string())
+}
+
+async fn handle_proxy_request(
+    request: Request<Body>,
+    faults: Arc<FaultInjector>,
+    ingestion_url: Arc<String>,
+) -> Result<Response<Body>, Infallible> {
+    let status = |status: StatusCode| {
+        Response::builder()
+            .status(status)
+            .body(Body::empty())
+            .expect("unable to build response")
+    };
+    let response = match get_proxy_action(&faults.state(), request.uri().path()) {
+        ProxyAction::Refuse => status(StatusCode::SERVICE_UNAVAILABLE),
+        ProxyAction::Drop => status(StatusCode::OK),
+        ProxyAction::Forward(latency) => {
+            if !latency.is_zero() {
+                tokio::time::sleep(latency).await;
+            }
+            match forward_request(request, &ingestion_url).await {
+                Ok(response) => response,
+                Err(_) => status(StatusCode::BAD_GATEWAY),
+            }
+        }
+    };
+    Ok(response)
+}
+
+// Sits between the stacks nodes and the event observer / bitcoin rpc proxy of the orchestrator
+pub async fn start_fault_proxy(
+    config: &DevnetEventObserverConfig,
+    faults: Arc<FaultInjector>,
+    devnet_event_tx: &Sender<DevnetEvent>,
+) -> Result<(), String> {
+    let port = config.devnet_config.fault_proxy_port;
+    let ingestion_url = Arc::new(format!(
+        "http://127.0.0.1:{}",
+        config.devnet_config.orchestrator_ingestion_port
+    ));
+
+    let make_service = make_service_fn(move |_| {
+        let faults = faults.clone();
+        let ingestion_url = ingestion_url.clone();
+        async move {
+            Ok::<_, Infallible>(service_fn(move |request| {
+                handle_proxy_request(request, faults.clone(), ingestion_url.clone())
+            }))
+        }
+    });
+
+    let address = SocketAddr::from(([0, 0, 0, 0], port));
+    let server = Server::try_bind(&address)
+        .map_err(|e| format!("unable to start fault proxy on port {}: {}", port, e))?
+        .serve(make_service);
+
+    let _ = devnet_event_tx.send(DevnetEvent::info(format!(
+        "Fault injection proxy listening on port {}",
+        port
+    )));
+    server
+        .await
+        .map_err(|e| format!("fault proxy stopped: {}", e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::runtime::fake::FakeRuntime;
+    use crate::test_utils::create_test_project;
+    use clarinet_files::chainhook_types::StacksNetwork;
+    use clarinet_files::{DevnetConfigFile, FileLocation, NetworkManifest};
+
+    fn build_devnet_config(enable_fault_injection: bool) -> DevnetConfig {
+        let project_dir = create_test_project(&format!("faults-{}", enable_fault_injection));
+        let overrides = DevnetConfigFile {
+            enable_fault_injection: Some(enable_fault_injection),
+            ..Default::default()
+        };
+        NetworkManifest::from_project_manifest_location(
+            &FileLocation::from_path(project_dir.join("Clarinet.toml")),
+            &StacksNetwork::Devnet.get_networks(),
+            None,
+            Some(overrides),
+        )
+        .unwrap()
+        .devnet
+        .unwrap()
+    }
+
+    #[test]
+    fn select_proxy_actions() {
+        let mut state = FaultsState::default();
+        assert_eq!(
+            get_proxy_action(&state, "/new_block"),
+            ProxyAction::Forward(Duration::ZERO)
+        );
+
+        state.event_latency_ms = 500;
+        state.bitcoin_disconnected = true;
+        assert_eq!(
+            get_proxy_action(&state, "/new_block"),
+            ProxyAction::Forward(Duration::from_millis(500))
+        );
+        assert_eq!(get_proxy_action(&state, "/"), ProxyAction::Refuse);
+
+        state.drop_events = true;
+        state.bitcoin_disconnected = false;
+        assert_eq!(
+            get_proxy_action(&state, "/new_burn_block"),
+            ProxyAction::Drop
+        );
+        assert_eq!(
+            get_proxy_action(&state, "/"),
+            ProxyAction::Forward(Duration::ZERO)
+        );
+    }
+
+    #[test]
+    fn inject_service_faults() {
+        let runtime = FakeRuntime::default();
+        let operations = runtime.operations.clone();
+        let faults = FaultInjector::new(
+            &build_devnet_config(false),
+            "project.devnet",
+            Some(Arc::new(runtime)),
+        );
+
+        hiro_system_kit::nestable_block_on(async {
+            let state = faults
+                .execute(FaultCommand::PauseService("stacks-node".into()))
+                .await
+                .unwrap();
+            assert_eq!(state.paused_services, vec!["stacks-node".to_string()]);
+            faults
+                .execute(FaultCommand::RestartService("postgres".into()))
+                .await
+                .unwrap();
+            let state = faults.execute(FaultCommand::Reset).await.unwrap();
+            assert_eq!(state, FaultsState::default());
+
+            assert!(faults
+                .execute(FaultCommand::PauseService("unknown".into()))
+                .await
+                .is_err());
+            // the proxy faults require the proxy
+            assert!(faults
+                .execute(FaultCommand::DropEvents(true))
+                .await
+                .is_err());
+        });
+
+        assert_eq!(
+            *operations.lock().unwrap(),
+            vec![
+                "pause_container stacks-node.project.devnet",
+                "restart_container postgres.project.devnet",
+                "unpause_container stacks-node.project.devnet",
+            ]
+        );
+    }
+
+    #[test]
+    fn inject_proxy_faults() {
+        let faults = FaultInjector::new(&build_devnet_config(true), "project.devnet", None);
+
+        hiro_system_kit::nestable_block_on(async {
+            faults
+                .execute(FaultCommand::SetEventLatency(1000))
+                .await
+                .unwrap();
+            let state = faults
+                .execute(FaultCommand::DisconnectBitcoinNode(true))
+                .await
+                .unwrap();
+            assert_eq!(state.event_latency_ms, 1000);
+            assert!(state.bitcoin_disconnected);
+            assert!(!state.drop_events);
+
+            // without runtime, the services can't be paused
+            assert!(faults
+                .execute(FaultCommand::PauseService("stacks-node".into()))
+                .await
+                .is_err());
+        });
+    }
+}
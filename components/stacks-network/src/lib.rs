@@ -5,9 +5,17 @@ extern crate serde_derive;
 
 mod chainhooks;
 pub mod chains_coordinator;
+mod control_server;
 mod event;
+mod explorer;
+mod faults;
 mod log;
 mod orchestrator;
+mod runtime;
+mod scenario;
+mod snapshot;
+#[cfg(test)]
+mod test_utils;
 mod ui;
 
 pub use chainhook_sdk::observer::MempoolAdmissionData;
@@ -17,11 +25,14 @@ pub use chainhooks::{load_chainhooks, parse_chainhook_full_specification};
 use chains_coordinator::BitcoinMiningCommand;
 use clarinet_files::NetworkManifest;
 pub use event::DevnetEvent;
+use faults::FaultInjector;
 pub use log::{LogData, LogLevel};
 pub use orchestrator::DevnetOrchestrator;
 use orchestrator::ServicesMapHosts;
+use snapshot::SnapshotConfig;
 use std::{
     sync::mpsc::{self, channel, Receiver, Sender},
+    sync::Arc,
     thread::sleep,
     time::Duration,
 };
@@ -52,6 +63,7 @@ async fn do_run_devnet(
     chainhooks: &mut Option<ChainhookConfig>,
     log_tx: Option<Sender<LogData>>,
     display_dashboard: bool,
+    json_output: bool,
     ctx: Context,
     orchestrator_terminated_tx: Sender<bool>,
     orchestrator_terminated_rx: Option<Receiver<bool>>,
@@ -102,7 +114,20 @@ async fn do_run_devnet(
         _ => ChainhookConfig::new(),
     };
     let devnet_path = devnet_config.working_dir.clone();
-    let config = DevnetEventObserverConfig::new(
+
+    // snapshots are only taken / restored when the local services are managed by clarinet
+    let snapshot = match devnet_config.snapshot {
+        Some(ref name) if start_local_devnet_services => Some(SnapshotConfig::new(
+            name,
+            &devnet_config,
+            &devnet,
+            &deployment,
+        )?),
+        _ => None,
+    };
+    devnet.snapshot = snapshot.clone();
+
+    let mut config = DevnetEventObserverConfig::new(
         devnet_config.clone(),
         devnet.manifest.clone(),
         network_manifest,
@@ -111,6 +136,13 @@ async fn do_run_devnet(
         &ctx,
         ip_address_setup,
     );
+    config.snapshot = snapshot;
+    let faults = Arc::new(FaultInjector::new(
+        &devnet_config,
+        devnet.network_name(),
+        devnet.runtime(),
+    ));
+    config.faults = Some(faults.clone());
 
     let chains_coordinator_tx = devnet_events_tx.clone();
     let (chains_coordinator_commands_tx, chains_coordinator_commands_rx) =
@@ -187,6 +219,7 @@ async fn do_run_devnet(
             &devnet_path,
             devnet_config.enable_subnet_node,
             !devnet_config.bitcoin_controller_automining_disabled,
+            faults,
             &ctx,
         )?;
 
@@ -215,7 +248,15 @@ async fn do_run_devnet(
 
         if log_tx.is_none() {
             loop {
-                match devnet_events_rx.recv() {
+                let event = devnet_events_rx.recv();
+                if json_output {
+                    if let Ok(ref event) = event {
+                        for json_event in event.to_json_events() {
+                            println!("{}", json_event);
+                        }
+                    }
+                }
+                match event {
                     Ok(DevnetEvent::Log(log)) => {
                         if let Some(ref log_tx) = log_tx {
                             let _ = log_tx.send(log.clone());
@@ -282,6 +323,7 @@ pub async fn do_run_chain_coordinator(
         chainhooks,
         log_tx,
         false,
+        false,
         ctx,
         orchestrator_terminated_tx,
         None,
@@ -298,6 +340,7 @@ pub async fn do_run_local_devnet(
     chainhooks: &mut Option<ChainhookConfig>,
     log_tx: Option<Sender<LogData>>,
     display_dashboard: bool,
+    json_output: bool,
     ctx: Context,
     orchestrator_terminated_tx: Sender<bool>,
     orchestrator_terminated_rx: Option<Receiver<bool>>,
@@ -316,6 +359,7 @@ pub async fn do_run_local_devnet(
         chainhooks,
         log_tx,
         display_dashboard,
+        json_output,
         ctx,
         orchestrator_terminated_tx,
         orchestrator_terminated_rx,
@@ -1,18 +1,12 @@
-use bollard::container::{
-    Config, CreateContainerOptions, KillContainerOptions, ListContainersOptions,
-    PruneContainersOptions, WaitContainerOptions,
-};
-use bollard::errors::Error as DockerError;
-use bollard::exec::CreateExecOptions;
-use bollard::image::CreateImageOptions;
+use bollard::container::Config;
 use bollard::models::{HostConfig, PortBinding};
-use bollard::network::{CreateNetworkOptions, PruneNetworksOptions};
-use bollard::service::Ipam;
-use bollard::Docker;
 use chainhook_sdk::utils::Context;
 use clarinet_files::chainhook_types::StacksNetwork;
-use clarinet_files::{DevnetConfigFile, NetworkManifest, ProjectManifest};
-use futures::stream::TryStreamExt;
+use clarinet_files::{
+    DevnetConfig, DevnetConfigFile, NetworkManifest, ProjectManifest, StacksNodeConfig,
+};
+use clarity_repl::clarity::util::hash::hex_bytes;
+use clarity_repl::clarity::util::secp256k1::{Secp256k1PrivateKey, Secp256k1PublicKey};
 use hiro_system_kit::slog;
 use reqwest::RequestBuilder;
 use serde_json::Value as JsonValue;
@@ -21,9 +15,12 @@ use std::fs::{self, File};
 use std::io::Write;
 use std::path::PathBuf;
 use std::sync::mpsc::{Receiver, Sender};
+use std::sync::Arc;
 use std::time::Duration;
 
 use crate::event::{DevnetEvent, ServiceStatusData, Status};
+use crate::runtime::{connect_runtime, ContainerRuntime};
+use crate::snapshot::SnapshotConfig;
 
 #[derive(Debug)]
 pub struct DevnetOrchestrator {
@@ -34,6 +31,7 @@ pub struct DevnetOrchestrator {
     pub termination_success_tx: Option<Sender<bool>>,
     pub can_exit: bool,
     stacks_node_container_id: Option<String>,
+    stacks_nodes_container_ids: Vec<String>,
     stacks_api_container_id: Option<String>,
     stacks_explorer_container_id: Option<String>,
     bitcoin_node_container_id: Option<String>,
@@ -41,8 +39,9 @@ pub struct DevnetOrchestrator {
     postgres_container_id: Option<String>,
     subnet_node_container_id: Option<String>,
     subnet_api_container_id: Option<String>,
-    docker_client: Option<Docker>,
+    runtime: Option<Arc<dyn ContainerRuntime>>,
     services_map_hosts: Option<ServicesMapHosts>,
+    pub snapshot: Option<SnapshotConfig>,
 }
 
 // pub enum DevnetServices {
@@ -114,29 +113,19 @@ impl DevnetOrchestrator {
             network_name.push_str(".net");
         }
 
-        let docker_client = match should_use_docker {
+        let runtime = match should_use_docker {
             true => match network_config.devnet {
-                Some(ref devnet) => {
-                    let client = Docker::connect_with_socket(
-                        &devnet.docker_host,
-                        120,
-                        bollard::API_DEFAULT_VERSION,
-                    )
-                    .or_else(|_| Docker::connect_with_socket_defaults())
-                    .or_else(|_| {
-                        let mut user_space_docker_socket =
-                            dirs::home_dir().expect("unable to retrieve homedir");
-                        user_space_docker_socket.push(".docker");
-                        user_space_docker_socket.push("run");
-                        user_space_docker_socket.push("docker.sock");
-                        Docker::connect_with_socket(
-                            user_space_docker_socket.to_str().unwrap(),
-                            120,
-                            bollard::API_DEFAULT_VERSION,
-                        )
-                    })
-                    .map_err(|e| format!("unable to connect to docker: {:?}", e))?;
-                    Some(client)
+                Some(ref mut devnet) => {
+                    let runtime = connect_runtime(devnet)?;
+                    if !runtime.supports_images() {
+                        // only the bitcoin and stacks nodes can run without containers
+                        devnet.disable_stacks_api = true;
+                        devnet.disable_stacks_explorer = true;
+                        devnet.disable_bitcoin_explorer = true;
+                        devnet.enable_subnet_node = false;
+                        devnet.disable_subnet_api = true;
+                    }
+                    Some(Arc::from(runtime))
                 }
                 None => unreachable!(),
             },
@@ -148,10 +137,11 @@ impl DevnetOrchestrator {
             network_name,
             manifest,
             network_config: Some(network_config),
-            docker_client,
+            runtime,
             can_exit: true,
             termination_success_tx: None,
             stacks_node_container_id: None,
+            stacks_nodes_container_ids: vec![],
             stacks_api_container_id: None,
             stacks_explorer_container_id: None,
             bitcoin_node_container_id: None,
@@ -160,9 +150,52 @@ impl DevnetOrchestrator {
             subnet_node_container_id: None,
             subnet_api_container_id: None,
             services_map_hosts: None,
+            snapshot: None,
         })
     }
 
+    // the path of a mounted file or directory, as seen by the service
+    fn service_path(&self, host_path: &str, container_path: &str) -> String {
+        match &self.runtime {
+            Some(runtime) => runtime.service_path(host_path, container_path),
+            None => container_path.to_string(),
+        }
+    }
+
+    // the address used by the services to reach the orchestrator
+    fn host_address(&self) -> &str {
+        match &self.runtime {
+            Some(runtime) => runtime.host_address(),
+            None => "host.docker.internal",
+        }
+    }
+
+    pub fn network_name(&self) -> &str {
+        &self.network_name
+    }
+
+    // shared with the fault injector, to pause and restart the services on command
+    pub fn runtime(&self) -> Option<Arc<dyn ContainerRuntime>> {
+        self.runtime.clone()
+    }
+
+    // the port the stacks nodes send their events and bitcoin rpc calls to
+    fn orchestrator_port(&self, devnet_config: &DevnetConfig) -> u16 {
+        if devnet_config.enable_fault_injection {
+            devnet_config.fault_proxy_port
+        } else {
+            devnet_config.orchestrator_ingestion_port
+        }
+    }
+
+    // the address used by the services to reach another service
+    fn service_host(&self, service: &str) -> String {
+        match &self.runtime {
+            Some(runtime) if !runtime.supports_images() => runtime.host_address().to_string(),
+            _ => format!("{}.{}", service, self.network_name),
+        }
+    }
+
     pub fn prepare_network_k8s_coordinator(
         &mut self,
         namespace: &str,
@@ -186,9 +219,9 @@ impl DevnetOrchestrator {
     }
 
     pub async fn prepare_local_network(&mut self) -> Result<ServicesMapHosts, String> {
-        let (docker, devnet_config) = match (&self.docker_client, &self.network_config) {
-            (Some(ref docker), Some(ref network_config)) => match network_config.devnet {
-                Some(ref devnet_config) => (docker, devnet_config),
+        let (runtime, devnet_config) = match (&self.runtime, &self.network_config) {
+            (Some(ref runtime), Some(ref network_config)) => match network_config.devnet {
+                Some(ref devnet_config) => (runtime, devnet_config),
                 _ => return Err("unable to get devnet config".to_string()),
             },
             _ => return Err("unable to get devnet config".to_string()),
@@ -197,53 +230,19 @@ impl DevnetOrchestrator {
         // First, let's make sure that we pruned staled resources correctly
         // self.clean_previous_session().await?;
 
-        let mut labels = HashMap::new();
-        labels.insert("project", self.network_name.as_str());
-
-        let mut options = HashMap::new();
-        options.insert("enable_ip_masquerade", "true");
-        options.insert("enable_icc", "true");
-        options.insert("host_binding_ipv4", "0.0.0.0");
-        options.insert("com.docker.network.bridge.enable_icc", "true");
-        options.insert("com.docker.network.bridge.enable_ip_masquerade", "true");
-        options.insert("com.docker.network.bridge.host_binding_ipv4", "0.0.0.0");
-
-        let network_id = docker
-            .create_network::<&str>(CreateNetworkOptions {
-                name: &self.network_name,
-                driver: "bridge",
-                ipam: Ipam {
-                    ..Default::default()
-                },
-                labels,
-                options,
-                ..Default::default()
-            })
+        let gateway = runtime
+            .create_network(&self.network_name)
             .await
             .map_err(|e| {
                 format!(
-                    "clarinet was unable to create network. Is docker running locally? (error: {})",
+                    "clarinet was unable to create network. Is {} running locally? (error: {})",
+                    runtime.name(),
                     e
                 )
-            })?
-            .id
-            .ok_or("unable to retrieve network_id")?;
+            })?;
 
-        let res = docker
-            .inspect_network::<&str>(&network_id, None)
-            .await
-            .map_err(|e| format!("unable to retrieve network: {}", e))?;
-
-        let gateway = res
-            .ipam
-            .as_ref()
-            .and_then(|ipam| ipam.config.as_ref())
-            .and_then(|config| config.first())
-            .and_then(|map| map.gateway.clone())
-            .ok_or("unable to retrieve gateway")?;
-
-        let services_map_hosts = if devnet_config.use_docker_gateway_routing {
-            ServicesMapHosts {
+        let services_map_hosts = match gateway {
+            Some(gateway) if devnet_config.use_docker_gateway_routing => ServicesMapHosts {
                 bitcoin_node_host: format!("{}:{}", gateway, devnet_config.bitcoin_node_rpc_port),
                 stacks_node_host: format!("{}:{}", gateway, devnet_config.stacks_node_rpc_port),
                 postgres_host: format!("{}:{}", gateway, devnet_config.postgres_port),
@@ -255,9 +254,8 @@ impl DevnetOrchestrator {
                 ),
                 subnet_node_host: format!("{}:{}", gateway, devnet_config.subnet_node_rpc_port),
                 subnet_api_host: format!("{}:{}", gateway, devnet_config.subnet_api_port),
-            }
-        } else {
-            ServicesMapHosts {
+            },
+            _ => ServicesMapHosts {
                 bitcoin_node_host: format!("localhost:{}", devnet_config.bitcoin_node_rpc_port),
                 stacks_node_host: format!("localhost:{}", devnet_config.stacks_node_rpc_port),
                 postgres_host: format!("localhost:{}", devnet_config.postgres_port),
@@ -266,7 +264,7 @@ impl DevnetOrchestrator {
                 bitcoin_explorer_host: format!("localhost:{}", devnet_config.bitcoin_explorer_port),
                 subnet_node_host: format!("localhost:{}", devnet_config.subnet_node_rpc_port),
                 subnet_api_host: format!("localhost:{}", devnet_config.subnet_api_port),
-            }
+            },
         };
 
         self.services_map_hosts = Some(services_map_hosts.clone());
@@ -280,9 +278,9 @@ impl DevnetOrchestrator {
         terminator_rx: Receiver<bool>,
         ctx: &Context,
     ) -> Result<(), String> {
-        let (_docker, devnet_config) = match (&self.docker_client, &self.network_config) {
-            (Some(ref docker), Some(ref network_config)) => match network_config.devnet {
-                Some(ref devnet_config) => (docker, devnet_config),
+        let (_runtime, devnet_config) = match (&self.runtime, &self.network_config) {
+            (Some(ref runtime), Some(ref network_config)) => match network_config.devnet {
+                Some(ref devnet_config) => (runtime, devnet_config),
                 _ => return Err("unable to get devnet config".to_string()),
             },
             _ => return Err("unable to get devnet config".to_string()),
@@ -302,11 +300,22 @@ impl DevnetOrchestrator {
         let disable_bitcoin_explorer = devnet_config.disable_bitcoin_explorer;
         let enable_subnet_node = devnet_config.enable_subnet_node;
         let disable_subnet_api = devnet_config.disable_subnet_api;
+        let stacks_nodes = devnet_config.stacks_nodes.clone();
 
         let _ = fs::create_dir(&devnet_config.working_dir);
         let _ = fs::create_dir(format!("{}/conf", devnet_config.working_dir));
         let _ = fs::create_dir(format!("{}/data", devnet_config.working_dir));
 
+        if let Some(ref snapshot) = self.snapshot {
+            if snapshot.restored.is_some() {
+                let _ = event_tx.send(DevnetEvent::info(format!(
+                    "Restoring chain state from snapshot '{}'",
+                    snapshot.name
+                )));
+                snapshot.restore()?;
+            }
+        }
+
         let bitcoin_explorer_port = devnet_config.bitcoin_explorer_port;
         let stacks_explorer_port = devnet_config.stacks_explorer_port;
         let stacks_api_port = devnet_config.stacks_api_port;
@@ -379,6 +388,17 @@ impl DevnetOrchestrator {
             }));
         }
 
+        // the additional stacks nodes are listed after the other services
+        let stacks_nodes_first_order = if enable_subnet_node { 7 } else { 5 };
+        for (i, node) in stacks_nodes.iter().enumerate() {
+            let _ = event_tx.send(DevnetEvent::ServiceStatus(ServiceStatusData {
+                order: stacks_nodes_first_order + i,
+                status: Status::Red,
+                name: node.name.clone(),
+                comment: "initializing".into(),
+            }));
+        }
+
         let _ = event_tx.send(DevnetEvent::info(format!(
             "Creating network {}",
             self.network_name
@@ -559,6 +579,39 @@ impl DevnetOrchestrator {
             }
         };
 
+        // Start the additional stacks nodes
+        if !stacks_nodes.is_empty() {
+            let _ = event_tx.send(DevnetEvent::info("Starting stacks nodes".to_string()));
+            match self.prepare_stacks_nodes_containers(boot_index, ctx).await {
+                Ok(_) => {}
+                Err(message) => {
+                    let _ = event_tx.send(DevnetEvent::FatalError(message.clone()));
+                    self.kill(ctx, Some(&message)).await;
+                    return Err(message);
+                }
+            };
+            match self.boot_stacks_nodes_containers().await {
+                Ok(_) => {}
+                Err(message) => {
+                    let _ = event_tx.send(DevnetEvent::FatalError(message.clone()));
+                    self.kill(ctx, Some(&message)).await;
+                    return Err(message);
+                }
+            };
+            for (i, node) in stacks_nodes.iter().enumerate() {
+                let _ = event_tx.send(DevnetEvent::ServiceStatus(ServiceStatusData {
+                    order: stacks_nodes_first_order + i,
+                    status: Status::Green,
+                    name: node.name.clone(),
+                    comment: format!(
+                        "{} - http://localhost:{}",
+                        if node.miner { "miner" } else { "follower" },
+                        node.rpc_port
+                    ),
+                }));
+            }
+        }
+
         // Start stacks-explorer
         if !disable_stacks_explorer {
             let _ = event_tx.send(DevnetEvent::ServiceStatus(ServiceStatusData {
@@ -645,17 +698,38 @@ impl DevnetOrchestrator {
                         name: "stacks-node".into(),
                         comment: "restarting".into(),
                     }));
+                    for (i, node) in stacks_nodes.iter().enumerate() {
+                        let _ = event_tx.send(DevnetEvent::ServiceStatus(ServiceStatusData {
+                            order: stacks_nodes_first_order + i,
+                            status: Status::Yellow,
+                            name: node.name.clone(),
+                            comment: "restarting".into(),
+                        }));
+                    }
 
                     let _ = event_tx.send(DevnetEvent::debug("Killing containers".into()));
                     let _ = self.stop_containers().await;
 
                     let _ = event_tx.send(DevnetEvent::debug("Restarting containers".into()));
-                    let (bitcoin_node_c_id, stacks_node_c_id) = self
+                    let (bitcoin_node_c_id, stacks_node_c_id, stacks_nodes_c_ids) = self
                         .start_containers(boot_index)
                         .await
                         .map_err(|e| format!("unable to reboot: {:?}", e))?;
                     self.bitcoin_node_container_id = Some(bitcoin_node_c_id);
                     self.stacks_node_container_id = Some(stacks_node_c_id);
+                    self.stacks_nodes_container_ids = stacks_nodes_c_ids;
+                    for (i, node) in stacks_nodes.iter().enumerate() {
+                        let _ = event_tx.send(DevnetEvent::ServiceStatus(ServiceStatusData {
+                            order: stacks_nodes_first_order + i,
+                            status: Status::Green,
+                            name: node.name.clone(),
+                            comment: format!(
+                                "{} - http://localhost:{}",
+                                if node.miner { "miner" } else { "follower" },
+                                node.rpc_port
+                            ),
+                        }));
+                    }
                 }
                 Err(_) => {
                     break;
@@ -671,7 +745,7 @@ impl DevnetOrchestrator {
                 Some(ref devnet_config) => devnet_config,
                 _ => return Err("unable to get devnet configuration".into()),
             },
-            _ => return Err("unable to get Docker client".into()),
+            _ => return Err("unable to get container runtime".into()),
         };
 
         let mut port_bindings = HashMap::new();
@@ -734,8 +808,9 @@ rpcport={bitcoin_node_rpc_port}
         bitcoind_data_path.push("data");
         bitcoind_data_path.push(format!("{}", boot_index));
         bitcoind_data_path.push("bitcoin");
-        fs::create_dir_all(bitcoind_data_path)
+        fs::create_dir_all(&bitcoind_data_path)
             .map_err(|e| format!("unable to create bitcoin directory: {:?}", e))?;
+        let bitcoind_data_dir = format!("{}", bitcoind_data_path.display());
 
         let mut exposed_ports = HashMap::new();
         exposed_ports.insert(
@@ -783,10 +858,25 @@ rpcport={bitcoin_node_rpc_port}
             }),
             cmd: Some(vec![
                 "/usr/local/bin/bitcoind".into(),
-                "-conf=/etc/bitcoin/bitcoin.conf".into(),
+                format!(
+                    "-conf={}",
+                    self.service_path(
+                        &format!("{}/conf/bitcoin.conf", devnet_config.working_dir),
+                        "/etc/bitcoin/bitcoin.conf"
+                    )
+                ),
                 "-nodebuglogfile".into(),
-                "-pid=/run/bitcoind.pid".into(),
-                // "-datadir=/root/.bitcoin".into(),
+                format!(
+                    "-pid={}",
+                    self.service_path(
+                        &format!("{}/bitcoind.pid", bitcoind_data_dir),
+                        "/run/bitcoind.pid"
+                    )
+                ),
+                format!(
+                    "-datadir={}",
+                    self.service_path(&bitcoind_data_dir, "/root/.bitcoin")
+                ),
             ]),
             ..Default::default()
         };
@@ -795,49 +885,40 @@ rpcport={bitcoin_node_rpc_port}
     }
 
     pub async fn prepare_bitcoin_node_container(&mut self, ctx: &Context) -> Result<(), String> {
-        let (docker, devnet_config) = match (&self.docker_client, &self.network_config) {
-            (Some(ref docker), Some(ref network_config)) => match network_config.devnet {
-                Some(ref devnet_config) => (docker, devnet_config),
+        let (runtime, devnet_config) = match (&self.runtime, &self.network_config) {
+            (Some(ref runtime), Some(ref network_config)) => match network_config.devnet {
+                Some(ref devnet_config) => (runtime, devnet_config),
                 _ => return Err("unable to get devnet configuration".into()),
             },
-            _ => return Err("unable to get Docker client".into()),
+            _ => return Err("unable to get container runtime".into()),
         };
 
-        let _info = docker
-            .create_image(
-                Some(CreateImageOptions {
-                    from_image: devnet_config.bitcoin_node_image_url.clone(),
-                    platform: devnet_config.docker_platform.clone(),
-                    ..Default::default()
-                }),
-                None,
-                None,
+        runtime
+            .pull_image(
+                &devnet_config.bitcoin_node_image_url,
+                &devnet_config.docker_platform,
             )
-            .try_collect::<Vec<_>>()
             .await
-            .map_err(|e| formatted_docker_error("unable to create bitcoind image", e))?;
+            .map_err(|e| format!("unable to create bitcoind image: {}", e))?;
 
         let config = self.prepare_bitcoin_node_config(1)?;
         let container_name = format!("bitcoin-node.{}", self.network_name);
-        let options = CreateContainerOptions {
-            name: container_name.as_str(),
-            platform: Some(&devnet_config.docker_platform),
-        };
-
-        let container = match docker
-            .create_container::<&str, String>(Some(options.clone()), config.clone())
+        let container = match runtime
+            .create_container(
+                &container_name,
+                config.clone(),
+                &devnet_config.docker_platform,
+            )
             .await
-            .map_err(|e| formatted_docker_error("unable to create bitcoind container", e))
         {
-            Ok(container) => container.id,
+            Ok(container) => container,
             Err(_e) => {
                 // Attempt to clean eventual subsequent artifacts
-                let _ = docker.kill_container::<String>(&container_name, None).await;
-                docker
-                    .create_container::<&str, String>(Some(options), config)
+                let _ = runtime.kill_container(&container_name).await;
+                runtime
+                    .create_container(&container_name, config, &devnet_config.docker_platform)
                     .await
-                    .map_err(|e| formatted_docker_error("unable to create bitcoind container", e))?
-                    .id
+                    .map_err(|e| format!("unable to create bitcoind container: {}", e))?
             }
         };
         ctx.try_log(|logger| slog::info!(logger, "Created container bitcoin-node: {}", container));
@@ -847,44 +928,24 @@ rpcport={bitcoin_node_rpc_port}
     }
 
     pub async fn clean_previous_session(&self) -> Result<(), String> {
-        let mut filters = HashMap::new();
-        filters.insert(
-            "label".to_string(),
-            vec![format!("project={}", self.network_name)],
-        );
-        let options = Some(ListContainersOptions {
-            all: true,
-            filters,
-            ..Default::default()
-        });
-
-        let docker = match &self.docker_client {
-            Some(ref docker) => docker,
-            _ => panic!("unable to get Docker client"),
+        let runtime = match &self.runtime {
+            Some(ref runtime) => runtime,
+            _ => panic!("unable to get container runtime"),
         };
-        let res = docker.list_containers(options).await;
+        let res = runtime
+            .list_containers(vec![format!("project={}", self.network_name)])
+            .await;
         let containers = match res {
             Ok(containers) => containers,
             Err(e) => {
-                let err = format!("unable to communicate with Docker: {}\nvisit https://docs.hiro.so/clarinet/troubleshooting#i-am-unable-to-start-devnet-though-my-docker-is-running to resolve this issue.", e);
+                let err = format!("unable to communicate with {}: {}\nvisit https://docs.hiro.so/clarinet/troubleshooting#i-am-unable-to-start-devnet-though-my-docker-is-running to resolve this issue.", runtime.name(), e);
                 return Err(err);
             }
         };
-        let options = KillContainerOptions { signal: "SIGKILL" };
-
-        for container in containers.iter() {
-            let container_id = match &container.id {
-                Some(id) => id,
-                None => continue,
-            };
-            let _ = docker
-                .kill_container(container_id, Some(options.clone()))
-                .await;
 
-            let _ = docker
-                .wait_container(container_id, None::<WaitContainerOptions<String>>)
-                .try_collect::<Vec<_>>()
-                .await;
+        for container_id in containers.iter() {
+            let _ = runtime.kill_container(container_id).await;
+            let _ = runtime.wait_container(container_id).await;
         }
         self.prune().await;
         Ok(())
@@ -896,26 +957,133 @@ rpcport={bitcoin_node_rpc_port}
             _ => return Err("unable to boot container".to_string()),
         };
 
-        let docker = match &self.docker_client {
-            Some(ref docker) => docker,
-            _ => return Err("unable to get Docker client".into()),
+        let runtime = match &self.runtime {
+            Some(ref runtime) => runtime,
+            _ => return Err("unable to get container runtime".into()),
         };
 
-        docker
-            .start_container::<String>(&container, None)
+        runtime
+            .start_container(&container)
             .await
-            .map_err(|e| formatted_docker_error("unable to start bitcoind container", e))?;
+            .map_err(|e| format!("unable to start bitcoind container: {}", e))?;
 
         Ok(())
     }
 
+    // the balances, burnchain and epochs settings, shared by all the stacks nodes
+    fn stacks_node_chain_conf(
+        &self,
+        network_config: &NetworkManifest,
+        devnet_config: &DevnetConfig,
+    ) -> String {
+        let mut stacks_conf = String::new();
+        for (_, account) in network_config.accounts.iter() {
+            stacks_conf.push_str(&format!(
+                r#"
+[[ustx_balance]]
+address = "{}"
+amount = {}
+"#,
+                account.stx_address, account.balance
+            ));
+        }
+
+        stacks_conf.push_str(&format!(
+            r#"
+[burnchain]
+chain = "bitcoin"
+mode = "{burnchain_mode}"
+magic_bytes = "T3"
+pox_prepare_length = 4
+pox_reward_length = 10
+burn_fee_cap = 20_000
+poll_time_secs = 1
+timeout = 30
+peer_host = "{host_address}"
+rpc_ssl = false
+wallet_name = "{miner_wallet_name}"
+username = "{bitcoin_node_username}"
+password = "{bitcoin_node_password}"
+rpc_port = {orchestrator_ingestion_port}
+peer_port = {bitcoin_node_p2p_port}
+"#,
+            burnchain_mode = if devnet_config.use_nakamoto {
+                "nakamoto-neon"
+            } else {
+                "krypton"
+            },
+            host_address = self.host_address(),
+            bitcoin_node_username = devnet_config.bitcoin_node_username,
+            bitcoin_node_password = devnet_config.bitcoin_node_password,
+            bitcoin_node_p2p_port = devnet_config.bitcoin_node_p2p_port,
+            orchestrator_ingestion_port = self.orchestrator_port(devnet_config),
+            miner_wallet_name = devnet_config.miner_wallet_name,
+        ));
+
+        stacks_conf.push_str(&format!(
+            r#"
+[[burnchain.epochs]]
+epoch_name = "1.0"
+start_height = 0
+
+[[burnchain.epochs]]
+epoch_name = "2.0"
+start_height = {epoch_2_0}
+
+[[burnchain.epochs]]
+epoch_name = "2.05"
+start_height = {epoch_2_05}
+
+[[burnchain.epochs]]
+epoch_name = "2.1"
+start_height = {epoch_2_1}
+
+[[burnchain.epochs]]
+epoch_name = "2.2"
+start_height = {epoch_2_2}
+
+[[burnchain.epochs]]
+epoch_name = "2.3"
+start_height = {epoch_2_3}
+
+[[burnchain.epochs]]
+epoch_name = "2.4"
+start_height = {epoch_2_4}
+"#,
+            epoch_2_0 = devnet_config.epoch_2_0,
+            epoch_2_05 = devnet_config.epoch_2_05,
+            epoch_2_1 = devnet_config.epoch_2_1,
+            epoch_2_2 = devnet_config.epoch_2_2,
+            epoch_2_3 = devnet_config.epoch_2_3,
+            epoch_2_4 = devnet_config.epoch_2_4,
+        ));
+
+        if devnet_config.use_nakamoto {
+            stacks_conf.push_str(&format!(
+                r#"
+[[burnchain.epochs]]
+epoch_name = "2.5"
+start_height = {epoch_2_5}
+
+[[burnchain.epochs]]
+epoch_name = "3.0"
+start_height = {epoch_3_0}
+"#,
+                epoch_2_5 = devnet_config.epoch_2_5,
+                epoch_3_0 = devnet_config.epoch_3_0,
+            ));
+        }
+
+        stacks_conf
+    }
+
     pub fn prepare_stacks_node_config(&self, boot_index: u32) -> Result<Config<String>, String> {
         let (network_config, devnet_config) = match &self.network_config {
             Some(ref network_config) => match network_config.devnet {
                 Some(ref devnet_config) => (network_config, devnet_config),
                 _ => return Err("unable to get devnet configuration".into()),
             },
-            _ => return Err("unable to get Docker client".into()),
+            _ => return Err("unable to get container runtime".into()),
         };
 
         let mut port_bindings = HashMap::new();
@@ -934,10 +1102,14 @@ rpcport={bitcoin_node_rpc_port}
             }]),
         );
 
+        let stacks_node_working_dir = self.service_path(
+            &format!("{}/data/{}/stacks", devnet_config.working_dir, boot_index),
+            "/devnet",
+        );
         let mut stacks_conf = format!(
             r#"
 [node]
-working_dir = "/devnet"
+working_dir = "{stacks_node_working_dir}"
 rpc_bind = "0.0.0.0:{stacks_node_rpc_port}"
 p2p_bind = "0.0.0.0:{stacks_node_p2p_port}"
 data_url = "http://127.0.0.1:{stacks_node_rpc_port}"
@@ -955,9 +1127,9 @@ microblock_frequency = 1000
 # inv_sync_interval = 10
 # download_interval = 10
 # walk_interval = 10
-disable_block_download = true
-disable_inbound_handshakes = true
-disable_inbound_walks = true
+disable_block_download = {isolated}
+disable_inbound_handshakes = {isolated}
+disable_inbound_walks = {isolated}
 public_ip_address = "1.1.1.1:1234"
 
 [miner]
@@ -973,33 +1145,28 @@ mining_key = "19ec1c3e31d139c989a23a27eac60d1abfad5277d3ae9604242514c738258efa01
 "#,
             stacks_node_rpc_port = devnet_config.stacks_node_rpc_port,
             stacks_node_p2p_port = devnet_config.stacks_node_p2p_port,
+            stacks_node_working_dir = stacks_node_working_dir,
             miner_secret_key_hex = devnet_config.miner_secret_key_hex,
             first_attempt_time_ms = devnet_config.stacks_node_first_attempt_time_ms,
             subsequent_attempt_time_ms = devnet_config.stacks_node_subsequent_attempt_time_ms,
             miner_coinbase_recipient = devnet_config.miner_coinbase_recipient,
+            // the additional stacks nodes need to peer with the main one
+            isolated = devnet_config.stacks_nodes.is_empty(),
         );
 
-        for (_, account) in network_config.accounts.iter() {
-            stacks_conf.push_str(&format!(
-                r#"
-[[ustx_balance]]
-address = "{}"
-amount = {}
-"#,
-                account.stx_address, account.balance
-            ));
-        }
+        stacks_conf.push_str(&self.stacks_node_chain_conf(network_config, devnet_config));
 
         stacks_conf.push_str(&format!(
             r#"
 # Add orchestrator (docker-host) as an event observer
 [[events_observer]]
-endpoint = "host.docker.internal:{orchestrator_ingestion_port}"
+endpoint = "{host_address}:{orchestrator_ingestion_port}"
 retry_count = 255
 include_data_events = true
 events_keys = ["*"]
 "#,
-            orchestrator_ingestion_port = devnet_config.orchestrator_ingestion_port,
+            host_address = self.host_address(),
+            orchestrator_ingestion_port = self.orchestrator_port(devnet_config),
         ));
 
         if !devnet_config.disable_stacks_api {
@@ -1041,114 +1208,248 @@ events_keys = ["*"]
             ));
         }
 
-        stacks_conf.push_str(&format!(
-            r#"
-[burnchain]
-chain = "bitcoin"
-mode = "{burnchain_mode}"
-magic_bytes = "T3"
-pox_prepare_length = 4
-pox_reward_length = 10
-burn_fee_cap = 20_000
-poll_time_secs = 1
-timeout = 30
-peer_host = "host.docker.internal"
-rpc_ssl = false
-wallet_name = "{miner_wallet_name}"
-username = "{bitcoin_node_username}"
-password = "{bitcoin_node_password}"
-rpc_port = {orchestrator_ingestion_port}
-peer_port = {bitcoin_node_p2p_port}
-"#,
-            burnchain_mode = if devnet_config.use_nakamoto {
-                "nakamoto-neon"
-            } else {
-                "krypton"
+        let mut stacks_conf_path = PathBuf::from(&devnet_config.working_dir);
+        stacks_conf_path.push("conf/Stacks.toml");
+        let mut file = File::create(stacks_conf_path)
+            .map_err(|e| format!("unable to create Stacks.toml: {:?}", e))?;
+        file.write_all(stacks_conf.as_bytes())
+            .map_err(|e| format!("unable to write Stacks.toml: {:?}", e))?;
+
+        let mut stacks_node_data_path = PathBuf::from(&devnet_config.working_dir);
+        stacks_node_data_path.push("data");
+        stacks_node_data_path.push(format!("{}", boot_index));
+        stacks_node_data_path.push("stacks");
+        fs::create_dir_all(stacks_node_data_path)
+            .map_err(|e| format!("unable to create stacks directory: {:?}", e))?;
+
+        let mut exposed_ports = HashMap::new();
+        exposed_ports.insert(
+            format!("{}/tcp", devnet_config.stacks_node_rpc_port),
+            HashMap::new(),
+        );
+        exposed_ports.insert(
+            format!("{}/tcp", devnet_config.stacks_node_p2p_port),
+            HashMap::new(),
+        );
+
+        let mut labels = HashMap::new();
+        labels.insert("project".to_string(), self.network_name.to_string());
+        labels.insert("reset".to_string(), "true".to_string());
+
+        let mut binds = vec![format!(
+            "{}/conf:/src/stacks-node/",
+            devnet_config.working_dir
+        )];
+
+        if devnet_config.bind_containers_volumes {
+            binds.push(format!(
+                "{}/data/{}/stacks:/devnet/",
+                devnet_config.working_dir, boot_index
+            ))
+        }
+
+        let mut env = vec![
+            "STACKS_LOG_PP=1".to_string(),
+            "BLOCKSTACK_USE_TEST_GENESIS_CHAINSTATE=1".to_string(),
+        ];
+        env.append(&mut devnet_config.stacks_node_env_vars.clone());
+
+        let config = Config {
+            labels: Some(labels),
+            image: Some(devnet_config.stacks_node_image_url.clone()),
+            // domainname: Some(self.network_name.to_string()),
+            tty: None,
+            exposed_ports: Some(exposed_ports),
+            entrypoint: Some(vec![
+                "stacks-node".into(),
+                "start".into(),
+                format!(
+                    "--config={}",
+                    self.service_path(
+                        &format!("{}/conf/Stacks.toml", devnet_config.working_dir),
+                        "/src/stacks-node/Stacks.toml"
+                    )
+                ),
+            ]),
+            env: Some(env),
+            host_config: Some(HostConfig {
+                auto_remove: Some(true),
+                binds: Some(binds),
+                network_mode: Some(self.network_name.clone()),
+                port_bindings: Some(port_bindings),
+                extra_hosts: Some(vec!["host.docker.internal:host-gateway".into()]),
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+
+        Ok(config)
+    }
+
+    pub async fn prepare_stacks_node_container(
+        &mut self,
+        boot_index: u32,
+        ctx: &Context,
+    ) -> Result<(), String> {
+        let (runtime, devnet_config) = match (&self.runtime, &self.network_config) {
+            (Some(ref runtime), Some(ref network_config)) => match network_config.devnet {
+                Some(ref devnet_config) => (runtime, devnet_config),
+                _ => return Err("unable to get devnet configuration".into()),
             },
-            bitcoin_node_username = devnet_config.bitcoin_node_username,
-            bitcoin_node_password = devnet_config.bitcoin_node_password,
-            bitcoin_node_p2p_port = devnet_config.bitcoin_node_p2p_port,
-            orchestrator_ingestion_port = devnet_config.orchestrator_ingestion_port,
-            miner_wallet_name = devnet_config.miner_wallet_name,
-        ));
+            _ => return Err("unable to get container runtime".into()),
+        };
 
-        stacks_conf.push_str(&format!(
-            r#"
-[[burnchain.epochs]]
-epoch_name = "1.0"
-start_height = 0
+        runtime
+            .pull_image(
+                &devnet_config.stacks_node_image_url,
+                &devnet_config.docker_platform,
+            )
+            .await
+            .map_err(|e| format!("unable to create image: {}", e))?;
 
-[[burnchain.epochs]]
-epoch_name = "2.0"
-start_height = {epoch_2_0}
+        let config = self.prepare_stacks_node_config(boot_index)?;
 
-[[burnchain.epochs]]
-epoch_name = "2.05"
-start_height = {epoch_2_05}
+        let container_name = format!("stacks-node.{}", self.network_name);
+        let container = runtime
+            .create_container(&container_name, config, &devnet_config.docker_platform)
+            .await
+            .map_err(|e| format!("unable to create container: {}", e))?;
 
-[[burnchain.epochs]]
-epoch_name = "2.1"
-start_height = {epoch_2_1}
+        ctx.try_log(|logger| slog::info!(logger, "Created container stacks-node: {}", container));
+        self.stacks_node_container_id = Some(container.clone());
 
-[[burnchain.epochs]]
-epoch_name = "2.2"
-start_height = {epoch_2_2}
+        Ok(())
+    }
 
-[[burnchain.epochs]]
-epoch_name = "2.3"
-start_height = {epoch_2_3}
+    pub async fn boot_stacks_node_container(&mut self) -> Result<(), String> {
+        let container = match &self.stacks_node_container_id {
+            Some(container) => container.clone(),
+            _ => return Err("unable to boot container".to_string()),
+        };
 
-[[burnchain.epochs]]
-epoch_name = "2.4"
-start_height = {epoch_2_4}
-"#,
-            epoch_2_0 = devnet_config.epoch_2_0,
-            epoch_2_05 = devnet_config.epoch_2_05,
-            epoch_2_1 = devnet_config.epoch_2_1,
-            epoch_2_2 = devnet_config.epoch_2_2,
-            epoch_2_3 = devnet_config.epoch_2_3,
-            epoch_2_4 = devnet_config.epoch_2_4,
-        ));
+        let runtime = match &self.runtime {
+            Some(ref runtime) => runtime,
+            _ => return Err("unable to get container runtime".into()),
+        };
+
+        runtime
+            .start_container(&container)
+            .await
+            .map_err(|e| format!("unable to start stacks-node container: {}", e))?;
+
+        Ok(())
+    }
+
+    pub fn prepare_additional_stacks_node_config(
+        &self,
+        node: &StacksNodeConfig,
+        boot_index: u32,
+    ) -> Result<Config<String>, String> {
+        let (network_config, devnet_config) = match &self.network_config {
+            Some(ref network_config) => match network_config.devnet {
+                Some(ref devnet_config) => (network_config, devnet_config),
+                _ => return Err("unable to get devnet configuration".into()),
+            },
+            _ => return Err("unable to get container runtime".into()),
+        };
+
+        let mut port_bindings = HashMap::new();
+        port_bindings.insert(
+            format!("{}/tcp", node.p2p_port),
+            Some(vec![PortBinding {
+                host_ip: Some(String::from("0.0.0.0")),
+                host_port: Some(format!("{}/tcp", node.p2p_port)),
+            }]),
+        );
+        port_bindings.insert(
+            format!("{}/tcp", node.rpc_port),
+            Some(vec![PortBinding {
+                host_ip: Some(String::from("0.0.0.0")),
+                host_port: Some(format!("{}/tcp", node.rpc_port)),
+            }]),
+        );
+
+        // the p2p identity of the main stacks-node is derived from its local_peer_seed
+        let main_node_seed = hex_bytes(&devnet_config.miner_secret_key_hex)
+            .map_err(|e| format!("unable to decode miner secret key: {:?}", e))?;
+        let main_node_public_key =
+            Secp256k1PublicKey::from_private(&Secp256k1PrivateKey::from_seed(&main_node_seed));
+
+        let stacks_node_working_dir = self.service_path(
+            &format!(
+                "{}/data/{}/{}",
+                devnet_config.working_dir, boot_index, node.name
+            ),
+            "/devnet",
+        );
+        let mut stacks_conf = format!(
+            r#"
+[node]
+working_dir = "{stacks_node_working_dir}"
+rpc_bind = "0.0.0.0:{rpc_port}"
+p2p_bind = "0.0.0.0:{p2p_port}"
+data_url = "http://127.0.0.1:{rpc_port}"
+p2p_address = "127.0.0.1:{p2p_port}"
+bootstrap_node = "{main_node_public_key}@{main_node_host}:{main_node_p2p_port}"
+miner = {miner}
+seed = "{secret_key_hex}"
+local_peer_seed = "{secret_key_hex}"
+pox_sync_sample_secs = 0
+wait_time_for_blocks = 0
+wait_time_for_microblocks = 0
+mine_microblocks = false
+microblock_frequency = 1000
+"#,
+            stacks_node_working_dir = stacks_node_working_dir,
+            rpc_port = node.rpc_port,
+            p2p_port = node.p2p_port,
+            main_node_public_key = main_node_public_key.to_hex(),
+            main_node_host = self.service_host("stacks-node"),
+            main_node_p2p_port = devnet_config.stacks_node_p2p_port,
+            miner = node.miner,
+            secret_key_hex = node.secret_key_hex,
+        );
 
-        if devnet_config.use_nakamoto {
+        if node.miner {
             stacks_conf.push_str(&format!(
                 r#"
-[[burnchain.epochs]]
-epoch_name = "2.5"
-start_height = {epoch_2_5}
-
-[[burnchain.epochs]]
-epoch_name = "3.0"
-start_height = {epoch_3_0}
+[miner]
+min_tx_fee = 1
+first_attempt_time_ms = {first_attempt_time_ms}
+second_attempt_time_ms = {subsequent_attempt_time_ms}
+block_reward_recipient = "{block_reward_recipient}"
+wait_for_block_download = false
+microblock_attempt_time_ms = 10
+self_signing_seed = 1
+mining_key = "{secret_key_hex}"
 "#,
-                epoch_2_5 = devnet_config.epoch_2_5,
-                epoch_3_0 = devnet_config.epoch_3_0,
+                first_attempt_time_ms = devnet_config.stacks_node_first_attempt_time_ms,
+                subsequent_attempt_time_ms = devnet_config.stacks_node_subsequent_attempt_time_ms,
+                block_reward_recipient = node.stx_address,
+                secret_key_hex = node.secret_key_hex,
             ));
         }
 
+        // the events observers are only registered on the main stacks-node
+        stacks_conf.push_str(&self.stacks_node_chain_conf(network_config, devnet_config));
+
         let mut stacks_conf_path = PathBuf::from(&devnet_config.working_dir);
-        stacks_conf_path.push("conf/Stacks.toml");
+        stacks_conf_path.push(format!("conf/{}.toml", node.name));
         let mut file = File::create(stacks_conf_path)
-            .map_err(|e| format!("unable to create Stacks.toml: {:?}", e))?;
+            .map_err(|e| format!("unable to create {}.toml: {:?}", node.name, e))?;
         file.write_all(stacks_conf.as_bytes())
-            .map_err(|e| format!("unable to write Stacks.toml: {:?}", e))?;
+            .map_err(|e| format!("unable to write {}.toml: {:?}", node.name, e))?;
 
         let mut stacks_node_data_path = PathBuf::from(&devnet_config.working_dir);
         stacks_node_data_path.push("data");
         stacks_node_data_path.push(format!("{}", boot_index));
-        stacks_node_data_path.push("stacks");
+        stacks_node_data_path.push(&node.name);
         fs::create_dir_all(stacks_node_data_path)
-            .map_err(|e| format!("unable to create stacks directory: {:?}", e))?;
+            .map_err(|e| format!("unable to create {} directory: {:?}", node.name, e))?;
 
         let mut exposed_ports = HashMap::new();
-        exposed_ports.insert(
-            format!("{}/tcp", devnet_config.stacks_node_rpc_port),
-            HashMap::new(),
-        );
-        exposed_ports.insert(
-            format!("{}/tcp", devnet_config.stacks_node_p2p_port),
-            HashMap::new(),
-        );
+        exposed_ports.insert(format!("{}/tcp", node.rpc_port), HashMap::new());
+        exposed_ports.insert(format!("{}/tcp", node.p2p_port), HashMap::new());
 
         let mut labels = HashMap::new();
         labels.insert("project".to_string(), self.network_name.to_string());
@@ -1161,8 +1462,8 @@ start_height = {epoch_3_0}
 
         if devnet_config.bind_containers_volumes {
             binds.push(format!(
-                "{}/data/{}/stacks:/devnet/",
-                devnet_config.working_dir, boot_index
+                "{}/data/{}/{}:/devnet/",
+                devnet_config.working_dir, boot_index, node.name
             ))
         }
 
@@ -1170,18 +1471,23 @@ start_height = {epoch_3_0}
             "STACKS_LOG_PP=1".to_string(),
             "BLOCKSTACK_USE_TEST_GENESIS_CHAINSTATE=1".to_string(),
         ];
-        env.append(&mut devnet_config.stacks_node_env_vars.clone());
+        env.append(&mut node.env_vars.clone());
 
         let config = Config {
             labels: Some(labels),
             image: Some(devnet_config.stacks_node_image_url.clone()),
-            // domainname: Some(self.network_name.to_string()),
             tty: None,
             exposed_ports: Some(exposed_ports),
             entrypoint: Some(vec![
                 "stacks-node".into(),
                 "start".into(),
-                "--config=/src/stacks-node/Stacks.toml".into(),
+                format!(
+                    "--config={}",
+                    self.service_path(
+                        &format!("{}/conf/{}.toml", devnet_config.working_dir, node.name),
+                        &format!("/src/stacks-node/{}.toml", node.name)
+                    )
+                ),
             ]),
             env: Some(env),
             host_config: Some(HostConfig {
@@ -1198,67 +1504,57 @@ start_height = {epoch_3_0}
         Ok(config)
     }
 
-    pub async fn prepare_stacks_node_container(
-        &mut self,
-        boot_index: u32,
-        ctx: &Context,
-    ) -> Result<(), String> {
-        let (docker, devnet_config) = match (&self.docker_client, &self.network_config) {
-            (Some(ref docker), Some(ref network_config)) => match network_config.devnet {
-                Some(ref devnet_config) => (docker, devnet_config),
+    // creates the containers of the stacks nodes running next to the main stacks-node
+    async fn create_stacks_nodes_containers(&self, boot_index: u32) -> Result<Vec<String>, String> {
+        let (runtime, devnet_config) = match (&self.runtime, &self.network_config) {
+            (Some(ref runtime), Some(ref network_config)) => match network_config.devnet {
+                Some(ref devnet_config) => (runtime, devnet_config),
                 _ => return Err("unable to get devnet configuration".into()),
             },
-            _ => return Err("unable to get Docker client".into()),
-        };
-
-        let _info = docker
-            .create_image(
-                Some(CreateImageOptions {
-                    from_image: devnet_config.stacks_node_image_url.clone(),
-                    platform: devnet_config.docker_platform.clone(),
-                    ..Default::default()
-                }),
-                None,
-                None,
-            )
-            .try_collect::<Vec<_>>()
-            .await
-            .map_err(|e| format!("unable to create image: {}", e))?;
-
-        let config = self.prepare_stacks_node_config(boot_index)?;
-
-        let options = CreateContainerOptions {
-            name: format!("stacks-node.{}", self.network_name),
-            platform: Some(devnet_config.docker_platform.to_string()),
+            _ => return Err("unable to get container runtime".into()),
         };
 
-        let container = docker
-            .create_container::<String, String>(Some(options), config)
-            .await
-            .map_err(|e| format!("unable to create container: {}", e))?
-            .id;
-
-        ctx.try_log(|logger| slog::info!(logger, "Created container stacks-node: {}", container));
-        self.stacks_node_container_id = Some(container.clone());
+        let mut containers_ids = vec![];
+        for node in devnet_config.stacks_nodes.iter() {
+            let config = self.prepare_additional_stacks_node_config(node, boot_index)?;
+            let container = runtime
+                .create_container(
+                    &format!("{}.{}", node.name, self.network_name),
+                    config,
+                    &devnet_config.docker_platform,
+                )
+                .await
+                .map_err(|e| format!("unable to create container: {}", e))?;
+            containers_ids.push(container);
+        }
+        Ok(containers_ids)
+    }
 
+    pub async fn prepare_stacks_nodes_containers(
+        &mut self,
+        boot_index: u32,
+        ctx: &Context,
+    ) -> Result<(), String> {
+        let containers_ids = self.create_stacks_nodes_containers(boot_index).await?;
+        for container in containers_ids.iter() {
+            ctx.try_log(|logger| slog::info!(logger, "Created container {}", container));
+        }
+        self.stacks_nodes_container_ids = containers_ids;
         Ok(())
     }
 
-    pub async fn boot_stacks_node_container(&mut self) -> Result<(), String> {
-        let container = match &self.stacks_node_container_id {
-            Some(container) => container.clone(),
-            _ => return Err("unable to boot container".to_string()),
-        };
-
-        let docker = match &self.docker_client {
-            Some(ref docker) => docker,
-            _ => return Err("unable to get Docker client".into()),
+    pub async fn boot_stacks_nodes_containers(&self) -> Result<(), String> {
+        let runtime = match &self.runtime {
+            Some(ref runtime) => runtime,
+            _ => return Err("unable to get container runtime".into()),
         };
 
-        docker
-            .start_container::<String>(&container, None)
-            .await
-            .map_err(|e| formatted_docker_error("unable to start stacks-node container", e))?;
+        for container in self.stacks_nodes_container_ids.iter() {
+            runtime
+                .start_container(container)
+                .await
+                .map_err(|e| format!("unable to start {} container: {}", container, e))?;
+        }
 
         Ok(())
     }
@@ -1269,7 +1565,7 @@ start_height = {epoch_3_0}
                 Some(devnet_config) => devnet_config,
                 _ => return Err("unable to get devnet configuration".into()),
             },
-            _ => return Err("unable to get Docker client".into()),
+            _ => return Err("unable to get container runtime".into()),
         };
 
         let mut port_bindings = HashMap::new();
@@ -1462,40 +1758,29 @@ events_keys = ["*"]
         boot_index: u32,
         ctx: &Context,
     ) -> Result<(), String> {
-        let (docker, devnet_config) = match (&self.docker_client, &self.network_config) {
-            (Some(ref docker), Some(ref network_config)) => match network_config.devnet {
-                Some(ref devnet_config) => (docker, devnet_config),
+        let (runtime, devnet_config) = match (&self.runtime, &self.network_config) {
+            (Some(ref runtime), Some(ref network_config)) => match network_config.devnet {
+                Some(ref devnet_config) => (runtime, devnet_config),
                 _ => return Err("unable to get devnet configuration".into()),
             },
-            _ => return Err("unable to get Docker client".into()),
+            _ => return Err("unable to get container runtime".into()),
         };
 
-        let _info = docker
-            .create_image(
-                Some(CreateImageOptions {
-                    from_image: devnet_config.subnet_node_image_url.clone(),
-                    platform: devnet_config.docker_platform.clone(),
-                    ..Default::default()
-                }),
-                None,
-                None,
+        runtime
+            .pull_image(
+                &devnet_config.subnet_node_image_url,
+                &devnet_config.docker_platform,
             )
-            .try_collect::<Vec<_>>()
             .await
             .map_err(|e| format!("unable to create image: {}", e))?;
 
         let config = self.prepare_subnet_node_config(boot_index)?;
 
-        let options = CreateContainerOptions {
-            name: format!("subnet-node.{}", self.network_name),
-            platform: Some(devnet_config.docker_platform.to_string()),
-        };
-
-        let container = docker
-            .create_container::<String, String>(Some(options), config)
+        let container_name = format!("subnet-node.{}", self.network_name);
+        let container = runtime
+            .create_container(&container_name, config, &devnet_config.docker_platform)
             .await
-            .map_err(|e| format!("unable to create container: {}", e))?
-            .id;
+            .map_err(|e| format!("unable to create container: {}", e))?;
 
         ctx.try_log(|logger| slog::info!(logger, "Created container subnet-node: {}", container));
         self.subnet_node_container_id = Some(container.clone());
@@ -1509,13 +1794,13 @@ events_keys = ["*"]
             _ => return Err("unable to boot container".to_string()),
         };
 
-        let docker = match &self.docker_client {
-            Some(ref docker) => docker,
-            _ => return Err("unable to get Docker client".into()),
+        let runtime = match &self.runtime {
+            Some(ref runtime) => runtime,
+            _ => return Err("unable to get container runtime".into()),
         };
 
-        docker
-            .start_container::<String>(&container, None)
+        runtime
+            .start_container(&container)
             .await
             .map_err(|e| format!("unable to start container - {}", e))?;
 
@@ -1523,25 +1808,19 @@ events_keys = ["*"]
     }
 
     pub async fn prepare_stacks_api_container(&mut self, ctx: &Context) -> Result<(), String> {
-        let (docker, _, devnet_config) = match (&self.docker_client, &self.network_config) {
-            (Some(ref docker), Some(ref network_config)) => match network_config.devnet {
-                Some(ref devnet_config) => (docker, network_config, devnet_config),
+        let (runtime, _, devnet_config) = match (&self.runtime, &self.network_config) {
+            (Some(ref runtime), Some(ref network_config)) => match network_config.devnet {
+                Some(ref devnet_config) => (runtime, network_config, devnet_config),
                 _ => return Err("unable to get devnet configuration".into()),
             },
-            _ => return Err("unable to get Docker client".into()),
+            _ => return Err("unable to get container runtime".into()),
         };
 
-        let _info = docker
-            .create_image(
-                Some(CreateImageOptions {
-                    from_image: devnet_config.stacks_api_image_url.clone(),
-                    platform: devnet_config.docker_platform.clone(),
-                    ..Default::default()
-                }),
-                None,
-                None,
+        runtime
+            .pull_image(
+                &devnet_config.stacks_api_image_url,
+                &devnet_config.docker_platform,
             )
-            .try_collect::<Vec<_>>()
             .await
             .map_err(|e| format!("unable to create image: {}", e))?;
 
@@ -1610,16 +1889,11 @@ events_keys = ["*"]
             ..Default::default()
         };
 
-        let options = CreateContainerOptions {
-            name: format!("stacks-api.{}", self.network_name),
-            platform: Some(devnet_config.docker_platform.to_string()),
-        };
-
-        let container = docker
-            .create_container::<String, String>(Some(options), config)
+        let container_name = format!("stacks-api.{}", self.network_name);
+        let container = runtime
+            .create_container(&container_name, config, &devnet_config.docker_platform)
             .await
-            .map_err(|e| format!("unable to create container: {}", e))?
-            .id;
+            .map_err(|e| format!("unable to create container: {}", e))?;
 
         ctx.try_log(|logger| slog::info!(logger, "Created container stacks-api: {}", container));
         self.stacks_api_container_id = Some(container);
@@ -1633,39 +1907,33 @@ events_keys = ["*"]
             _ => return Err("unable to boot container".to_string()),
         };
 
-        let docker = match &self.docker_client {
-            Some(ref docker) => docker,
-            _ => return Err("unable to get Docker client".into()),
+        let runtime = match &self.runtime {
+            Some(ref runtime) => runtime,
+            _ => return Err("unable to get container runtime".into()),
         };
 
-        docker
-            .start_container::<String>(&container, None)
+        runtime
+            .start_container(&container)
             .await
-            .map_err(|e| formatted_docker_error("unable to start stacks-api container", e))?;
+            .map_err(|e| format!("unable to start stacks-api container: {}", e))?;
 
         Ok(())
     }
 
     pub async fn prepare_subnet_api_container(&mut self, ctx: &Context) -> Result<(), String> {
-        let (docker, _, devnet_config) = match (&self.docker_client, &self.network_config) {
-            (Some(ref docker), Some(ref network_config)) => match network_config.devnet {
-                Some(ref devnet_config) => (docker, network_config, devnet_config),
+        let (runtime, _, devnet_config) = match (&self.runtime, &self.network_config) {
+            (Some(ref runtime), Some(ref network_config)) => match network_config.devnet {
+                Some(ref devnet_config) => (runtime, network_config, devnet_config),
                 _ => return Err("unable to get devnet configuration".into()),
             },
-            _ => return Err("unable to get Docker client".into()),
+            _ => return Err("unable to get container runtime".into()),
         };
 
-        let _info = docker
-            .create_image(
-                Some(CreateImageOptions {
-                    from_image: devnet_config.subnet_api_image_url.clone(),
-                    platform: devnet_config.docker_platform.clone(),
-                    ..Default::default()
-                }),
-                None,
-                None,
+        runtime
+            .pull_image(
+                &devnet_config.subnet_api_image_url,
+                &devnet_config.docker_platform,
             )
-            .try_collect::<Vec<_>>()
             .await
             .map_err(|e| format!("unable to create image: {}", e))?;
 
@@ -1734,16 +2002,11 @@ events_keys = ["*"]
             ..Default::default()
         };
 
-        let options = CreateContainerOptions {
-            name: format!("subnet-api.{}", self.network_name),
-            platform: Some(devnet_config.docker_platform.to_string()),
-        };
-
-        let container = docker
-            .create_container::<String, String>(Some(options), config)
+        let container_name = format!("subnet-api.{}", self.network_name);
+        let container = runtime
+            .create_container(&container_name, config, &devnet_config.docker_platform)
             .await
-            .map_err(|e| format!("unable to create container: {}", e))?
-            .id;
+            .map_err(|e| format!("unable to create container: {}", e))?;
 
         ctx.try_log(|logger| slog::info!(logger, "Created container subnet-api: {}", container));
         self.subnet_api_container_id = Some(container);
@@ -1753,12 +2016,12 @@ events_keys = ["*"]
 
     pub async fn boot_subnet_api_container(&self) -> Result<(), String> {
         // Before booting the subnet-api, we need to create an additional DB in the postgres container.
-        let (docker, _, devnet_config) = match (&self.docker_client, &self.network_config) {
-            (Some(ref docker), Some(ref network_config)) => match network_config.devnet {
-                Some(ref devnet_config) => (docker, network_config, devnet_config),
+        let (runtime, _, devnet_config) = match (&self.runtime, &self.network_config) {
+            (Some(ref runtime), Some(ref network_config)) => match network_config.devnet {
+                Some(ref devnet_config) => (runtime, network_config, devnet_config),
                 _ => return Err("unable to get devnet configuration".into()),
             },
-            _ => return Err("unable to get Docker client".into()),
+            _ => return Err("unable to get container runtime".into()),
         };
 
         let postgres_container = match &self.postgres_container_id {
@@ -1771,65 +2034,55 @@ events_keys = ["*"]
             devnet_config.subnet_api_postgres_database
         );
 
-        let config = CreateExecOptions {
-            cmd: Some(vec!["psql", "-U", "postgres", "-c", psql_command.as_str()]),
-            attach_stdout: Some(false),
-            attach_stderr: Some(false),
-            ..Default::default()
-        };
-
-        let exec = docker
-            .create_exec::<&str>(&postgres_container, config)
-            .await
-            .map_err(|e| formatted_docker_error("unable to create exec command", e))?;
-
         // Pause to ensure the postgres container is ready.
         // TODO
         std::thread::sleep(std::time::Duration::from_secs(10));
 
-        let _res = docker
-            .start_exec(&exec.id, None)
-            .await
-            .map_err(|e| formatted_docker_error("unable to start exec command", e))?;
+        runtime
+            .exec(
+                &postgres_container,
+                vec![
+                    "psql".into(),
+                    "-U".into(),
+                    "postgres".into(),
+                    "-c".into(),
+                    psql_command,
+                ],
+            )
+            .await?;
 
         let container = match &self.subnet_api_container_id {
             Some(container) => container.clone(),
             _ => return Err("unable to boot container".to_string()),
         };
 
-        let docker = match &self.docker_client {
-            Some(ref docker) => docker,
-            _ => return Err("unable to get Docker client".into()),
+        let runtime = match &self.runtime {
+            Some(ref runtime) => runtime,
+            _ => return Err("unable to get container runtime".into()),
         };
 
-        docker
-            .start_container::<String>(&container, None)
+        runtime
+            .start_container(&container)
             .await
-            .map_err(|e| formatted_docker_error("unable to start stacks-api container", e))?;
+            .map_err(|e| format!("unable to start stacks-api container: {}", e))?;
 
         Ok(())
     }
 
     pub async fn prepare_postgres_container(&mut self, ctx: &Context) -> Result<(), String> {
-        let (docker, _, devnet_config) = match (&self.docker_client, &self.network_config) {
-            (Some(ref docker), Some(ref network_config)) => match network_config.devnet {
-                Some(ref devnet_config) => (docker, network_config, devnet_config),
+        let (runtime, _, devnet_config) = match (&self.runtime, &self.network_config) {
+            (Some(ref runtime), Some(ref network_config)) => match network_config.devnet {
+                Some(ref devnet_config) => (runtime, network_config, devnet_config),
                 _ => return Err("unable to get devnet configuration".into()),
             },
-            _ => return Err("unable to get Docker client".into()),
+            _ => return Err("unable to get container runtime".into()),
         };
 
-        let _info = docker
-            .create_image(
-                Some(CreateImageOptions {
-                    from_image: devnet_config.postgres_image_url.clone(),
-                    platform: devnet_config.docker_platform.clone(),
-                    ..Default::default()
-                }),
-                None,
-                None,
+        runtime
+            .pull_image(
+                &devnet_config.postgres_image_url,
+                &devnet_config.docker_platform,
             )
-            .try_collect::<Vec<_>>()
             .await
             .map_err(|e| format!("unable to create image: {}", e))?;
 
@@ -1867,16 +2120,11 @@ events_keys = ["*"]
             ..Default::default()
         };
 
-        let options = CreateContainerOptions {
-            name: format!("postgres.{}", self.network_name),
-            platform: Some(devnet_config.docker_platform.to_string()),
-        };
-
-        let container = docker
-            .create_container::<String, String>(Some(options), config)
+        let container_name = format!("postgres.{}", self.network_name);
+        let container = runtime
+            .create_container(&container_name, config, &devnet_config.docker_platform)
             .await
-            .map_err(|e| format!("unable to create container: {}", e))?
-            .id;
+            .map_err(|e| format!("unable to create container: {}", e))?;
 
         ctx.try_log(|logger| slog::info!(logger, "Created container postgres: {}", container));
         self.postgres_container_id = Some(container);
@@ -1890,39 +2138,33 @@ events_keys = ["*"]
             _ => return Err("unable to boot container".to_string()),
         };
 
-        let docker = match &self.docker_client {
-            Some(ref docker) => docker,
-            _ => return Err("unable to get Docker client".into()),
+        let runtime = match &self.runtime {
+            Some(ref runtime) => runtime,
+            _ => return Err("unable to get container runtime".into()),
         };
 
-        docker
-            .start_container::<String>(&container, None)
+        runtime
+            .start_container(&container)
             .await
-            .map_err(|e| formatted_docker_error("unable to start postgres container", e))?;
+            .map_err(|e| format!("unable to start postgres container: {}", e))?;
 
         Ok(())
     }
 
     pub async fn prepare_stacks_explorer_container(&mut self, ctx: &Context) -> Result<(), String> {
-        let (docker, _, devnet_config) = match (&self.docker_client, &self.network_config) {
-            (Some(ref docker), Some(ref network_config)) => match network_config.devnet {
-                Some(ref devnet_config) => (docker, network_config, devnet_config),
+        let (runtime, _, devnet_config) = match (&self.runtime, &self.network_config) {
+            (Some(ref runtime), Some(ref network_config)) => match network_config.devnet {
+                Some(ref devnet_config) => (runtime, network_config, devnet_config),
                 _ => return Err("unable to get devnet configuration".into()),
             },
-            _ => return Err("unable to get Docker client".into()),
+            _ => return Err("unable to get container runtime".into()),
         };
 
-        let _info = docker
-            .create_image(
-                Some(CreateImageOptions {
-                    from_image: devnet_config.stacks_explorer_image_url.clone(),
-                    platform: devnet_config.docker_platform.clone(),
-                    ..Default::default()
-                }),
-                None,
-                None,
+        runtime
+            .pull_image(
+                &devnet_config.stacks_explorer_image_url,
+                &devnet_config.docker_platform,
             )
-            .try_collect::<Vec<_>>()
             .await
             .map_err(|e| format!("unable to create image: {}", e))?;
         let explorer_guest_port = 3000;
@@ -1976,16 +2218,11 @@ events_keys = ["*"]
             ..Default::default()
         };
 
-        let options = CreateContainerOptions {
-            name: format!("stacks-explorer.{}", self.network_name),
-            platform: Some(devnet_config.docker_platform.to_string()),
-        };
-
-        let container = docker
-            .create_container::<String, String>(Some(options), config)
+        let container_name = format!("stacks-explorer.{}", self.network_name);
+        let container = runtime
+            .create_container(&container_name, config, &devnet_config.docker_platform)
             .await
-            .map_err(|e| format!("unable to create container: {}", e))?
-            .id;
+            .map_err(|e| format!("unable to create container: {}", e))?;
 
         ctx.try_log(|logger| {
             slog::info!(logger, "Created container stacks-explorer: {}", container)
@@ -2001,13 +2238,13 @@ events_keys = ["*"]
             _ => return Err("unable to boot container".to_string()),
         };
 
-        let docker = match &self.docker_client {
-            Some(ref docker) => docker,
-            _ => return Err("unable to get Docker client".into()),
+        let runtime = match &self.runtime {
+            Some(ref runtime) => runtime,
+            _ => return Err("unable to get container runtime".into()),
         };
 
-        docker
-            .start_container::<String>(&container, None)
+        runtime
+            .start_container(&container)
             .await
             .map_err(|e| format!("unable to create container: {}", e))?;
 
@@ -2018,25 +2255,19 @@ events_keys = ["*"]
         &mut self,
         ctx: &Context,
     ) -> Result<(), String> {
-        let (docker, _, devnet_config) = match (&self.docker_client, &self.network_config) {
-            (Some(ref docker), Some(ref network_config)) => match network_config.devnet {
-                Some(ref devnet_config) => (docker, network_config, devnet_config),
+        let (runtime, _, devnet_config) = match (&self.runtime, &self.network_config) {
+            (Some(ref runtime), Some(ref network_config)) => match network_config.devnet {
+                Some(ref devnet_config) => (runtime, network_config, devnet_config),
                 _ => return Err("unable to get devnet configuration".into()),
             },
-            _ => return Err("unable to get Docker client".into()),
+            _ => return Err("unable to get container runtime".into()),
         };
 
-        let _info = docker
-            .create_image(
-                Some(CreateImageOptions {
-                    from_image: devnet_config.bitcoin_explorer_image_url.clone(),
-                    platform: devnet_config.docker_platform.clone(),
-                    ..Default::default()
-                }),
-                None,
-                None,
+        runtime
+            .pull_image(
+                &devnet_config.bitcoin_explorer_image_url,
+                &devnet_config.docker_platform,
             )
-            .try_collect::<Vec<_>>()
             .await
             .map_err(|e| format!("unable to create image: {}", e))?;
 
@@ -2096,16 +2327,11 @@ events_keys = ["*"]
             ..Default::default()
         };
 
-        let options = CreateContainerOptions {
-            name: format!("bitcoin-explorer.{}", self.network_name),
-            platform: Some(devnet_config.docker_platform.to_string()),
-        };
-
-        let container = docker
-            .create_container::<String, String>(Some(options), config)
+        let container_name = format!("bitcoin-explorer.{}", self.network_name);
+        let container = runtime
+            .create_container(&container_name, config, &devnet_config.docker_platform)
             .await
-            .map_err(|e| format!("unable to create container: {}", e))?
-            .id;
+            .map_err(|e| format!("unable to create container: {}", e))?;
 
         ctx.try_log(|logger| {
             slog::info!(logger, "Created container bitcoin-explorer: {}", container)
@@ -2121,13 +2347,13 @@ events_keys = ["*"]
             _ => return Err("unable to boot container".to_string()),
         };
 
-        let docker = match &self.docker_client {
-            Some(ref docker) => docker,
-            _ => return Err("unable to get Docker client".into()),
+        let runtime = match &self.runtime {
+            Some(ref runtime) => runtime,
+            _ => return Err("unable to get container runtime".into()),
         };
 
-        docker
-            .start_container::<String>(&container, None)
+        runtime
+            .start_container(&container)
             .await
             .map_err(|e| format!("unable to create container: {}", e))?;
 
@@ -2135,83 +2361,50 @@ events_keys = ["*"]
     }
 
     pub async fn stop_containers(&self) -> Result<(), String> {
-        let containers_ids = match (
+        let (stacks_node_c_id, bitcoin_node_c_id) = match (
             &self.stacks_node_container_id,
-            &self.stacks_api_container_id,
-            &self.stacks_explorer_container_id,
             &self.bitcoin_node_container_id,
-            &self.bitcoin_explorer_container_id,
-            &self.postgres_container_id,
         ) {
-            (Some(c1), Some(c2), Some(c3), Some(c4), Some(c5), Some(c6)) => {
-                (c1, c2, c3, c4, c5, c6)
-            }
+            (Some(c1), Some(c2)) => (c1, c2),
             _ => return Err("unable to boot container".to_string()),
         };
-        let (
-            stacks_node_c_id,
-            stacks_api_c_id,
-            stacks_explorer_c_id,
-            bitcoin_node_c_id,
-            bitcoin_explorer_c_id,
-            postgres_c_id,
-        ) = containers_ids;
-
-        let docker = match &self.docker_client {
-            Some(ref docker) => docker,
-            _ => return Err("unable to get Docker client".into()),
-        };
-
-        let options = KillContainerOptions { signal: "SIGKILL" };
-
-        let _ = docker
-            .kill_container(stacks_node_c_id, Some(options.clone()))
-            .await;
-
-        let _ = docker
-            .kill_container(stacks_api_c_id, Some(options.clone()))
-            .await;
-
-        let _ = docker
-            .kill_container(stacks_explorer_c_id, Some(options.clone()))
-            .await;
-
-        let _ = docker
-            .kill_container(bitcoin_node_c_id, Some(options.clone()))
-            .await;
 
-        let _ = docker
-            .kill_container(bitcoin_explorer_c_id, Some(options.clone()))
-            .await;
+        let runtime = match &self.runtime {
+            Some(ref runtime) => runtime,
+            _ => return Err("unable to get container runtime".into()),
+        };
 
-        let _ = docker
-            .kill_container(postgres_c_id, Some(options.clone()))
-            .await;
+        // the services disabled in the config, or not supported by the runtime, have no container
+        let containers_ids = [
+            Some(stacks_node_c_id),
+            self.stacks_api_container_id.as_ref(),
+            self.stacks_explorer_container_id.as_ref(),
+            Some(bitcoin_node_c_id),
+            self.bitcoin_explorer_container_id.as_ref(),
+            self.postgres_container_id.as_ref(),
+        ];
+        for container_id in containers_ids.into_iter().flatten() {
+            let _ = runtime.kill_container(container_id).await;
+        }
+        for container_id in self.stacks_nodes_container_ids.iter() {
+            let _ = runtime.kill_container(container_id).await;
+        }
 
-        let _ = docker
-            .wait_container(stacks_node_c_id, None::<WaitContainerOptions<String>>)
-            .try_collect::<Vec<_>>()
-            .await;
+        let _ = runtime.wait_container(stacks_node_c_id).await;
 
         Ok(())
     }
 
-    pub async fn start_containers(&self, boot_index: u32) -> Result<(String, String), String> {
-        let containers_ids = match (
-            &self.stacks_api_container_id,
-            &self.stacks_explorer_container_id,
-            &self.bitcoin_explorer_container_id,
-            &self.postgres_container_id,
-        ) {
-            (Some(c1), Some(c2), Some(c3), Some(c4)) => (c1, c2, c3, c4),
-            _ => return Err("unable to boot container".to_string()),
-        };
-        let (stacks_api_c_id, stacks_explorer_c_id, bitcoin_explorer_c_id, postgres_c_id) =
-            containers_ids;
-
-        let docker = match &self.docker_client {
-            Some(ref docker) => docker,
-            _ => return Err("unable to get Docker client".into()),
+    pub async fn start_containers(
+        &self,
+        boot_index: u32,
+    ) -> Result<(String, String, Vec<String>), String> {
+        let (runtime, devnet_config) = match (&self.runtime, &self.network_config) {
+            (Some(ref runtime), Some(ref network_config)) => match network_config.devnet {
+                Some(ref devnet_config) => (runtime, devnet_config),
+                _ => return Err("unable to get devnet configuration".into()),
+            },
+            _ => return Err("unable to get container runtime".into()),
         };
 
         // TODO(lgalabru): should we spawn
@@ -2219,161 +2412,121 @@ events_keys = ["*"]
         // ?
 
         // Prune
-        let mut filters = HashMap::new();
-        filters.insert(
-            "label".to_string(),
-            vec![
+        let _ = runtime
+            .prune_containers(vec![
                 format!("project={}", self.network_name),
                 "reset=true".to_string(),
-            ],
-        );
-        let _ = docker
-            .prune_containers(Some(PruneContainersOptions { filters }))
+            ])
             .await;
 
         let bitcoin_node_config = self.prepare_bitcoin_node_config(boot_index)?;
-
-        let platform = self
-            .network_config
-            .as_ref()
-            .and_then(|c| c.devnet.as_ref())
-            .map(|c| c.docker_platform.to_string());
-
-        let options = CreateContainerOptions {
-            name: format!("bitcoin-node.{}", self.network_name),
-            platform: platform.clone(),
-        };
-        let bitcoin_node_c_id = docker
-            .create_container::<String, String>(Some(options), bitcoin_node_config)
+        let bitcoin_node_c_id = runtime
+            .create_container(
+                &format!("bitcoin-node.{}", self.network_name),
+                bitcoin_node_config,
+                &devnet_config.docker_platform,
+            )
             .await
-            .map_err(|e| format!("unable to create container: {}", e))?
-            .id;
+            .map_err(|e| format!("unable to create container: {}", e))?;
 
         let stacks_node_config = self.prepare_stacks_node_config(boot_index)?;
-
-        let options = CreateContainerOptions {
-            name: format!("stacks-node.{}", self.network_name),
-            platform,
-        };
-        let stacks_node_c_id = docker
-            .create_container::<String, String>(Some(options), stacks_node_config)
+        let stacks_node_c_id = runtime
+            .create_container(
+                &format!("stacks-node.{}", self.network_name),
+                stacks_node_config,
+                &devnet_config.docker_platform,
+            )
             .await
-            .map_err(|e| format!("unable to create container: {}", e))?
-            .id;
-
-        // Start all the containers
-        let _ = docker
-            .start_container::<String>(&bitcoin_node_c_id, None)
-            .await;
-
-        let _ = docker
-            .start_container::<String>(bitcoin_explorer_c_id, None)
-            .await;
-
-        let _ = docker.start_container::<String>(postgres_c_id, None).await;
-
-        let _ = docker
-            .start_container::<String>(stacks_api_c_id, None)
-            .await;
+            .map_err(|e| format!("unable to create container: {}", e))?;
 
-        let _ = docker
-            .start_container::<String>(stacks_explorer_c_id, None)
-            .await;
+        let stacks_nodes_c_ids = self.create_stacks_nodes_containers(boot_index).await?;
 
-        let _ = docker
-            .start_container::<String>(&stacks_node_c_id, None)
-            .await;
+        // Start all the containers
+        let containers_ids = [
+            Some(&bitcoin_node_c_id),
+            self.bitcoin_explorer_container_id.as_ref(),
+            self.postgres_container_id.as_ref(),
+            self.stacks_api_container_id.as_ref(),
+            self.stacks_explorer_container_id.as_ref(),
+            Some(&stacks_node_c_id),
+        ];
+        for container_id in containers_ids.into_iter().flatten() {
+            let _ = runtime.start_container(container_id).await;
+        }
+        for container_id in stacks_nodes_c_ids.iter() {
+            let _ = runtime.start_container(container_id).await;
+        }
 
-        Ok((bitcoin_node_c_id, stacks_node_c_id))
+        Ok((bitcoin_node_c_id, stacks_node_c_id, stacks_nodes_c_ids))
     }
 
     pub async fn kill(&self, ctx: &Context, fatal_message: Option<&str>) {
-        let (docker, devnet_config) = match (&self.docker_client, &self.network_config) {
-            (Some(ref docker), Some(ref network_config)) => match network_config.devnet {
-                Some(ref devnet_config) => (docker, devnet_config),
+        let (runtime, devnet_config) = match (&self.runtime, &self.network_config) {
+            (Some(ref runtime), Some(ref network_config)) => match network_config.devnet {
+                Some(ref devnet_config) => (runtime, devnet_config),
                 _ => return,
             },
             _ => return,
         };
-        let options = Some(KillContainerOptions { signal: "SIGKILL" });
-
         // Terminate containers
         if let Some(ref bitcoin_explorer_container_id) = self.bitcoin_explorer_container_id {
-            let _ = docker
-                .kill_container(bitcoin_explorer_container_id, options.clone())
-                .await;
+            let _ = runtime.kill_container(bitcoin_explorer_container_id).await;
             ctx.try_log(|logger| slog::info!(logger, "Terminating bitcoin-explorer"));
-            let _ = docker
-                .remove_container(bitcoin_explorer_container_id, None)
+            let _ = runtime
+                .remove_container(bitcoin_explorer_container_id)
                 .await;
         }
 
         if let Some(ref stacks_explorer_container_id) = self.stacks_explorer_container_id {
-            let _ = docker
-                .kill_container(stacks_explorer_container_id, options.clone())
-                .await;
+            let _ = runtime.kill_container(stacks_explorer_container_id).await;
             ctx.try_log(|logger| slog::info!(logger, "Terminating stacks-explorer"));
-            let _ = docker
-                .remove_container(stacks_explorer_container_id, None)
-                .await;
+            let _ = runtime.remove_container(stacks_explorer_container_id).await;
         }
 
         if let Some(ref bitcoin_node_container_id) = self.bitcoin_node_container_id {
-            let _ = docker
-                .kill_container(bitcoin_node_container_id, options.clone())
-                .await;
+            let _ = runtime.kill_container(bitcoin_node_container_id).await;
             ctx.try_log(|logger| slog::info!(logger, "Terminating bitcoin-node"));
-            let _ = docker
-                .remove_container(bitcoin_node_container_id, None)
-                .await;
+            let _ = runtime.remove_container(bitcoin_node_container_id).await;
         }
 
         if let Some(ref stacks_api_container_id) = self.stacks_api_container_id {
-            let _ = docker
-                .kill_container(stacks_api_container_id, options.clone())
-                .await;
+            let _ = runtime.kill_container(stacks_api_container_id).await;
             ctx.try_log(|logger| slog::info!(logger, "Terminating stacks-api"));
-            let _ = docker.remove_container(stacks_api_container_id, None).await;
+            let _ = runtime.remove_container(stacks_api_container_id).await;
         }
 
         if let Some(ref postgres_container_id) = self.postgres_container_id {
-            let _ = docker
-                .kill_container(postgres_container_id, options.clone())
-                .await;
+            let _ = runtime.kill_container(postgres_container_id).await;
             ctx.try_log(|logger| slog::info!(logger, "Terminating postgres"));
-            let _ = docker.remove_container(postgres_container_id, None).await;
+            let _ = runtime.remove_container(postgres_container_id).await;
         }
 
         if let Some(ref stacks_node_container_id) = self.stacks_node_container_id {
-            let _ = docker
-                .kill_container(stacks_node_container_id, options.clone())
-                .await;
+            let _ = runtime.kill_container(stacks_node_container_id).await;
             ctx.try_log(|logger| slog::info!(logger, "Terminating stacks-node"));
-            let _ = docker
-                .remove_container(stacks_node_container_id, None)
-                .await;
+            let _ = runtime.remove_container(stacks_node_container_id).await;
+        }
+
+        for stacks_node_container_id in self.stacks_nodes_container_ids.iter() {
+            let _ = runtime.kill_container(stacks_node_container_id).await;
+            ctx.try_log(|logger| slog::info!(logger, "Terminating {}", stacks_node_container_id));
+            let _ = runtime.remove_container(stacks_node_container_id).await;
         }
 
         if let Some(ref subnet_node_container_id) = self.subnet_node_container_id {
-            let _ = docker
-                .kill_container(subnet_node_container_id, options.clone())
-                .await;
+            let _ = runtime.kill_container(subnet_node_container_id).await;
             ctx.try_log(|logger| slog::info!(logger, "Terminating subnet-node"));
-            let _ = docker
-                .remove_container(subnet_node_container_id, None)
-                .await;
+            let _ = runtime.remove_container(subnet_node_container_id).await;
         }
 
         if let Some(ref subnet_api_container_id) = self.subnet_api_container_id {
-            let _ = docker
-                .kill_container(subnet_api_container_id, options)
-                .await;
+            let _ = runtime.kill_container(subnet_api_container_id).await;
             ctx.try_log(|logger| slog::info!(logger, "Terminating subnet-api"));
-            let _ = docker.remove_container(subnet_api_container_id, None).await;
+            let _ = runtime.remove_container(subnet_api_container_id).await;
         }
 
         // Delete network
-        let _ = docker.remove_network(&self.network_name).await;
+        let _ = runtime.remove_network(&self.network_name).await;
 
         ctx.try_log(|logger| slog::info!(logger, "Pruning network and containers"));
 
@@ -2398,25 +2551,14 @@ events_keys = ["*"]
     }
 
     pub async fn prune(&self) {
-        let docker = match &self.docker_client {
-            Some(ref docker) => docker,
+        let runtime = match &self.runtime {
+            Some(ref runtime) => runtime,
             _ => return,
         };
 
-        let mut filters = HashMap::new();
-        filters.insert(
-            "label".to_string(),
-            vec![format!("project={}", self.network_name)],
-        );
-        let _ = docker
-            .prune_containers(Some(PruneContainersOptions {
-                filters: filters.clone(),
-            }))
-            .await;
-
-        let _ = docker
-            .prune_networks(Some(PruneNetworksOptions { filters }))
-            .await;
+        let labels = vec![format!("project={}", self.network_name)];
+        let _ = runtime.prune_containers(labels.clone()).await;
+        let _ = runtime.prune_networks(labels).await;
     }
 
     pub async fn initialize_bitcoin_node(
@@ -2442,6 +2584,14 @@ events_keys = ["*"]
         let faucet_address = Address::from_str(&devnet_config.faucet_btc_address)
             .map_err(|e| format!("unable to create faucet address: {:?}", e))?;
 
+        // the additional miners need their own utxos to commit blocks
+        let mut additional_miners_addresses = vec![];
+        for node in devnet_config.stacks_nodes.iter().filter(|node| node.miner) {
+            let address = Address::from_str(&node.btc_address)
+                .map_err(|e| format!("unable to create {} address: {:?}", node.name, e))?;
+            additional_miners_addresses.push(address);
+        }
+
         let bitcoin_node_url = format!(
             "http://{}/",
             self.services_map_hosts.as_ref().unwrap().bitcoin_node_host
@@ -2496,6 +2646,27 @@ events_keys = ["*"]
             let _ = devnet_event_tx.send(DevnetEvent::info("Waiting for bitcoin-node".to_string()));
         }
 
+        // the blocks and the wallet are already part of the restored chain state
+        if let Some(SnapshotConfig {
+            restored: Some(_), ..
+        }) = self.snapshot
+        {
+            let _ = base_builder(
+                &bitcoin_node_url,
+                &devnet_config.bitcoin_node_username,
+                &devnet_config.bitcoin_node_password,
+            )
+            .json(&json!({
+                "jsonrpc": "1.0",
+                "id": "stacks-network",
+                "method": "loadwallet",
+                "params": [json!(devnet_config.miner_wallet_name)]
+            }))
+            .send()
+            .await;
+            return Ok(());
+        }
+
         let mut error_count = 0;
         loop {
             let rpc_call = base_builder(
@@ -2528,6 +2699,43 @@ events_keys = ["*"]
             let _ = devnet_event_tx.send(DevnetEvent::info("Waiting for bitcoin-node".to_string()));
         }
 
+        for address in additional_miners_addresses.iter() {
+            let mut error_count = 0;
+            loop {
+                let rpc_call = base_builder(
+                    &bitcoin_node_url,
+                    &devnet_config.bitcoin_node_username,
+                    &devnet_config.bitcoin_node_password,
+                )
+                .json(&json!({
+                    "jsonrpc": "1.0",
+                    "id": "stacks-network",
+                    "method": "generatetoaddress",
+                    "params": [json!(1), json!(address)]
+                }))
+                .send()
+                .await
+                .map_err(|e| format!("unable to send 'generatetoaddress' request ({})", e));
+
+                match rpc_call {
+                    Ok(_r) => break,
+                    Err(e) => {
+                        error_count += 1;
+                        if error_count > max_errors {
+                            return Err(e);
+                        } else if error_count > 1 {
+                            let _ = devnet_event_tx.send(DevnetEvent::error(e));
+                        }
+                    }
+                }
+                std::thread::sleep(std::time::Duration::from_secs(1));
+                let _ =
+                    devnet_event_tx.send(DevnetEvent::info("Waiting for bitcoin-node".to_string()));
+            }
+        }
+
+        // keep the same chain height, whatever the number of miners
+        let faucet_blocks = 97usize.saturating_sub(additional_miners_addresses.len());
         let mut error_count = 0;
         loop {
             let rpc_call = base_builder(
@@ -2539,7 +2747,7 @@ events_keys = ["*"]
                 "jsonrpc": "1.0",
                 "id": "stacks-network",
                 "method": "generatetoaddress",
-                "params": [json!(97), json!(faucet_address)]
+                "params": [json!(faucet_blocks), json!(faucet_address)]
             }))
             .send()
             .await
@@ -2785,9 +2993,16 @@ events_keys = ["*"]
             std::thread::sleep(std::time::Duration::from_secs(1));
             let _ = devnet_event_tx.send(DevnetEvent::info("Waiting for bitcoin-node".to_string()));
         }
-        // Index devnet's wallets by default
-        for (_, account) in accounts.iter() {
-            let address = Address::from_str(&account.btc_address)
+        // Index devnet's wallets (and the additional miners) by default
+        let indexed_addresses = accounts.values().map(|account| &account.btc_address).chain(
+            devnet_config
+                .stacks_nodes
+                .iter()
+                .filter(|node| node.miner)
+                .map(|node| &node.btc_address),
+        );
+        for btc_address in indexed_addresses {
+            let address = Address::from_str(btc_address)
                 .map_err(|e| format!("unable to create address: {:?}", e))?;
 
             let mut error_count = 0;
@@ -2872,13 +3087,178 @@ events_keys = ["*"]
     }
 }
 
-fn formatted_docker_error(message: &str, error: DockerError) -> String {
-    let error = match &error {
-        DockerError::DockerResponseServerError {
-            status_code: _c,
-            message: m,
-        } => m.to_string(),
-        _ => format!("{:?}", error),
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::runtime::fake::FakeRuntime;
+    use crate::test_utils::create_test_project;
+    use clarinet_files::{
+        FileLocation, StacksNodeConfigFile, DEFAULT_BITCOIN_NODE_IMAGE, DEFAULT_STACKS_NODE_IMAGE,
     };
-    format!("{}: {}", message, error)
+    use std::sync::{Arc, Mutex};
+
+    fn build_orchestrator(
+        name: &str,
+        runtime: FakeRuntime,
+        stacks_nodes: Option<Vec<StacksNodeConfigFile>>,
+    ) -> DevnetOrchestrator {
+        let project_dir = create_test_project(name);
+        let manifest = ProjectManifest::from_location(&FileLocation::from_path(
+            project_dir.join("Clarinet.toml"),
+        ))
+        .unwrap();
+        let overrides = DevnetConfigFile {
+            working_dir: Some(format!("{}", project_dir.join("devnet").display())),
+            disable_stacks_api: Some(true),
+            disable_stacks_explorer: Some(true),
+            disable_bitcoin_explorer: Some(true),
+            stacks_nodes,
+            ..Default::default()
+        };
+        let mut orchestrator =
+            DevnetOrchestrator::new(manifest, None, Some(overrides), false).unwrap();
+        orchestrator.runtime = Some(Arc::new(runtime));
+        orchestrator
+    }
+
+    #[test]
+    fn boot_sequence_uses_runtime() {
+        let operations = Arc::new(Mutex::new(vec![]));
+        let mut orchestrator = build_orchestrator(
+            "boot-sequence",
+            FakeRuntime {
+                operations: operations.clone(),
+                ..Default::default()
+            },
+            None,
+        );
+        let ctx = Context::empty();
+
+        hiro_system_kit::nestable_block_on(async {
+            let services_map_hosts = orchestrator.prepare_local_network().await.unwrap();
+            assert_eq!(services_map_hosts.bitcoin_node_host, "localhost:18443");
+            orchestrator
+                .prepare_bitcoin_node_container(&ctx)
+                .await
+                .unwrap();
+            orchestrator.boot_bitcoin_node_container().await.unwrap();
+            orchestrator
+                .prepare_stacks_node_container(1, &ctx)
+                .await
+                .unwrap();
+            orchestrator.boot_stacks_node_container().await.unwrap();
+            orchestrator.kill(&ctx, None).await;
+        });
+
+        let network_name = "runtime-test.devnet";
+        let expected = vec![
+            format!("create_network {}", network_name),
+            format!("pull_image {}", DEFAULT_BITCOIN_NODE_IMAGE),
+            format!("create_container bitcoin-node.{}", network_name),
+            format!("start_container bitcoin-node.{}", network_name),
+            format!("pull_image {}", DEFAULT_STACKS_NODE_IMAGE),
+            format!("create_container stacks-node.{}", network_name),
+            format!("start_container stacks-node.{}", network_name),
+            format!("kill_container bitcoin-node.{}", network_name),
+            format!("remove_container bitcoin-node.{}", network_name),
+            format!("kill_container stacks-node.{}", network_name),
+            format!("remove_container stacks-node.{}", network_name),
+            format!("remove_network {}", network_name),
+            "prune_containers".to_string(),
+            "prune_networks".to_string(),
+        ];
+        assert_eq!(*operations.lock().unwrap(), expected);
+    }
+
+    #[test]
+    fn boot_sequence_reports_runtime_errors() {
+        let operations = Arc::new(Mutex::new(vec![]));
+        let mut orchestrator = build_orchestrator(
+            "boot-sequence-error",
+            FakeRuntime {
+                operations: operations.clone(),
+                failing_containers: vec!["bitcoin-node".to_string()],
+            },
+            None,
+        );
+        let ctx = Context::empty();
+
+        let result =
+            hiro_system_kit::nestable_block_on(orchestrator.prepare_bitcoin_node_container(&ctx));
+        assert_eq!(
+            result,
+            Err(
+                "unable to create bitcoind container: bitcoin-node.runtime-test.devnet failed"
+                    .to_string()
+            )
+        );
+        // the container creation is retried once, after killing the previous container
+        assert_eq!(
+            *operations.lock().unwrap(),
+            vec![
+                format!("pull_image {}", DEFAULT_BITCOIN_NODE_IMAGE),
+                "kill_container bitcoin-node.runtime-test.devnet".to_string(),
+            ]
+        );
+    }
+
+    #[test]
+    fn boot_additional_stacks_nodes() {
+        let operations = Arc::new(Mutex::new(vec![]));
+        let node = |miner: bool| StacksNodeConfigFile {
+            name: None,
+            miner: Some(miner),
+            p2p_port: None,
+            rpc_port: None,
+            mnemonic: None,
+            derivation_path: None,
+            env_vars: None,
+        };
+        let mut orchestrator = build_orchestrator(
+            "stacks-nodes",
+            FakeRuntime {
+                operations: operations.clone(),
+                ..Default::default()
+            },
+            Some(vec![node(true), node(false)]),
+        );
+        let working_dir = orchestrator
+            .network_config
+            .as_ref()
+            .and_then(|config| config.devnet.as_ref())
+            .map(|devnet| devnet.working_dir.clone())
+            .unwrap();
+        fs::create_dir_all(format!("{}/conf", working_dir)).unwrap();
+        let ctx = Context::empty();
+
+        hiro_system_kit::nestable_block_on(async {
+            orchestrator
+                .prepare_stacks_nodes_containers(1, &ctx)
+                .await
+                .unwrap();
+            orchestrator.boot_stacks_nodes_containers().await.unwrap();
+        });
+
+        let network_name = "runtime-test.devnet";
+        let expected = vec![
+            format!("create_container stacks-node-1.{}", network_name),
+            format!("create_container stacks-node-2.{}", network_name),
+            format!("start_container stacks-node-1.{}", network_name),
+            format!("start_container stacks-node-2.{}", network_name),
+        ];
+        assert_eq!(*operations.lock().unwrap(), expected);
+
+        let miner_conf =
+            fs::read_to_string(format!("{}/conf/stacks-node-1.toml", working_dir)).unwrap();
+        assert!(miner_conf.contains("rpc_bind = \"0.0.0.0:20453\""));
+        assert!(miner_conf.contains("miner = true"));
+        assert!(miner_conf.contains("[miner]"));
+        assert!(miner_conf.contains(&format!("@stacks-node.{}:20444\"", network_name)));
+
+        let follower_conf =
+            fs::read_to_string(format!("{}/conf/stacks-node-2.toml", working_dir)).unwrap();
+        assert!(follower_conf.contains("p2p_bind = \"0.0.0.0:20464\""));
+        assert!(follower_conf.contains("miner = false"));
+        assert!(!follower_conf.contains("[miner]"));
+    }
 }
@@ -0,0 +1,284 @@
+use async_trait::async_trait;
+use bollard::container::{
+    Config, CreateContainerOptions, KillContainerOptions, ListContainersOptions,
+    PruneContainersOptions, RestartContainerOptions, WaitContainerOptions,
+};
+use bollard::errors::Error as DockerError;
+use bollard::exec::CreateExecOptions;
+use bollard::image::CreateImageOptions;
+use bollard::network::{CreateNetworkOptions, PruneNetworksOptions};
+use bollard::service::Ipam;
+use bollard::Docker;
+use futures::stream::TryStreamExt;
+use std::collections::HashMap;
+
+use super::ContainerRuntime;
+
+// Docker, or any engine exposing the Docker API on a socket (Podman)
+#[derive(Debug)]
+pub struct DockerRuntime {
+    name: String,
+    docker: Docker,
+}
+
+fn connect_with_socket(socket: &str) -> Result<Docker, DockerError> {
+    Docker::connect_with_socket(socket, 120, bollard::API_DEFAULT_VERSION)
+}
+
+impl DockerRuntime {
+    pub fn connect_docker(docker_host: &str) -> Result<DockerRuntime, String> {
+        let docker = connect_with_socket(docker_host)
+            .or_else(|_| Docker::connect_with_socket_defaults())
+            .or_else(|_| {
+                let mut user_space_docker_socket =
+                    dirs::home_dir().expect("unable to retrieve homedir");
+                user_space_docker_socket.push(".docker");
+                user_space_docker_socket.push("run");
+                user_space_docker_socket.push("docker.sock");
+                connect_with_socket(user_space_docker_socket.to_str().unwrap())
+            })
+            .map_err(|e| format!("unable to connect to docker: {:?}", e))?;
+        Ok(DockerRuntime {
+            name: "docker".into(),
+            docker,
+        })
+    }
+
+    pub fn connect_podman(podman_host: &str) -> Result<DockerRuntime, String> {
+        let docker = connect_with_socket(podman_host)
+            .or_else(|e| {
+                // rootless podman exposes its socket in the user runtime dir
+                match std::env::var("XDG_RUNTIME_DIR") {
+                    Ok(runtime_dir) => {
+                        connect_with_socket(&format!("{}/podman/podman.sock", runtime_dir))
+                    }
+                    Err(_) => Err(e),
+                }
+            })
+            .map_err(|e| format!("unable to connect to podman: {:?}", e))?;
+        Ok(DockerRuntime {
+            name: "podman".into(),
+            docker,
+        })
+    }
+}
+
+fn docker_error_message(error: DockerError) -> String {
+    match &error {
+        DockerError::DockerResponseServerError {
+            status_code: _c,
+            message: m,
+        } => m.to_string(),
+        _ => format!("{:?}", error),
+    }
+}
+
+fn labels_filters(labels: Vec<String>) -> HashMap<String, Vec<String>> {
+    let mut filters = HashMap::new();
+    filters.insert("label".to_string(), labels);
+    filters
+}
+
+#[async_trait]
+impl ContainerRuntime for DockerRuntime {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    async fn create_network(&self, name: &str) -> Result<Option<String>, String> {
+        let mut labels = HashMap::new();
+        labels.insert("project", name);
+
+        let mut options = HashMap::new();
+        options.insert("enable_ip_masquerade", "true");
+        options.insert("enable_icc", "true");
+        options.insert("host_binding_ipv4", "0.0.0.0");
+        options.insert("com.docker.network.bridge.enable_icc", "true");
+        options.insert("com.docker.network.bridge.enable_ip_masquerade", "true");
+        options.insert("com.docker.network.bridge.host_binding_ipv4", "0.0.0.0");
+
+        let network_id = self
+            .docker
+            .create_network::<&str>(CreateNetworkOptions {
+                name,
+                driver: "bridge",
+                ipam: Ipam {
+                    ..Default::default()
+                },
+                labels,
+                options,
+                ..Default::default()
+            })
+            .await
+            .map_err(docker_error_message)?
+            .id
+            .ok_or("unable to retrieve network_id")?;
+
+        let res = self
+            .docker
+            .inspect_network::<&str>(&network_id, None)
+            .await
+            .map_err(|e| format!("unable to retrieve network: {}", e))?;
+
+        let gateway = res
+            .ipam
+            .as_ref()
+            .and_then(|ipam| ipam.config.as_ref())
+            .and_then(|config| config.first())
+            .and_then(|map| map.gateway.clone())
+            .ok_or("unable to retrieve gateway")?;
+
+        Ok(Some(gateway))
+    }
+
+    async fn remove_network(&self, name: &str) -> Result<(), String> {
+        self.docker
+            .remove_network(name)
+            .await
+            .map_err(docker_error_message)
+    }
+
+    async fn pull_image(&self, image: &str, platform: &str) -> Result<(), String> {
+        let _info = self
+            .docker
+            .create_image(
+                Some(CreateImageOptions {
+                    from_image: image.to_string(),
+                    platform: platform.to_string(),
+                    ..Default::default()
+                }),
+                None,
+                None,
+            )
+            .try_collect::<Vec<_>>()
+            .await
+            .map_err(docker_error_message)?;
+        Ok(())
+    }
+
+    async fn create_container(
+        &self,
+        name: &str,
+        config: Config<String>,
+        platform: &str,
+    ) -> Result<String, String> {
+        let options = CreateContainerOptions {
+            name: name.to_string(),
+            platform: Some(platform.to_string()),
+        };
+        let container = self
+            .docker
+            .create_container::<String, String>(Some(options), config)
+            .await
+            .map_err(docker_error_message)?;
+        Ok(container.id)
+    }
+
+    async fn start_container(&self, id: &str) -> Result<(), String> {
+        self.docker
+            .start_container::<String>(id, None)
+            .await
+            .map_err(docker_error_message)
+    }
+
+    async fn kill_container(&self, id: &str) -> Result<(), String> {
+        let options = KillContainerOptions { signal: "SIGKILL" };
+        self.docker
+            .kill_container(id, Some(options))
+            .await
+            .map_err(docker_error_message)
+    }
+
+    async fn remove_container(&self, id: &str) -> Result<(), String> {
+        self.docker
+            .remove_container(id, None)
+            .await
+            .map_err(docker_error_message)
+    }
+
+    async fn wait_container(&self, id: &str) -> Result<(), String> {
+        self.docker
+            .wait_container(id, None::<WaitContainerOptions<String>>)
+            .try_collect::<Vec<_>>()
+            .await
+            .map_err(docker_error_message)?;
+        Ok(())
+    }
+
+    async fn pause_container(&self, id: &str) -> Result<(), String> {
+        self.docker
+            .pause_container(id)
+            .await
+            .map_err(docker_error_message)
+    }
+
+    async fn unpause_container(&self, id: &str) -> Result<(), String> {
+        self.docker
+            .unpause_container(id)
+            .await
+            .map_err(docker_error_message)
+    }
+
+    async fn restart_container(&self, id: &str) -> Result<(), String> {
+        self.docker
+            .restart_container(id, Some(RestartContainerOptions { t: 5 }))
+            .await
+            .map_err(docker_error_message)
+    }
+
+    async fn exec(&self, id: &str, cmd: Vec<String>) -> Result<(), String> {
+        let config = CreateExecOptions {
+            cmd: Some(cmd),
+            attach_stdout: Some(false),
+            attach_stderr: Some(false),
+            ..Default::default()
+        };
+        let exec = self
+            .docker
+            .create_exec::<String>(id, config)
+            .await
+            .map_err(|e| format!("unable to create exec command: {}", docker_error_message(e)))?;
+        self.docker
+            .start_exec(&exec.id, None)
+            .await
+            .map_err(|e| format!("unable to start exec command: {}", docker_error_message(e)))?;
+        Ok(())
+    }
+
+    async fn list_containers(&self, labels: Vec<String>) -> Result<Vec<String>, String> {
+        let options = Some(ListContainersOptions {
+            all: true,
+            filters: labels_filters(labels),
+            ..Default::default()
+        });
+        let containers = self
+            .docker
+            .list_containers(options)
+            .await
+            .map_err(docker_error_message)?;
+        Ok(containers
+            .into_iter()
+            .filter_map(|container| container.id)
+            .collect())
+    }
+
+    async fn prune_containers(&self, labels: Vec<String>) -> Result<(), String> {
+        self.docker
+            .prune_containers(Some(PruneContainersOptions {
+                filters: labels_filters(labels),
+            }))
+            .await
+            .map_err(docker_error_message)?;
+        Ok(())
+    }
+
+    async fn prune_networks(&self, labels: Vec<String>) -> Result<(), String> {
+        self.docker
+            .prune_networks(Some(PruneNetworksOptions {
+                filters: labels_filters(labels),
+            }))
+            .await
+            .map_err(docker_error_message)?;
+        Ok(())
+    }
+}
@@ -0,0 +1,108 @@
+use async_trait::async_trait;
+use bollard::container::Config;
+use std::sync::{Arc, Mutex};
+
+use super::ContainerRuntime;
+
+// Records the operations requested by the orchestrator, without running anything
+#[derive(Debug, Default)]
+pub struct FakeRuntime {
+    pub operations: Arc<Mutex<Vec<String>>>,
+    // containers whose creation fails
+    pub failing_containers: Vec<String>,
+}
+
+impl FakeRuntime {
+    fn record(&self, operation: String) {
+        self.operations.lock().unwrap().push(operation);
+    }
+}
+
+#[async_trait]
+impl ContainerRuntime for FakeRuntime {
+    fn name(&self) -> &str {
+        "fake"
+    }
+
+    async fn create_network(&self, name: &str) -> Result<Option<String>, String> {
+        self.record(format!("create_network {}", name));
+        Ok(Some("172.18.0.1".into()))
+    }
+
+    async fn remove_network(&self, name: &str) -> Result<(), String> {
+        self.record(format!("remove_network {}", name));
+        Ok(())
+    }
+
+    async fn pull_image(&self, image: &str, _platform: &str) -> Result<(), String> {
+        self.record(format!("pull_image {}", image));
+        Ok(())
+    }
+
+    async fn create_container(
+        &self,
+        name: &str,
+        _config: Config<String>,
+        _platform: &str,
+    ) -> Result<String, String> {
+        if self.failing_containers.iter().any(|c| name.starts_with(c)) {
+            return Err(format!("{} failed", name));
+        }
+        self.record(format!("create_container {}", name));
+        Ok(name.to_string())
+    }
+
+    async fn start_container(&self, id: &str) -> Result<(), String> {
+        self.record(format!("start_container {}", id));
+        Ok(())
+    }
+
+    async fn kill_container(&self, id: &str) -> Result<(), String> {
+        self.record(format!("kill_container {}", id));
+        Ok(())
+    }
+
+    async fn remove_container(&self, id: &str) -> Result<(), String> {
+        self.record(format!("remove_container {}", id));
+        Ok(())
+    }
+
+    async fn wait_container(&self, id: &str) -> Result<(), String> {
+        self.record(format!("wait_container {}", id));
+        Ok(())
+    }
+
+    async fn pause_container(&self, id: &str) -> Result<(), String> {
+        self.record(format!("pause_container {}", id));
+        Ok(())
+    }
+
+    async fn unpause_container(&self, id: &str) -> Result<(), String> {
+        self.record(format!("unpause_container {}", id));
+        Ok(())
+    }
+
+    async fn restart_container(&self, id: &str) -> Result<(), String> {
+        self.record(format!("restart_container {}", id));
+        Ok(())
+    }
+
+    async fn exec(&self, id: &str, cmd: Vec<String>) -> Result<(), String> {
+        self.record(format!("exec {} {}", id, cmd.join(" ")));
+        Ok(())
+    }
+
+    async fn list_containers(&self, _labels: Vec<String>) -> Result<Vec<String>, String> {
+        Ok(vec![])
+    }
+
+    async fn prune_containers(&self, _labels: Vec<String>) -> Result<(), String> {
+        self.record("prune_containers".into());
+        Ok(())
+    }
+
+    async fn prune_networks(&self, _labels: Vec<String>) -> Result<(), String> {
+        self.record("prune_networks".into());
+        Ok(())
+    }
+}
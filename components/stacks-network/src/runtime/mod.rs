@@ -0,0 +1,89 @@
+mod docker;
+#[cfg(test)]
+pub mod fake;
+mod native;
+
+use async_trait::async_trait;
+use bollard::container::Config;
+use clarinet_files::DevnetConfig;
+use std::fmt::Debug;
+
+pub use docker::DockerRuntime;
+pub use native::NativeRuntime;
+
+// The runtime in charge of the devnet services. Services are described with
+// container configs, native runtimes only use the command, env and binds.
+#[async_trait]
+pub trait ContainerRuntime: Debug + Send + Sync {
+    fn name(&self) -> &str;
+
+    // native runtimes can only start the bitcoin and stacks nodes
+    fn supports_images(&self) -> bool {
+        true
+    }
+
+    // the address used by the services to reach the host
+    fn host_address(&self) -> &str {
+        "host.docker.internal"
+    }
+
+    // a path as seen by the service, for a path mounted in the container
+    fn service_path(&self, _host_path: &str, container_path: &str) -> String {
+        container_path.to_string()
+    }
+
+    // returns the gateway of the network, if any
+    async fn create_network(&self, name: &str) -> Result<Option<String>, String>;
+
+    async fn remove_network(&self, name: &str) -> Result<(), String>;
+
+    async fn pull_image(&self, image: &str, platform: &str) -> Result<(), String>;
+
+    // returns the id of the container
+    async fn create_container(
+        &self,
+        name: &str,
+        config: Config<String>,
+        platform: &str,
+    ) -> Result<String, String>;
+
+    async fn start_container(&self, id: &str) -> Result<(), String>;
+
+    async fn kill_container(&self, id: &str) -> Result<(), String>;
+
+    async fn remove_container(&self, id: &str) -> Result<(), String>;
+
+    async fn wait_container(&self, id: &str) -> Result<(), String>;
+
+    // freezes the processes of the container, used to inject faults
+    async fn pause_container(&self, id: &str) -> Result<(), String>;
+
+    async fn unpause_container(&self, id: &str) -> Result<(), String>;
+
+    async fn restart_container(&self, id: &str) -> Result<(), String>;
+
+    async fn exec(&self, id: &str, cmd: Vec<String>) -> Result<(), String>;
+
+    // ids of the containers matching the labels (`key=value`)
+    async fn list_containers(&self, labels: Vec<String>) -> Result<Vec<String>, String>;
+
+    async fn prune_containers(&self, labels: Vec<String>) -> Result<(), String>;
+
+    async fn prune_networks(&self, labels: Vec<String>) -> Result<(), String>;
+}
+
+pub fn connect_runtime(devnet_config: &DevnetConfig) -> Result<Box<dyn ContainerRuntime>, String> {
+    match devnet_config.container_runtime.as_str() {
+        "docker" => Ok(Box::new(DockerRuntime::connect_docker(
+            &devnet_config.docker_host,
+        )?)),
+        "podman" => Ok(Box::new(DockerRuntime::connect_podman(
+            &devnet_config.docker_host,
+        )?)),
+        "native" => Ok(Box::new(NativeRuntime::new(&devnet_config.working_dir))),
+        runtime => Err(format!(
+            "unknown container runtime '{}' (expected docker, podman or native)",
+            runtime
+        )),
+    }
+}
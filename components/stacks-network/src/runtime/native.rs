@@ -0,0 +1,262 @@
+use async_trait::async_trait;
+use bollard::container::Config;
+use std::collections::HashMap;
+use std::fs::File;
+use std::path::{Path, PathBuf};
+use std::process::{Child, Command, Stdio};
+use std::sync::Mutex;
+
+use super::ContainerRuntime;
+
+#[derive(Debug)]
+struct NativeProcess {
+    command: Vec<String>,
+    env: Vec<(String, String)>,
+    labels: Vec<String>,
+    child: Option<Child>,
+}
+
+impl NativeProcess {
+    fn is_running(&mut self) -> bool {
+        match self.child {
+            Some(ref mut child) => matches!(child.try_wait(), Ok(None)),
+            None => false,
+        }
+    }
+
+    fn kill(&mut self) -> Result<(), String> {
+        if let Some(mut child) = self.child.take() {
+            child
+                .kill()
+                .map_err(|e| format!("unable to kill {}: {}", self.command[0], e))?;
+            let _ = child.wait();
+        }
+        Ok(())
+    }
+
+    // processes are suspended and resumed with signals, the way `docker pause` freezes them
+    fn signal(&mut self, signal: &str) -> Result<(), String> {
+        let pid = match self.child {
+            Some(ref child) => child.id(),
+            None => return Err(format!("{} is not running", self.command[0])),
+        };
+        let status = Command::new("kill")
+            .args([signal, &pid.to_string()])
+            .status()
+            .map_err(|e| format!("unable to signal {}: {}", self.command[0], e))?;
+        if !status.success() {
+            return Err(format!("unable to signal {}: {}", self.command[0], status));
+        }
+        Ok(())
+    }
+}
+
+// Runs locally installed binaries (bitcoind, stacks-node) instead of containers.
+// The binary is looked up in the PATH, using the file name of the container command.
+#[derive(Debug)]
+pub struct NativeRuntime {
+    working_dir: String,
+    processes: Mutex<HashMap<String, NativeProcess>>,
+}
+
+impl NativeRuntime {
+    pub fn new(working_dir: &str) -> NativeRuntime {
+        NativeRuntime {
+            working_dir: working_dir.to_string(),
+            processes: Mutex::new(HashMap::new()),
+        }
+    }
+
+    fn with_process<F, R>(&self, id: &str, f: F) -> Result<R, String>
+    where
+        F: FnOnce(&mut NativeProcess) -> Result<R, String>,
+    {
+        let mut processes = self.processes.lock().map_err(|e| e.to_string())?;
+        match processes.get_mut(id) {
+            Some(process) => f(process),
+            None => Err(format!("unknown process {}", id)),
+        }
+    }
+}
+
+impl Drop for NativeRuntime {
+    fn drop(&mut self) {
+        if let Ok(mut processes) = self.processes.lock() {
+            for process in processes.values_mut() {
+                let _ = process.kill();
+            }
+        }
+    }
+}
+
+fn get_command(config: &Config<String>) -> Vec<String> {
+    let mut command = config.entrypoint.clone().unwrap_or_default();
+    command.append(&mut config.cmd.clone().unwrap_or_default());
+    if let Some(program) = command.first_mut() {
+        if let Some(file_name) = Path::new(program).file_name() {
+            *program = file_name.to_string_lossy().to_string();
+        }
+    }
+    command
+}
+
+#[async_trait]
+impl ContainerRuntime for NativeRuntime {
+    fn name(&self) -> &str {
+        "native"
+    }
+
+    fn supports_images(&self) -> bool {
+        false
+    }
+
+    fn host_address(&self) -> &str {
+        "127.0.0.1"
+    }
+
+    fn service_path(&self, host_path: &str, _container_path: &str) -> String {
+        host_path.to_string()
+    }
+
+    async fn create_network(&self, _name: &str) -> Result<Option<String>, String> {
+        Ok(None)
+    }
+
+    async fn remove_network(&self, _name: &str) -> Result<(), String> {
+        Ok(())
+    }
+
+    async fn pull_image(&self, _image: &str, _platform: &str) -> Result<(), String> {
+        Ok(())
+    }
+
+    async fn create_container(
+        &self,
+        name: &str,
+        config: Config<String>,
+        _platform: &str,
+    ) -> Result<String, String> {
+        let command = get_command(&config);
+        if command.is_empty() {
+            return Err(format!("no command to run for {}", name));
+        }
+        let env = config
+            .env
+            .unwrap_or_default()
+            .iter()
+            .filter_map(|var| var.split_once('='))
+            .map(|(key, value)| (key.to_string(), value.to_string()))
+            .collect();
+        let labels = config
+            .labels
+            .unwrap_or_default()
+            .into_iter()
+            .map(|(key, value)| format!("{}={}", key, value))
+            .collect();
+
+        let mut processes = self.processes.lock().map_err(|e| e.to_string())?;
+        if let Some(mut previous) = processes.remove(name) {
+            previous.kill()?;
+        }
+        processes.insert(
+            name.to_string(),
+            NativeProcess {
+                command,
+                env,
+                labels,
+                child: None,
+            },
+        );
+        Ok(name.to_string())
+    }
+
+    async fn start_container(&self, id: &str) -> Result<(), String> {
+        let mut log_path = PathBuf::from(&self.working_dir);
+        log_path.push(format!("{}.log", id));
+        self.with_process(id, |process| {
+            if process.is_running() {
+                return Ok(());
+            }
+            let log_file = File::create(&log_path)
+                .map_err(|e| format!("unable to create {}: {}", log_path.display(), e))?;
+            let err_file = log_file
+                .try_clone()
+                .map_err(|e| format!("unable to create {}: {}", log_path.display(), e))?;
+            let child = Command::new(&process.command[0])
+                .args(&process.command[1..])
+                .envs(process.env.clone())
+                .stdin(Stdio::null())
+                .stdout(log_file)
+                .stderr(err_file)
+                .spawn()
+                .map_err(|e| format!("unable to start {}: {}", process.command[0], e))?;
+            process.child = Some(child);
+            Ok(())
+        })
+    }
+
+    async fn kill_container(&self, id: &str) -> Result<(), String> {
+        self.with_process(id, |process| process.kill())
+    }
+
+    async fn remove_container(&self, id: &str) -> Result<(), String> {
+        let mut processes = self.processes.lock().map_err(|e| e.to_string())?;
+        match processes.remove(id) {
+            Some(mut process) => process.kill(),
+            None => Ok(()),
+        }
+    }
+
+    async fn wait_container(&self, id: &str) -> Result<(), String> {
+        let child = self.with_process(id, |process| Ok(process.child.take()))?;
+        if let Some(mut child) = child {
+            // waiting on the child blocks, keep it off the async runtime threads
+            tokio::task::spawn_blocking(move || child.wait())
+                .await
+                .map_err(|e| e.to_string())?
+                .map_err(|e| e.to_string())?;
+        }
+        Ok(())
+    }
+
+    async fn pause_container(&self, id: &str) -> Result<(), String> {
+        self.with_process(id, |process| process.signal("-STOP"))
+    }
+
+    async fn unpause_container(&self, id: &str) -> Result<(), String> {
+        self.with_process(id, |process| process.signal("-CONT"))
+    }
+
+    async fn restart_container(&self, id: &str) -> Result<(), String> {
+        self.kill_container(id).await?;
+        self.start_container(id).await
+    }
+
+    async fn exec(&self, id: &str, _cmd: Vec<String>) -> Result<(), String> {
+        Err(format!(
+            "unable to exec in {}: not supported by the native runtime",
+            id
+        ))
+    }
+
+    async fn list_containers(&self, labels: Vec<String>) -> Result<Vec<String>, String> {
+        let processes = self.processes.lock().map_err(|e| e.to_string())?;
+        Ok(processes
+            .iter()
+            .filter(|(_, process)| labels.iter().all(|label| process.labels.contains(label)))
+            .map(|(id, _)| id.clone())
+            .collect())
+    }
+
+    async fn prune_containers(&self, labels: Vec<String>) -> Result<(), String> {
+        let mut processes = self.processes.lock().map_err(|e| e.to_string())?;
+        processes.retain(|_, process| {
+            process.is_running() || !labels.iter().all(|label| process.labels.contains(label))
+        });
+        Ok(())
+    }
+
+    async fn prune_networks(&self, _labels: Vec<String>) -> Result<(), String> {
+        Ok(())
+    }
+}
@@ -0,0 +1,467 @@
+use crate::chains_coordinator::{mine_bitcoin_block, DevnetEventObserverConfig};
+use crate::event::{DevnetEvent, ScenarioReportData};
+
+use clarinet_files::{AccountConfig, DevnetConfig, ProjectManifest};
+use clarity_repl::clarity::vm::types::QualifiedContractIdentifier;
+use clarity_repl::clarity::vm::{ClarityName, Value as ClarityValue};
+use clarity_repl::repl::DEFAULT_EPOCH;
+use clarity_repl::utils::parse_clarity_value;
+use stacks_rpc_client::clarity::codec::TransactionAnchorMode;
+use stacks_rpc_client::crypto::{encode_contract_call, Wallet};
+use stacks_rpc_client::StacksRpc;
+use std::collections::HashMap;
+use std::convert::TryFrom;
+use std::sync::mpsc::Sender;
+use std::time::Duration;
+
+// Scenarios are yaml files, listed in the `scenarios` field of settings/Devnet.toml:
+//
+// name: counter
+// steps:
+//   - at_block_height: 5
+//     contract_call:
+//       contract: counter
+//       function: increment
+//       args: ["u1"]
+//       sender: wallet_1
+//   - mine_until_burn_height: 120
+//   - assert_read_only:
+//       contract: counter
+//       function: get-count
+//       expected: "u1"
+//
+// Contract names without an issuer are deployed by the `deployer` account.
+
+// maximum number of seconds spent waiting for a block height
+const BLOCK_HEIGHT_TIMEOUT: u64 = 300;
+
+#[derive(Deserialize, Debug)]
+struct ScenarioFile {
+    name: String,
+    steps: Vec<ScenarioStepFile>,
+}
+
+#[derive(Deserialize, Debug)]
+struct ScenarioStepFile {
+    at_block_height: Option<u64>,
+    contract_call: Option<ContractCallFile>,
+    mine_until_burn_height: Option<u64>,
+    assert_read_only: Option<ReadOnlyAssertionFile>,
+}
+
+#[derive(Deserialize, Debug)]
+struct ContractCallFile {
+    contract: String,
+    function: String,
+    #[serde(default)]
+    args: Vec<String>,
+    sender: Option<String>,
+}
+
+#[derive(Deserialize, Debug)]
+struct ReadOnlyAssertionFile {
+    contract: String,
+    function: String,
+    #[serde(default)]
+    args: Vec<String>,
+    sender: Option<String>,
+    expected: String,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Scenario {
+    pub name: String,
+    pub steps: Vec<ScenarioStep>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct ScenarioStep {
+    // stacks block height to wait for before executing the action
+    pub at_block_height: Option<u64>,
+    pub action: ScenarioAction,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum ScenarioAction {
+    ContractCall {
+        contract: String,
+        function: String,
+        args: Vec<String>,
+        sender: String,
+    },
+    MineUntilBurnHeight(u64),
+    AssertReadOnly {
+        contract: String,
+        function: String,
+        args: Vec<String>,
+        sender: String,
+        expected: String,
+    },
+}
+
+impl ScenarioAction {
+    fn describe(&self) -> String {
+        match self {
+            ScenarioAction::ContractCall {
+                contract, function, ..
+            } => format!("contract-call {}::{}", contract, function),
+            ScenarioAction::MineUntilBurnHeight(height) => {
+                format!("mine until burn height {}", height)
+            }
+            ScenarioAction::AssertReadOnly {
+                contract, function, ..
+            } => format!("assert {}::{}", contract, function),
+        }
+    }
+}
+
+impl Scenario {
+    pub fn from_file_content(content: &[u8]) -> Result<Scenario, String> {
+        let file: ScenarioFile = serde_yaml::from_slice(content)
+            .map_err(|e| format!("unable to parse scenario: {}", e))?;
+
+        let mut steps = vec![];
+        for (i, step) in file.steps.into_iter().enumerate() {
+            let mut actions = vec![];
+            if let Some(call) = step.contract_call {
+                actions.push(ScenarioAction::ContractCall {
+                    contract: call.contract,
+                    function: call.function,
+                    args: call.args,
+                    sender: call.sender.unwrap_or("deployer".into()),
+                });
+            }
+            if let Some(height) = step.mine_until_burn_height {
+                actions.push(ScenarioAction::MineUntilBurnHeight(height));
+            }
+            if let Some(assertion) = step.assert_read_only {
+                actions.push(ScenarioAction::AssertReadOnly {
+                    contract: assertion.contract,
+                    function: assertion.function,
+                    args: assertion.args,
+                    sender: assertion.sender.unwrap_or("deployer".into()),
+                    expected: assertion.expected,
+                });
+            }
+            let action = match (actions.pop(), actions.is_empty()) {
+                (Some(action), true) => action,
+                _ => {
+                    return Err(format!(
+                        "scenario '{}', step {}: expected exactly one of contract_call, mine_until_burn_height or assert_read_only",
+                        file.name,
+                        i + 1
+                    ))
+                }
+            };
+            steps.push(ScenarioStep {
+                at_block_height: step.at_block_height,
+                action,
+            });
+        }
+
+        Ok(Scenario {
+            name: file.name,
+            steps,
+        })
+    }
+}
+
+pub fn load_scenarios(
+    manifest: &ProjectManifest,
+    devnet_config: &DevnetConfig,
+) -> Result<Vec<Scenario>, String> {
+    let project_root = manifest.location.get_project_root_location()?;
+    let mut scenarios = vec![];
+    for path in devnet_config.scenarios.iter() {
+        let mut location = project_root.clone();
+        location.append_path(path)?;
+        let content = location.read_content()?;
+        let scenario =
+            Scenario::from_file_content(&content).map_err(|e| format!("{}: {}", location, e))?;
+        scenarios.push(scenario);
+    }
+    Ok(scenarios)
+}
+
+// args and expected values are literals, they are parsed but never executed
+fn parse_scenario_value(expression: &str) -> Result<ClarityValue, String> {
+    parse_clarity_value(expression, DEFAULT_EPOCH)
+        .map_err(|e| format!("invalid clarity value {}: {}", expression, e))
+}
+
+// Executes the scenarios against the running devnet, once the deployment is completed
+#[derive(Clone, Debug)]
+pub struct ScenarioRunner {
+    scenarios: Vec<Scenario>,
+    accounts: Vec<AccountConfig>,
+    devnet_config: DevnetConfig,
+    stacks_rpc_url: String,
+    bitcoin_node_host: String,
+    fee_rate: u64,
+}
+
+impl ScenarioRunner {
+    pub fn new(config: &DevnetEventObserverConfig, scenarios: Vec<Scenario>) -> ScenarioRunner {
+        ScenarioRunner {
+            scenarios,
+            accounts: config.accounts.clone(),
+            devnet_config: config.devnet_config.clone(),
+            stacks_rpc_url: config.consolidated_stacks_rpc_url(),
+            bitcoin_node_host: config.services_map_hosts.bitcoin_node_host.clone(),
+            fee_rate: config.deployment_fee_rate,
+        }
+    }
+
+    // blocking, the stacks rpc client can't be used from a runtime
+    pub fn run(&self, devnet_event_tx: &Sender<DevnetEvent>) {
+        let mut nonces = HashMap::new();
+        for scenario in self.scenarios.iter() {
+            let _ = devnet_event_tx.send(DevnetEvent::info(format!(
+                "Running scenario '{}'",
+                scenario.name
+            )));
+            let mut report = ScenarioReportData {
+                name: scenario.name.clone(),
+                passed: true,
+                steps_executed: 0,
+                error: None,
+            };
+            for (i, step) in scenario.steps.iter().enumerate() {
+                let res = self.run_step(step, &mut nonces);
+                if let Err(e) = res {
+                    report.passed = false;
+                    report.error = Some(format!(
+                        "step {} ({}): {}",
+                        i + 1,
+                        step.action.describe(),
+                        e
+                    ));
+                    break;
+                }
+                report.steps_executed += 1;
+                let _ = devnet_event_tx.send(DevnetEvent::debug(format!(
+                    "Scenario '{}': step {} ({}) executed",
+                    scenario.name,
+                    i + 1,
+                    step.action.describe()
+                )));
+            }
+            let _ = devnet_event_tx.send(match report.error {
+                Some(ref e) => {
+                    DevnetEvent::error(format!("Scenario '{}' failed: {}", scenario.name, e))
+                }
+                None => DevnetEvent::success(format!("Scenario '{}' passed", scenario.name)),
+            });
+            let _ = devnet_event_tx.send(DevnetEvent::ScenarioCompleted(report));
+        }
+    }
+
+    fn run_step(
+        &self,
+        step: &ScenarioStep,
+        nonces: &mut HashMap<String, u64>,
+    ) -> Result<(), String> {
+        let stacks_rpc = StacksRpc::new(&self.stacks_rpc_url);
+        if let Some(height) = step.at_block_height {
+            self.wait_for_block_height(&stacks_rpc, height)?;
+        }
+        match &step.action {
+            ScenarioAction::ContractCall {
+                contract,
+                function,
+                args,
+                sender,
+            } => {
+                let contract_id = self.get_contract_id(contract)?;
+                let account = self.get_account(sender)?;
+                let function_args = args
+                    .iter()
+                    .map(|arg| parse_scenario_value(arg))
+                    .collect::<Result<Vec<_>, _>>()?;
+                let function_name = ClarityName::try_from(function.clone())
+                    .map_err(|_| format!("invalid function name {}", function))?;
+
+                let nonce = stacks_rpc
+                    .get_nonce(&account.stx_address)
+                    .map_err(|e| format!("unable to retrieve nonce: {}", e))?;
+                // the node only knows the nonce of confirmed transactions
+                let nonce = match nonces.get(&account.stx_address) {
+                    Some(pending_nonce) if *pending_nonce > nonce => *pending_nonce,
+                    _ => nonce,
+                };
+                let wallet = Wallet {
+                    mnemonic: account.mnemonic.clone(),
+                    derivation: account.derivation.clone(),
+                    mainnet: false,
+                };
+                let transaction = encode_contract_call(
+                    &contract_id,
+                    function_name,
+                    function_args,
+                    &wallet,
+                    nonce,
+                    self.fee_rate * 1000,
+                    TransactionAnchorMode::Any,
+                )?;
+                stacks_rpc
+                    .post_transaction(&transaction)
+                    .map_err(|e| format!("unable to broadcast transaction: {}", e))?;
+                nonces.insert(account.stx_address.clone(), nonce + 1);
+                Ok(())
+            }
+            ScenarioAction::MineUntilBurnHeight(height) => loop {
+                let info = stacks_rpc
+                    .get_info()
+                    .map_err(|e| format!("unable to retrieve stacks-node info: {}", e))?;
+                if info.burn_block_height >= *height {
+                    return Ok(());
+                }
+                hiro_system_kit::nestable_block_on(mine_bitcoin_block(
+                    &self.bitcoin_node_host,
+                    &self.devnet_config.bitcoin_node_username,
+                    &self.devnet_config.bitcoin_node_password,
+                    &self.devnet_config.miner_btc_address,
+                ))?;
+                // let the stacks-node process the new burn block
+                std::thread::sleep(Duration::from_secs(1));
+            },
+            ScenarioAction::AssertReadOnly {
+                contract,
+                function,
+                args,
+                sender,
+                expected,
+            } => {
+                let contract_id = self.get_contract_id(contract)?;
+                let account = self.get_account(sender)?;
+                let function_args = args
+                    .iter()
+                    .map(|arg| parse_scenario_value(arg))
+                    .collect::<Result<Vec<_>, _>>()?;
+                let expected_value = parse_scenario_value(expected)?;
+                let value = stacks_rpc
+                    .call_read_only_fn(
+                        &contract_id.issuer.to_address(),
+                        &contract_id.name.to_string(),
+                        function,
+                        function_args,
+                        &account.stx_address,
+                    )
+                    .map_err(|e| format!("read-only call failed: {}", e))?;
+                if value != expected_value {
+                    return Err(format!("expected {}, got {}", expected_value, value));
+                }
+                Ok(())
+            }
+        }
+    }
+
+    fn wait_for_block_height(&self, stacks_rpc: &StacksRpc, height: u64) -> Result<(), String> {
+        for _ in 0..BLOCK_HEIGHT_TIMEOUT {
+            if let Ok(info) = stacks_rpc.get_info() {
+                if info.stacks_tip_height >= height {
+                    return Ok(());
+                }
+            }
+            std::thread::sleep(Duration::from_secs(1));
+        }
+        Err(format!("block height {} not reached", height))
+    }
+
+    fn get_account(&self, label: &str) -> Result<&AccountConfig, String> {
+        self.accounts
+            .iter()
+            .find(|account| account.label == label)
+            .ok_or(format!("unknown account {}", label))
+    }
+
+    fn get_contract_id(&self, contract: &str) -> Result<QualifiedContractIdentifier, String> {
+        let contract_id = if contract.contains('.') {
+            contract.to_string()
+        } else {
+            format!("{}.{}", self.get_account("deployer")?.stx_address, contract)
+        };
+        QualifiedContractIdentifier::parse(&contract_id)
+            .map_err(|e| format!("invalid contract identifier {}: {}", contract_id, e))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_scenario() {
+        let content = r#"
+name: counter
+steps:
+  - at_block_height: 5
+    contract_call:
+      contract: counter
+      function: increment
+      args: ["u1"]
+      sender: wallet_1
+  - mine_until_burn_height: 120
+  - assert_read_only:
+      contract: ST1PQHQKV0RJXZFY1DGX8MNSNYVE3VGZJSRTPGZGM.counter
+      function: get-count
+      expected: "u1"
+"#;
+        let scenario = Scenario::from_file_content(content.as_bytes()).unwrap();
+        assert_eq!(scenario.name, "counter");
+        assert_eq!(
+            scenario.steps,
+            vec![
+                ScenarioStep {
+                    at_block_height: Some(5),
+                    action: ScenarioAction::ContractCall {
+                        contract: "counter".into(),
+                        function: "increment".into(),
+                        args: vec!["u1".into()],
+                        sender: "wallet_1".into(),
+                    },
+                },
+                ScenarioStep {
+                    at_block_height: None,
+                    action: ScenarioAction::MineUntilBurnHeight(120),
+                },
+                ScenarioStep {
+                    at_block_height: None,
+                    action: ScenarioAction::AssertReadOnly {
+                        contract: "ST1PQHQKV0RJXZFY1DGX8MNSNYVE3VGZJSRTPGZGM.counter".into(),
+                        function: "get-count".into(),
+                        args: vec![],
+                        sender: "deployer".into(),
+                        expected: "u1".into(),
+                    },
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn reject_ambiguous_steps() {
+        let content = r#"
+name: invalid
+steps:
+  - at_block_height: 5
+  - mine_until_burn_height: 120
+    assert_read_only:
+      contract: counter
+      function: get-count
+      expected: "u1"
+"#;
+        let err = Scenario::from_file_content(content.as_bytes()).unwrap_err();
+        assert!(err.contains("step 1"));
+    }
+
+    #[test]
+    fn parse_scenario_values_as_literals() {
+        assert_eq!(
+            parse_scenario_value("(some u1)").unwrap(),
+            ClarityValue::some(ClarityValue::UInt(1)).unwrap()
+        );
+        assert!(parse_scenario_value("(+ u1 u1)").is_err());
+        assert!(parse_scenario_value("(stx-transfer? u1 tx-sender tx-sender)").is_err());
+    }
+}
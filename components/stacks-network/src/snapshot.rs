@@ -0,0 +1,351 @@
+use crate::orchestrator::DevnetOrchestrator;
+use crate::runtime::ContainerRuntime;
+use clarinet_deployments::types::DeploymentSpecification;
+use clarinet_files::{DevnetConfig, NetworkManifest};
+use clarity_repl::clarity::util::hash::Sha256Sum;
+use serde_json::json;
+use stacks_rpc_client::StacksRpc;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+const SNAPSHOT_METADATA_FILE: &str = "snapshot.json";
+
+// the data directories of the first boot, the only ones captured by a snapshot
+const SNAPSHOT_DATA_DIRS: [&str; 2] = ["bitcoin", "stacks"];
+
+// the services writing in these directories, paused while they are copied
+const SNAPSHOT_SERVICES: [&str; 2] = ["bitcoin-node", "stacks-node"];
+
+// the state of the chains coordinator once the deployment is completed
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq)]
+pub struct CoordinatorState {
+    pub deployed_contracts: Vec<String>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct SnapshotMetadata {
+    pub name: String,
+    pub deployment_plan_hash: String,
+    pub created_at: u64,
+    pub bitcoin_block_height: u64,
+    pub stacks_block_height: u64,
+    #[serde(default)]
+    pub coordinator: CoordinatorState,
+}
+
+#[derive(Clone, Debug)]
+pub struct SnapshotConfig {
+    pub name: String,
+    pub location: PathBuf,
+    pub working_dir: String,
+    pub deployment_plan_hash: String,
+    // set when the devnet boots from an existing snapshot
+    pub restored: Option<SnapshotMetadata>,
+    runtime: Option<Arc<dyn ContainerRuntime>>,
+    containers: Vec<String>,
+}
+
+impl SnapshotConfig {
+    pub fn new(
+        name: &str,
+        devnet_config: &DevnetConfig,
+        devnet: &DevnetOrchestrator,
+        deployment: &DeploymentSpecification,
+    ) -> Result<SnapshotConfig, String> {
+        if !devnet_config.bind_containers_volumes && devnet_config.container_runtime != "native" {
+            return Err(
+                "devnet snapshots require the containers volumes to be bound (bind_containers_volumes = true)"
+                    .into(),
+            );
+        }
+        let network_manifest = match devnet.network_config {
+            Some(ref network_manifest) => network_manifest,
+            None => return Err("unable to retrieve the network manifest".into()),
+        };
+        let mut location = PathBuf::from(devnet.manifest.project.cache_location.to_string());
+        location.push("devnet-snapshots");
+        location.push(name);
+
+        let deployment_plan_hash =
+            compute_deployment_plan_hash(deployment, network_manifest, devnet_config)?;
+        let restored = match load_snapshot_metadata(&location) {
+            Some(metadata) if metadata.deployment_plan_hash == deployment_plan_hash => {
+                Some(metadata)
+            }
+            _ => None,
+        };
+
+        Ok(SnapshotConfig {
+            name: name.to_string(),
+            location,
+            working_dir: devnet_config.working_dir.clone(),
+            deployment_plan_hash,
+            restored,
+            runtime: devnet.runtime(),
+            containers: SNAPSHOT_SERVICES
+                .iter()
+                .map(|service| format!("{}.{}", service, devnet.network_name()))
+                .collect(),
+        })
+    }
+
+    // copies the chain state of the snapshot in the data directory of the first boot
+    pub fn restore(&self) -> Result<(), String> {
+        let data_path = get_boot_data_path(&self.working_dir);
+        for dir in SNAPSHOT_DATA_DIRS {
+            let target = data_path.join(dir);
+            if target.exists() {
+                fs::remove_dir_all(&target)
+                    .map_err(|e| format!("unable to clean {}: {}", target.display(), e))?;
+            }
+            copy_dir(&self.location.join("data").join(dir), &target)?;
+        }
+        Ok(())
+    }
+
+    // copies the chain state of the running devnet, once the deployment is completed
+    pub fn save(
+        &self,
+        stacks_rpc_url: &str,
+        coordinator: CoordinatorState,
+    ) -> Result<SnapshotMetadata, String> {
+        let info = StacksRpc::new(stacks_rpc_url)
+            .get_info()
+            .map_err(|e| format!("unable to retrieve stacks-node info: {:?}", e))?;
+        self.copy_paused_services_data()?;
+
+        let metadata = SnapshotMetadata {
+            name: self.name.clone(),
+            deployment_plan_hash: self.deployment_plan_hash.clone(),
+            created_at: clarity_repl::clarity::util::get_epoch_time_secs(),
+            bitcoin_block_height: info.burn_block_height,
+            stacks_block_height: info.stacks_tip_height,
+            coordinator,
+        };
+        let content = serde_json::to_vec_pretty(&metadata)
+            .map_err(|e| format!("unable to serialize snapshot metadata: {}", e))?;
+        fs::write(self.location.join(SNAPSHOT_METADATA_FILE), content)
+            .map_err(|e| format!("unable to write snapshot metadata: {}", e))?;
+        Ok(metadata)
+    }
+
+    // the services are paused while their data is copied, so that the files are not
+    // written in the meantime. they are resumed even if the copy fails
+    fn copy_paused_services_data(&self) -> Result<(), String> {
+        let runtime = match self.runtime {
+            Some(ref runtime) => runtime,
+            None => return Err("the devnet services are not managed by clarinet".into()),
+        };
+
+        let mut paused = vec![];
+        let mut result = Ok(());
+        for container in self.containers.iter() {
+            match hiro_system_kit::nestable_block_on(runtime.pause_container(container)) {
+                Ok(()) => paused.push(container),
+                Err(e) => {
+                    result = Err(e);
+                    break;
+                }
+            }
+        }
+        if result.is_ok() {
+            result = self.copy_data();
+        }
+        for container in paused {
+            if let Err(e) = hiro_system_kit::nestable_block_on(runtime.unpause_container(container))
+            {
+                result = result.and(Err(e));
+            }
+        }
+        result
+    }
+
+    fn copy_data(&self) -> Result<(), String> {
+        if self.location.exists() {
+            fs::remove_dir_all(&self.location)
+                .map_err(|e| format!("unable to clean {}: {}", self.location.display(), e))?;
+        }
+        let data_path = get_boot_data_path(&self.working_dir);
+        for dir in SNAPSHOT_DATA_DIRS {
+            copy_dir(&data_path.join(dir), &self.location.join("data").join(dir))?;
+        }
+        Ok(())
+    }
+}
+
+// a snapshot can only be reused with the deployment plan (and contracts) it was taken with,
+// and with the devnet settings the chains were booted with
+pub fn compute_deployment_plan_hash(
+    deployment: &DeploymentSpecification,
+    network_manifest: &NetworkManifest,
+    devnet_config: &DevnetConfig,
+) -> Result<String, String> {
+    let mut content = deployment.to_file_content()?;
+    for (source, _) in deployment.contracts.values() {
+        content.extend_from_slice(source.as_bytes());
+    }
+    let accounts = network_manifest
+        .accounts
+        .iter()
+        .map(|(name, account)| {
+            (
+                name,
+                &account.stx_address,
+                &account.btc_address,
+                account.balance,
+            )
+        })
+        .collect::<Vec<_>>();
+    let devnet_settings = json!({
+        "accounts": accounts,
+        "epochs": [
+            devnet_config.epoch_2_0,
+            devnet_config.epoch_2_05,
+            devnet_config.epoch_2_1,
+            devnet_config.epoch_2_2,
+            devnet_config.epoch_2_3,
+            devnet_config.epoch_2_4,
+            devnet_config.epoch_2_5,
+            devnet_config.epoch_3_0,
+        ],
+        "pox_stacking_orders": devnet_config.pox_stacking_orders,
+        "pox_delegation_orders": devnet_config.pox_delegation_orders,
+        "miner": [
+            &devnet_config.miner_stx_address,
+            &devnet_config.miner_btc_address,
+            &devnet_config.miner_coinbase_recipient,
+        ],
+    });
+    content.extend_from_slice(devnet_settings.to_string().as_bytes());
+    Ok(Sha256Sum::from_data(&content).to_hex())
+}
+
+fn load_snapshot_metadata(location: &Path) -> Option<SnapshotMetadata> {
+    let content = fs::read(location.join(SNAPSHOT_METADATA_FILE)).ok()?;
+    serde_json::from_slice(&content).ok()
+}
+
+fn get_boot_data_path(working_dir: &str) -> PathBuf {
+    let mut data_path = PathBuf::from(working_dir);
+    data_path.push("data");
+    data_path.push("1");
+    data_path
+}
+
+fn copy_dir(source: &Path, target: &Path) -> Result<(), String> {
+    fs::create_dir_all(target)
+        .map_err(|e| format!("unable to create {}: {}", target.display(), e))?;
+    let entries =
+        fs::read_dir(source).map_err(|e| format!("unable to read {}: {}", source.display(), e))?;
+    for entry in entries {
+        let entry = entry.map_err(|e| format!("unable to read {}: {}", source.display(), e))?;
+        let path = entry.path();
+        let target_path = target.join(entry.file_name());
+        if path.is_dir() {
+            copy_dir(&path, &target_path)?;
+        } else {
+            fs::copy(&path, &target_path)
+                .map_err(|e| format!("unable to copy {}: {}", path.display(), e))?;
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::runtime::fake::FakeRuntime;
+
+    #[test]
+    fn copy_snapshot_data() {
+        let mut root = std::env::temp_dir();
+        root.push(format!("devnet-snapshot-{}", std::process::id()));
+        let source = root.join("source");
+        fs::create_dir_all(source.join("regtest/blocks")).unwrap();
+        fs::write(source.join("regtest/blocks/blk00000.dat"), b"block").unwrap();
+        fs::write(source.join("settings.json"), b"{}").unwrap();
+
+        let target = root.join("target");
+        copy_dir(&source, &target).unwrap();
+        assert_eq!(
+            fs::read(target.join("regtest/blocks/blk00000.dat")).unwrap(),
+            b"block"
+        );
+        assert_eq!(fs::read(target.join("settings.json")).unwrap(), b"{}");
+
+        assert!(copy_dir(&root.join("missing"), &target).is_err());
+        let _ = fs::remove_dir_all(root);
+    }
+
+    #[test]
+    fn pause_services_while_copying_data() {
+        let mut working_dir = std::env::temp_dir();
+        working_dir.push(format!("devnet-snapshot-save-{}", std::process::id()));
+        let data_path = get_boot_data_path(&working_dir.to_string_lossy());
+        for dir in SNAPSHOT_DATA_DIRS {
+            fs::create_dir_all(data_path.join(dir)).unwrap();
+            fs::write(data_path.join(dir).join("chainstate"), dir).unwrap();
+        }
+
+        let runtime = FakeRuntime::default();
+        let operations = runtime.operations.clone();
+        let snapshot = SnapshotConfig {
+            name: "deployed".into(),
+            location: working_dir.join("snapshot"),
+            working_dir: working_dir.to_string_lossy().to_string(),
+            deployment_plan_hash: "abcd".into(),
+            restored: None,
+            runtime: Some(Arc::new(runtime)),
+            containers: vec!["bitcoin-node.devnet".into(), "stacks-node.devnet".into()],
+        };
+        snapshot.copy_paused_services_data().unwrap();
+
+        assert_eq!(
+            fs::read(snapshot.location.join("data/stacks/chainstate")).unwrap(),
+            b"stacks"
+        );
+        assert_eq!(
+            *operations.lock().unwrap(),
+            vec![
+                "pause_container bitcoin-node.devnet",
+                "pause_container stacks-node.devnet",
+                "unpause_container bitcoin-node.devnet",
+                "unpause_container stacks-node.devnet",
+            ]
+        );
+
+        // the services are resumed when the copy fails
+        fs::remove_dir_all(data_path.join("bitcoin")).unwrap();
+        operations.lock().unwrap().clear();
+        assert!(snapshot.copy_paused_services_data().is_err());
+        assert_eq!(operations.lock().unwrap().len(), 4);
+        let _ = fs::remove_dir_all(working_dir);
+    }
+
+    #[test]
+    fn read_snapshot_metadata() {
+        let mut location = std::env::temp_dir();
+        location.push(format!("devnet-snapshot-metadata-{}", std::process::id()));
+        fs::create_dir_all(&location).unwrap();
+        assert_eq!(load_snapshot_metadata(&location), None);
+
+        let metadata = SnapshotMetadata {
+            name: "deployed".into(),
+            deployment_plan_hash: "abcd".into(),
+            created_at: 1700000000,
+            bitcoin_block_height: 110,
+            stacks_block_height: 6,
+            coordinator: CoordinatorState {
+                deployed_contracts: vec!["counter".into()],
+            },
+        };
+        fs::write(
+            location.join(SNAPSHOT_METADATA_FILE),
+            serde_json::to_vec(&metadata).unwrap(),
+        )
+        .unwrap();
+        assert_eq!(load_snapshot_metadata(&location), Some(metadata));
+        let _ = fs::remove_dir_all(location);
+    }
+}
@@ -0,0 +1,20 @@
+use std::fs;
+use std::path::PathBuf;
+
+// Writes a minimal project (Clarinet.toml and settings/Devnet.toml) in a temporary directory
+pub fn create_test_project(name: &str) -> PathBuf {
+    let mut project_dir = std::env::temp_dir();
+    project_dir.push(format!("{}-{}", name, std::process::id()));
+    fs::create_dir_all(project_dir.join("settings")).unwrap();
+    fs::write(
+        project_dir.join("Clarinet.toml"),
+        "[project]\nname = \"runtime-test\"\n",
+    )
+    .unwrap();
+    fs::write(
+        project_dir.join("settings/Devnet.toml"),
+        "[network]\nname = \"devnet\"\n\n[accounts.deployer]\nbalance = 100_000_000\n",
+    )
+    .unwrap();
+    project_dir
+}
@@ -1,3 +1,4 @@
+use super::transaction::matches_filter;
 use super::util::{StatefulList, TabsState};
 use crate::event::ServiceStatusData;
 use crate::{LogData, MempoolAdmissionData};
@@ -25,6 +26,10 @@ pub struct App<'a> {
     pub mempool: StatefulList<MempoolAdmissionData>,
     pub logs: StatefulList<LogData>,
     pub services: StatefulList<ServiceStatusData>,
+    pub show_transaction_details: bool,
+    // transactions are filtered by sender or contract
+    pub transactions_filter: String,
+    pub editing_filter: bool,
 }
 
 impl<'a> App<'a> {
@@ -40,23 +45,104 @@ impl<'a> App<'a> {
             logs: StatefulList::with_items(vec![]),
             services: StatefulList::with_items(vec![]),
             subnet_enabled,
+            show_transaction_details: false,
+            transactions_filter: String::new(),
+            editing_filter: false,
         }
     }
 
+    pub fn selected_block(&self) -> Option<&BlockData> {
+        if self.tabs.titles.is_empty() {
+            return None;
+        }
+        self.blocks
+            .get((self.tabs.titles.len() - 1) - self.tabs.index)
+    }
+
+    pub fn visible_transactions(&self) -> Vec<&StacksTransactionData> {
+        let transactions = match self.selected_block() {
+            Some(BlockData::Block(block)) => &block.transactions,
+            Some(BlockData::Microblock(microblock)) => &microblock.transactions,
+            None => return vec![],
+        };
+        transactions
+            .iter()
+            .filter(|tx| {
+                matches_filter(
+                    &tx.metadata.sender,
+                    &tx.metadata.description,
+                    &self.transactions_filter,
+                )
+            })
+            .collect()
+    }
+
+    pub fn selected_transaction(&self) -> Option<&StacksTransactionData> {
+        let index = self.transactions.state.selected()?;
+        self.visible_transactions().get(index).copied()
+    }
+
+    fn reset_transaction_selection(&mut self) {
+        self.transactions.state.select(None);
+        self.show_transaction_details = false;
+    }
+
     pub fn on_up(&mut self) {
-        self.transactions.previous();
+        let count = self.visible_transactions().len();
+        let index = previous_index(self.transactions.state.selected(), count);
+        self.transactions.state.select(index);
     }
 
     pub fn on_down(&mut self) {
-        self.transactions.next();
+        let count = self.visible_transactions().len();
+        let index = next_index(self.transactions.state.selected(), count);
+        self.transactions.state.select(index);
     }
 
     pub fn on_right(&mut self) {
         self.tabs.next();
+        self.reset_transaction_selection();
     }
 
     pub fn on_left(&mut self) {
         self.tabs.previous();
+        self.reset_transaction_selection();
+    }
+
+    pub fn on_enter(&mut self) {
+        if self.editing_filter {
+            self.editing_filter = false;
+        } else if self.selected_transaction().is_some() {
+            self.show_transaction_details = !self.show_transaction_details;
+        }
+    }
+
+    pub fn on_escape(&mut self) {
+        if self.editing_filter {
+            self.editing_filter = false;
+            self.transactions_filter.clear();
+        } else if self.show_transaction_details {
+            self.show_transaction_details = false;
+        } else {
+            self.transactions_filter.clear();
+            self.reset_transaction_selection();
+        }
+    }
+
+    pub fn start_filter_edition(&mut self) {
+        self.editing_filter = true;
+        self.transactions_filter.clear();
+        self.reset_transaction_selection();
+    }
+
+    pub fn on_filter_key(&mut self, c: Option<char>) {
+        match c {
+            Some(c) => self.transactions_filter.push(c),
+            None => {
+                self.transactions_filter.pop();
+            }
+        }
+        self.reset_transaction_selection();
     }
 
     pub fn on_key(&mut self, c: char) {
@@ -162,3 +248,96 @@ impl<'a> App<'a> {
         }
     }
 }
+
+// The selection wraps around the visible transactions, and stays within them when the list
+// got shorter than the selected index
+fn previous_index(selected: Option<usize>, count: usize) -> Option<usize> {
+    if count == 0 {
+        return None;
+    }
+    match selected {
+        Some(index) if index > 0 => Some(index.min(count) - 1),
+        _ => Some(count - 1),
+    }
+}
+
+fn next_index(selected: Option<usize>, count: usize) -> Option<usize> {
+    if count == 0 {
+        return None;
+    }
+    match selected {
+        Some(index) if index + 1 < count => Some(index + 1),
+        _ => Some(0),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn move_selection_within_visible_transactions() {
+        assert_eq!(previous_index(None, 0), None);
+        assert_eq!(next_index(Some(2), 0), None);
+
+        assert_eq!(next_index(None, 3), Some(0));
+        assert_eq!(next_index(Some(1), 3), Some(2));
+        assert_eq!(next_index(Some(2), 3), Some(0));
+        assert_eq!(previous_index(None, 3), Some(2));
+        assert_eq!(previous_index(Some(0), 3), Some(2));
+        assert_eq!(previous_index(Some(2), 3), Some(1));
+
+        // the list was filtered down to 2 transactions while the 5th one was selected
+        assert_eq!(next_index(Some(4), 2), Some(0));
+        assert_eq!(previous_index(Some(4), 2), Some(1));
+    }
+
+    #[test]
+    fn reset_selection_when_switching_tabs() {
+        let mut app = App::new("devnet", "/tmp", false);
+        app.tabs.titles.push_front(Span::from("[1]"));
+        app.tabs.titles.push_front(Span::from("[2]"));
+
+        app.transactions.state.select(Some(1));
+        app.show_transaction_details = true;
+        app.on_right();
+        assert_eq!(app.tabs.index, 1);
+        assert_eq!(app.transactions.state.selected(), None);
+        assert!(!app.show_transaction_details);
+
+        app.transactions.state.select(Some(0));
+        app.on_left();
+        assert_eq!(app.tabs.index, 0);
+        assert_eq!(app.transactions.state.selected(), None);
+    }
+
+    #[test]
+    fn escape_filter_and_selection() {
+        let mut app = App::new("devnet", "/tmp", false);
+        app.start_filter_edition();
+        app.on_filter_key(Some('S'));
+        app.on_filter_key(Some('T'));
+        app.on_filter_key(None);
+        assert_eq!(app.transactions_filter, "S");
+
+        // escaping the edition discards the filter
+        app.on_escape();
+        assert!(!app.editing_filter);
+        assert!(app.transactions_filter.is_empty());
+
+        app.transactions_filter.push_str("counter");
+        app.transactions.state.select(Some(0));
+        app.show_transaction_details = true;
+        // the details are closed first, then the filter and the selection are cleared
+        app.on_escape();
+        assert!(!app.show_transaction_details);
+        assert_eq!(app.transactions_filter, "counter");
+        app.on_escape();
+        assert!(app.transactions_filter.is_empty());
+        assert_eq!(app.transactions.state.selected(), None);
+
+        // without any block, there is nothing to select
+        app.on_down();
+        assert_eq!(app.transactions.state.selected(), None);
+    }
+}
@@ -1,5 +1,6 @@
 #[allow(dead_code)]
 mod app;
+pub mod transaction;
 #[allow(clippy::module_inception)]
 mod ui;
 #[allow(dead_code)]
@@ -7,6 +8,7 @@ mod util;
 
 use super::DevnetEvent;
 
+use crate::faults::{FaultCommand, FaultInjector};
 use crate::{chains_coordinator::BitcoinMiningCommand, ChainsCoordinatorCommand};
 
 use app::App;
@@ -18,6 +20,7 @@ use crossterm::{
 };
 use ratatui::{backend::CrosstermBackend, Terminal};
 use std::sync::mpsc::{Receiver, Sender};
+use std::sync::Arc;
 use std::{
     error::Error,
     io::{stdout, Stdout},
@@ -25,6 +28,9 @@ use std::{
     time::{Duration, Instant},
 };
 
+// latency applied to the events deliveries when toggled from the dashboard
+const EVENT_LATENCY_MS: u64 = 2000;
+
 pub fn start_ui(
     devnet_events_tx: Sender<DevnetEvent>,
     devnet_events_rx: Receiver<DevnetEvent>,
@@ -33,6 +39,7 @@ pub fn start_ui(
     devnet_path: &str,
     subnet_enabled: bool,
     automining_enabled: bool,
+    faults: Arc<FaultInjector>,
     ctx: &Context,
 ) -> Result<(), String> {
     let res = do_start_ui(
@@ -43,6 +50,7 @@ pub fn start_ui(
         devnet_path,
         subnet_enabled,
         automining_enabled,
+        faults,
         ctx,
     );
     if let Err(ref _e) = res {
@@ -59,6 +67,7 @@ pub fn do_start_ui(
     devnet_path: &str,
     subnet_enabled: bool,
     automining_enabled: bool,
+    faults: Arc<FaultInjector>,
     ctx: &Context,
 ) -> Result<(), String> {
     enable_raw_mode().map_err(|e| format!("unable to start terminal ui: {}", e))?;
@@ -73,6 +82,7 @@ pub fn do_start_ui(
         Terminal::new(backend).map_err(|e| format!("unable to start terminal ui: {}", e))?;
 
     // Setup input handling
+    let faults_events_tx = devnet_events_tx.clone();
     let tick_rate = Duration::from_millis(500);
     thread::spawn(move || {
         let mut last_tick = Instant::now();
@@ -124,6 +134,17 @@ pub fn do_start_ui(
             }
         };
         match event {
+            DevnetEvent::KeyEvent(event)
+                if app.editing_filter && event.modifiers != KeyModifiers::CONTROL =>
+            {
+                match event.code {
+                    KeyCode::Char(c) => app.on_filter_key(Some(c)),
+                    KeyCode::Backspace => app.on_filter_key(None),
+                    KeyCode::Enter => app.on_enter(),
+                    KeyCode::Esc => app.on_escape(),
+                    _ => {}
+                }
+            }
             DevnetEvent::KeyEvent(event) => match (event.modifiers, event.code) {
                 (KeyModifiers::CONTROL, KeyCode::Char('c')) => {
                     app.display_log(
@@ -155,10 +176,33 @@ pub fn do_start_ui(
                         );
                     }
                 }
+                // the fault shortcuts are only bound when fault injection is enabled
+                (KeyModifiers::NONE, KeyCode::Char(c @ ('l' | 'd' | 'b' | 'p' | 'r')))
+                    if faults.is_enabled() =>
+                {
+                    let state = faults.state();
+                    let command = match c {
+                        'l' if state.event_latency_ms == 0 => {
+                            FaultCommand::SetEventLatency(EVENT_LATENCY_MS)
+                        }
+                        'l' => FaultCommand::SetEventLatency(0),
+                        'd' => FaultCommand::DropEvents(!state.drop_events),
+                        'b' => FaultCommand::DisconnectBitcoinNode(!state.bitcoin_disconnected),
+                        'p' if state.paused_services.iter().any(|s| s == "stacks-node") => {
+                            FaultCommand::ResumeService("stacks-node".into())
+                        }
+                        'p' => FaultCommand::PauseService("stacks-node".into()),
+                        _ => FaultCommand::RestartService("stacks-node".into()),
+                    };
+                    inject_fault(&faults, command, &faults_events_tx);
+                }
                 (KeyModifiers::NONE, KeyCode::Left) => app.on_left(),
                 (KeyModifiers::NONE, KeyCode::Up) => app.on_up(),
                 (KeyModifiers::NONE, KeyCode::Right) => app.on_right(),
                 (KeyModifiers::NONE, KeyCode::Down) => app.on_down(),
+                (KeyModifiers::NONE, KeyCode::Enter) => app.on_enter(),
+                (KeyModifiers::NONE, KeyCode::Esc) => app.on_escape(),
+                (KeyModifiers::NONE, KeyCode::Char('/')) => app.start_filter_edition(),
                 _ => {}
             },
             DevnetEvent::Tick => {
@@ -241,6 +285,9 @@ pub fn do_start_ui(
             DevnetEvent::ProtocolDeployingProgress(_) => {
                 // Display something
             }
+            DevnetEvent::ScenarioCompleted(_) => {
+                // Already reported through logs
+            }
             DevnetEvent::FatalError(message) => {
                 app.display_log(DevnetEvent::log_error(format!("Fatal: {}", message)), ctx);
                 let _ = terminate(
@@ -272,6 +319,29 @@ pub fn do_start_ui(
     Ok(())
 }
 
+// the services commands can take a while, they are executed out of the ui loop
+fn inject_fault(
+    faults: &Arc<FaultInjector>,
+    command: FaultCommand,
+    devnet_events_tx: &Sender<DevnetEvent>,
+) {
+    let faults = faults.clone();
+    let devnet_events_tx = devnet_events_tx.clone();
+    thread::spawn(move || {
+        let description = command.to_string();
+        let result = hiro_system_kit::nestable_block_on(faults.execute(command));
+        let _ = match result {
+            Ok(_) => devnet_events_tx.send(DevnetEvent::warning(format!(
+                "Fault injection: {}",
+                description
+            ))),
+            Err(e) => {
+                devnet_events_tx.send(DevnetEvent::error(format!("Unable to inject fault: {}", e)))
+            }
+        };
+    });
+}
+
 fn terminate(
     terminal: &mut Terminal<CrosstermBackend<Stdout>>,
     chains_coordinator_commands_tx: crossbeam_channel::Sender<ChainsCoordinatorCommand>,
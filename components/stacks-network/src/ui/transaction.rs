@@ -0,0 +1,165 @@
+use chainhook_sdk::types::StacksTransactionData;
+use clarity_repl::clarity::codec::StacksMessageCodec;
+use clarity_repl::clarity::util::hash::hex_bytes;
+use clarity_repl::codec::{StacksTransaction, TransactionPayload};
+use serde_json::Value as JsonValue;
+use std::io::Cursor;
+
+// Everything displayed in the transaction details pane
+pub struct TransactionDetails {
+    pub txid: String,
+    pub sender: String,
+    pub success: bool,
+    pub result: String,
+    pub payload: Vec<String>,
+    pub events: Vec<String>,
+    pub costs: Vec<String>,
+}
+
+impl TransactionDetails {
+    pub fn new(tx: &StacksTransactionData) -> TransactionDetails {
+        let payload = match decode_payload(&tx.metadata.raw_tx) {
+            Ok(payload) => payload,
+            Err(e) => vec![format!("unable to decode transaction: {}", e)],
+        };
+        let events = tx
+            .metadata
+            .receipt
+            .events
+            .iter()
+            .map(|event| format_event(serde_json::to_value(event).unwrap_or_default()))
+            .collect();
+        let costs = match serde_json::to_value(&tx.metadata.execution_cost) {
+            Ok(JsonValue::Object(costs)) => costs
+                .iter()
+                .map(|(key, value)| format!("{}: {}", key, value))
+                .collect(),
+            _ => vec![],
+        };
+        TransactionDetails {
+            txid: tx.transaction_identifier.hash.clone(),
+            sender: tx.metadata.sender.clone(),
+            success: tx.metadata.success,
+            result: tx.metadata.result.clone(),
+            payload,
+            events,
+            costs,
+        }
+    }
+}
+
+// A transaction matches a filter when its sender or its contract contains the filter, the
+// contract being part of the description of the transaction
+pub fn matches_filter(sender: &str, description: &str, filter: &str) -> bool {
+    sender.contains(filter) || description.contains(filter)
+}
+
+pub fn decode_payload(raw_tx: &str) -> Result<Vec<String>, String> {
+    let bytes = hex_bytes(raw_tx.trim_start_matches("0x")).map_err(|e| e.to_string())?;
+    let mut cursor = Cursor::new(&bytes);
+    let tx = StacksTransaction::consensus_deserialize(&mut cursor).map_err(|e| e.to_string())?;
+
+    let mut lines = vec![
+        format!("nonce: {}", tx.get_origin_nonce()),
+        format!("fee: {} µSTX", tx.get_tx_fee()),
+    ];
+    match tx.payload {
+        TransactionPayload::TokenTransfer(recipient, amount, _) => {
+            lines.push("type: STX transfer".into());
+            lines.push(format!("recipient: {}", recipient));
+            lines.push(format!("amount: {} µSTX", amount));
+        }
+        TransactionPayload::ContractCall(call) => {
+            lines.push("type: contract-call".into());
+            lines.push(format!("contract: {}.{}", call.address, call.contract_name));
+            lines.push(format!("function: {}", call.function_name));
+            for (i, arg) in call.function_args.iter().enumerate() {
+                lines.push(format!("arg {}: {}", i + 1, arg));
+            }
+        }
+        TransactionPayload::SmartContract(contract, clarity_version) => {
+            lines.push("type: contract deployment".into());
+            lines.push(format!("contract: {}", contract.name));
+            if let Some(clarity_version) = clarity_version {
+                lines.push(format!("clarity version: {:?}", clarity_version));
+            }
+            lines.push(format!("source: {} bytes", contract.code_body.len()));
+        }
+        payload => lines.push(format!("type: {}", payload.name())),
+    }
+    Ok(lines)
+}
+
+// events are serialized as {"type": "...", "data": {...}}
+fn format_event(event: JsonValue) -> String {
+    match (event.get("type"), event.get("data")) {
+        (Some(JsonValue::String(event_type)), Some(data)) => format!("{} {}", event_type, data),
+        _ => event.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use clarinet_files::{DEFAULT_DERIVATION_PATH, DEFAULT_FAUCET_MNEMONIC};
+    use clarity_repl::clarity::util::hash::to_hex;
+    use clarity_repl::clarity::vm::types::QualifiedContractIdentifier;
+    use clarity_repl::clarity::vm::{ClarityName, Value};
+    use clarity_repl::codec::TransactionAnchorMode;
+    use stacks_rpc_client::crypto::{encode_contract_call, Wallet};
+    use std::convert::TryFrom;
+
+    #[test]
+    fn decode_contract_call_payload() {
+        let wallet = Wallet {
+            mnemonic: DEFAULT_FAUCET_MNEMONIC.into(),
+            derivation: DEFAULT_DERIVATION_PATH.into(),
+            mainnet: false,
+        };
+        let contract_id =
+            QualifiedContractIdentifier::parse("ST1PQHQKV0RJXZFY1DGX8MNSNYVE3VGZJSRTPGZGM.counter")
+                .unwrap();
+        let tx = encode_contract_call(
+            &contract_id,
+            ClarityName::try_from("increment".to_string()).unwrap(),
+            vec![Value::UInt(3)],
+            &wallet,
+            4,
+            2000,
+            TransactionAnchorMode::Any,
+        )
+        .unwrap();
+        let raw_tx = format!("0x{}", to_hex(&tx.serialize_to_vec()));
+
+        let payload = decode_payload(&raw_tx).unwrap();
+        assert_eq!(
+            payload,
+            vec![
+                "nonce: 4",
+                "fee: 2000 µSTX",
+                "type: contract-call",
+                "contract: ST1PQHQKV0RJXZFY1DGX8MNSNYVE3VGZJSRTPGZGM.counter",
+                "function: increment",
+                "arg 1: u3",
+            ]
+        );
+    }
+
+    #[test]
+    fn decode_invalid_payload() {
+        assert!(decode_payload("0xzz").is_err());
+    }
+
+    #[test]
+    fn filter_transactions() {
+        let sender = "ST1SJ3DTE5DN7X54YDH5D64R3BCB6A2AG2ZQ8YPD5";
+        let description =
+            "invoked: ST1PQHQKV0RJXZFY1DGX8MNSNYVE3VGZJSRTPGZGM.counter::increment(u3)";
+
+        assert!(matches_filter(sender, description, ""));
+        assert!(matches_filter(sender, description, "ST1SJ3"));
+        assert!(matches_filter(sender, description, "counter"));
+        assert!(!matches_filter(sender, description, "ST2CY5"));
+        assert!(!matches_filter(sender, description, "Counter"));
+    }
+}
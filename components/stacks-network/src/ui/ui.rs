@@ -1,8 +1,8 @@
-use super::{app::BlockData, App};
+use super::{app::BlockData, transaction::TransactionDetails, App};
 
 use crate::{event::Status, log::LogLevel};
 
-use chainhook_sdk::types::{StacksBlockData, StacksMicroblockData, StacksTransactionData};
+use chainhook_sdk::types::{StacksBlockData, StacksMicroblockData};
 use ratatui::{prelude::*, widgets::*};
 
 pub fn draw(f: &mut Frame, app: &mut App) {
@@ -147,20 +147,23 @@ fn draw_blocks(f: &mut Frame, app: &mut App, area: Rect) {
 
     f.render_widget(blocks, blocks_components[0]);
 
-    if app.tabs.titles.is_empty() {
-        return;
-    }
-    let transactions = match &app.blocks[(app.tabs.titles.len() - 1) - app.tabs.index] {
-        BlockData::Block(selected_block) => {
+    match app.selected_block() {
+        Some(BlockData::Block(selected_block)) => {
             draw_block_details(f, block_details_components[0], selected_block);
-            &selected_block.transactions
         }
-        BlockData::Microblock(selected_microblock) => {
+        Some(BlockData::Microblock(selected_microblock)) => {
             draw_microblock_details(f, block_details_components[0], selected_microblock);
-            &selected_microblock.transactions
         }
+        None => return,
     };
-    draw_transactions(f, block_details_components[1], transactions);
+
+    match app.selected_transaction() {
+        Some(transaction) if app.show_transaction_details => {
+            let details = TransactionDetails::new(transaction);
+            draw_transaction_details(f, block_details_components[1], &details);
+        }
+        _ => draw_transactions(f, block_details_components[1], app),
+    }
 }
 
 fn draw_block_details(f: &mut Frame, area: Rect, block: &StacksBlockData) {
@@ -296,8 +299,9 @@ fn draw_microblock_details(f: &mut Frame, area: Rect, microblock: &StacksMicrobl
     f.render_widget(paragraph, labels[8]);
 }
 
-fn draw_transactions(f: &mut Frame, area: Rect, transactions: &[StacksTransactionData]) {
-    let transactions: Vec<ListItem> = transactions
+fn draw_transactions(f: &mut Frame, area: Rect, app: &mut App) {
+    let transactions: Vec<ListItem> = app
+        .visible_transactions()
         .iter()
         .map(|t| {
             let tx_info = Line::from(vec![
@@ -320,12 +324,17 @@ fn draw_transactions(f: &mut Frame, area: Rect, transactions: &[StacksTransactio
         })
         .collect();
 
+    let title = if app.editing_filter || !app.transactions_filter.is_empty() {
+        format!("Transactions (filter: {})", app.transactions_filter)
+    } else {
+        "Transactions".to_string()
+    };
     let list = List::new(transactions)
         .block(
             Block::default()
                 .borders(Borders::LEFT)
                 .style(Style::default().fg(Color::White))
-                .title("Transactions"),
+                .title(title),
         )
         .highlight_style(
             Style::default()
@@ -335,13 +344,55 @@ fn draw_transactions(f: &mut Frame, area: Rect, transactions: &[StacksTransactio
         .highlight_symbol("* ");
     let mut inner_area = area;
     inner_area.height = inner_area.height.saturating_sub(1);
-    f.render_widget(list, inner_area);
+    f.render_stateful_widget(list, inner_area, &mut app.transactions.state);
+}
+
+fn draw_transaction_details(f: &mut Frame, area: Rect, details: &TransactionDetails) {
+    let bold = Style::default().add_modifier(Modifier::BOLD);
+    let mut lines = vec![
+        Line::from(vec![
+            Span::raw(match details.success {
+                true => "🟩 ",
+                false => "🟥 ",
+            }),
+            Span::styled(details.txid.clone(), bold),
+        ]),
+        Line::from(format!("sender: {}", details.sender)),
+        Line::from(format!("result: {}", details.result)),
+        Line::from(""),
+        Line::from(Span::styled("Payload", bold)),
+    ];
+    lines.extend(details.payload.iter().map(|line| Line::from(line.clone())));
+
+    lines.push(Line::from(""));
+    lines.push(Line::from(Span::styled("Events", bold)));
+    if details.events.is_empty() {
+        lines.push(Line::from("none"));
+    }
+    lines.extend(details.events.iter().map(|event| Line::from(event.clone())));
+
+    lines.push(Line::from(""));
+    lines.push(Line::from(Span::styled("Costs", bold)));
+    lines.extend(details.costs.iter().map(|cost| Line::from(cost.clone())));
+
+    let paragraph = Paragraph::new(lines)
+        .block(
+            Block::default()
+                .borders(Borders::LEFT)
+                .style(Style::default().fg(Color::White))
+                .title("Transaction details (esc to close)"),
+        )
+        .wrap(Wrap { trim: false });
+    f.render_widget(paragraph, area);
 }
 
 fn draw_help(f: &mut Frame, app: &mut App, area: Rect) {
     // let help =
     //     " ⬅️  ➡️  Explore blocks          ⬆️  ⬇️  Explore transactions          0️⃣  Genesis Reset";
-    let help = format!(" ⬅️  ➡️  Explore blocks          Path: {}", app.devnet_path);
+    let help = format!(
+        " ⬅️  ➡️  Explore blocks   ⬆️  ⬇️  Select transaction   ⏎ Details   / Filter   l d b p r Faults   Path: {}",
+        app.devnet_path
+    );
     let paragraph = Paragraph::new(help.clone())
         .style(Style::default().fg(Color::White))
         .block(Block::default().borders(Borders::NONE));